@@ -3,10 +3,10 @@
 //! These represent aggregate expressions like array expressions
 //! and record expressions.
 
+use super::arena::Child;
 use super::expressions::Expression;
 use super::primitives::Identifier;
-use super::typedenoters::TypeDenoter;
-use super::{Ast, AstObject, AstVisitor, CommonState};
+use super::{Ast, AstVisitor, CommonState};
 use crate::scanner::SourcePosition;
 use std::fmt;
 
@@ -17,6 +17,25 @@ pub enum ArrayAggregate {
 }
 
 impl ArrayAggregate {
+    pub fn common_state(&self) -> &CommonState {
+        use ArrayAggregate::*;
+
+        match *self {
+            SingleArrayAggregate(ref state) => &state.common_state,
+            MultipleArrayAggregate(ref state) => &state.common_state,
+        }
+    }
+
+    /// the number of elements in the aggregate
+    pub fn elem_count(&self) -> usize {
+        use ArrayAggregate::*;
+
+        match *self {
+            SingleArrayAggregate(ref state) => state.elem_count,
+            MultipleArrayAggregate(ref state) => state.elem_count,
+        }
+    }
+
     pub fn get_single_array_aggregate(&self) -> Option<&SingleArrayAggregateState> {
         match *self {
             ArrayAggregate::SingleArrayAggregate(ref single) => Some(&single),
@@ -60,7 +79,7 @@ impl fmt::Display for ArrayAggregate {
 }
 
 impl Ast for ArrayAggregate {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         use ArrayAggregate::*;
 
         match *self {
@@ -72,8 +91,7 @@ impl Ast for ArrayAggregate {
 
 #[derive(Debug, Clone)]
 pub struct SingleArrayAggregateState {
-    pub expr: Box<Expression>,
-    pub td: Option<Box<TypeDenoter>>,
+    pub expr: Child<Expression>,
     pub elem_count: usize,
     pub common_state: CommonState,
 }
@@ -81,9 +99,8 @@ pub struct SingleArrayAggregateState {
 impl SingleArrayAggregateState {
     pub fn new(expr: Expression) -> Self {
         SingleArrayAggregateState {
-            expr: Box::new(expr),
-            td: None,
-            elem_count: 0,
+            expr: Child::new(expr),
+            elem_count: 1,
             common_state: CommonState::default(),
         }
     }
@@ -110,16 +127,15 @@ impl fmt::Display for SingleArrayAggregateState {
 }
 
 impl Ast for SingleArrayAggregateState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_single_array_aggregate(self, arg)
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct MultipleArrayAggregateState {
-    pub expr: Box<Expression>,
-    pub aa: Box<ArrayAggregate>,
-    pub td: Option<TypeDenoter>,
+    pub expr: Child<Expression>,
+    pub aa: Child<ArrayAggregate>,
     pub elem_count: usize,
     pub common_state: CommonState,
 }
@@ -127,10 +143,9 @@ pub struct MultipleArrayAggregateState {
 impl MultipleArrayAggregateState {
     pub fn new(expr: Expression, aa: ArrayAggregate) -> Self {
         MultipleArrayAggregateState {
-            expr: Box::new(expr),
-            aa: Box::new(aa),
-            td: None,
-            elem_count: 0,
+            expr: Child::new(expr),
+            elem_count: aa.elem_count() + 1,
+            aa: Child::new(aa),
             common_state: CommonState::default(),
         }
     }
@@ -165,7 +180,7 @@ impl fmt::Display for MultipleArrayAggregateState {
 }
 
 impl Ast for MultipleArrayAggregateState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_multiple_array_aggregate(self, arg)
     }
 }
@@ -177,6 +192,15 @@ pub enum RecordAggregate {
 }
 
 impl RecordAggregate {
+    pub fn common_state(&self) -> &CommonState {
+        use RecordAggregate::*;
+
+        match *self {
+            SingleRecordAggregate(ref state) => &state.common_state,
+            MultipleRecordAggregate(ref state) => &state.common_state,
+        }
+    }
+
     pub fn get_single_record_aggregate(&self) -> Option<&SingleRecordAggregateState> {
         match *self {
             RecordAggregate::SingleRecordAggregate(ref single) => Some(&single),
@@ -220,7 +244,7 @@ impl fmt::Display for RecordAggregate {
 }
 
 impl Ast for RecordAggregate {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         use RecordAggregate::*;
 
         match *self {
@@ -233,7 +257,7 @@ impl Ast for RecordAggregate {
 #[derive(Debug, Clone)]
 pub struct SingleRecordAggregateState {
     pub id: Identifier,
-    pub expr: Box<Expression>,
+    pub expr: Child<Expression>,
     pub common_state: CommonState,
 }
 
@@ -241,7 +265,7 @@ impl SingleRecordAggregateState {
     pub fn new(id: Identifier, expr: Expression) -> Self {
         SingleRecordAggregateState {
             id: id,
-            expr: Box::new(expr),
+            expr: Child::new(expr),
             common_state: CommonState::default(),
         }
     }
@@ -272,7 +296,7 @@ impl fmt::Display for SingleRecordAggregateState {
 }
 
 impl Ast for SingleRecordAggregateState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_single_record_aggregate(self, arg)
     }
 }
@@ -280,8 +304,8 @@ impl Ast for SingleRecordAggregateState {
 #[derive(Debug, Clone)]
 pub struct MultipleRecordAggregateState {
     pub id: Identifier,
-    pub expr: Box<Expression>,
-    pub ra: Box<RecordAggregate>,
+    pub expr: Child<Expression>,
+    pub ra: Child<RecordAggregate>,
     pub common_state: CommonState,
 }
 
@@ -289,8 +313,8 @@ impl MultipleRecordAggregateState {
     pub fn new(id: Identifier, expr: Expression, ra: RecordAggregate) -> Self {
         MultipleRecordAggregateState {
             id: id,
-            expr: Box::new(expr),
-            ra: Box::new(ra),
+            expr: Child::new(expr),
+            ra: Child::new(ra),
             common_state: CommonState::default(),
         }
     }
@@ -326,7 +350,7 @@ impl fmt::Display for MultipleRecordAggregateState {
 }
 
 impl Ast for MultipleRecordAggregateState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_multiple_record_aggregate(self, arg)
     }
 }
@@ -0,0 +1,496 @@
+//! The arena that the nodes of a program are stored in
+//!
+//! The Parser builds each node with its children inside it. When the tree becomes a Program, every
+//! node is given a NodeId, and each command, expression, declaration, vname, aggregate, and
+//! parameter is moved out of its parent into the program's arena, at the slot of its NodeId. The
+//! parent keeps a `Child::Stored` handle on the NodeId in its place. Type denoters, identifiers,
+//! operators, and literals are small, and stay inside their parents, but are numbered all the
+//! same.
+//!
+//! The standard environment has an arena of its own, which the arena of a program is linked to
+//! when the program is checked, so that a NodeId leads to a declaration of either one.
+
+use super::aggregates::*;
+use super::commands::*;
+use super::declarations::*;
+use super::expressions::*;
+use super::parameters::*;
+use super::primitives::*;
+use super::typedenoters::*;
+use super::vnames::*;
+use super::{Ast, AstVisitor, CommonState, NodeId, NodeIds};
+
+use std::fmt;
+use std::mem;
+use std::sync::Arc;
+
+/// A child of a node - either a node of its own that has not been stored in an arena yet, or the
+/// NodeId of the node in the arena of its program
+#[derive(Debug, Clone)]
+pub enum Child<T> {
+    Detached(Box<T>),
+    Stored(NodeId),
+}
+
+impl<T: Stored> Child<T> {
+    pub fn new(node: T) -> Self {
+        Child::Detached(Box::new(node))
+    }
+
+    pub fn id(&self) -> NodeId {
+        match *self {
+            Child::Detached(ref node) => node.id(),
+            Child::Stored(id) => id,
+        }
+    }
+
+    /// the node, looked up in the arena if it has been stored there. A stored node that is not in
+    /// the arena is a bug - either the arena is not that of the node's program, or the node has
+    /// been taken out of it by a visitor that is visiting it.
+    pub fn get<'a>(&'a self, nodes: &'a Nodes) -> &'a T {
+        match *self {
+            Child::Detached(ref node) => node,
+            Child::Stored(id) => nodes
+                .get(id)
+                .unwrap_or_else(|| panic!("node {} is not in the arena", id)),
+        }
+    }
+
+    /// the node itself, which must have been detached from the arena
+    pub fn into_inner(self) -> T {
+        match self {
+            Child::Detached(node) => *node,
+            Child::Stored(id) => panic!("node {} is still in the arena", id),
+        }
+    }
+
+    pub fn get_mut<'a>(&'a mut self, nodes: &'a mut Nodes) -> &'a mut T {
+        match *self {
+            Child::Detached(ref mut node) => node,
+            Child::Stored(id) => nodes
+                .get_mut(id)
+                .unwrap_or_else(|| panic!("node {} is not in the arena", id)),
+        }
+    }
+}
+
+impl<T: PartialEq> PartialEq for Child<T> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Child::Detached(ref node1), Child::Detached(ref node2)) => node1 == node2,
+            (Child::Stored(id1), Child::Stored(id2)) => id1 == id2,
+            (_, _) => false,
+        }
+    }
+}
+
+impl<T: Eq> Eq for Child<T> {}
+
+impl<T: fmt::Display> fmt::Display for Child<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Child::Detached(ref node) => write!(f, "{}", node),
+            Child::Stored(id) => write!(f, "{}", id),
+        }
+    }
+}
+
+impl<T: Stored + Ast> Ast for Child<T> {
+    /// a stored node is taken out of the visitor's arena while it is being visited, and put back
+    /// once the visit is over
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
+        match *self {
+            Child::Detached(ref mut node) => node.accept(visitor, arg),
+            Child::Stored(id) => {
+                let mut node: T = visitor.nodes().take(id);
+                let result = node.accept(visitor, arg);
+                visitor.nodes().insert(node);
+                result
+            }
+        }
+    }
+}
+
+/// A node that is stored in the arena
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Node {
+    ActualParameter(ActualParameter),
+    ActualParameterSequence(ActualParameterSequence),
+    ArrayAggregate(ArrayAggregate),
+    Command(Command),
+    Declaration(Declaration),
+    Expression(Expression),
+    FormalParameter(FormalParameter),
+    FormalParameterSequence(FormalParameterSequence),
+    RecordAggregate(RecordAggregate),
+    Vname(Vname),
+}
+
+/// The kinds of node that are stored in the arena
+pub trait Stored: Tree + Sized {
+    fn id(&self) -> NodeId;
+    fn into_node(self) -> Node;
+    fn from_node(node: &Node) -> Option<&Self>;
+    fn from_node_mut(node: &mut Node) -> Option<&mut Self>;
+    fn from_owned_node(node: Node) -> Option<Self>;
+}
+
+macro_rules! stored {
+    ($($kind:ident),*) => {
+        $(
+            impl Stored for $kind {
+                fn id(&self) -> NodeId {
+                    self.common_state().id
+                }
+
+                fn into_node(self) -> Node {
+                    Node::$kind(self)
+                }
+
+                fn from_node(node: &Node) -> Option<&Self> {
+                    match *node {
+                        Node::$kind(ref node) => Some(node),
+                        _ => None,
+                    }
+                }
+
+                fn from_node_mut(node: &mut Node) -> Option<&mut Self> {
+                    match *node {
+                        Node::$kind(ref mut node) => Some(node),
+                        _ => None,
+                    }
+                }
+
+                fn from_owned_node(node: Node) -> Option<Self> {
+                    match node {
+                        Node::$kind(node) => Some(node),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+stored!(
+    ActualParameter,
+    ActualParameterSequence,
+    ArrayAggregate,
+    Command,
+    Declaration,
+    Expression,
+    FormalParameter,
+    FormalParameterSequence,
+    RecordAggregate,
+    Vname
+);
+
+/// The nodes of a program (or of the standard environment), each at the slot of its NodeId
+#[derive(Clone, Default)]
+pub struct Nodes {
+    /// the NodeId of the first slot
+    first: u32,
+    slots: Vec<Option<Node>>,
+    /// the arena of the standard environment that the program was checked in
+    environment: Option<Arc<Nodes>>,
+}
+
+impl Nodes {
+    /// an arena for the nodes of a program
+    pub fn new() -> Self {
+        Nodes::default()
+    }
+
+    /// an arena for the nodes of a standard environment
+    pub fn environment() -> Self {
+        Nodes {
+            first: NodeIds::ENVIRONMENT,
+            ..Nodes::default()
+        }
+    }
+
+    /// link the arena to the arena of the standard environment, so that the declarations of the
+    /// environment can be looked up in it as well
+    pub fn link_environment(&mut self, environment: Arc<Nodes>) {
+        self.environment = Some(environment);
+    }
+
+    fn slot(&self, id: NodeId) -> Option<usize> {
+        id.index()
+            .checked_sub(self.first)
+            .map(|slot| slot as usize)
+            .filter(|&slot| slot < self.slots.len())
+    }
+
+    /// the node with the NodeId, whether it is a node of this arena, or of the environment that it
+    /// is linked to
+    pub fn node(&self, id: NodeId) -> Option<&Node> {
+        match self.slot(id) {
+            Some(slot) => self.slots[slot].as_ref(),
+            None => self.environment.as_ref().and_then(|env| env.node(id)),
+        }
+    }
+
+    pub fn get<T: Stored>(&self, id: NodeId) -> Option<&T> {
+        self.node(id).and_then(T::from_node)
+    }
+
+    /// the node with the NodeId, if it is a node of this arena - the nodes of the environment are
+    /// never changed
+    pub fn get_mut<T: Stored>(&mut self, id: NodeId) -> Option<&mut T> {
+        let slot = self.slot(id)?;
+        self.slots[slot].as_mut().and_then(T::from_node_mut)
+    }
+
+    /// store the node at the slot of its NodeId
+    pub fn insert<T: Stored>(&mut self, node: T) {
+        let id = node.id();
+        assert!(id.is_assigned(), "only numbered nodes can be stored");
+        let slot = (id.index() - self.first) as usize;
+        if slot >= self.slots.len() {
+            self.slots.resize(slot + 1, None);
+        }
+        self.slots[slot] = Some(node.into_node());
+    }
+
+    /// take the node out of the arena, leaving its slot empty until it is put back
+    pub fn take<T: Stored>(&mut self, id: NodeId) -> T {
+        self.slot(id)
+            .and_then(|slot| self.slots[slot].take())
+            .and_then(T::from_owned_node)
+            .unwrap_or_else(|| panic!("node {} is not in the arena", id))
+    }
+
+    /// what the identifier or operator that is linked to the NodeId stands for
+    pub fn definition(&self, id: NodeId) -> Option<Definition<'_>> {
+        match *self.node(id)? {
+            Node::Declaration(ref decl) => Definition::of(decl),
+            Node::FormalParameter(ref fp) => Some(Definition::Parameter(fp)),
+            _ => None,
+        }
+    }
+
+    /// the nodes of this arena, in the order of their NodeIds
+    pub fn iter(&self) -> impl Iterator<Item = (NodeId, &Node)> {
+        let first = self.first;
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(move |(slot, node)| {
+                node.as_ref()
+                    .map(|node| (NodeId(first + slot as u32), node))
+            })
+    }
+}
+
+impl PartialEq for Nodes {
+    fn eq(&self, other: &Self) -> bool {
+        self.slots == other.slots
+    }
+}
+
+impl Eq for Nodes {}
+
+impl fmt::Debug for Nodes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// A node, along with the nodes below it
+pub trait Tree {
+    /// number the node and every node below it that has not been numbered yet, and move the
+    /// children that are still detached into the arena
+    fn adopt(&mut self, nodes: &mut Nodes, ids: &mut NodeIds);
+
+    /// take the stored children back out of the arena, so that the node holds its whole tree
+    fn detach(&mut self, nodes: &mut Nodes);
+}
+
+impl<T: Stored> Tree for Child<T> {
+    fn adopt(&mut self, nodes: &mut Nodes, ids: &mut NodeIds) {
+        if let Child::Detached(ref mut node) = *self {
+            node.adopt(nodes, ids);
+            let id = node.id();
+            if let Child::Detached(node) = mem::replace(self, Child::Stored(id)) {
+                nodes.insert(*node);
+            }
+        }
+    }
+
+    fn detach(&mut self, nodes: &mut Nodes) {
+        if let Child::Stored(id) = *self {
+            let mut node: T = nodes.take(id);
+            node.detach(nodes);
+            *self = Child::new(node);
+        }
+    }
+}
+
+impl<T: Tree> Tree for Box<T> {
+    fn adopt(&mut self, nodes: &mut Nodes, ids: &mut NodeIds) {
+        self.as_mut().adopt(nodes, ids);
+    }
+
+    fn detach(&mut self, nodes: &mut Nodes) {
+        self.as_mut().detach(nodes);
+    }
+}
+
+impl CommonState {
+    fn number(&mut self, ids: &mut NodeIds) {
+        if !self.id.is_assigned() {
+            self.id = ids.allocate();
+        }
+    }
+}
+
+/// the nodes are numbered before their children, so that a tree is numbered in the order that
+/// its nodes appear in the source
+macro_rules! tree_states {
+    ($($state:ident { $($child:ident),* })*) => {
+        $(
+            impl Tree for $state {
+                fn adopt(&mut self, _nodes: &mut Nodes, ids: &mut NodeIds) {
+                    self.common_state.number(ids);
+                    $(self.$child.adopt(_nodes, ids);)*
+                }
+
+                fn detach(&mut self, _nodes: &mut Nodes) {
+                    $(self.$child.detach(_nodes);)*
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! tree_variants {
+    ($($kind:ident { $($variant:ident),* })*) => {
+        $(
+            impl Tree for $kind {
+                fn adopt(&mut self, nodes: &mut Nodes, ids: &mut NodeIds) {
+                    match *self {
+                        $($kind::$variant(ref mut node) => node.adopt(nodes, ids),)*
+                    }
+                }
+
+                fn detach(&mut self, nodes: &mut Nodes) {
+                    match *self {
+                        $($kind::$variant(ref mut node) => node.detach(nodes),)*
+                    }
+                }
+            }
+        )*
+    };
+}
+
+tree_states! {
+    AssignCommandState { vname, expr }
+    CallCommandState { id, aps }
+    EmptyCommandState {}
+    IfCommandState { expr, cmd1, cmd2 }
+    LetCommandState { decl, cmd }
+    SequentialCommandState { cmd1, cmd2 }
+    WhileCommandState { expr, cmd }
+
+    ArrayExpressionState { aa }
+    BinaryExpressionState { expr1, op, expr2 }
+    CallExpressionState { id, aps }
+    CharacterExpressionState { cl }
+    EmptyExpressionState {}
+    IfExpressionState { expr1, expr2, expr3 }
+    IntegerExpressionState { il }
+    LetExpressionState { decl, expr }
+    RecordExpressionState { ra }
+    UnaryExpressionState { op, expr }
+    VnameExpressionState { vname }
+
+    SingleArrayAggregateState { expr }
+    MultipleArrayAggregateState { expr, aa }
+    SingleRecordAggregateState { id, expr }
+    MultipleRecordAggregateState { id, expr, ra }
+
+    BinaryOperatorDeclarationState { arg1_type, op, arg2_type, res_type }
+    ConstDeclarationState { id, expr }
+    FuncDeclarationState { id, fps, td, expr }
+    ProcDeclarationState { id, fps, cmd }
+    SequentialDeclarationState { decl1, decl2 }
+    TypeDeclarationState { id, td }
+    UnaryOperatorDeclarationState { op, argtype, res_type }
+    VarDeclarationState { id, td }
+
+    EmptyFormalParameterSequenceState {}
+    SingleFormalParameterSequenceState { fp }
+    MultipleFormalParameterSequenceState { fp, fps }
+    ConstFormalParameterState { id, td }
+    FuncFormalParameterState { id, fps, td }
+    ProcFormalParameterState { id, fps }
+    VarFormalParameterState { id, td }
+
+    EmptyActualParameterSequenceState {}
+    SingleActualParameterSequenceState { ap }
+    MultipleActualParameterSequenceState { ap, aps }
+    ConstActualParameterState { expr }
+    FuncActualParameterState { id }
+    ProcActualParameterState { id }
+    VarActualParameterState { vname }
+
+    AnyTypeDenoterState {}
+    ArrayTypeDenoterState { il, td }
+    BoolTypeDenoterState {}
+    CharTypeDenoterState {}
+    ErrorTypeDenoterState {}
+    IntTypeDenoterState {}
+    RecordTypeDenoterState { ftd }
+    SimpleTypeDenoterState { id }
+    SingleFieldTypeDenoterState { id, td }
+    MultipleFieldTypeDenoterState { id, td, ftd }
+
+    DotVnameState { vname, id }
+    SimpleVnameState { id }
+    SubscriptVnameState { vname, expr }
+
+    CharacterLiteral {}
+    Identifier {}
+    IntegerLiteral {}
+    Operator {}
+}
+
+tree_variants! {
+    Command {
+        AssignCommand, CallCommand, EmptyCommand, IfCommand, LetCommand, SequentialCommand,
+        WhileCommand
+    }
+    Expression {
+        ArrayExpression, BinaryExpression, CallExpression, CharacterExpression, EmptyExpression,
+        IfExpression, IntegerExpression, LetExpression, RecordExpression, UnaryExpression,
+        VnameExpression
+    }
+    ArrayAggregate { SingleArrayAggregate, MultipleArrayAggregate }
+    RecordAggregate { SingleRecordAggregate, MultipleRecordAggregate }
+    Declaration {
+        BinaryOperatorDeclaration, ConstDeclaration, FormalParameterDeclaration, FuncDeclaration,
+        ProcDeclaration, SequentialDeclaration, TypeDeclaration, UnaryOperatorDeclaration,
+        VarDeclaration
+    }
+    FormalParameterSequence {
+        EmptyFormalParameterSequence, SingleFormalParameterSequence,
+        MultipleFormalParameterSequence
+    }
+    FormalParameter {
+        ConstFormalParameter, FuncFormalParameter, ProcFormalParameter, VarFormalParameter
+    }
+    ActualParameterSequence {
+        EmptyActualParameterSequence, SingleActualParameterSequence,
+        MultipleActualParameterSequence
+    }
+    ActualParameter {
+        ConstActualParameter, FuncActualParameter, ProcActualParameter, VarActualParameter
+    }
+    TypeDenoter {
+        AnyTypeDenoter, ArrayTypeDenoter, BoolTypeDenoter, CharTypeDenoter, ErrorTypeDenoter,
+        IntTypeDenoter, RecordTypeDenoter, SimpleTypeDenoter
+    }
+    FieldTypeDenoter { SingleFieldTypeDenoter, MultipleFieldTypeDenoter }
+    Vname { DotVname, SimpleVname, SubscriptVname }
+}
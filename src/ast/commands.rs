@@ -1,11 +1,12 @@
 //! command asts
 
+use super::arena::Child;
 use super::declarations::Declaration;
 use super::expressions::Expression;
 use super::parameters::ActualParameterSequence;
 use super::primitives::Identifier;
 use super::vnames::Vname;
-use super::{Ast, AstObject, AstVisitor, CommonState};
+use super::{Ast, AstVisitor, CommonState};
 use crate::scanner::SourcePosition;
 use std::fmt;
 
@@ -21,6 +22,20 @@ pub enum Command {
 }
 
 impl Command {
+    pub fn common_state(&self) -> &CommonState {
+        use Command::*;
+
+        match *self {
+            AssignCommand(ref state) => &state.common_state,
+            CallCommand(ref state) => &state.common_state,
+            EmptyCommand(ref state) => &state.common_state,
+            IfCommand(ref state) => &state.common_state,
+            LetCommand(ref state) => &state.common_state,
+            SequentialCommand(ref state) => &state.common_state,
+            WhileCommand(ref state) => &state.common_state,
+        }
+    }
+
     pub fn get_assign_command(&self) -> Option<&AssignCommandState> {
         match *self {
             Command::AssignCommand(ref assign) => Some(&assign),
@@ -107,7 +122,7 @@ impl fmt::Display for Command {
 }
 
 impl Ast for Command {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         use Command::*;
 
         match *self {
@@ -124,16 +139,16 @@ impl Ast for Command {
 
 #[derive(Debug, Clone)]
 pub struct AssignCommandState {
-    pub vname: Box<Vname>,
-    pub expr: Box<Expression>,
+    pub vname: Child<Vname>,
+    pub expr: Child<Expression>,
     pub common_state: CommonState,
 }
 
 impl AssignCommandState {
     pub fn new(vname: Vname, expr: Expression) -> Self {
         AssignCommandState {
-            vname: Box::new(vname),
-            expr: Box::new(expr),
+            vname: Child::new(vname),
+            expr: Child::new(expr),
             common_state: CommonState::default(),
         }
     }
@@ -160,7 +175,7 @@ impl fmt::Display for AssignCommandState {
 }
 
 impl Ast for AssignCommandState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_assign_command(self, arg)
     }
 }
@@ -168,7 +183,7 @@ impl Ast for AssignCommandState {
 #[derive(Debug, Clone)]
 pub struct CallCommandState {
     pub id: Identifier,
-    pub aps: Box<ActualParameterSequence>,
+    pub aps: Child<ActualParameterSequence>,
     pub common_state: CommonState,
 }
 
@@ -176,7 +191,7 @@ impl CallCommandState {
     pub fn new(id: Identifier, aps: ActualParameterSequence) -> Self {
         CallCommandState {
             id: id,
-            aps: Box::new(aps),
+            aps: Child::new(aps),
             common_state: CommonState::default(),
         }
     }
@@ -207,7 +222,7 @@ impl fmt::Display for CallCommandState {
 }
 
 impl Ast for CallCommandState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_call_command(self, arg)
     }
 }
@@ -246,22 +261,22 @@ impl fmt::Display for EmptyCommandState {
 }
 
 impl Ast for EmptyCommandState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_empty_command(self, arg)
     }
 }
 #[derive(Debug, Clone)]
 pub struct LetCommandState {
-    pub decl: Box<Declaration>,
-    pub cmd: Box<Command>,
+    pub decl: Child<Declaration>,
+    pub cmd: Child<Command>,
     pub common_state: CommonState,
 }
 
 impl LetCommandState {
     pub fn new(decl: Declaration, cmd: Command) -> Self {
         LetCommandState {
-            decl: Box::new(decl),
-            cmd: Box::new(cmd),
+            decl: Child::new(decl),
+            cmd: Child::new(cmd),
             common_state: CommonState::default(),
         }
     }
@@ -288,25 +303,25 @@ impl fmt::Display for LetCommandState {
 }
 
 impl Ast for LetCommandState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_let_command(self, arg)
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct IfCommandState {
-    pub expr: Box<Expression>,
-    pub cmd1: Box<Command>,
-    pub cmd2: Box<Command>,
+    pub expr: Child<Expression>,
+    pub cmd1: Child<Command>,
+    pub cmd2: Child<Command>,
     pub common_state: CommonState,
 }
 
 impl IfCommandState {
     pub fn new(expr: Expression, cmd1: Command, cmd2: Command) -> Self {
         IfCommandState {
-            expr: Box::new(expr),
-            cmd1: Box::new(cmd1),
-            cmd2: Box::new(cmd2),
+            expr: Child::new(expr),
+            cmd1: Child::new(cmd1),
+            cmd2: Child::new(cmd2),
             common_state: CommonState::default(),
         }
     }
@@ -342,23 +357,23 @@ impl fmt::Display for IfCommandState {
 }
 
 impl Ast for IfCommandState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_if_command(self, arg)
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct WhileCommandState {
-    pub expr: Box<Expression>,
-    pub cmd: Box<Command>,
+    pub expr: Child<Expression>,
+    pub cmd: Child<Command>,
     pub common_state: CommonState,
 }
 
 impl WhileCommandState {
     pub fn new(expr: Expression, cmd: Command) -> Self {
         WhileCommandState {
-            expr: Box::new(expr),
-            cmd: Box::new(cmd),
+            expr: Child::new(expr),
+            cmd: Child::new(cmd),
             common_state: CommonState::default(),
         }
     }
@@ -385,23 +400,23 @@ impl fmt::Display for WhileCommandState {
 }
 
 impl Ast for WhileCommandState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_while_command(self, arg)
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct SequentialCommandState {
-    pub cmd1: Box<Command>,
-    pub cmd2: Box<Command>,
+    pub cmd1: Child<Command>,
+    pub cmd2: Child<Command>,
     pub common_state: CommonState,
 }
 
 impl SequentialCommandState {
     pub fn new(cmd1: Command, cmd2: Command) -> Self {
         SequentialCommandState {
-            cmd1: Box::new(cmd1),
-            cmd2: Box::new(cmd2),
+            cmd1: Child::new(cmd1),
+            cmd2: Child::new(cmd2),
             common_state: CommonState::default(),
         }
     }
@@ -432,7 +447,7 @@ impl fmt::Display for SequentialCommandState {
 }
 
 impl Ast for SequentialCommandState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_sequential_command(self, arg)
     }
 }
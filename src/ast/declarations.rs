@@ -1,11 +1,12 @@
 //! declaration asts
 
+use super::arena::Child;
 use super::commands::Command;
 use super::expressions::Expression;
-use super::parameters::FormalParameterSequence;
+use super::parameters::{FormalParameter, FormalParameterSequence};
 use super::primitives::{Identifier, Operator};
 use super::typedenoters::TypeDenoter;
-use super::{Ast, AstObject, AstVisitor, CommonState};
+use super::{Ast, AstVisitor, CommonState};
 use crate::scanner::SourcePosition;
 use std::fmt;
 
@@ -13,6 +14,8 @@ use std::fmt;
 pub enum Declaration {
     BinaryOperatorDeclaration(BinaryOperatorDeclarationState),
     ConstDeclaration(ConstDeclarationState),
+    /// a formal parameter, as it is declared within the body of its routine
+    FormalParameterDeclaration(FormalParameter),
     FuncDeclaration(FuncDeclarationState),
     ProcDeclaration(ProcDeclarationState),
     SequentialDeclaration(SequentialDeclarationState),
@@ -36,6 +39,13 @@ impl Declaration {
         }
     }
 
+    pub fn get_formal_parameter_declaration(&self) -> Option<&FormalParameter> {
+        match *self {
+            Declaration::FormalParameterDeclaration(ref fp) => Some(fp),
+            _ => None,
+        }
+    }
+
     pub fn get_func_declaration(&self) -> Option<&FuncDeclarationState> {
         match *self {
             Declaration::FuncDeclaration(ref func) => Some(&func),
@@ -77,6 +87,38 @@ impl Declaration {
             _ => None,
         }
     }
+
+    pub fn common_state(&self) -> &CommonState {
+        use Declaration::*;
+
+        match *self {
+            BinaryOperatorDeclaration(ref decl) => &decl.common_state,
+            ConstDeclaration(ref decl) => &decl.common_state,
+            FormalParameterDeclaration(ref decl) => decl.common_state(),
+            FuncDeclaration(ref decl) => &decl.common_state,
+            ProcDeclaration(ref decl) => &decl.common_state,
+            SequentialDeclaration(ref decl) => &decl.common_state,
+            TypeDeclaration(ref decl) => &decl.common_state,
+            UnaryOperatorDeclaration(ref decl) => &decl.common_state,
+            VarDeclaration(ref decl) => &decl.common_state,
+        }
+    }
+
+    pub fn common_state_mut(&mut self) -> &mut CommonState {
+        use Declaration::*;
+
+        match *self {
+            BinaryOperatorDeclaration(ref mut decl) => &mut decl.common_state,
+            ConstDeclaration(ref mut decl) => &mut decl.common_state,
+            FormalParameterDeclaration(ref mut decl) => decl.common_state_mut(),
+            FuncDeclaration(ref mut decl) => &mut decl.common_state,
+            ProcDeclaration(ref mut decl) => &mut decl.common_state,
+            SequentialDeclaration(ref mut decl) => &mut decl.common_state,
+            TypeDeclaration(ref mut decl) => &mut decl.common_state,
+            UnaryOperatorDeclaration(ref mut decl) => &mut decl.common_state,
+            VarDeclaration(ref mut decl) => &mut decl.common_state,
+        }
+    }
 }
 
 impl PartialEq for Declaration {
@@ -91,6 +133,9 @@ impl PartialEq for Declaration {
             (ConstDeclaration(ref constdecl1), ConstDeclaration(constdecl2)) => {
                 constdecl1 == constdecl2
             }
+            (FormalParameterDeclaration(ref fp1), FormalParameterDeclaration(ref fp2)) => {
+                fp1 == fp2
+            }
             (FuncDeclaration(ref funcdecl1), FuncDeclaration(funcdecl2)) => funcdecl1 == funcdecl2,
             (ProcDeclaration(ref procdecl1), ProcDeclaration(ref procdecl2)) => {
                 procdecl1 == procdecl2
@@ -121,6 +166,9 @@ impl fmt::Display for Declaration {
             ConstDeclaration(ref decl) => {
                 write!(f, "ConstDeclaration({})", decl)
             }
+            FormalParameterDeclaration(ref decl) => {
+                write!(f, "FormalParameterDeclaration({})", decl)
+            }
             FuncDeclaration(ref decl) => {
                 write!(f, "FuncDeclaration({})", decl)
             }
@@ -140,12 +188,13 @@ impl fmt::Display for Declaration {
 }
 
 impl Ast for Declaration {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         use Declaration::*;
 
         match *self {
             BinaryOperatorDeclaration(ref mut binopdecl) => binopdecl.accept(visitor, arg),
             ConstDeclaration(ref mut constdecl) => constdecl.accept(visitor, arg),
+            FormalParameterDeclaration(ref mut fp) => fp.accept(visitor, arg),
             FuncDeclaration(ref mut funcdecl) => funcdecl.accept(visitor, arg),
             ProcDeclaration(ref mut procdecl) => procdecl.accept(visitor, arg),
             SequentialDeclaration(ref mut seqdecl) => seqdecl.accept(visitor, arg),
@@ -216,7 +265,7 @@ impl fmt::Display for BinaryOperatorDeclarationState {
 }
 
 impl Ast for BinaryOperatorDeclarationState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_binary_operator_declaration(self, arg)
     }
 }
@@ -270,7 +319,7 @@ impl fmt::Display for UnaryOperatorDeclarationState {
 }
 
 impl Ast for UnaryOperatorDeclarationState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_unary_operator_declaration(self, arg)
     }
 }
@@ -278,7 +327,7 @@ impl Ast for UnaryOperatorDeclarationState {
 #[derive(Debug, Clone)]
 pub struct ConstDeclarationState {
     pub id: Identifier,
-    pub expr: Box<Expression>,
+    pub expr: Child<Expression>,
     pub common_state: CommonState,
 }
 
@@ -286,7 +335,7 @@ impl ConstDeclarationState {
     pub fn new(id: Identifier, expr: Expression) -> Self {
         ConstDeclarationState {
             id: id,
-            expr: Box::new(expr),
+            expr: Child::new(expr),
             common_state: CommonState::default(),
         }
     }
@@ -313,7 +362,7 @@ impl fmt::Display for ConstDeclarationState {
 }
 
 impl Ast for ConstDeclarationState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_const_declaration(self, arg)
     }
 }
@@ -356,7 +405,7 @@ impl fmt::Display for VarDeclarationState {
 }
 
 impl Ast for VarDeclarationState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_var_declaration(self, arg)
     }
 }
@@ -364,8 +413,8 @@ impl Ast for VarDeclarationState {
 #[derive(Debug, Clone)]
 pub struct ProcDeclarationState {
     pub id: Identifier,
-    pub fps: Box<FormalParameterSequence>,
-    pub cmd: Box<Command>,
+    pub fps: Child<FormalParameterSequence>,
+    pub cmd: Child<Command>,
     pub common_state: CommonState,
 }
 
@@ -373,8 +422,8 @@ impl ProcDeclarationState {
     pub fn new(id: Identifier, fps: FormalParameterSequence, cmd: Command) -> Self {
         ProcDeclarationState {
             id: id,
-            fps: Box::new(fps),
-            cmd: Box::new(cmd),
+            fps: Child::new(fps),
+            cmd: Child::new(cmd),
             common_state: CommonState::default(),
         }
     }
@@ -410,16 +459,16 @@ impl fmt::Display for ProcDeclarationState {
 }
 
 impl Ast for ProcDeclarationState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_proc_declaration(self, arg)
     }
 }
 #[derive(Debug, Clone)]
 pub struct FuncDeclarationState {
     pub id: Identifier,
-    pub fps: Box<FormalParameterSequence>,
+    pub fps: Child<FormalParameterSequence>,
     pub td: Box<TypeDenoter>,
-    pub expr: Box<Expression>,
+    pub expr: Child<Expression>,
     pub common_state: CommonState,
 }
 
@@ -432,9 +481,9 @@ impl FuncDeclarationState {
     ) -> Self {
         FuncDeclarationState {
             id: id,
-            fps: Box::new(fps),
+            fps: Child::new(fps),
             td: Box::new(td),
-            expr: Box::new(expr),
+            expr: Child::new(expr),
             common_state: CommonState::default(),
         }
     }
@@ -474,7 +523,7 @@ impl fmt::Display for FuncDeclarationState {
 }
 
 impl Ast for FuncDeclarationState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_func_declaration(self, arg)
     }
 }
@@ -517,23 +566,23 @@ impl fmt::Display for TypeDeclarationState {
 }
 
 impl Ast for TypeDeclarationState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_type_declaration(self, arg)
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct SequentialDeclarationState {
-    pub decl1: Box<Declaration>,
-    pub decl2: Box<Declaration>,
+    pub decl1: Child<Declaration>,
+    pub decl2: Child<Declaration>,
     pub common_state: CommonState,
 }
 
 impl SequentialDeclarationState {
     pub fn new(decl1: Declaration, decl2: Declaration) -> Self {
         SequentialDeclarationState {
-            decl1: Box::new(decl1),
-            decl2: Box::new(decl2),
+            decl1: Child::new(decl1),
+            decl2: Child::new(decl2),
             common_state: CommonState::default(),
         }
     }
@@ -568,7 +617,56 @@ impl fmt::Display for SequentialDeclarationState {
 }
 
 impl Ast for SequentialDeclarationState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_sequential_declaration(self, arg)
     }
 }
+
+/// What an identifier or operator stands for - one of the declarations that bind a name, or a
+/// formal parameter of the routine that it is used in. Either way, it is borrowed from the tree
+/// that declares it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Definition<'a> {
+    BinaryOperator(&'a BinaryOperatorDeclarationState),
+    Const(&'a ConstDeclarationState),
+    Func(&'a FuncDeclarationState),
+    Parameter(&'a FormalParameter),
+    Proc(&'a ProcDeclarationState),
+    Type(&'a TypeDeclarationState),
+    UnaryOperator(&'a UnaryOperatorDeclarationState),
+    Var(&'a VarDeclarationState),
+}
+
+impl<'a> Definition<'a> {
+    /// what the declaration defines, unless it is a sequence of declarations
+    pub fn of(decl: &'a Declaration) -> Option<Self> {
+        use Declaration::*;
+
+        match *decl {
+            BinaryOperatorDeclaration(ref decl) => Some(Definition::BinaryOperator(decl)),
+            ConstDeclaration(ref decl) => Some(Definition::Const(decl)),
+            FormalParameterDeclaration(ref fp) => Some(Definition::Parameter(fp)),
+            FuncDeclaration(ref decl) => Some(Definition::Func(decl)),
+            ProcDeclaration(ref decl) => Some(Definition::Proc(decl)),
+            SequentialDeclaration(_) => None,
+            TypeDeclaration(ref decl) => Some(Definition::Type(decl)),
+            UnaryOperatorDeclaration(ref decl) => Some(Definition::UnaryOperator(decl)),
+            VarDeclaration(ref decl) => Some(Definition::Var(decl)),
+        }
+    }
+
+    pub fn common_state(&self) -> &'a CommonState {
+        use Definition::*;
+
+        match *self {
+            BinaryOperator(decl) => &decl.common_state,
+            Const(decl) => &decl.common_state,
+            Func(decl) => &decl.common_state,
+            Parameter(fp) => fp.common_state(),
+            Proc(decl) => &decl.common_state,
+            Type(decl) => &decl.common_state,
+            UnaryOperator(decl) => &decl.common_state,
+            Var(decl) => &decl.common_state,
+        }
+    }
+}
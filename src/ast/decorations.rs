@@ -0,0 +1,67 @@
+//! The side tables of a program
+//!
+//! The Checker links every applied occurrence of an identifier or operator to its declaration,
+//! and works out the type of every expression and vname, and the type that every type identifier
+//! stands for. The Encoder works out the runtime entity of every declaration. Each of these
+//! decorations is recorded here against the NodeId of the node that it decorates, rather than in
+//! the node itself.
+//!
+//! The declarations themselves are not copied into the side tables. A link leads to the NodeId of
+//! the declaration, which is either a node in the arena of the program, or one in the arena of the
+//! standard environment that the program was checked in.
+
+use super::runtime_entities::RuntimeEntity;
+use super::typedenoters::TypeDenoter;
+use super::NodeId;
+
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Default)]
+pub struct Decorations {
+    links: HashMap<NodeId, NodeId>,
+    types: HashMap<NodeId, TypeDenoter>,
+    entities: HashMap<NodeId, RuntimeEntity>,
+}
+
+impl Decorations {
+    pub fn new() -> Self {
+        Decorations::default()
+    }
+
+    /// link an applied occurrence of an identifier or operator to its declaration
+    pub fn link(&mut self, node: NodeId, decl: NodeId) {
+        self.links.insert(node, decl);
+    }
+
+    /// the NodeId of the declaration that the node is linked to
+    pub fn linked(&self, node: NodeId) -> Option<NodeId> {
+        self.links.get(&node).cloned()
+    }
+
+    /// every node that is linked to the declaration, in no particular order
+    pub fn references(&self, decl: NodeId) -> Vec<NodeId> {
+        self.links
+            .iter()
+            .filter(|&(_, linked)| *linked == decl)
+            .map(|(node, _)| *node)
+            .collect()
+    }
+
+    pub fn set_type(&mut self, node: NodeId, td: TypeDenoter) {
+        self.types.insert(node, td);
+    }
+
+    /// the type of the expression or vname, or the type that a type identifier stands for
+    pub fn type_of(&self, node: NodeId) -> Option<&TypeDenoter> {
+        self.types.get(&node)
+    }
+
+    pub fn set_entity(&mut self, node: NodeId, entity: RuntimeEntity) {
+        self.entities.insert(node, entity);
+    }
+
+    /// the runtime entity of the declaration (or vname)
+    pub fn entity_of(&self, node: NodeId) -> Option<&RuntimeEntity> {
+        self.entities.get(&node)
+    }
+}
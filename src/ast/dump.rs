@@ -0,0 +1,275 @@
+//! Dumps of the whole Ast
+//!
+//! A program can be shown as an indented tree, one node to a line, or as JSON for tools written
+//! in other languages. Both give the kind and position of every node, the spelling of every
+//! identifier, operator and literal, and - once the program has been checked - the type of every
+//! expression and vname.
+//!
+//! The JSON for a node is an object with, in this order, its `kind`, its `position` as a `start`
+//! and a `finish` that each have a `line` and a `column`, its `spelling` if it has one, its
+//! `type` if it has one, and its `children` in the order that they appear in the source.
+
+use super::aggregates::*;
+use super::arena::{Child, Nodes};
+use super::commands::*;
+use super::declarations::*;
+use super::expressions::*;
+use super::parameters::*;
+use super::primitives::*;
+use super::typedenoters::*;
+use super::visit_ref::{self, AstRefVisitor};
+use super::vnames::*;
+use super::{CommonState, Program};
+use crate::cst::SyntaxKind;
+use crate::json::Json;
+use crate::scanner::{Position, SourcePosition};
+
+impl Program {
+    /// the program as an indented tree, such as
+    ///
+    /// ```text
+    /// AssignCommand 1:1-1:6
+    ///   SimpleVname 1:1-1:1 : Integer
+    ///     Identifier "n" 1:1-1:1
+    /// ```
+    pub fn tree(&self) -> String {
+        let mut tree = String::new();
+        self.dump().write_tree(0, &mut tree);
+        tree
+    }
+
+    pub fn to_json(&self) -> Json {
+        self.dump().to_json()
+    }
+
+    fn dump(&self) -> Node {
+        let mut dumper = Dumper {
+            program: self,
+            stack: vec![Node::new(SyntaxKind::Program, self.common_state.position)],
+        };
+        dumper.visit_command(&self.cmd, ());
+        dumper.stack.pop().unwrap()
+    }
+}
+
+struct Node {
+    kind: SyntaxKind,
+    position: SourcePosition,
+    spelling: Option<String>,
+    /// the name of the type of the node
+    td: Option<String>,
+    children: Vec<Node>,
+}
+
+impl Node {
+    fn new(kind: SyntaxKind, position: SourcePosition) -> Self {
+        Node {
+            kind,
+            position,
+            spelling: None,
+            td: None,
+            children: Vec::new(),
+        }
+    }
+
+    fn write_tree(&self, depth: usize, tree: &mut String) {
+        tree.push_str(&"  ".repeat(depth));
+        tree.push_str(&format!("{:?}", self.kind));
+        if let Some(ref spelling) = self.spelling {
+            tree.push_str(&format!(" {:?}", spelling));
+        }
+        tree.push_str(&format!(
+            " {}:{}-{}:{}",
+            self.position.start.line,
+            self.position.start.column,
+            self.position.finish.line,
+            self.position.finish.column
+        ));
+        if let Some(ref td) = self.td {
+            tree.push_str(" : ");
+            tree.push_str(td);
+        }
+        tree.push('\n');
+
+        for child in &self.children {
+            child.write_tree(depth + 1, tree);
+        }
+    }
+
+    fn to_json(&self) -> Json {
+        let position = |position: Position| {
+            Json::object(vec![
+                ("line", Json::from(position.line)),
+                ("column", Json::from(position.column)),
+            ])
+        };
+
+        let mut members = vec![
+            ("kind", Json::from(format!("{:?}", self.kind))),
+            (
+                "position",
+                Json::object(vec![
+                    ("start", position(self.position.start)),
+                    ("finish", position(self.position.finish)),
+                ]),
+            ),
+        ];
+        if let Some(ref spelling) = self.spelling {
+            members.push(("spelling", Json::from(spelling.as_str())));
+        }
+        if let Some(ref td) = self.td {
+            members.push(("type", Json::from(td.as_str())));
+        }
+        members.push((
+            "children",
+            Json::from(self.children.iter().map(Node::to_json).collect::<Vec<_>>()),
+        ));
+        Json::object(members)
+    }
+}
+
+struct Dumper<'a> {
+    program: &'a Program,
+    /// the nodes that have been entered and not yet left, innermost last
+    stack: Vec<Node>,
+}
+
+impl<'a> Dumper<'a> {
+    fn enter(&mut self, kind: SyntaxKind, state: &CommonState) {
+        let mut node = Node::new(kind, state.position);
+        node.td = self
+            .program
+            .decorations
+            .type_of(state.id)
+            .map(TypeDenoter::name);
+        self.stack.push(node);
+    }
+
+    fn leave(&mut self) {
+        let node = self.stack.pop().unwrap();
+        let parent = self.stack.len() - 1;
+        self.stack[parent].children.push(node);
+    }
+
+    fn leaf(&mut self, kind: SyntaxKind, state: &CommonState, spelling: &str) {
+        self.enter(kind, state);
+        let node = self.stack.len() - 1;
+        self.stack[node].spelling = Some(spelling.to_owned());
+        self.leave();
+    }
+}
+
+impl<'a> AstRefVisitor<'a> for Dumper<'a> {
+    type Arg = ();
+    type Output = ();
+
+    fn nodes(&self) -> &'a Nodes {
+        &self.program.nodes
+    }
+
+    fn visit_command(&mut self, cmd: &'a Child<Command>, arg: ()) {
+        let cmd = cmd.get(self.nodes());
+        self.enter(SyntaxKind::from(cmd), cmd.common_state());
+        visit_ref::walk_command(self, cmd, arg);
+        self.leave();
+    }
+
+    fn visit_expression(&mut self, expr: &'a Child<Expression>, arg: ()) {
+        let expr = expr.get(self.nodes());
+        self.enter(SyntaxKind::from(expr), expr.common_state());
+        visit_ref::walk_expression(self, expr, arg);
+        self.leave();
+    }
+
+    fn visit_array_aggregate(&mut self, agg: &'a Child<ArrayAggregate>, arg: ()) {
+        let agg = agg.get(self.nodes());
+        self.enter(SyntaxKind::from(agg), agg.common_state());
+        visit_ref::walk_array_aggregate(self, agg, arg);
+        self.leave();
+    }
+
+    fn visit_record_aggregate(&mut self, agg: &'a Child<RecordAggregate>, arg: ()) {
+        let agg = agg.get(self.nodes());
+        self.enter(SyntaxKind::from(agg), agg.common_state());
+        visit_ref::walk_record_aggregate(self, agg, arg);
+        self.leave();
+    }
+
+    fn visit_declaration(&mut self, decl: &'a Child<Declaration>, arg: ()) {
+        let decl = decl.get(self.nodes());
+        self.enter(SyntaxKind::from(decl), decl.common_state());
+        visit_ref::walk_declaration(self, decl, arg);
+        self.leave();
+    }
+
+    fn visit_type_denoter(&mut self, td: &'a TypeDenoter, arg: ()) {
+        self.enter(SyntaxKind::from(td), td.common_state());
+        visit_ref::walk_type_denoter(self, td, arg);
+        self.leave();
+    }
+
+    fn visit_field_type_denoter(&mut self, td: &'a FieldTypeDenoter, arg: ()) {
+        self.enter(SyntaxKind::from(td), td.common_state());
+        visit_ref::walk_field_type_denoter(self, td, arg);
+        self.leave();
+    }
+
+    fn visit_formal_parameter_sequence(
+        &mut self,
+        fps: &'a Child<FormalParameterSequence>,
+        arg: (),
+    ) {
+        let fps = fps.get(self.nodes());
+        self.enter(SyntaxKind::from(fps), fps.common_state());
+        visit_ref::walk_formal_parameter_sequence(self, fps, arg);
+        self.leave();
+    }
+
+    fn visit_formal_parameter(&mut self, fp: &'a Child<FormalParameter>, arg: ()) {
+        let fp = fp.get(self.nodes());
+        self.enter(SyntaxKind::from(fp), fp.common_state());
+        visit_ref::walk_formal_parameter(self, fp, arg);
+        self.leave();
+    }
+
+    fn visit_actual_parameter_sequence(
+        &mut self,
+        aps: &'a Child<ActualParameterSequence>,
+        arg: (),
+    ) {
+        let aps = aps.get(self.nodes());
+        self.enter(SyntaxKind::from(aps), aps.common_state());
+        visit_ref::walk_actual_parameter_sequence(self, aps, arg);
+        self.leave();
+    }
+
+    fn visit_actual_parameter(&mut self, ap: &'a Child<ActualParameter>, arg: ()) {
+        let ap = ap.get(self.nodes());
+        self.enter(SyntaxKind::from(ap), ap.common_state());
+        visit_ref::walk_actual_parameter(self, ap, arg);
+        self.leave();
+    }
+
+    fn visit_vname(&mut self, vname: &'a Child<Vname>, arg: ()) {
+        let vname = vname.get(self.nodes());
+        self.enter(SyntaxKind::from(vname), vname.common_state());
+        visit_ref::walk_vname(self, vname, arg);
+        self.leave();
+    }
+
+    fn visit_identifier(&mut self, id: &'a Identifier, _arg: ()) {
+        self.leaf(SyntaxKind::Identifier, &id.common_state, &id.spelling);
+    }
+
+    fn visit_operator(&mut self, op: &'a Operator, _arg: ()) {
+        self.leaf(SyntaxKind::Operator, &op.common_state, &op.spelling);
+    }
+
+    fn visit_integer_literal(&mut self, il: &'a IntegerLiteral, _arg: ()) {
+        self.leaf(SyntaxKind::IntegerLiteral, &il.common_state, &il.spelling);
+    }
+
+    fn visit_character_literal(&mut self, cl: &'a CharacterLiteral, _arg: ()) {
+        self.leaf(SyntaxKind::CharacterLiteral, &cl.common_state, &cl.spelling);
+    }
+}
@@ -1,12 +1,12 @@
 //! expression asts
 
 use super::aggregates::{ArrayAggregate, RecordAggregate};
+use super::arena::Child;
 use super::declarations::Declaration;
 use super::parameters::ActualParameterSequence;
 use super::primitives::{CharacterLiteral, Identifier, IntegerLiteral, Operator};
-use super::typedenoters::TypeDenoter;
 use super::vnames::Vname;
-use super::{Ast, AstObject, AstVisitor, CommonState};
+use super::{Ast, AstVisitor, CommonState};
 use crate::scanner::SourcePosition;
 use std::fmt;
 
@@ -26,6 +26,24 @@ pub enum Expression {
 }
 
 impl Expression {
+    pub fn common_state(&self) -> &CommonState {
+        use Expression::*;
+
+        match *self {
+            ArrayExpression(ref state) => &state.common_state,
+            BinaryExpression(ref state) => &state.common_state,
+            CallExpression(ref state) => &state.common_state,
+            CharacterExpression(ref state) => &state.common_state,
+            EmptyExpression(ref state) => &state.common_state,
+            IfExpression(ref state) => &state.common_state,
+            IntegerExpression(ref state) => &state.common_state,
+            LetExpression(ref state) => &state.common_state,
+            RecordExpression(ref state) => &state.common_state,
+            UnaryExpression(ref state) => &state.common_state,
+            VnameExpression(ref state) => &state.common_state,
+        }
+    }
+
     pub fn get_array_expression(&self) -> Option<&ArrayExpressionState> {
         match *self {
             Expression::ArrayExpression(ref array) => Some(&array),
@@ -148,7 +166,7 @@ impl fmt::Display for Expression {
 }
 
 impl Ast for Expression {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         use Expression::*;
 
         match *self {
@@ -170,7 +188,6 @@ impl Ast for Expression {
 #[derive(Debug, Clone)]
 pub struct IntegerExpressionState {
     pub il: IntegerLiteral,
-    pub td: Option<Box<TypeDenoter>>,
     pub common_state: CommonState,
 }
 
@@ -178,7 +195,6 @@ impl IntegerExpressionState {
     pub fn new(il: IntegerLiteral) -> Self {
         IntegerExpressionState {
             il: il,
-            td: None,
             common_state: CommonState::default(),
         }
     }
@@ -205,7 +221,7 @@ impl fmt::Display for IntegerExpressionState {
 }
 
 impl Ast for IntegerExpressionState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_integer_expression(self, arg)
     }
 }
@@ -213,7 +229,6 @@ impl Ast for IntegerExpressionState {
 #[derive(Debug, Clone)]
 pub struct CharacterExpressionState {
     pub cl: CharacterLiteral,
-    pub td: Option<Box<TypeDenoter>>,
     pub common_state: CommonState,
 }
 
@@ -221,7 +236,6 @@ impl CharacterExpressionState {
     pub fn new(cl: CharacterLiteral) -> Self {
         CharacterExpressionState {
             cl: cl,
-            td: None,
             common_state: CommonState::default(),
         }
     }
@@ -248,7 +262,7 @@ impl fmt::Display for CharacterExpressionState {
 }
 
 impl Ast for CharacterExpressionState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_character_expression(self, arg)
     }
 }
@@ -287,23 +301,21 @@ impl fmt::Display for EmptyExpressionState {
 }
 
 impl Ast for EmptyExpressionState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_empty_expression(self, arg)
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct VnameExpressionState {
-    pub vname: Box<Vname>,
-    pub td: Option<Box<TypeDenoter>>,
+    pub vname: Child<Vname>,
     pub common_state: CommonState,
 }
 
 impl VnameExpressionState {
     pub fn new(vname: Vname) -> Self {
         VnameExpressionState {
-            vname: Box::new(vname),
-            td: None,
+            vname: Child::new(vname),
             common_state: CommonState::default(),
         }
     }
@@ -330,7 +342,7 @@ impl fmt::Display for VnameExpressionState {
 }
 
 impl Ast for VnameExpressionState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_vname_expression(self, arg)
     }
 }
@@ -338,8 +350,7 @@ impl Ast for VnameExpressionState {
 #[derive(Debug, Clone)]
 pub struct CallExpressionState {
     pub id: Identifier,
-    pub aps: Box<ActualParameterSequence>,
-    pub td: Option<Box<TypeDenoter>>,
+    pub aps: Child<ActualParameterSequence>,
     pub common_state: CommonState,
 }
 
@@ -347,8 +358,7 @@ impl CallExpressionState {
     pub fn new(id: Identifier, aps: ActualParameterSequence) -> Self {
         CallExpressionState {
             id: id,
-            aps: Box::new(aps),
-            td: None,
+            aps: Child::new(aps),
             common_state: CommonState::default(),
         }
     }
@@ -379,27 +389,25 @@ impl fmt::Display for CallExpressionState {
 }
 
 impl Ast for CallExpressionState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_call_expression(self, arg)
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct IfExpressionState {
-    pub expr1: Box<Expression>,
-    pub expr2: Box<Expression>,
-    pub expr3: Box<Expression>,
-    pub td: Option<Box<TypeDenoter>>,
+    pub expr1: Child<Expression>,
+    pub expr2: Child<Expression>,
+    pub expr3: Child<Expression>,
     pub common_state: CommonState,
 }
 
 impl IfExpressionState {
     pub fn new(expr1: Expression, expr2: Expression, expr3: Expression) -> Self {
         IfExpressionState {
-            expr1: Box::new(expr1),
-            expr2: Box::new(expr2),
-            expr3: Box::new(expr3),
-            td: None,
+            expr1: Child::new(expr1),
+            expr2: Child::new(expr2),
+            expr3: Child::new(expr3),
             common_state: CommonState::default(),
         }
     }
@@ -435,25 +443,23 @@ impl fmt::Display for IfExpressionState {
 }
 
 impl Ast for IfExpressionState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_if_expression(self, arg)
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct LetExpressionState {
-    pub decl: Box<Declaration>,
-    pub expr: Box<Expression>,
-    pub td: Option<Box<TypeDenoter>>,
+    pub decl: Child<Declaration>,
+    pub expr: Child<Expression>,
     pub common_state: CommonState,
 }
 
 impl LetExpressionState {
     pub fn new(decl: Declaration, expr: Expression) -> Self {
         LetExpressionState {
-            decl: Box::new(decl),
-            expr: Box::new(expr),
-            td: None,
+            decl: Child::new(decl),
+            expr: Child::new(expr),
             common_state: CommonState::default(),
         }
     }
@@ -484,7 +490,7 @@ impl fmt::Display for LetExpressionState {
 }
 
 impl Ast for LetExpressionState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_let_expression(self, arg)
     }
 }
@@ -492,8 +498,7 @@ impl Ast for LetExpressionState {
 #[derive(Debug, Clone)]
 pub struct UnaryExpressionState {
     pub op: Operator,
-    pub expr: Box<Expression>,
-    pub td: Option<Box<TypeDenoter>>,
+    pub expr: Child<Expression>,
     pub common_state: CommonState,
 }
 
@@ -501,8 +506,7 @@ impl UnaryExpressionState {
     pub fn new(op: Operator, expr: Expression) -> Self {
         UnaryExpressionState {
             op: op,
-            expr: Box::new(expr),
-            td: None,
+            expr: Child::new(expr),
             common_state: CommonState::default(),
         }
     }
@@ -529,27 +533,25 @@ impl fmt::Display for UnaryExpressionState {
 }
 
 impl Ast for UnaryExpressionState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_unary_expression(self, arg)
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct BinaryExpressionState {
-    pub expr1: Box<Expression>,
+    pub expr1: Child<Expression>,
     pub op: Operator,
-    pub expr2: Box<Expression>,
-    pub td: Option<Box<TypeDenoter>>,
+    pub expr2: Child<Expression>,
     pub common_state: CommonState,
 }
 
 impl BinaryExpressionState {
     pub fn new(expr1: Expression, op: Operator, expr2: Expression) -> Self {
         BinaryExpressionState {
-            expr1: Box::new(expr1),
+            expr1: Child::new(expr1),
             op: op,
-            expr2: Box::new(expr2),
-            td: None,
+            expr2: Child::new(expr2),
             common_state: CommonState::default(),
         }
     }
@@ -585,25 +587,23 @@ impl fmt::Display for BinaryExpressionState {
 }
 
 impl Ast for BinaryExpressionState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_binary_expression(self, arg)
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct ArrayExpressionState {
-    pub aa: Box<ArrayAggregate>,
+    pub aa: Child<ArrayAggregate>,
     pub elem_count: usize,
-    pub td: Option<Box<TypeDenoter>>,
     pub common_state: CommonState,
 }
 
 impl ArrayExpressionState {
     pub fn new(aa: ArrayAggregate) -> Self {
         ArrayExpressionState {
-            aa: Box::new(aa),
-            elem_count: 0,
-            td: None,
+            elem_count: aa.elem_count(),
+            aa: Child::new(aa),
             common_state: CommonState::default(),
         }
     }
@@ -630,23 +630,21 @@ impl fmt::Display for ArrayExpressionState {
 }
 
 impl Ast for ArrayExpressionState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_array_expression(self, arg)
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct RecordExpressionState {
-    pub ra: Box<RecordAggregate>,
-    pub td: Option<Box<TypeDenoter>>,
+    pub ra: Child<RecordAggregate>,
     pub common_state: CommonState,
 }
 
 impl RecordExpressionState {
     pub fn new(ra: RecordAggregate) -> Self {
         RecordExpressionState {
-            ra: Box::new(ra),
-            td: None,
+            ra: Child::new(ra),
             common_state: CommonState::default(),
         }
     }
@@ -673,7 +671,7 @@ impl fmt::Display for RecordExpressionState {
 }
 
 impl Ast for RecordExpressionState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_record_expression(self, arg)
     }
 }
@@ -0,0 +1,1279 @@
+//! Rewriting the Triangle Ast
+//!
+//! An AstFold takes the Ast apart and builds a new one out of it. Every method rebuilds its node
+//! by default, out of its children folded in turn, so a rewriting pass only implements the
+//! methods for the nodes that it changes. A node may be replaced with a different variant of the
+//! same kind - the method for an if command returns a Command, which need not be an if command.
+//!
+//! A rebuilt node keeps the position and the NodeId of the node that it was built from, so the
+//! decorations of the program still apply to the parts of it that were not replaced. A node that
+//! the fold makes afresh is numbered when the program is rebuilt.
+
+use super::aggregates::*;
+use super::arena::{Child, Tree};
+use super::commands::*;
+use super::declarations::*;
+use super::expressions::*;
+use super::parameters::*;
+use super::primitives::*;
+use super::typedenoters::*;
+use super::vnames::*;
+use super::Program;
+
+/// Rewriter for the Triangle Ast
+pub trait AstFold {
+    fn fold_program(&mut self, program: Program) -> Program {
+        walk_program(self, program)
+    }
+
+    fn fold_command(&mut self, cmd: Command) -> Command {
+        walk_command(self, cmd)
+    }
+
+    fn fold_assign_command(&mut self, cmd: AssignCommandState) -> Command {
+        walk_assign_command(self, cmd)
+    }
+
+    fn fold_call_command(&mut self, cmd: CallCommandState) -> Command {
+        walk_call_command(self, cmd)
+    }
+
+    fn fold_empty_command(&mut self, cmd: EmptyCommandState) -> Command {
+        Command::EmptyCommand(cmd)
+    }
+
+    fn fold_if_command(&mut self, cmd: IfCommandState) -> Command {
+        walk_if_command(self, cmd)
+    }
+
+    fn fold_let_command(&mut self, cmd: LetCommandState) -> Command {
+        walk_let_command(self, cmd)
+    }
+
+    fn fold_sequential_command(&mut self, cmd: SequentialCommandState) -> Command {
+        walk_sequential_command(self, cmd)
+    }
+
+    fn fold_while_command(&mut self, cmd: WhileCommandState) -> Command {
+        walk_while_command(self, cmd)
+    }
+
+    fn fold_expression(&mut self, expr: Expression) -> Expression {
+        walk_expression(self, expr)
+    }
+
+    fn fold_array_expression(&mut self, expr: ArrayExpressionState) -> Expression {
+        walk_array_expression(self, expr)
+    }
+
+    fn fold_binary_expression(&mut self, expr: BinaryExpressionState) -> Expression {
+        walk_binary_expression(self, expr)
+    }
+
+    fn fold_call_expression(&mut self, expr: CallExpressionState) -> Expression {
+        walk_call_expression(self, expr)
+    }
+
+    fn fold_character_expression(&mut self, expr: CharacterExpressionState) -> Expression {
+        walk_character_expression(self, expr)
+    }
+
+    fn fold_empty_expression(&mut self, expr: EmptyExpressionState) -> Expression {
+        Expression::EmptyExpression(expr)
+    }
+
+    fn fold_if_expression(&mut self, expr: IfExpressionState) -> Expression {
+        walk_if_expression(self, expr)
+    }
+
+    fn fold_integer_expression(&mut self, expr: IntegerExpressionState) -> Expression {
+        walk_integer_expression(self, expr)
+    }
+
+    fn fold_let_expression(&mut self, expr: LetExpressionState) -> Expression {
+        walk_let_expression(self, expr)
+    }
+
+    fn fold_record_expression(&mut self, expr: RecordExpressionState) -> Expression {
+        walk_record_expression(self, expr)
+    }
+
+    fn fold_unary_expression(&mut self, expr: UnaryExpressionState) -> Expression {
+        walk_unary_expression(self, expr)
+    }
+
+    fn fold_vname_expression(&mut self, expr: VnameExpressionState) -> Expression {
+        walk_vname_expression(self, expr)
+    }
+
+    fn fold_array_aggregate(&mut self, agg: ArrayAggregate) -> ArrayAggregate {
+        walk_array_aggregate(self, agg)
+    }
+
+    fn fold_single_array_aggregate(&mut self, agg: SingleArrayAggregateState) -> ArrayAggregate {
+        walk_single_array_aggregate(self, agg)
+    }
+
+    fn fold_multiple_array_aggregate(
+        &mut self,
+        agg: MultipleArrayAggregateState,
+    ) -> ArrayAggregate {
+        walk_multiple_array_aggregate(self, agg)
+    }
+
+    fn fold_record_aggregate(&mut self, agg: RecordAggregate) -> RecordAggregate {
+        walk_record_aggregate(self, agg)
+    }
+
+    fn fold_single_record_aggregate(&mut self, agg: SingleRecordAggregateState) -> RecordAggregate {
+        walk_single_record_aggregate(self, agg)
+    }
+
+    fn fold_multiple_record_aggregate(
+        &mut self,
+        agg: MultipleRecordAggregateState,
+    ) -> RecordAggregate {
+        walk_multiple_record_aggregate(self, agg)
+    }
+
+    fn fold_declaration(&mut self, decl: Declaration) -> Declaration {
+        walk_declaration(self, decl)
+    }
+
+    fn fold_binary_operator_declaration(
+        &mut self,
+        decl: BinaryOperatorDeclarationState,
+    ) -> Declaration {
+        walk_binary_operator_declaration(self, decl)
+    }
+
+    fn fold_const_declaration(&mut self, decl: ConstDeclarationState) -> Declaration {
+        walk_const_declaration(self, decl)
+    }
+
+    fn fold_func_declaration(&mut self, decl: FuncDeclarationState) -> Declaration {
+        walk_func_declaration(self, decl)
+    }
+
+    fn fold_proc_declaration(&mut self, decl: ProcDeclarationState) -> Declaration {
+        walk_proc_declaration(self, decl)
+    }
+
+    fn fold_sequential_declaration(&mut self, decl: SequentialDeclarationState) -> Declaration {
+        walk_sequential_declaration(self, decl)
+    }
+
+    fn fold_type_declaration(&mut self, decl: TypeDeclarationState) -> Declaration {
+        walk_type_declaration(self, decl)
+    }
+
+    fn fold_unary_operator_declaration(
+        &mut self,
+        decl: UnaryOperatorDeclarationState,
+    ) -> Declaration {
+        walk_unary_operator_declaration(self, decl)
+    }
+
+    fn fold_var_declaration(&mut self, decl: VarDeclarationState) -> Declaration {
+        walk_var_declaration(self, decl)
+    }
+
+    fn fold_type_denoter(&mut self, td: TypeDenoter) -> TypeDenoter {
+        walk_type_denoter(self, td)
+    }
+
+    fn fold_any_type_denoter(&mut self, td: AnyTypeDenoterState) -> TypeDenoter {
+        TypeDenoter::AnyTypeDenoter(td)
+    }
+
+    fn fold_array_type_denoter(&mut self, td: ArrayTypeDenoterState) -> TypeDenoter {
+        walk_array_type_denoter(self, td)
+    }
+
+    fn fold_bool_type_denoter(&mut self, td: BoolTypeDenoterState) -> TypeDenoter {
+        TypeDenoter::BoolTypeDenoter(td)
+    }
+
+    fn fold_char_type_denoter(&mut self, td: CharTypeDenoterState) -> TypeDenoter {
+        TypeDenoter::CharTypeDenoter(td)
+    }
+
+    fn fold_error_type_denoter(&mut self, td: ErrorTypeDenoterState) -> TypeDenoter {
+        TypeDenoter::ErrorTypeDenoter(td)
+    }
+
+    fn fold_int_type_denoter(&mut self, td: IntTypeDenoterState) -> TypeDenoter {
+        TypeDenoter::IntTypeDenoter(td)
+    }
+
+    fn fold_record_type_denoter(&mut self, td: RecordTypeDenoterState) -> TypeDenoter {
+        walk_record_type_denoter(self, td)
+    }
+
+    fn fold_simple_type_denoter(&mut self, td: SimpleTypeDenoterState) -> TypeDenoter {
+        walk_simple_type_denoter(self, td)
+    }
+
+    fn fold_field_type_denoter(&mut self, td: FieldTypeDenoter) -> FieldTypeDenoter {
+        walk_field_type_denoter(self, td)
+    }
+
+    fn fold_single_field_type_denoter(
+        &mut self,
+        td: SingleFieldTypeDenoterState,
+    ) -> FieldTypeDenoter {
+        walk_single_field_type_denoter(self, td)
+    }
+
+    fn fold_multiple_field_type_denoter(
+        &mut self,
+        td: MultipleFieldTypeDenoterState,
+    ) -> FieldTypeDenoter {
+        walk_multiple_field_type_denoter(self, td)
+    }
+
+    fn fold_formal_parameter_sequence(
+        &mut self,
+        fps: FormalParameterSequence,
+    ) -> FormalParameterSequence {
+        walk_formal_parameter_sequence(self, fps)
+    }
+
+    fn fold_empty_formal_parameter_sequence(
+        &mut self,
+        fps: EmptyFormalParameterSequenceState,
+    ) -> FormalParameterSequence {
+        FormalParameterSequence::EmptyFormalParameterSequence(fps)
+    }
+
+    fn fold_single_formal_parameter_sequence(
+        &mut self,
+        fps: SingleFormalParameterSequenceState,
+    ) -> FormalParameterSequence {
+        walk_single_formal_parameter_sequence(self, fps)
+    }
+
+    fn fold_multiple_formal_parameter_sequence(
+        &mut self,
+        fps: MultipleFormalParameterSequenceState,
+    ) -> FormalParameterSequence {
+        walk_multiple_formal_parameter_sequence(self, fps)
+    }
+
+    fn fold_formal_parameter(&mut self, fp: FormalParameter) -> FormalParameter {
+        walk_formal_parameter(self, fp)
+    }
+
+    fn fold_const_formal_parameter(&mut self, fp: ConstFormalParameterState) -> FormalParameter {
+        walk_const_formal_parameter(self, fp)
+    }
+
+    fn fold_func_formal_parameter(&mut self, fp: FuncFormalParameterState) -> FormalParameter {
+        walk_func_formal_parameter(self, fp)
+    }
+
+    fn fold_proc_formal_parameter(&mut self, fp: ProcFormalParameterState) -> FormalParameter {
+        walk_proc_formal_parameter(self, fp)
+    }
+
+    fn fold_var_formal_parameter(&mut self, fp: VarFormalParameterState) -> FormalParameter {
+        walk_var_formal_parameter(self, fp)
+    }
+
+    fn fold_actual_parameter_sequence(
+        &mut self,
+        aps: ActualParameterSequence,
+    ) -> ActualParameterSequence {
+        walk_actual_parameter_sequence(self, aps)
+    }
+
+    fn fold_empty_actual_parameter_sequence(
+        &mut self,
+        aps: EmptyActualParameterSequenceState,
+    ) -> ActualParameterSequence {
+        ActualParameterSequence::EmptyActualParameterSequence(aps)
+    }
+
+    fn fold_single_actual_parameter_sequence(
+        &mut self,
+        aps: SingleActualParameterSequenceState,
+    ) -> ActualParameterSequence {
+        walk_single_actual_parameter_sequence(self, aps)
+    }
+
+    fn fold_multiple_actual_parameter_sequence(
+        &mut self,
+        aps: MultipleActualParameterSequenceState,
+    ) -> ActualParameterSequence {
+        walk_multiple_actual_parameter_sequence(self, aps)
+    }
+
+    fn fold_actual_parameter(&mut self, ap: ActualParameter) -> ActualParameter {
+        walk_actual_parameter(self, ap)
+    }
+
+    fn fold_const_actual_parameter(&mut self, ap: ConstActualParameterState) -> ActualParameter {
+        walk_const_actual_parameter(self, ap)
+    }
+
+    fn fold_func_actual_parameter(&mut self, ap: FuncActualParameterState) -> ActualParameter {
+        walk_func_actual_parameter(self, ap)
+    }
+
+    fn fold_proc_actual_parameter(&mut self, ap: ProcActualParameterState) -> ActualParameter {
+        walk_proc_actual_parameter(self, ap)
+    }
+
+    fn fold_var_actual_parameter(&mut self, ap: VarActualParameterState) -> ActualParameter {
+        walk_var_actual_parameter(self, ap)
+    }
+
+    fn fold_vname(&mut self, vname: Vname) -> Vname {
+        walk_vname(self, vname)
+    }
+
+    fn fold_dot_vname(&mut self, vname: DotVnameState) -> Vname {
+        walk_dot_vname(self, vname)
+    }
+
+    fn fold_simple_vname(&mut self, vname: SimpleVnameState) -> Vname {
+        walk_simple_vname(self, vname)
+    }
+
+    fn fold_subscript_vname(&mut self, vname: SubscriptVnameState) -> Vname {
+        walk_subscript_vname(self, vname)
+    }
+
+    fn fold_identifier(&mut self, id: Identifier) -> Identifier {
+        id
+    }
+
+    fn fold_operator(&mut self, op: Operator) -> Operator {
+        op
+    }
+
+    fn fold_integer_literal(&mut self, il: IntegerLiteral) -> IntegerLiteral {
+        il
+    }
+
+    fn fold_character_literal(&mut self, cl: CharacterLiteral) -> CharacterLiteral {
+        cl
+    }
+}
+
+pub fn walk_program<F: AstFold + ?Sized>(folder: &mut F, program: Program) -> Program {
+    let Program {
+        mut cmd,
+        mut nodes,
+        decorations,
+        ids,
+        common_state,
+    } = program;
+    cmd.detach(&mut nodes);
+    let mut program = Program {
+        cmd: Child::new(folder.fold_command(cmd.into_inner())),
+        nodes,
+        decorations,
+        ids,
+        common_state,
+    };
+    program.cmd.adopt(&mut program.nodes, &mut program.ids);
+    program
+}
+
+pub fn walk_command<F: AstFold + ?Sized>(folder: &mut F, cmd: Command) -> Command {
+    match cmd {
+        Command::AssignCommand(state) => folder.fold_assign_command(state),
+        Command::CallCommand(state) => folder.fold_call_command(state),
+        Command::EmptyCommand(state) => folder.fold_empty_command(state),
+        Command::IfCommand(state) => folder.fold_if_command(state),
+        Command::LetCommand(state) => folder.fold_let_command(state),
+        Command::SequentialCommand(state) => folder.fold_sequential_command(state),
+        Command::WhileCommand(state) => folder.fold_while_command(state),
+    }
+}
+
+pub fn walk_assign_command<F: AstFold + ?Sized>(
+    folder: &mut F,
+    cmd: AssignCommandState,
+) -> Command {
+    let AssignCommandState {
+        vname,
+        expr,
+        common_state,
+    } = cmd;
+    Command::AssignCommand(AssignCommandState {
+        vname: Child::new(folder.fold_vname(vname.into_inner())),
+        expr: Child::new(folder.fold_expression(expr.into_inner())),
+        common_state,
+    })
+}
+
+pub fn walk_call_command<F: AstFold + ?Sized>(folder: &mut F, cmd: CallCommandState) -> Command {
+    let CallCommandState {
+        id,
+        aps,
+        common_state,
+    } = cmd;
+    Command::CallCommand(CallCommandState {
+        id: folder.fold_identifier(id),
+        aps: Child::new(folder.fold_actual_parameter_sequence(aps.into_inner())),
+        common_state,
+    })
+}
+
+pub fn walk_if_command<F: AstFold + ?Sized>(folder: &mut F, cmd: IfCommandState) -> Command {
+    let IfCommandState {
+        expr,
+        cmd1,
+        cmd2,
+        common_state,
+    } = cmd;
+    Command::IfCommand(IfCommandState {
+        expr: Child::new(folder.fold_expression(expr.into_inner())),
+        cmd1: Child::new(folder.fold_command(cmd1.into_inner())),
+        cmd2: Child::new(folder.fold_command(cmd2.into_inner())),
+        common_state,
+    })
+}
+
+pub fn walk_let_command<F: AstFold + ?Sized>(folder: &mut F, cmd: LetCommandState) -> Command {
+    let LetCommandState {
+        decl,
+        cmd,
+        common_state,
+    } = cmd;
+    Command::LetCommand(LetCommandState {
+        decl: Child::new(folder.fold_declaration(decl.into_inner())),
+        cmd: Child::new(folder.fold_command(cmd.into_inner())),
+        common_state,
+    })
+}
+
+pub fn walk_sequential_command<F: AstFold + ?Sized>(
+    folder: &mut F,
+    cmd: SequentialCommandState,
+) -> Command {
+    let SequentialCommandState {
+        cmd1,
+        cmd2,
+        common_state,
+    } = cmd;
+    Command::SequentialCommand(SequentialCommandState {
+        cmd1: Child::new(folder.fold_command(cmd1.into_inner())),
+        cmd2: Child::new(folder.fold_command(cmd2.into_inner())),
+        common_state,
+    })
+}
+
+pub fn walk_while_command<F: AstFold + ?Sized>(folder: &mut F, cmd: WhileCommandState) -> Command {
+    let WhileCommandState {
+        expr,
+        cmd,
+        common_state,
+    } = cmd;
+    Command::WhileCommand(WhileCommandState {
+        expr: Child::new(folder.fold_expression(expr.into_inner())),
+        cmd: Child::new(folder.fold_command(cmd.into_inner())),
+        common_state,
+    })
+}
+
+pub fn walk_expression<F: AstFold + ?Sized>(folder: &mut F, expr: Expression) -> Expression {
+    match expr {
+        Expression::ArrayExpression(state) => folder.fold_array_expression(state),
+        Expression::BinaryExpression(state) => folder.fold_binary_expression(state),
+        Expression::CallExpression(state) => folder.fold_call_expression(state),
+        Expression::CharacterExpression(state) => folder.fold_character_expression(state),
+        Expression::EmptyExpression(state) => folder.fold_empty_expression(state),
+        Expression::IfExpression(state) => folder.fold_if_expression(state),
+        Expression::IntegerExpression(state) => folder.fold_integer_expression(state),
+        Expression::LetExpression(state) => folder.fold_let_expression(state),
+        Expression::RecordExpression(state) => folder.fold_record_expression(state),
+        Expression::UnaryExpression(state) => folder.fold_unary_expression(state),
+        Expression::VnameExpression(state) => folder.fold_vname_expression(state),
+    }
+}
+
+pub fn walk_array_expression<F: AstFold + ?Sized>(
+    folder: &mut F,
+    expr: ArrayExpressionState,
+) -> Expression {
+    let ArrayExpressionState {
+        aa,
+        elem_count,
+        common_state,
+    } = expr;
+    Expression::ArrayExpression(ArrayExpressionState {
+        aa: Child::new(folder.fold_array_aggregate(aa.into_inner())),
+        elem_count,
+        common_state,
+    })
+}
+
+pub fn walk_binary_expression<F: AstFold + ?Sized>(
+    folder: &mut F,
+    expr: BinaryExpressionState,
+) -> Expression {
+    let BinaryExpressionState {
+        expr1,
+        op,
+        expr2,
+        common_state,
+    } = expr;
+    Expression::BinaryExpression(BinaryExpressionState {
+        expr1: Child::new(folder.fold_expression(expr1.into_inner())),
+        op: folder.fold_operator(op),
+        expr2: Child::new(folder.fold_expression(expr2.into_inner())),
+        common_state,
+    })
+}
+
+pub fn walk_call_expression<F: AstFold + ?Sized>(
+    folder: &mut F,
+    expr: CallExpressionState,
+) -> Expression {
+    let CallExpressionState {
+        id,
+        aps,
+        common_state,
+    } = expr;
+    Expression::CallExpression(CallExpressionState {
+        id: folder.fold_identifier(id),
+        aps: Child::new(folder.fold_actual_parameter_sequence(aps.into_inner())),
+        common_state,
+    })
+}
+
+pub fn walk_character_expression<F: AstFold + ?Sized>(
+    folder: &mut F,
+    expr: CharacterExpressionState,
+) -> Expression {
+    let CharacterExpressionState { cl, common_state } = expr;
+    Expression::CharacterExpression(CharacterExpressionState {
+        cl: folder.fold_character_literal(cl),
+        common_state,
+    })
+}
+
+pub fn walk_if_expression<F: AstFold + ?Sized>(
+    folder: &mut F,
+    expr: IfExpressionState,
+) -> Expression {
+    let IfExpressionState {
+        expr1,
+        expr2,
+        expr3,
+        common_state,
+    } = expr;
+    Expression::IfExpression(IfExpressionState {
+        expr1: Child::new(folder.fold_expression(expr1.into_inner())),
+        expr2: Child::new(folder.fold_expression(expr2.into_inner())),
+        expr3: Child::new(folder.fold_expression(expr3.into_inner())),
+        common_state,
+    })
+}
+
+pub fn walk_integer_expression<F: AstFold + ?Sized>(
+    folder: &mut F,
+    expr: IntegerExpressionState,
+) -> Expression {
+    let IntegerExpressionState { il, common_state } = expr;
+    Expression::IntegerExpression(IntegerExpressionState {
+        il: folder.fold_integer_literal(il),
+        common_state,
+    })
+}
+
+pub fn walk_let_expression<F: AstFold + ?Sized>(
+    folder: &mut F,
+    expr: LetExpressionState,
+) -> Expression {
+    let LetExpressionState {
+        decl,
+        expr,
+        common_state,
+    } = expr;
+    Expression::LetExpression(LetExpressionState {
+        decl: Child::new(folder.fold_declaration(decl.into_inner())),
+        expr: Child::new(folder.fold_expression(expr.into_inner())),
+        common_state,
+    })
+}
+
+pub fn walk_record_expression<F: AstFold + ?Sized>(
+    folder: &mut F,
+    expr: RecordExpressionState,
+) -> Expression {
+    let RecordExpressionState { ra, common_state } = expr;
+    Expression::RecordExpression(RecordExpressionState {
+        ra: Child::new(folder.fold_record_aggregate(ra.into_inner())),
+        common_state,
+    })
+}
+
+pub fn walk_unary_expression<F: AstFold + ?Sized>(
+    folder: &mut F,
+    expr: UnaryExpressionState,
+) -> Expression {
+    let UnaryExpressionState {
+        op,
+        expr,
+        common_state,
+    } = expr;
+    Expression::UnaryExpression(UnaryExpressionState {
+        op: folder.fold_operator(op),
+        expr: Child::new(folder.fold_expression(expr.into_inner())),
+        common_state,
+    })
+}
+
+pub fn walk_vname_expression<F: AstFold + ?Sized>(
+    folder: &mut F,
+    expr: VnameExpressionState,
+) -> Expression {
+    let VnameExpressionState {
+        vname,
+        common_state,
+    } = expr;
+    Expression::VnameExpression(VnameExpressionState {
+        vname: Child::new(folder.fold_vname(vname.into_inner())),
+        common_state,
+    })
+}
+
+pub fn walk_array_aggregate<F: AstFold + ?Sized>(
+    folder: &mut F,
+    agg: ArrayAggregate,
+) -> ArrayAggregate {
+    match agg {
+        ArrayAggregate::SingleArrayAggregate(state) => folder.fold_single_array_aggregate(state),
+        ArrayAggregate::MultipleArrayAggregate(state) => {
+            folder.fold_multiple_array_aggregate(state)
+        }
+    }
+}
+
+pub fn walk_single_array_aggregate<F: AstFold + ?Sized>(
+    folder: &mut F,
+    agg: SingleArrayAggregateState,
+) -> ArrayAggregate {
+    let SingleArrayAggregateState {
+        expr,
+        elem_count,
+        common_state,
+    } = agg;
+    ArrayAggregate::SingleArrayAggregate(SingleArrayAggregateState {
+        expr: Child::new(folder.fold_expression(expr.into_inner())),
+        elem_count,
+        common_state,
+    })
+}
+
+pub fn walk_multiple_array_aggregate<F: AstFold + ?Sized>(
+    folder: &mut F,
+    agg: MultipleArrayAggregateState,
+) -> ArrayAggregate {
+    let MultipleArrayAggregateState {
+        expr,
+        aa,
+        elem_count,
+        common_state,
+    } = agg;
+    ArrayAggregate::MultipleArrayAggregate(MultipleArrayAggregateState {
+        expr: Child::new(folder.fold_expression(expr.into_inner())),
+        aa: Child::new(folder.fold_array_aggregate(aa.into_inner())),
+        elem_count,
+        common_state,
+    })
+}
+
+pub fn walk_record_aggregate<F: AstFold + ?Sized>(
+    folder: &mut F,
+    agg: RecordAggregate,
+) -> RecordAggregate {
+    match agg {
+        RecordAggregate::SingleRecordAggregate(state) => folder.fold_single_record_aggregate(state),
+        RecordAggregate::MultipleRecordAggregate(state) => {
+            folder.fold_multiple_record_aggregate(state)
+        }
+    }
+}
+
+pub fn walk_single_record_aggregate<F: AstFold + ?Sized>(
+    folder: &mut F,
+    agg: SingleRecordAggregateState,
+) -> RecordAggregate {
+    let SingleRecordAggregateState {
+        id,
+        expr,
+        common_state,
+    } = agg;
+    RecordAggregate::SingleRecordAggregate(SingleRecordAggregateState {
+        id: folder.fold_identifier(id),
+        expr: Child::new(folder.fold_expression(expr.into_inner())),
+        common_state,
+    })
+}
+
+pub fn walk_multiple_record_aggregate<F: AstFold + ?Sized>(
+    folder: &mut F,
+    agg: MultipleRecordAggregateState,
+) -> RecordAggregate {
+    let MultipleRecordAggregateState {
+        id,
+        expr,
+        ra,
+        common_state,
+    } = agg;
+    RecordAggregate::MultipleRecordAggregate(MultipleRecordAggregateState {
+        id: folder.fold_identifier(id),
+        expr: Child::new(folder.fold_expression(expr.into_inner())),
+        ra: Child::new(folder.fold_record_aggregate(ra.into_inner())),
+        common_state,
+    })
+}
+
+pub fn walk_declaration<F: AstFold + ?Sized>(folder: &mut F, decl: Declaration) -> Declaration {
+    match decl {
+        Declaration::BinaryOperatorDeclaration(state) => {
+            folder.fold_binary_operator_declaration(state)
+        }
+        Declaration::ConstDeclaration(state) => folder.fold_const_declaration(state),
+        Declaration::FormalParameterDeclaration(fp) => {
+            Declaration::FormalParameterDeclaration(folder.fold_formal_parameter(fp))
+        }
+        Declaration::FuncDeclaration(state) => folder.fold_func_declaration(state),
+        Declaration::ProcDeclaration(state) => folder.fold_proc_declaration(state),
+        Declaration::SequentialDeclaration(state) => folder.fold_sequential_declaration(state),
+        Declaration::TypeDeclaration(state) => folder.fold_type_declaration(state),
+        Declaration::UnaryOperatorDeclaration(state) => {
+            folder.fold_unary_operator_declaration(state)
+        }
+        Declaration::VarDeclaration(state) => folder.fold_var_declaration(state),
+    }
+}
+
+pub fn walk_binary_operator_declaration<F: AstFold + ?Sized>(
+    folder: &mut F,
+    decl: BinaryOperatorDeclarationState,
+) -> Declaration {
+    let BinaryOperatorDeclarationState {
+        arg1_type,
+        op,
+        arg2_type,
+        res_type,
+        common_state,
+    } = decl;
+    Declaration::BinaryOperatorDeclaration(BinaryOperatorDeclarationState {
+        arg1_type: Box::new(folder.fold_type_denoter(*arg1_type)),
+        op: folder.fold_operator(op),
+        arg2_type: Box::new(folder.fold_type_denoter(*arg2_type)),
+        res_type: Box::new(folder.fold_type_denoter(*res_type)),
+        common_state,
+    })
+}
+
+pub fn walk_const_declaration<F: AstFold + ?Sized>(
+    folder: &mut F,
+    decl: ConstDeclarationState,
+) -> Declaration {
+    let ConstDeclarationState {
+        id,
+        expr,
+        common_state,
+    } = decl;
+    Declaration::ConstDeclaration(ConstDeclarationState {
+        id: folder.fold_identifier(id),
+        expr: Child::new(folder.fold_expression(expr.into_inner())),
+        common_state,
+    })
+}
+
+pub fn walk_func_declaration<F: AstFold + ?Sized>(
+    folder: &mut F,
+    decl: FuncDeclarationState,
+) -> Declaration {
+    let FuncDeclarationState {
+        id,
+        fps,
+        td,
+        expr,
+        common_state,
+    } = decl;
+    Declaration::FuncDeclaration(FuncDeclarationState {
+        id: folder.fold_identifier(id),
+        fps: Child::new(folder.fold_formal_parameter_sequence(fps.into_inner())),
+        td: Box::new(folder.fold_type_denoter(*td)),
+        expr: Child::new(folder.fold_expression(expr.into_inner())),
+        common_state,
+    })
+}
+
+pub fn walk_proc_declaration<F: AstFold + ?Sized>(
+    folder: &mut F,
+    decl: ProcDeclarationState,
+) -> Declaration {
+    let ProcDeclarationState {
+        id,
+        fps,
+        cmd,
+        common_state,
+    } = decl;
+    Declaration::ProcDeclaration(ProcDeclarationState {
+        id: folder.fold_identifier(id),
+        fps: Child::new(folder.fold_formal_parameter_sequence(fps.into_inner())),
+        cmd: Child::new(folder.fold_command(cmd.into_inner())),
+        common_state,
+    })
+}
+
+pub fn walk_sequential_declaration<F: AstFold + ?Sized>(
+    folder: &mut F,
+    decl: SequentialDeclarationState,
+) -> Declaration {
+    let SequentialDeclarationState {
+        decl1,
+        decl2,
+        common_state,
+    } = decl;
+    Declaration::SequentialDeclaration(SequentialDeclarationState {
+        decl1: Child::new(folder.fold_declaration(decl1.into_inner())),
+        decl2: Child::new(folder.fold_declaration(decl2.into_inner())),
+        common_state,
+    })
+}
+
+pub fn walk_type_declaration<F: AstFold + ?Sized>(
+    folder: &mut F,
+    decl: TypeDeclarationState,
+) -> Declaration {
+    let TypeDeclarationState {
+        id,
+        td,
+        common_state,
+    } = decl;
+    Declaration::TypeDeclaration(TypeDeclarationState {
+        id: folder.fold_identifier(id),
+        td: Box::new(folder.fold_type_denoter(*td)),
+        common_state,
+    })
+}
+
+pub fn walk_unary_operator_declaration<F: AstFold + ?Sized>(
+    folder: &mut F,
+    decl: UnaryOperatorDeclarationState,
+) -> Declaration {
+    let UnaryOperatorDeclarationState {
+        op,
+        argtype,
+        res_type,
+        common_state,
+    } = decl;
+    Declaration::UnaryOperatorDeclaration(UnaryOperatorDeclarationState {
+        op: folder.fold_operator(op),
+        argtype: Box::new(folder.fold_type_denoter(*argtype)),
+        res_type: Box::new(folder.fold_type_denoter(*res_type)),
+        common_state,
+    })
+}
+
+pub fn walk_var_declaration<F: AstFold + ?Sized>(
+    folder: &mut F,
+    decl: VarDeclarationState,
+) -> Declaration {
+    let VarDeclarationState {
+        id,
+        td,
+        common_state,
+    } = decl;
+    Declaration::VarDeclaration(VarDeclarationState {
+        id: folder.fold_identifier(id),
+        td: Box::new(folder.fold_type_denoter(*td)),
+        common_state,
+    })
+}
+
+pub fn walk_type_denoter<F: AstFold + ?Sized>(folder: &mut F, td: TypeDenoter) -> TypeDenoter {
+    match td {
+        TypeDenoter::AnyTypeDenoter(state) => folder.fold_any_type_denoter(state),
+        TypeDenoter::ArrayTypeDenoter(state) => folder.fold_array_type_denoter(state),
+        TypeDenoter::BoolTypeDenoter(state) => folder.fold_bool_type_denoter(state),
+        TypeDenoter::CharTypeDenoter(state) => folder.fold_char_type_denoter(state),
+        TypeDenoter::ErrorTypeDenoter(state) => folder.fold_error_type_denoter(state),
+        TypeDenoter::IntTypeDenoter(state) => folder.fold_int_type_denoter(state),
+        TypeDenoter::RecordTypeDenoter(state) => folder.fold_record_type_denoter(state),
+        TypeDenoter::SimpleTypeDenoter(state) => folder.fold_simple_type_denoter(state),
+    }
+}
+
+pub fn walk_array_type_denoter<F: AstFold + ?Sized>(
+    folder: &mut F,
+    td: ArrayTypeDenoterState,
+) -> TypeDenoter {
+    let ArrayTypeDenoterState {
+        il,
+        td,
+        common_state,
+    } = td;
+    TypeDenoter::ArrayTypeDenoter(ArrayTypeDenoterState {
+        il: folder.fold_integer_literal(il),
+        td: Box::new(folder.fold_type_denoter(*td)),
+        common_state,
+    })
+}
+
+pub fn walk_record_type_denoter<F: AstFold + ?Sized>(
+    folder: &mut F,
+    td: RecordTypeDenoterState,
+) -> TypeDenoter {
+    let RecordTypeDenoterState { ftd, common_state } = td;
+    TypeDenoter::RecordTypeDenoter(RecordTypeDenoterState {
+        ftd: Box::new(folder.fold_field_type_denoter(*ftd)),
+        common_state,
+    })
+}
+
+pub fn walk_simple_type_denoter<F: AstFold + ?Sized>(
+    folder: &mut F,
+    td: SimpleTypeDenoterState,
+) -> TypeDenoter {
+    let SimpleTypeDenoterState { id, common_state } = td;
+    TypeDenoter::SimpleTypeDenoter(SimpleTypeDenoterState {
+        id: folder.fold_identifier(id),
+        common_state,
+    })
+}
+
+pub fn walk_field_type_denoter<F: AstFold + ?Sized>(
+    folder: &mut F,
+    td: FieldTypeDenoter,
+) -> FieldTypeDenoter {
+    match td {
+        FieldTypeDenoter::SingleFieldTypeDenoter(state) => {
+            folder.fold_single_field_type_denoter(state)
+        }
+        FieldTypeDenoter::MultipleFieldTypeDenoter(state) => {
+            folder.fold_multiple_field_type_denoter(state)
+        }
+    }
+}
+
+pub fn walk_single_field_type_denoter<F: AstFold + ?Sized>(
+    folder: &mut F,
+    td: SingleFieldTypeDenoterState,
+) -> FieldTypeDenoter {
+    let SingleFieldTypeDenoterState {
+        id,
+        td,
+        common_state,
+    } = td;
+    FieldTypeDenoter::SingleFieldTypeDenoter(SingleFieldTypeDenoterState {
+        id: folder.fold_identifier(id),
+        td: Box::new(folder.fold_type_denoter(*td)),
+        common_state,
+    })
+}
+
+pub fn walk_multiple_field_type_denoter<F: AstFold + ?Sized>(
+    folder: &mut F,
+    td: MultipleFieldTypeDenoterState,
+) -> FieldTypeDenoter {
+    let MultipleFieldTypeDenoterState {
+        id,
+        td,
+        ftd,
+        common_state,
+    } = td;
+    FieldTypeDenoter::MultipleFieldTypeDenoter(MultipleFieldTypeDenoterState {
+        id: folder.fold_identifier(id),
+        td: Box::new(folder.fold_type_denoter(*td)),
+        ftd: Box::new(folder.fold_field_type_denoter(*ftd)),
+        common_state,
+    })
+}
+
+pub fn walk_formal_parameter_sequence<F: AstFold + ?Sized>(
+    folder: &mut F,
+    fps: FormalParameterSequence,
+) -> FormalParameterSequence {
+    match fps {
+        FormalParameterSequence::EmptyFormalParameterSequence(state) => {
+            folder.fold_empty_formal_parameter_sequence(state)
+        }
+        FormalParameterSequence::SingleFormalParameterSequence(state) => {
+            folder.fold_single_formal_parameter_sequence(state)
+        }
+        FormalParameterSequence::MultipleFormalParameterSequence(state) => {
+            folder.fold_multiple_formal_parameter_sequence(state)
+        }
+    }
+}
+
+pub fn walk_single_formal_parameter_sequence<F: AstFold + ?Sized>(
+    folder: &mut F,
+    fps: SingleFormalParameterSequenceState,
+) -> FormalParameterSequence {
+    let SingleFormalParameterSequenceState { fp, common_state } = fps;
+    FormalParameterSequence::SingleFormalParameterSequence(SingleFormalParameterSequenceState {
+        fp: Child::new(folder.fold_formal_parameter(fp.into_inner())),
+        common_state,
+    })
+}
+
+pub fn walk_multiple_formal_parameter_sequence<F: AstFold + ?Sized>(
+    folder: &mut F,
+    fps: MultipleFormalParameterSequenceState,
+) -> FormalParameterSequence {
+    let MultipleFormalParameterSequenceState {
+        fp,
+        fps,
+        common_state,
+    } = fps;
+    FormalParameterSequence::MultipleFormalParameterSequence(MultipleFormalParameterSequenceState {
+        fp: Child::new(folder.fold_formal_parameter(fp.into_inner())),
+        fps: Child::new(folder.fold_formal_parameter_sequence(fps.into_inner())),
+        common_state,
+    })
+}
+
+pub fn walk_formal_parameter<F: AstFold + ?Sized>(
+    folder: &mut F,
+    fp: FormalParameter,
+) -> FormalParameter {
+    match fp {
+        FormalParameter::ConstFormalParameter(state) => folder.fold_const_formal_parameter(state),
+        FormalParameter::FuncFormalParameter(state) => folder.fold_func_formal_parameter(state),
+        FormalParameter::ProcFormalParameter(state) => folder.fold_proc_formal_parameter(state),
+        FormalParameter::VarFormalParameter(state) => folder.fold_var_formal_parameter(state),
+    }
+}
+
+pub fn walk_const_formal_parameter<F: AstFold + ?Sized>(
+    folder: &mut F,
+    fp: ConstFormalParameterState,
+) -> FormalParameter {
+    let ConstFormalParameterState {
+        id,
+        td,
+        common_state,
+    } = fp;
+    FormalParameter::ConstFormalParameter(ConstFormalParameterState {
+        id: folder.fold_identifier(id),
+        td: Box::new(folder.fold_type_denoter(*td)),
+        common_state,
+    })
+}
+
+pub fn walk_func_formal_parameter<F: AstFold + ?Sized>(
+    folder: &mut F,
+    fp: FuncFormalParameterState,
+) -> FormalParameter {
+    let FuncFormalParameterState {
+        id,
+        fps,
+        td,
+        common_state,
+    } = fp;
+    FormalParameter::FuncFormalParameter(FuncFormalParameterState {
+        id: folder.fold_identifier(id),
+        fps: Child::new(folder.fold_formal_parameter_sequence(fps.into_inner())),
+        td: Box::new(folder.fold_type_denoter(*td)),
+        common_state,
+    })
+}
+
+pub fn walk_proc_formal_parameter<F: AstFold + ?Sized>(
+    folder: &mut F,
+    fp: ProcFormalParameterState,
+) -> FormalParameter {
+    let ProcFormalParameterState {
+        id,
+        fps,
+        common_state,
+    } = fp;
+    FormalParameter::ProcFormalParameter(ProcFormalParameterState {
+        id: folder.fold_identifier(id),
+        fps: Child::new(folder.fold_formal_parameter_sequence(fps.into_inner())),
+        common_state,
+    })
+}
+
+pub fn walk_var_formal_parameter<F: AstFold + ?Sized>(
+    folder: &mut F,
+    fp: VarFormalParameterState,
+) -> FormalParameter {
+    let VarFormalParameterState {
+        id,
+        td,
+        common_state,
+    } = fp;
+    FormalParameter::VarFormalParameter(VarFormalParameterState {
+        id: folder.fold_identifier(id),
+        td: Box::new(folder.fold_type_denoter(*td)),
+        common_state,
+    })
+}
+
+pub fn walk_actual_parameter_sequence<F: AstFold + ?Sized>(
+    folder: &mut F,
+    aps: ActualParameterSequence,
+) -> ActualParameterSequence {
+    match aps {
+        ActualParameterSequence::EmptyActualParameterSequence(state) => {
+            folder.fold_empty_actual_parameter_sequence(state)
+        }
+        ActualParameterSequence::SingleActualParameterSequence(state) => {
+            folder.fold_single_actual_parameter_sequence(state)
+        }
+        ActualParameterSequence::MultipleActualParameterSequence(state) => {
+            folder.fold_multiple_actual_parameter_sequence(state)
+        }
+    }
+}
+
+pub fn walk_single_actual_parameter_sequence<F: AstFold + ?Sized>(
+    folder: &mut F,
+    aps: SingleActualParameterSequenceState,
+) -> ActualParameterSequence {
+    let SingleActualParameterSequenceState { ap, common_state } = aps;
+    ActualParameterSequence::SingleActualParameterSequence(SingleActualParameterSequenceState {
+        ap: Child::new(folder.fold_actual_parameter(ap.into_inner())),
+        common_state,
+    })
+}
+
+pub fn walk_multiple_actual_parameter_sequence<F: AstFold + ?Sized>(
+    folder: &mut F,
+    aps: MultipleActualParameterSequenceState,
+) -> ActualParameterSequence {
+    let MultipleActualParameterSequenceState {
+        ap,
+        aps,
+        common_state,
+    } = aps;
+    ActualParameterSequence::MultipleActualParameterSequence(MultipleActualParameterSequenceState {
+        ap: Child::new(folder.fold_actual_parameter(ap.into_inner())),
+        aps: Child::new(folder.fold_actual_parameter_sequence(aps.into_inner())),
+        common_state,
+    })
+}
+
+pub fn walk_actual_parameter<F: AstFold + ?Sized>(
+    folder: &mut F,
+    ap: ActualParameter,
+) -> ActualParameter {
+    match ap {
+        ActualParameter::ConstActualParameter(state) => folder.fold_const_actual_parameter(state),
+        ActualParameter::FuncActualParameter(state) => folder.fold_func_actual_parameter(state),
+        ActualParameter::ProcActualParameter(state) => folder.fold_proc_actual_parameter(state),
+        ActualParameter::VarActualParameter(state) => folder.fold_var_actual_parameter(state),
+    }
+}
+
+pub fn walk_const_actual_parameter<F: AstFold + ?Sized>(
+    folder: &mut F,
+    ap: ConstActualParameterState,
+) -> ActualParameter {
+    let ConstActualParameterState { expr, common_state } = ap;
+    ActualParameter::ConstActualParameter(ConstActualParameterState {
+        expr: Child::new(folder.fold_expression(expr.into_inner())),
+        common_state,
+    })
+}
+
+pub fn walk_func_actual_parameter<F: AstFold + ?Sized>(
+    folder: &mut F,
+    ap: FuncActualParameterState,
+) -> ActualParameter {
+    let FuncActualParameterState { id, common_state } = ap;
+    ActualParameter::FuncActualParameter(FuncActualParameterState {
+        id: folder.fold_identifier(id),
+        common_state,
+    })
+}
+
+pub fn walk_proc_actual_parameter<F: AstFold + ?Sized>(
+    folder: &mut F,
+    ap: ProcActualParameterState,
+) -> ActualParameter {
+    let ProcActualParameterState { id, common_state } = ap;
+    ActualParameter::ProcActualParameter(ProcActualParameterState {
+        id: folder.fold_identifier(id),
+        common_state,
+    })
+}
+
+pub fn walk_var_actual_parameter<F: AstFold + ?Sized>(
+    folder: &mut F,
+    ap: VarActualParameterState,
+) -> ActualParameter {
+    let VarActualParameterState {
+        vname,
+        common_state,
+    } = ap;
+    ActualParameter::VarActualParameter(VarActualParameterState {
+        vname: Child::new(folder.fold_vname(vname.into_inner())),
+        common_state,
+    })
+}
+
+pub fn walk_vname<F: AstFold + ?Sized>(folder: &mut F, vname: Vname) -> Vname {
+    match vname {
+        Vname::DotVname(state) => folder.fold_dot_vname(state),
+        Vname::SimpleVname(state) => folder.fold_simple_vname(state),
+        Vname::SubscriptVname(state) => folder.fold_subscript_vname(state),
+    }
+}
+
+pub fn walk_dot_vname<F: AstFold + ?Sized>(folder: &mut F, vname: DotVnameState) -> Vname {
+    let DotVnameState {
+        vname,
+        id,
+        offset,
+        indexed,
+        common_state,
+    } = vname;
+    Vname::DotVname(DotVnameState {
+        vname: Child::new(folder.fold_vname(vname.into_inner())),
+        id: folder.fold_identifier(id),
+        offset,
+        indexed,
+        common_state,
+    })
+}
+
+pub fn walk_simple_vname<F: AstFold + ?Sized>(folder: &mut F, vname: SimpleVnameState) -> Vname {
+    let SimpleVnameState {
+        id,
+        offset,
+        indexed,
+        common_state,
+    } = vname;
+    Vname::SimpleVname(SimpleVnameState {
+        id: folder.fold_identifier(id),
+        offset,
+        indexed,
+        common_state,
+    })
+}
+
+pub fn walk_subscript_vname<F: AstFold + ?Sized>(
+    folder: &mut F,
+    vname: SubscriptVnameState,
+) -> Vname {
+    let SubscriptVnameState {
+        vname,
+        expr,
+        offset,
+        indexed,
+        common_state,
+    } = vname;
+    Vname::SubscriptVname(SubscriptVnameState {
+        vname: Child::new(folder.fold_vname(vname.into_inner())),
+        expr: Child::new(folder.fold_expression(expr.into_inner())),
+        offset,
+        indexed,
+        common_state,
+    })
+}
@@ -4,388 +4,686 @@
 //! This Ast is produced in the form of a hierarchical tree by the Parser, decorated by the
 //! Checker, and ultimately used by the Encoder to generate binary code for the TAM (Triangle
 //! Abstract Machine).
+//!
+//! Every node has a NodeId of its own, and the nodes of a program are stored in its arena at the
+//! slot of their NodeId, with each parent holding the NodeIds of its children. The decorations of
+//! the tree - the declaration that each identifier refers to, the type of each expression, and the
+//! runtime entity of each declaration - are not stored in the nodes, but in the side tables of the
+//! program's `Decorations`, keyed by NodeId.
 
 use crate::scanner::SourcePosition;
 
 use std::default;
 use std::fmt;
+use std::mem;
 
 pub mod aggregates;
+pub mod arena;
 pub mod commands;
 pub mod declarations;
+pub mod decorations;
+pub mod dump;
 pub mod expressions;
+pub mod fold;
 pub mod parameters;
 pub mod primitives;
+pub mod query;
 pub mod runtime_entities;
 pub mod typedenoters;
+pub mod visit;
+pub mod visit_ref;
 pub mod vnames;
 
 use aggregates::*;
+use arena::{Child, Nodes, Tree};
 use commands::*;
 use declarations::*;
+use decorations::Decorations;
 use expressions::*;
 use parameters::*;
 use primitives::*;
-use runtime_entities::RuntimeEntity;
 use typedenoters::*;
 use vnames::*;
 
 /// Any entity that wants to be traversable needs to implement this trait
 pub trait Ast {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject;
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output;
 }
 
-/// The AstVisitor visitor will be used by the parser, checker, and encoder for different
-/// processing of the Ast, returning different values at each phase, or even within the
-/// same phase.
-/// This object enumerates all the possible variants that can be returned by a visitor method.
-#[derive(Debug, Clone)]
-pub enum AstObject {
-    Null,
-    Frame(Frame),
-    Size(usize),
-    Declaration(Box<Declaration>),
-    FormalParameterSequence(Box<FormalParameterSequence>),
-    FormalParameter(Box<FormalParameter>),
-    TypeDenoter(Box<TypeDenoter>),
-}
+/// Visitor for the Triangle Ast - both the checker and the encoder (code generator)
+/// make use of this visitor to traverse the parsed and checked asts respectively. Each visitor
+/// chooses what its visits are given and what they return - the Checker works out types, and
+/// the Encoder works out sizes.
+///
+/// Every method walks the children of its node by default (see the `visit` module), so a new
+/// pass only needs to implement the methods for the nodes that it cares about.
+pub trait AstVisitor {
+    /// what each visit is given by its parent
+    type Arg: Clone;
 
-impl AstObject {
-    pub fn get_declaration(&self) -> Option<&Box<Declaration>> {
-        match *self {
-            AstObject::Declaration(ref decl) => Some(&decl),
-            _ => None,
-        }
+    /// what each visit hands back to its parent
+    type Output: Default;
+
+    /// the arena that the stored nodes being visited are taken from, and put back into
+    fn nodes(&mut self) -> &mut Nodes;
+
+    fn visit_program(&mut self, program: &mut Program, arg: Self::Arg) -> Self::Output {
+        visit::walk_program(self, program, arg)
     }
 
-    pub fn get_formal_parameter_sequence(&self) -> Option<&Box<FormalParameterSequence>> {
-        match *self {
-            AstObject::FormalParameterSequence(ref fps) => Some(&fps),
-            _ => None,
-        }
+    fn visit_empty_command(
+        &mut self,
+        _cmd: &mut EmptyCommandState,
+        _arg: Self::Arg,
+    ) -> Self::Output {
+        Self::Output::default()
     }
 
-    pub fn get_formal_parameter(&self) -> Option<&Box<FormalParameter>> {
-        match *self {
-            AstObject::FormalParameter(ref fp) => Some(&fp),
-            _ => None,
-        }
+    fn visit_assign_command(
+        &mut self,
+        cmd: &mut AssignCommandState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_assign_command(self, cmd, arg)
     }
 
-    pub fn get_type_denoter(&self) -> Option<&Box<TypeDenoter>> {
-        match *self {
-            AstObject::TypeDenoter(ref td) => Some(&td),
-            _ => None,
-        }
+    fn visit_call_command(&mut self, cmd: &mut CallCommandState, arg: Self::Arg) -> Self::Output {
+        visit::walk_call_command(self, cmd, arg)
+    }
+
+    fn visit_let_command(&mut self, cmd: &mut LetCommandState, arg: Self::Arg) -> Self::Output {
+        visit::walk_let_command(self, cmd, arg)
+    }
+
+    fn visit_if_command(&mut self, cmd: &mut IfCommandState, arg: Self::Arg) -> Self::Output {
+        visit::walk_if_command(self, cmd, arg)
+    }
+
+    fn visit_while_command(&mut self, cmd: &mut WhileCommandState, arg: Self::Arg) -> Self::Output {
+        visit::walk_while_command(self, cmd, arg)
     }
-}
 
-/// Visitor for the Triangle Ast - both the checker and the encoder (code generator)
-/// make use of this visitor to traverse the parsed and checked asts respectively.
-pub trait AstVisitor {
-    fn visit_program(&mut self, program: &mut Program, arg: AstObject) -> AstObject;
-    fn visit_empty_command(&mut self, cmd: &mut EmptyCommandState, arg: AstObject) -> AstObject;
-    fn visit_assign_command(&mut self, cmd: &mut AssignCommandState, arg: AstObject) -> AstObject;
-    fn visit_call_command(&mut self, cmd: &mut CallCommandState, arg: AstObject) -> AstObject;
-    fn visit_let_command(&mut self, cmd: &mut LetCommandState, arg: AstObject) -> AstObject;
-    fn visit_if_command(&mut self, cmd: &mut IfCommandState, arg: AstObject) -> AstObject;
-    fn visit_while_command(&mut self, cmd: &mut WhileCommandState, arg: AstObject) -> AstObject;
     fn visit_sequential_command(
         &mut self,
         cmd: &mut SequentialCommandState,
-        arg: AstObject,
-    ) -> AstObject;
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_sequential_command(self, cmd, arg)
+    }
 
     fn visit_empty_expression(
         &mut self,
-        expr: &mut EmptyExpressionState,
-        arg: AstObject,
-    ) -> AstObject;
+        _expr: &mut EmptyExpressionState,
+        _arg: Self::Arg,
+    ) -> Self::Output {
+        Self::Output::default()
+    }
+
     fn visit_integer_expression(
         &mut self,
         expr: &mut IntegerExpressionState,
-        arg: AstObject,
-    ) -> AstObject;
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_integer_expression(self, expr, arg)
+    }
+
     fn visit_character_expression(
         &mut self,
         expr: &mut CharacterExpressionState,
-        arg: AstObject,
-    ) -> AstObject;
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_character_expression(self, expr, arg)
+    }
+
     fn visit_vname_expression(
         &mut self,
         expr: &mut VnameExpressionState,
-        arg: AstObject,
-    ) -> AstObject;
-    fn visit_let_expression(&mut self, expr: &mut LetExpressionState, arg: AstObject) -> AstObject;
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_vname_expression(self, expr, arg)
+    }
+
+    fn visit_let_expression(
+        &mut self,
+        expr: &mut LetExpressionState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_let_expression(self, expr, arg)
+    }
+
     fn visit_call_expression(
         &mut self,
         expr: &mut CallExpressionState,
-        arg: AstObject,
-    ) -> AstObject;
-    fn visit_if_expression(&mut self, expr: &mut IfExpressionState, arg: AstObject) -> AstObject;
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_call_expression(self, expr, arg)
+    }
+
+    fn visit_if_expression(
+        &mut self,
+        expr: &mut IfExpressionState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_if_expression(self, expr, arg)
+    }
+
     fn visit_unary_expression(
         &mut self,
         expr: &mut UnaryExpressionState,
-        arg: AstObject,
-    ) -> AstObject;
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_unary_expression(self, expr, arg)
+    }
+
     fn visit_binary_expression(
         &mut self,
         expr: &mut BinaryExpressionState,
-        arg: AstObject,
-    ) -> AstObject;
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_binary_expression(self, expr, arg)
+    }
+
     fn visit_array_expression(
         &mut self,
         expr: &mut ArrayExpressionState,
-        arg: AstObject,
-    ) -> AstObject;
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_array_expression(self, expr, arg)
+    }
+
     fn visit_record_expression(
         &mut self,
         expr: &mut RecordExpressionState,
-        arg: AstObject,
-    ) -> AstObject;
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_record_expression(self, expr, arg)
+    }
+
     fn visit_single_array_aggregate(
         &mut self,
         agg: &mut SingleArrayAggregateState,
-        arg: AstObject,
-    ) -> AstObject;
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_single_array_aggregate(self, agg, arg)
+    }
+
     fn visit_multiple_array_aggregate(
         &mut self,
         agg: &mut MultipleArrayAggregateState,
-        arg: AstObject,
-    ) -> AstObject;
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_multiple_array_aggregate(self, agg, arg)
+    }
+
     fn visit_single_record_aggregate(
         &mut self,
         agg: &mut SingleRecordAggregateState,
-        arg: AstObject,
-    ) -> AstObject;
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_single_record_aggregate(self, agg, arg)
+    }
+
     fn visit_multiple_record_aggregate(
         &mut self,
         agg: &mut MultipleRecordAggregateState,
-        arg: AstObject,
-    ) -> AstObject;
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_multiple_record_aggregate(self, agg, arg)
+    }
+
     fn visit_const_declaration(
         &mut self,
         decl: &mut ConstDeclarationState,
-        arg: AstObject,
-    ) -> AstObject;
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_const_declaration(self, decl, arg)
+    }
+
     fn visit_var_declaration(
         &mut self,
         decl: &mut VarDeclarationState,
-        arg: AstObject,
-    ) -> AstObject;
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_var_declaration(self, decl, arg)
+    }
+
     fn visit_proc_declaration(
         &mut self,
         decl: &mut ProcDeclarationState,
-        arg: AstObject,
-    ) -> AstObject;
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_proc_declaration(self, decl, arg)
+    }
+
     fn visit_func_declaration(
         &mut self,
         decl: &mut FuncDeclarationState,
-        arg: AstObject,
-    ) -> AstObject;
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_func_declaration(self, decl, arg)
+    }
+
     fn visit_type_declaration(
         &mut self,
         decl: &mut TypeDeclarationState,
-        arg: AstObject,
-    ) -> AstObject;
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_type_declaration(self, decl, arg)
+    }
+
     fn visit_unary_operator_declaration(
         &mut self,
         decl: &mut UnaryOperatorDeclarationState,
-        arg: AstObject,
-    ) -> AstObject;
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_unary_operator_declaration(self, decl, arg)
+    }
+
     fn visit_binary_operator_declaration(
         &mut self,
         decl: &mut BinaryOperatorDeclarationState,
-        arg: AstObject,
-    ) -> AstObject;
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_binary_operator_declaration(self, decl, arg)
+    }
+
     fn visit_sequential_declaration(
         &mut self,
         decl: &mut SequentialDeclarationState,
-        arg: AstObject,
-    ) -> AstObject;
-    fn visit_any_type_denoter(&mut self, td: &mut AnyTypeDenoterState, arg: AstObject)
-        -> AstObject;
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_sequential_declaration(self, decl, arg)
+    }
+
+    fn visit_any_type_denoter(
+        &mut self,
+        _td: &mut AnyTypeDenoterState,
+        _arg: Self::Arg,
+    ) -> Self::Output {
+        Self::Output::default()
+    }
+
     fn visit_error_type_denoter(
         &mut self,
-        td: &mut ErrorTypeDenoterState,
-        arg: AstObject,
-    ) -> AstObject;
+        _td: &mut ErrorTypeDenoterState,
+        _arg: Self::Arg,
+    ) -> Self::Output {
+        Self::Output::default()
+    }
+
     fn visit_bool_type_denoter(
         &mut self,
-        td: &mut BoolTypeDenoterState,
-        arg: AstObject,
-    ) -> AstObject;
+        _td: &mut BoolTypeDenoterState,
+        _arg: Self::Arg,
+    ) -> Self::Output {
+        Self::Output::default()
+    }
+
     fn visit_char_type_denoter(
         &mut self,
-        td: &mut CharTypeDenoterState,
-        arg: AstObject,
-    ) -> AstObject;
-    fn visit_int_type_denoter(&mut self, td: &mut IntTypeDenoterState, arg: AstObject)
-        -> AstObject;
+        _td: &mut CharTypeDenoterState,
+        _arg: Self::Arg,
+    ) -> Self::Output {
+        Self::Output::default()
+    }
+
+    fn visit_int_type_denoter(
+        &mut self,
+        _td: &mut IntTypeDenoterState,
+        _arg: Self::Arg,
+    ) -> Self::Output {
+        Self::Output::default()
+    }
+
     fn visit_array_type_denoter(
         &mut self,
         td: &mut ArrayTypeDenoterState,
-        arg: AstObject,
-    ) -> AstObject;
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_array_type_denoter(self, td, arg)
+    }
+
     fn visit_simple_type_denoter(
         &mut self,
         td: &mut SimpleTypeDenoterState,
-        arg: AstObject,
-    ) -> AstObject;
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_simple_type_denoter(self, td, arg)
+    }
+
     fn visit_single_field_type_denoter(
         &mut self,
         td: &mut SingleFieldTypeDenoterState,
-        arg: AstObject,
-    ) -> AstObject;
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_single_field_type_denoter(self, td, arg)
+    }
+
     fn visit_multiple_field_type_denoter(
         &mut self,
         td: &mut MultipleFieldTypeDenoterState,
-        arg: AstObject,
-    ) -> AstObject;
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_multiple_field_type_denoter(self, td, arg)
+    }
+
     fn visit_record_type_denoter(
         &mut self,
         td: &mut RecordTypeDenoterState,
-        arg: AstObject,
-    ) -> AstObject;
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_record_type_denoter(self, td, arg)
+    }
+
     fn visit_empty_formal_parameter_sequence(
         &mut self,
-        fps: &mut EmptyFormalParameterSequenceState,
-        arg: AstObject,
-    ) -> AstObject;
+        _fps: &mut EmptyFormalParameterSequenceState,
+        _arg: Self::Arg,
+    ) -> Self::Output {
+        Self::Output::default()
+    }
+
     fn visit_single_formal_parameter_sequence(
         &mut self,
         fps: &mut SingleFormalParameterSequenceState,
-        arg: AstObject,
-    ) -> AstObject;
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_single_formal_parameter_sequence(self, fps, arg)
+    }
+
     fn visit_multiple_formal_parameter_sequence(
         &mut self,
         fps: &mut MultipleFormalParameterSequenceState,
-        arg: AstObject,
-    ) -> AstObject;
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_multiple_formal_parameter_sequence(self, fps, arg)
+    }
+
     fn visit_const_formal_parameter(
         &mut self,
         fp: &mut ConstFormalParameterState,
-        arg: AstObject,
-    ) -> AstObject;
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_const_formal_parameter(self, fp, arg)
+    }
+
     fn visit_var_formal_parameter(
         &mut self,
         fp: &mut VarFormalParameterState,
-        arg: AstObject,
-    ) -> AstObject;
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_var_formal_parameter(self, fp, arg)
+    }
+
     fn visit_proc_formal_parameter(
         &mut self,
         fp: &mut ProcFormalParameterState,
-        arg: AstObject,
-    ) -> AstObject;
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_proc_formal_parameter(self, fp, arg)
+    }
+
     fn visit_func_formal_parameter(
         &mut self,
         fp: &mut FuncFormalParameterState,
-        arg: AstObject,
-    ) -> AstObject;
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_func_formal_parameter(self, fp, arg)
+    }
+
     fn visit_empty_actual_parameter_sequence(
         &mut self,
-        aps: &mut EmptyActualParameterSequenceState,
-        arg: AstObject,
-    ) -> AstObject;
+        _aps: &mut EmptyActualParameterSequenceState,
+        _arg: Self::Arg,
+    ) -> Self::Output {
+        Self::Output::default()
+    }
+
     fn visit_single_actual_parameter_sequence(
         &mut self,
         aps: &mut SingleActualParameterSequenceState,
-        arg: AstObject,
-    ) -> AstObject;
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_single_actual_parameter_sequence(self, aps, arg)
+    }
+
     fn visit_multiple_actual_parameter_sequence(
         &mut self,
         aps: &mut MultipleActualParameterSequenceState,
-        arg: AstObject,
-    ) -> AstObject;
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_multiple_actual_parameter_sequence(self, aps, arg)
+    }
+
     fn visit_const_actual_parameter(
         &mut self,
         ap: &mut ConstActualParameterState,
-        arg: AstObject,
-    ) -> AstObject;
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_const_actual_parameter(self, ap, arg)
+    }
+
     fn visit_var_actual_parameter(
         &mut self,
         ap: &mut VarActualParameterState,
-        arg: AstObject,
-    ) -> AstObject;
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_var_actual_parameter(self, ap, arg)
+    }
+
     fn visit_proc_actual_parameter(
         &mut self,
         ap: &mut ProcActualParameterState,
-        arg: AstObject,
-    ) -> AstObject;
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_proc_actual_parameter(self, ap, arg)
+    }
+
     fn visit_func_actual_parameter(
         &mut self,
         ap: &mut FuncActualParameterState,
-        arg: AstObject,
-    ) -> AstObject;
-    fn visit_simple_vname(&mut self, vname: &mut SimpleVnameState, arg: AstObject) -> AstObject;
-    fn visit_dot_vname(&mut self, vname: &mut DotVnameState, arg: AstObject) -> AstObject;
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_func_actual_parameter(self, ap, arg)
+    }
+
+    fn visit_simple_vname(&mut self, vname: &mut SimpleVnameState, arg: Self::Arg) -> Self::Output {
+        visit::walk_simple_vname(self, vname, arg)
+    }
+
+    fn visit_dot_vname(&mut self, vname: &mut DotVnameState, arg: Self::Arg) -> Self::Output {
+        visit::walk_dot_vname(self, vname, arg)
+    }
+
     fn visit_subscript_vname(
         &mut self,
         vname: &mut SubscriptVnameState,
-        arg: AstObject,
-    ) -> AstObject;
-    fn visit_identifier(&mut self, id: &mut Identifier, arg: AstObject) -> AstObject;
-    fn visit_integer_literal(&mut self, il: &mut IntegerLiteral, arg: AstObject) -> AstObject;
-    fn visit_character_literal(&mut self, cl: &mut CharacterLiteral, arg: AstObject) -> AstObject;
-    fn visit_operator(&mut self, op: &mut Operator, arg: AstObject) -> AstObject;
+        arg: Self::Arg,
+    ) -> Self::Output {
+        visit::walk_subscript_vname(self, vname, arg)
+    }
+
+    fn visit_identifier(&mut self, _id: &mut Identifier, _arg: Self::Arg) -> Self::Output {
+        Self::Output::default()
+    }
+
+    fn visit_integer_literal(&mut self, _il: &mut IntegerLiteral, _arg: Self::Arg) -> Self::Output {
+        Self::Output::default()
+    }
+
+    fn visit_character_literal(
+        &mut self,
+        _cl: &mut CharacterLiteral,
+        _arg: Self::Arg,
+    ) -> Self::Output {
+        Self::Output::default()
+    }
+
+    fn visit_operator(&mut self, _op: &mut Operator, _arg: Self::Arg) -> Self::Output {
+        Self::Output::default()
+    }
 }
 
 /// A frame represents the runtime state of execution of a function
-#[derive(Debug, Clone)]
+#[derive(Debug, Copy, Clone)]
 pub struct Frame {
     pub level: usize,
     pub size: usize,
 }
 
+impl Frame {
+    pub fn new(level: usize, size: usize) -> Self {
+        Frame { level, size }
+    }
+
+    /// a frame at the same level, expanded by `size` words
+    pub fn expand(&self, size: usize) -> Self {
+        Frame::new(self.level, self.size + size)
+    }
+}
+
 /// The core AST types representing the entities
 /// of the Triangle language.
 
+/// The identity of a node. Every node of a program is given a NodeId of its own when the program
+/// is created, which is also the slot of the program's arena that the node is stored in. A clone
+/// of a node stands for the same node, and so has the same NodeId.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(u32);
+
+impl NodeId {
+    /// the NodeId of a node that has not been numbered yet
+    const UNASSIGNED: NodeId = NodeId(u32::MAX);
+
+    pub fn index(&self) -> u32 {
+        self.0
+    }
+
+    /// whether the node has been numbered
+    pub fn is_assigned(&self) -> bool {
+        *self != NodeId::UNASSIGNED
+    }
+}
+
+impl fmt::Display for NodeId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// The NodeIds that a tree is numbered with. The nodes of a program are numbered from 0, and
+/// those of the standard environment from the upper half of the range, so that the declarations
+/// of the environment never have the NodeId of a node of a program that is checked in it.
+#[derive(Debug, Clone)]
+pub struct NodeIds {
+    next: u32,
+    end: u32,
+}
+
+impl NodeIds {
+    const ENVIRONMENT: u32 = 1 << 31;
+
+    /// the NodeIds of the nodes of a program
+    pub fn new() -> Self {
+        NodeIds {
+            next: 0,
+            end: NodeIds::ENVIRONMENT,
+        }
+    }
+
+    /// the NodeIds of the nodes of a standard environment
+    pub fn environment() -> Self {
+        NodeIds {
+            next: NodeIds::ENVIRONMENT,
+            end: NodeId::UNASSIGNED.0,
+        }
+    }
+
+    /// the next NodeId. There are more than enough for any program, so running out of them is a
+    /// bug rather than an error in the program.
+    pub fn allocate(&mut self) -> NodeId {
+        assert!(
+            self.next < self.end,
+            "ran out of NodeIds to number the tree with"
+        );
+        let id = NodeId(self.next);
+        self.next += 1;
+        id
+    }
+}
+
+impl default::Default for NodeIds {
+    fn default() -> Self {
+        NodeIds::new()
+    }
+}
+
 /// Common state that is shared by every Ast
 #[derive(Debug, Clone)]
 pub struct CommonState {
     pub position: SourcePosition,
-    pub entity: RuntimeEntity,
+    pub id: NodeId,
 }
 
 impl CommonState {
-    pub fn new(position: SourcePosition, entity: runtime_entities::RuntimeEntity) -> Self {
-        CommonState { position, entity }
+    pub fn new(position: SourcePosition) -> Self {
+        CommonState {
+            position,
+            id: NodeId::UNASSIGNED,
+        }
     }
 }
 
 impl default::Default for CommonState {
     fn default() -> Self {
-        CommonState {
-            position: SourcePosition::default(),
-            entity: runtime_entities::RuntimeEntity::None,
-        }
+        CommonState::new(SourcePosition::default())
     }
 }
 
 #[derive(Debug)]
 pub struct Program {
-    pub cmd: Box<Command>,
+    pub cmd: Child<Command>,
+    /// every node of the program, other than the program itself
+    pub nodes: Nodes,
+    /// the side tables that the Checker and the Encoder decorate the program with
+    pub decorations: Decorations,
+    /// the NodeIds that the nodes of the program have been numbered with, and that any node
+    /// added to it is numbered from
+    pub ids: NodeIds,
     pub common_state: CommonState,
 }
 
 impl Program {
+    /// a program of the command, with every node of the command numbered and stored in the
+    /// program's arena
     pub fn new(cmd: Command) -> Self {
-        Program {
-            cmd: Box::new(cmd),
-            common_state: CommonState::default(),
-        }
+        Program::new_with_position(cmd, SourcePosition::default())
     }
 
     pub fn new_with_position(cmd: Command, position: SourcePosition) -> Self {
-        let mut program = Program::new(cmd);
-        program.common_state.position = position;
+        let mut program = Program {
+            cmd: Child::new(cmd),
+            nodes: Nodes::new(),
+            decorations: Decorations::new(),
+            ids: NodeIds::new(),
+            common_state: CommonState::new(position),
+        };
+        program.common_state.id = program.ids.allocate();
+        program.cmd.adopt(&mut program.nodes, &mut program.ids);
         program
     }
+
+    /// what the identifier or operator that is linked to the NodeId stands for - a declaration
+    /// or a formal parameter of the program, or a declaration of the standard environment that
+    /// it was checked in
+    pub fn definition(&self, decl: NodeId) -> Option<Definition<'_>> {
+        self.nodes.definition(decl)
+    }
 }
 
 impl PartialEq for Program {
     fn eq(&self, other: &Program) -> bool {
-        self.cmd == other.cmd
+        self.cmd == other.cmd && self.nodes == other.nodes
     }
 }
 
@@ -393,12 +691,16 @@ impl Eq for Program {}
 
 impl fmt::Display for Program {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Program::new({})", self.cmd)
+        write!(f, "{}", self.tree())
     }
 }
 
 impl Ast for Program {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
-        visitor.visit_program(self, arg)
+    /// the nodes of the program are lent to the visitor for as long as it visits the program
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
+        mem::swap(visitor.nodes(), &mut self.nodes);
+        let result = visitor.visit_program(self, arg);
+        mem::swap(visitor.nodes(), &mut self.nodes);
+        result
     }
 }
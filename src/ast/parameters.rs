@@ -1,10 +1,11 @@
 //! parameter asts
 
+use super::arena::Child;
 use super::expressions::Expression;
 use super::primitives::Identifier;
 use super::typedenoters::TypeDenoter;
 use super::vnames::Vname;
-use super::{Ast, AstObject, AstVisitor, CommonState};
+use super::{Ast, AstVisitor, CommonState};
 use crate::scanner::SourcePosition;
 use std::fmt;
 
@@ -16,6 +17,16 @@ pub enum FormalParameterSequence {
 }
 
 impl FormalParameterSequence {
+    pub fn common_state(&self) -> &CommonState {
+        use FormalParameterSequence::*;
+
+        match *self {
+            EmptyFormalParameterSequence(ref state) => &state.common_state,
+            SingleFormalParameterSequence(ref state) => &state.common_state,
+            MultipleFormalParameterSequence(ref state) => &state.common_state,
+        }
+    }
+
     pub fn get_empty_formal_parameter_sequence(
         &self,
     ) -> Option<&EmptyFormalParameterSequenceState> {
@@ -84,7 +95,7 @@ impl fmt::Display for FormalParameterSequence {
 }
 
 impl Ast for FormalParameterSequence {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         use FormalParameterSequence::*;
 
         match *self {
@@ -129,21 +140,21 @@ impl fmt::Display for EmptyFormalParameterSequenceState {
 }
 
 impl Ast for EmptyFormalParameterSequenceState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_empty_formal_parameter_sequence(self, arg)
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct SingleFormalParameterSequenceState {
-    pub fp: Box<FormalParameter>,
+    pub fp: Child<FormalParameter>,
     pub common_state: CommonState,
 }
 
 impl SingleFormalParameterSequenceState {
     pub fn new(fp: FormalParameter) -> Self {
         SingleFormalParameterSequenceState {
-            fp: Box::new(fp),
+            fp: Child::new(fp),
             common_state: CommonState::default(),
         }
     }
@@ -170,23 +181,23 @@ impl fmt::Display for SingleFormalParameterSequenceState {
 }
 
 impl Ast for SingleFormalParameterSequenceState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_single_formal_parameter_sequence(self, arg)
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct MultipleFormalParameterSequenceState {
-    pub fp: Box<FormalParameter>,
-    pub fps: Box<FormalParameterSequence>,
+    pub fp: Child<FormalParameter>,
+    pub fps: Child<FormalParameterSequence>,
     pub common_state: CommonState,
 }
 
 impl MultipleFormalParameterSequenceState {
     pub fn new(fp: FormalParameter, fps: FormalParameterSequence) -> Self {
         MultipleFormalParameterSequenceState {
-            fp: Box::new(fp),
-            fps: Box::new(fps),
+            fp: Child::new(fp),
+            fps: Child::new(fps),
             common_state: CommonState::default(),
         }
     }
@@ -221,7 +232,7 @@ impl fmt::Display for MultipleFormalParameterSequenceState {
 }
 
 impl Ast for MultipleFormalParameterSequenceState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_multiple_formal_parameter_sequence(self, arg)
     }
 }
@@ -262,10 +273,43 @@ impl FormalParameter {
             _ => None,
         }
     }
+
+    pub fn id(&self) -> &Identifier {
+        use FormalParameter::*;
+
+        match *self {
+            ConstFormalParameter(ref fp) => &fp.id,
+            FuncFormalParameter(ref fp) => &fp.id,
+            ProcFormalParameter(ref fp) => &fp.id,
+            VarFormalParameter(ref fp) => &fp.id,
+        }
+    }
+
+    pub fn common_state(&self) -> &CommonState {
+        use FormalParameter::*;
+
+        match *self {
+            ConstFormalParameter(ref fp) => &fp.common_state,
+            FuncFormalParameter(ref fp) => &fp.common_state,
+            ProcFormalParameter(ref fp) => &fp.common_state,
+            VarFormalParameter(ref fp) => &fp.common_state,
+        }
+    }
+
+    pub fn common_state_mut(&mut self) -> &mut CommonState {
+        use FormalParameter::*;
+
+        match *self {
+            ConstFormalParameter(ref mut fp) => &mut fp.common_state,
+            FuncFormalParameter(ref mut fp) => &mut fp.common_state,
+            ProcFormalParameter(ref mut fp) => &mut fp.common_state,
+            VarFormalParameter(ref mut fp) => &mut fp.common_state,
+        }
+    }
 }
 
 impl Ast for FormalParameter {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         use FormalParameter::*;
 
         match *self {
@@ -344,7 +388,7 @@ impl fmt::Display for VarFormalParameterState {
 }
 
 impl Ast for VarFormalParameterState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_var_formal_parameter(self, arg)
     }
 }
@@ -391,7 +435,7 @@ impl fmt::Display for ConstFormalParameterState {
 }
 
 impl Ast for ConstFormalParameterState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_const_formal_parameter(self, arg)
     }
 }
@@ -399,7 +443,7 @@ impl Ast for ConstFormalParameterState {
 #[derive(Debug, Clone)]
 pub struct ProcFormalParameterState {
     pub id: Identifier,
-    pub fps: Box<FormalParameterSequence>,
+    pub fps: Child<FormalParameterSequence>,
     pub common_state: CommonState,
 }
 
@@ -407,7 +451,7 @@ impl ProcFormalParameterState {
     pub fn new(id: Identifier, fps: FormalParameterSequence) -> Self {
         ProcFormalParameterState {
             id: id,
-            fps: Box::new(fps),
+            fps: Child::new(fps),
             common_state: CommonState::default(),
         }
     }
@@ -442,7 +486,7 @@ impl fmt::Display for ProcFormalParameterState {
 }
 
 impl Ast for ProcFormalParameterState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_proc_formal_parameter(self, arg)
     }
 }
@@ -450,7 +494,7 @@ impl Ast for ProcFormalParameterState {
 #[derive(Debug, Clone)]
 pub struct FuncFormalParameterState {
     pub id: Identifier,
-    pub fps: Box<FormalParameterSequence>,
+    pub fps: Child<FormalParameterSequence>,
     pub td: Box<TypeDenoter>,
     pub common_state: CommonState,
 }
@@ -459,7 +503,7 @@ impl FuncFormalParameterState {
     pub fn new(id: Identifier, fps: FormalParameterSequence, td: TypeDenoter) -> Self {
         FuncFormalParameterState {
             id: id,
-            fps: Box::new(fps),
+            fps: Child::new(fps),
             td: Box::new(td),
             common_state: CommonState::default(),
         }
@@ -496,7 +540,7 @@ impl fmt::Display for FuncFormalParameterState {
 }
 
 impl Ast for FuncFormalParameterState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_func_formal_parameter(self, arg)
     }
 }
@@ -509,6 +553,16 @@ pub enum ActualParameterSequence {
 }
 
 impl ActualParameterSequence {
+    pub fn common_state(&self) -> &CommonState {
+        use ActualParameterSequence::*;
+
+        match *self {
+            EmptyActualParameterSequence(ref state) => &state.common_state,
+            SingleActualParameterSequence(ref state) => &state.common_state,
+            MultipleActualParameterSequence(ref state) => &state.common_state,
+        }
+    }
+
     pub fn get_empty_actual_parameter_sequence(
         &self,
     ) -> Option<&EmptyActualParameterSequenceState> {
@@ -598,7 +652,7 @@ impl fmt::Display for ActualParameterSequence {
 }
 
 impl Ast for ActualParameterSequence {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         use ActualParameterSequence::*;
 
         match *self {
@@ -643,21 +697,21 @@ impl fmt::Display for EmptyActualParameterSequenceState {
 }
 
 impl Ast for EmptyActualParameterSequenceState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_empty_actual_parameter_sequence(self, arg)
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct SingleActualParameterSequenceState {
-    pub ap: Box<ActualParameter>,
+    pub ap: Child<ActualParameter>,
     pub common_state: CommonState,
 }
 
 impl SingleActualParameterSequenceState {
     pub fn new(ap: ActualParameter) -> Self {
         SingleActualParameterSequenceState {
-            ap: Box::new(ap),
+            ap: Child::new(ap),
             common_state: CommonState::default(),
         }
     }
@@ -684,23 +738,23 @@ impl fmt::Display for SingleActualParameterSequenceState {
 }
 
 impl Ast for SingleActualParameterSequenceState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_single_actual_parameter_sequence(self, arg)
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct MultipleActualParameterSequenceState {
-    pub ap: Box<ActualParameter>,
-    pub aps: Box<ActualParameterSequence>,
+    pub ap: Child<ActualParameter>,
+    pub aps: Child<ActualParameterSequence>,
     pub common_state: CommonState,
 }
 
 impl MultipleActualParameterSequenceState {
     pub fn new(ap: ActualParameter, aps: ActualParameterSequence) -> Self {
         MultipleActualParameterSequenceState {
-            ap: Box::new(ap),
-            aps: Box::new(aps),
+            ap: Child::new(ap),
+            aps: Child::new(aps),
             common_state: CommonState::default(),
         }
     }
@@ -735,7 +789,7 @@ impl fmt::Display for MultipleActualParameterSequenceState {
 }
 
 impl Ast for MultipleActualParameterSequenceState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_multiple_actual_parameter_sequence(self, arg)
     }
 }
@@ -749,6 +803,17 @@ pub enum ActualParameter {
 }
 
 impl ActualParameter {
+    pub fn common_state(&self) -> &CommonState {
+        use ActualParameter::*;
+
+        match *self {
+            ConstActualParameter(ref state) => &state.common_state,
+            FuncActualParameter(ref state) => &state.common_state,
+            ProcActualParameter(ref state) => &state.common_state,
+            VarActualParameter(ref state) => &state.common_state,
+        }
+    }
+
     pub fn get_const_actual_parameter(&self) -> Option<&ConstActualParameterState> {
         match *self {
             ActualParameter::ConstActualParameter(ref cnst) => Some(&cnst),
@@ -806,7 +871,7 @@ impl fmt::Display for ActualParameter {
 }
 
 impl Ast for ActualParameter {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         use ActualParameter::*;
 
         match *self {
@@ -820,14 +885,14 @@ impl Ast for ActualParameter {
 
 #[derive(Debug, Clone)]
 pub struct VarActualParameterState {
-    pub vname: Box<Vname>,
+    pub vname: Child<Vname>,
     pub common_state: CommonState,
 }
 
 impl VarActualParameterState {
     pub fn new(vname: Vname) -> Self {
         VarActualParameterState {
-            vname: Box::new(vname),
+            vname: Child::new(vname),
             common_state: CommonState::default(),
         }
     }
@@ -854,21 +919,21 @@ impl fmt::Display for VarActualParameterState {
 }
 
 impl Ast for VarActualParameterState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_var_actual_parameter(self, arg)
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct ConstActualParameterState {
-    pub expr: Box<Expression>,
+    pub expr: Child<Expression>,
     pub common_state: CommonState,
 }
 
 impl ConstActualParameterState {
     pub fn new(expr: Expression) -> Self {
         ConstActualParameterState {
-            expr: Box::new(expr),
+            expr: Child::new(expr),
             common_state: CommonState::default(),
         }
     }
@@ -895,7 +960,7 @@ impl fmt::Display for ConstActualParameterState {
 }
 
 impl Ast for ConstActualParameterState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_const_actual_parameter(self, arg)
     }
 }
@@ -936,7 +1001,7 @@ impl fmt::Display for ProcActualParameterState {
 }
 
 impl Ast for ProcActualParameterState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_proc_actual_parameter(self, arg)
     }
 }
@@ -977,7 +1042,7 @@ impl fmt::Display for FuncActualParameterState {
 }
 
 impl Ast for FuncActualParameterState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_func_actual_parameter(self, arg)
     }
 }
@@ -1,9 +1,7 @@
 //! primitive/terminal asts
 
-use super::declarations::Declaration;
-use super::typedenoters::TypeDenoter;
 use super::CommonState;
-use super::{Ast, AstObject, AstVisitor};
+use super::{Ast, AstVisitor};
 use crate::scanner::SourcePosition;
 use std::default::Default;
 use std::fmt;
@@ -44,7 +42,7 @@ impl fmt::Display for IntegerLiteral {
 }
 
 impl Ast for IntegerLiteral {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_integer_literal(self, arg)
     }
 }
@@ -85,7 +83,7 @@ impl fmt::Display for CharacterLiteral {
 }
 
 impl Ast for CharacterLiteral {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_character_literal(self, arg)
     }
 }
@@ -93,8 +91,6 @@ impl Ast for CharacterLiteral {
 #[derive(Debug, Clone)]
 pub struct Identifier {
     pub spelling: String,
-    pub td: Option<Box<TypeDenoter>>,
-    pub decl: Option<Box<Declaration>>,
     pub common_state: CommonState,
 }
 
@@ -102,8 +98,6 @@ impl Identifier {
     pub fn new(spelling: &str) -> Self {
         Identifier {
             spelling: String::from(spelling),
-            td: None,
-            decl: None,
             common_state: CommonState::default(),
         }
     }
@@ -130,7 +124,7 @@ impl fmt::Display for Identifier {
 }
 
 impl Ast for Identifier {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_identifier(self, arg)
     }
 }
@@ -139,8 +133,6 @@ impl Default for Identifier {
     fn default() -> Self {
         Identifier {
             spelling: String::from("default"),
-            td: None,
-            decl: None,
             common_state: CommonState::default(),
         }
     }
@@ -149,7 +141,6 @@ impl Default for Identifier {
 #[derive(Debug, Clone)]
 pub struct Operator {
     pub spelling: String,
-    pub decl: Option<Box<Declaration>>,
     pub common_state: CommonState,
 }
 
@@ -157,7 +148,6 @@ impl Operator {
     pub fn new(spelling: &str) -> Self {
         Operator {
             spelling: String::from(spelling),
-            decl: None,
             common_state: CommonState::default(),
         }
     }
@@ -184,7 +174,7 @@ impl fmt::Display for Operator {
 }
 
 impl Ast for Operator {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_operator(self, arg)
     }
 }
@@ -0,0 +1,243 @@
+//! Questions about a checked program
+//!
+//! Answers the questions that an editor asks about the position under the cursor - which
+//! declaration an identifier or operator stands for, what type an expression has, and where
+//! else a declaration is used - from the decorations that the Checker left in the program.
+//! Positions are as the Scanner gives them, counting lines and columns from 1.
+
+use super::arena::{Child, Nodes};
+use super::declarations::*;
+use super::expressions::Expression;
+use super::parameters::FormalParameter;
+use super::primitives::{Identifier, Operator};
+use super::typedenoters::TypeDenoter;
+use super::visit_ref::{self, AstRefVisitor};
+use super::vnames::Vname;
+use super::{NodeId, Program};
+use crate::scanner::{Position, SourcePosition};
+
+use std::collections::HashMap;
+
+/// an occurrence of an identifier or operator in the program
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Occurrence {
+    pub node: NodeId,
+    pub spelling: String,
+    pub position: SourcePosition,
+    /// the NodeId of the declaration that the occurrence stands for, if it was declared
+    pub declaration: Option<NodeId>,
+    /// whether this is the occurrence that declares the identifier, rather than one that uses it
+    pub defining: bool,
+}
+
+impl Program {
+    /// every identifier and operator in the program, in the order that they appear
+    pub fn occurrences(&self) -> Vec<Occurrence> {
+        self.index().occurrences
+    }
+
+    /// the identifier or operator at the position
+    pub fn occurrence_at(&self, position: Position) -> Option<Occurrence> {
+        self.occurrences()
+            .into_iter()
+            .find(|occurrence| occurrence.position.contains(position))
+    }
+
+    /// the declaration of the identifier or operator at the position - the declaration that it
+    /// is linked to, or the one that it declares
+    pub fn definition_at(&self, position: Position) -> Option<Definition<'_>> {
+        let decl = self.occurrence_at(position)?.declaration?;
+        self.definition(decl)
+    }
+
+    /// the type of the innermost expression or vname at the position
+    pub fn type_at(&self, position: Position) -> Option<&TypeDenoter> {
+        self.index()
+            .typed
+            .into_iter()
+            .filter(|(_, span)| span.contains(position))
+            .max_by(|(_, span1), (_, span2)| {
+                span1
+                    .start
+                    .cmp(&span2.start)
+                    .then(span2.finish.cmp(&span1.finish))
+            })
+            .and_then(|(node, _)| self.decorations.type_of(node))
+    }
+
+    /// every occurrence of the declaration - where it is declared, followed by each use of it,
+    /// in the order that they appear. Declarations of the standard environment are not declared
+    /// anywhere in the program.
+    pub fn references_of(&self, decl: Definition) -> Vec<Occurrence> {
+        let decl = decl.common_state().id;
+        let mut references = self
+            .occurrences()
+            .into_iter()
+            .filter(|occurrence| occurrence.declaration == Some(decl))
+            .collect::<Vec<_>>();
+        references.sort_by_key(|occurrence| !occurrence.defining);
+        references
+    }
+
+    /// a one-line summary of the declaration, such as `var x : Integer`
+    pub fn describe(&self, decl: Definition) -> String {
+        use Definition::*;
+
+        match decl {
+            BinaryOperator(decl) => format!(
+                "{} : {}, {} -> {}",
+                decl.op.spelling,
+                decl.arg1_type.name(),
+                decl.arg2_type.name(),
+                decl.res_type.name()
+            ),
+            Const(decl) => match self.decorations.type_of(decl.expr.id()) {
+                Some(td) => format!("const {} : {}", decl.id.spelling, td.name()),
+                None => format!("const {}", decl.id.spelling),
+            },
+            Parameter(fp) => match *fp {
+                FormalParameter::ConstFormalParameter(ref fp) => {
+                    format!("{} : {}", fp.id.spelling, fp.td.name())
+                }
+                FormalParameter::VarFormalParameter(ref fp) => {
+                    format!("var {} : {}", fp.id.spelling, fp.td.name())
+                }
+                FormalParameter::ProcFormalParameter(ref fp) => format!("proc {}", fp.id.spelling),
+                FormalParameter::FuncFormalParameter(ref fp) => {
+                    format!("func {} : {}", fp.id.spelling, fp.td.name())
+                }
+            },
+            Func(decl) => format!("func {} : {}", decl.id.spelling, decl.td.name()),
+            Proc(decl) => format!("proc {}", decl.id.spelling),
+            Type(decl) => format!("type {} ~ {}", decl.id.spelling, decl.td.name()),
+            UnaryOperator(decl) => format!(
+                "{} : {} -> {}",
+                decl.op.spelling,
+                decl.argtype.name(),
+                decl.res_type.name()
+            ),
+            Var(decl) => format!("var {} : {}", decl.id.spelling, decl.td.name()),
+        }
+    }
+
+    fn index(&self) -> Index<'_> {
+        let mut index = Index {
+            program: self,
+            occurrences: Vec::new(),
+            typed: Vec::new(),
+            defining: HashMap::new(),
+        };
+        index.visit_program(self, ());
+        index
+    }
+}
+
+/// the identifiers and operators of a program, and the nodes that have types
+struct Index<'a> {
+    program: &'a Program,
+    occurrences: Vec<Occurrence>,
+    typed: Vec<(NodeId, SourcePosition)>,
+    /// the identifiers and operators that declare something, and the declarations that they
+    /// declare
+    defining: HashMap<NodeId, NodeId>,
+}
+
+impl<'a> Index<'a> {
+    fn occurrence(&mut self, spelling: &str, node: NodeId, position: SourcePosition) {
+        let (declaration, defining) = match self.defining.get(&node) {
+            Some(decl) => (Some(*decl), true),
+            None => (self.program.decorations.linked(node), false),
+        };
+        self.occurrences.push(Occurrence {
+            node,
+            spelling: spelling.to_owned(),
+            position,
+            declaration,
+            defining,
+        });
+    }
+
+    fn declares(&mut self, name: NodeId, decl: NodeId) {
+        self.defining.insert(name, decl);
+    }
+}
+
+impl<'a> AstRefVisitor<'a> for Index<'a> {
+    type Arg = ();
+    type Output = ();
+
+    fn nodes(&self) -> &'a Nodes {
+        &self.program.nodes
+    }
+
+    fn visit_expression(&mut self, expr: &'a Child<Expression>, arg: ()) {
+        let expr = expr.get(self.nodes());
+        let state = expr.common_state();
+        self.typed.push((state.id, state.position));
+        visit_ref::walk_expression(self, expr, arg)
+    }
+
+    fn visit_vname(&mut self, vname: &'a Child<Vname>, arg: ()) {
+        let vname = vname.get(self.nodes());
+        let state = vname.common_state();
+        self.typed.push((state.id, state.position));
+        visit_ref::walk_vname(self, vname, arg)
+    }
+
+    fn visit_binary_operator_declaration(
+        &mut self,
+        decl: &'a BinaryOperatorDeclarationState,
+        arg: (),
+    ) {
+        self.declares(decl.op.common_state.id, decl.common_state.id);
+        visit_ref::walk_binary_operator_declaration(self, decl, arg)
+    }
+
+    fn visit_const_declaration(&mut self, decl: &'a ConstDeclarationState, arg: ()) {
+        self.declares(decl.id.common_state.id, decl.common_state.id);
+        visit_ref::walk_const_declaration(self, decl, arg)
+    }
+
+    fn visit_func_declaration(&mut self, decl: &'a FuncDeclarationState, arg: ()) {
+        self.declares(decl.id.common_state.id, decl.common_state.id);
+        visit_ref::walk_func_declaration(self, decl, arg)
+    }
+
+    fn visit_proc_declaration(&mut self, decl: &'a ProcDeclarationState, arg: ()) {
+        self.declares(decl.id.common_state.id, decl.common_state.id);
+        visit_ref::walk_proc_declaration(self, decl, arg)
+    }
+
+    fn visit_type_declaration(&mut self, decl: &'a TypeDeclarationState, arg: ()) {
+        self.declares(decl.id.common_state.id, decl.common_state.id);
+        visit_ref::walk_type_declaration(self, decl, arg)
+    }
+
+    fn visit_unary_operator_declaration(
+        &mut self,
+        decl: &'a UnaryOperatorDeclarationState,
+        arg: (),
+    ) {
+        self.declares(decl.op.common_state.id, decl.common_state.id);
+        visit_ref::walk_unary_operator_declaration(self, decl, arg)
+    }
+
+    fn visit_var_declaration(&mut self, decl: &'a VarDeclarationState, arg: ()) {
+        self.declares(decl.id.common_state.id, decl.common_state.id);
+        visit_ref::walk_var_declaration(self, decl, arg)
+    }
+
+    fn visit_formal_parameter(&mut self, fp: &'a Child<FormalParameter>, arg: ()) {
+        let fp = fp.get(self.nodes());
+        self.declares(fp.id().common_state.id, fp.common_state().id);
+        visit_ref::walk_formal_parameter(self, fp, arg)
+    }
+
+    fn visit_identifier(&mut self, id: &'a Identifier, _arg: ()) {
+        self.occurrence(&id.spelling, id.common_state.id, id.common_state.position);
+    }
+
+    fn visit_operator(&mut self, op: &'a Operator, _arg: ()) {
+        self.occurrence(&op.spelling, op.common_state.id, op.common_state.position);
+    }
+}
@@ -9,6 +9,7 @@ pub enum RuntimeEntity {
     UnknownAddress(UnknownAddressState),
     KnownValue(KnownValueState),
     UnknownValue(UnknownValueState),
+    KnownRoutine(KnownRoutineState),
     PrimitiveRoutine(PrimitiveRoutineState),
     EqualityRoutine(EqualityRoutineState),
     UnknownRoutine(UnknownRoutineState),
@@ -49,8 +50,8 @@ impl KnownAddressState {
 
 #[derive(Debug, Clone)]
 pub struct UnknownAddressState {
-    size: usize,
-    address: EntityAddress,
+    pub size: usize,
+    pub address: EntityAddress,
 }
 
 impl UnknownAddressState {
@@ -64,8 +65,8 @@ impl UnknownAddressState {
 
 #[derive(Debug, Clone)]
 pub struct KnownValueState {
-    size: usize,
-    value: i32,
+    pub size: usize,
+    pub value: i32,
 }
 
 impl KnownValueState {
@@ -76,8 +77,8 @@ impl KnownValueState {
 
 #[derive(Debug, Clone)]
 pub struct UnknownValueState {
-    size: usize,
-    address: EntityAddress,
+    pub size: usize,
+    pub address: EntityAddress,
 }
 
 impl UnknownValueState {
@@ -91,8 +92,8 @@ impl UnknownValueState {
 
 #[derive(Debug, Clone)]
 pub struct KnownRoutineState {
-    size: usize,
-    address: EntityAddress,
+    pub size: usize,
+    pub address: EntityAddress,
 }
 
 impl KnownRoutineState {
@@ -106,8 +107,8 @@ impl KnownRoutineState {
 
 #[derive(Debug, Clone)]
 pub struct UnknownRoutineState {
-    size: usize,
-    address: EntityAddress,
+    pub size: usize,
+    pub address: EntityAddress,
 }
 
 impl UnknownRoutineState {
@@ -121,8 +122,8 @@ impl UnknownRoutineState {
 
 #[derive(Debug, Clone)]
 pub struct PrimitiveRoutineState {
-    size: usize,
-    displacement: isize,
+    pub size: usize,
+    pub displacement: isize,
 }
 
 impl PrimitiveRoutineState {
@@ -133,8 +134,8 @@ impl PrimitiveRoutineState {
 
 #[derive(Debug, Clone)]
 pub struct EqualityRoutineState {
-    size: usize,
-    displacement: isize,
+    pub size: usize,
+    pub displacement: isize,
 }
 
 impl EqualityRoutineState {
@@ -145,8 +146,8 @@ impl EqualityRoutineState {
 
 #[derive(Debug, Clone)]
 pub struct FieldState {
-    size: usize,
-    offset: isize,
+    pub size: usize,
+    pub offset: isize,
 }
 
 impl FieldState {
@@ -157,7 +158,7 @@ impl FieldState {
 
 #[derive(Debug, Clone)]
 pub struct TypeRepresentationState {
-    size: usize,
+    pub size: usize,
 }
 
 impl TypeRepresentationState {
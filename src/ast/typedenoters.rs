@@ -1,7 +1,7 @@
 //! type-denoter asts
 
 use super::primitives::{Identifier, IntegerLiteral};
-use super::{Ast, AstObject, AstVisitor, CommonState};
+use super::{Ast, AstVisitor, CommonState};
 use crate::scanner::SourcePosition;
 use std::default::Default;
 use std::fmt;
@@ -19,6 +19,39 @@ pub enum TypeDenoter {
 }
 
 impl TypeDenoter {
+    pub fn common_state(&self) -> &CommonState {
+        use TypeDenoter::*;
+
+        match *self {
+            AnyTypeDenoter(ref state) => &state.common_state,
+            ArrayTypeDenoter(ref state) => &state.common_state,
+            BoolTypeDenoter(ref state) => &state.common_state,
+            CharTypeDenoter(ref state) => &state.common_state,
+            ErrorTypeDenoter(ref state) => &state.common_state,
+            IntTypeDenoter(ref state) => &state.common_state,
+            RecordTypeDenoter(ref state) => &state.common_state,
+            SimpleTypeDenoter(ref state) => &state.common_state,
+        }
+    }
+
+    /// the type as it would be written in Triangle
+    pub fn name(&self) -> String {
+        use TypeDenoter::*;
+
+        match *self {
+            AnyTypeDenoter(_) => String::from("any type"),
+            ArrayTypeDenoter(ref array) => {
+                format!("array {} of {}", array.il.spelling, array.td.name())
+            }
+            BoolTypeDenoter(_) => String::from("Boolean"),
+            CharTypeDenoter(_) => String::from("Char"),
+            ErrorTypeDenoter(_) => String::from("error type"),
+            IntTypeDenoter(_) => String::from("Integer"),
+            RecordTypeDenoter(ref record) => format!("record {} end", record.ftd.names()),
+            SimpleTypeDenoter(ref simple) => simple.id.spelling.clone(),
+        }
+    }
+
     pub fn get_any_type_denoter(&self) -> Option<&AnyTypeDenoterState> {
         match *self {
             TypeDenoter::AnyTypeDenoter(ref any) => Some(&any),
@@ -117,7 +150,7 @@ impl fmt::Display for TypeDenoter {
 }
 
 impl Ast for TypeDenoter {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         use TypeDenoter::*;
 
         match *self {
@@ -141,7 +174,7 @@ impl Default for TypeDenoter {
 
 #[derive(Debug, Clone)]
 pub struct BoolTypeDenoterState {
-    common_state: CommonState,
+    pub common_state: CommonState,
 }
 
 impl BoolTypeDenoterState {
@@ -173,13 +206,13 @@ impl fmt::Display for BoolTypeDenoterState {
 }
 
 impl Ast for BoolTypeDenoterState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_bool_type_denoter(self, arg)
     }
 }
 #[derive(Debug, Clone)]
 pub struct CharTypeDenoterState {
-    common_state: CommonState,
+    pub common_state: CommonState,
 }
 
 impl CharTypeDenoterState {
@@ -211,14 +244,14 @@ impl fmt::Display for CharTypeDenoterState {
 }
 
 impl Ast for CharTypeDenoterState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_char_type_denoter(self, arg)
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct IntTypeDenoterState {
-    common_state: CommonState,
+    pub common_state: CommonState,
 }
 
 impl IntTypeDenoterState {
@@ -250,13 +283,13 @@ impl fmt::Display for IntTypeDenoterState {
 }
 
 impl Ast for IntTypeDenoterState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_int_type_denoter(self, arg)
     }
 }
 #[derive(Debug, Clone)]
 pub struct AnyTypeDenoterState {
-    common_state: CommonState,
+    pub common_state: CommonState,
 }
 
 impl AnyTypeDenoterState {
@@ -288,14 +321,14 @@ impl fmt::Display for AnyTypeDenoterState {
 }
 
 impl Ast for AnyTypeDenoterState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_any_type_denoter(self, arg)
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct ErrorTypeDenoterState {
-    common_state: CommonState,
+    pub common_state: CommonState,
 }
 
 impl ErrorTypeDenoterState {
@@ -327,7 +360,7 @@ impl fmt::Display for ErrorTypeDenoterState {
 }
 
 impl Ast for ErrorTypeDenoterState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_error_type_denoter(self, arg)
     }
 }
@@ -367,7 +400,7 @@ impl fmt::Display for SimpleTypeDenoterState {
 }
 
 impl Ast for SimpleTypeDenoterState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_simple_type_denoter(self, arg)
     }
 }
@@ -413,7 +446,7 @@ impl fmt::Display for ArrayTypeDenoterState {
 }
 
 impl Ast for ArrayTypeDenoterState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_array_type_denoter(self, arg)
     }
 }
@@ -454,7 +487,7 @@ impl fmt::Display for RecordTypeDenoterState {
 }
 
 impl Ast for RecordTypeDenoterState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_record_type_denoter(self, arg)
     }
 }
@@ -466,6 +499,32 @@ pub enum FieldTypeDenoter {
 }
 
 impl FieldTypeDenoter {
+    pub fn common_state(&self) -> &CommonState {
+        use FieldTypeDenoter::*;
+
+        match *self {
+            SingleFieldTypeDenoter(ref state) => &state.common_state,
+            MultipleFieldTypeDenoter(ref state) => &state.common_state,
+        }
+    }
+
+    /// the fields as they would be written in Triangle
+    pub fn names(&self) -> String {
+        use FieldTypeDenoter::*;
+
+        match *self {
+            SingleFieldTypeDenoter(ref single) => {
+                format!("{}: {}", single.id.spelling, single.td.name())
+            }
+            MultipleFieldTypeDenoter(ref multiple) => format!(
+                "{}: {}, {}",
+                multiple.id.spelling,
+                multiple.td.name(),
+                multiple.ftd.names()
+            ),
+        }
+    }
+
     pub fn get_single_field_type_denoter(&self) -> Option<&SingleFieldTypeDenoterState> {
         match *self {
             FieldTypeDenoter::SingleFieldTypeDenoter(ref single) => Some(&single),
@@ -510,6 +569,17 @@ impl fmt::Display for FieldTypeDenoter {
     }
 }
 
+impl Ast for FieldTypeDenoter {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
+        use FieldTypeDenoter::*;
+
+        match *self {
+            SingleFieldTypeDenoter(ref mut ftd) => ftd.accept(visitor, arg),
+            MultipleFieldTypeDenoter(ref mut ftd) => ftd.accept(visitor, arg),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SingleFieldTypeDenoterState {
     pub id: Identifier,
@@ -552,7 +622,7 @@ impl fmt::Display for SingleFieldTypeDenoterState {
 }
 
 impl Ast for SingleFieldTypeDenoterState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_single_field_type_denoter(self, arg)
     }
 }
@@ -606,7 +676,7 @@ impl fmt::Display for MultipleFieldTypeDenoterState {
 }
 
 impl Ast for MultipleFieldTypeDenoterState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_multiple_field_type_denoter(self, arg)
     }
 }
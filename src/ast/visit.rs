@@ -0,0 +1,502 @@
+//! The default traversal of the AstVisitor
+//!
+//! Every method of the AstVisitor walks the children of its node by default, in the order that
+//! they appear in the source, passing each of them the argument it was given, and returns the
+//! default of its Output. A visitor only needs to implement the methods for the nodes that it cares about, and an
+//! implementation that still wants to walk the children of its node can call the `walk_`
+//! function for it.
+
+use super::aggregates::*;
+use super::commands::*;
+use super::declarations::*;
+use super::expressions::*;
+use super::parameters::*;
+use super::typedenoters::*;
+use super::vnames::*;
+use super::{Ast, AstVisitor, Program};
+
+pub fn walk_program<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    program: &mut Program,
+    arg: V::Arg,
+) -> V::Output {
+    program.cmd.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_assign_command<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    cmd: &mut AssignCommandState,
+    arg: V::Arg,
+) -> V::Output {
+    cmd.vname.accept(visitor, arg.clone());
+    cmd.expr.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_call_command<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    cmd: &mut CallCommandState,
+    arg: V::Arg,
+) -> V::Output {
+    cmd.id.accept(visitor, arg.clone());
+    cmd.aps.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_if_command<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    cmd: &mut IfCommandState,
+    arg: V::Arg,
+) -> V::Output {
+    cmd.expr.accept(visitor, arg.clone());
+    cmd.cmd1.accept(visitor, arg.clone());
+    cmd.cmd2.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_let_command<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    cmd: &mut LetCommandState,
+    arg: V::Arg,
+) -> V::Output {
+    cmd.decl.accept(visitor, arg.clone());
+    cmd.cmd.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_sequential_command<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    cmd: &mut SequentialCommandState,
+    arg: V::Arg,
+) -> V::Output {
+    cmd.cmd1.accept(visitor, arg.clone());
+    cmd.cmd2.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_while_command<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    cmd: &mut WhileCommandState,
+    arg: V::Arg,
+) -> V::Output {
+    cmd.expr.accept(visitor, arg.clone());
+    cmd.cmd.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_array_expression<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    expr: &mut ArrayExpressionState,
+    arg: V::Arg,
+) -> V::Output {
+    expr.aa.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_binary_expression<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    expr: &mut BinaryExpressionState,
+    arg: V::Arg,
+) -> V::Output {
+    expr.expr1.accept(visitor, arg.clone());
+    expr.op.accept(visitor, arg.clone());
+    expr.expr2.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_call_expression<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    expr: &mut CallExpressionState,
+    arg: V::Arg,
+) -> V::Output {
+    expr.id.accept(visitor, arg.clone());
+    expr.aps.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_character_expression<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    expr: &mut CharacterExpressionState,
+    arg: V::Arg,
+) -> V::Output {
+    expr.cl.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_if_expression<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    expr: &mut IfExpressionState,
+    arg: V::Arg,
+) -> V::Output {
+    expr.expr1.accept(visitor, arg.clone());
+    expr.expr2.accept(visitor, arg.clone());
+    expr.expr3.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_integer_expression<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    expr: &mut IntegerExpressionState,
+    arg: V::Arg,
+) -> V::Output {
+    expr.il.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_let_expression<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    expr: &mut LetExpressionState,
+    arg: V::Arg,
+) -> V::Output {
+    expr.decl.accept(visitor, arg.clone());
+    expr.expr.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_record_expression<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    expr: &mut RecordExpressionState,
+    arg: V::Arg,
+) -> V::Output {
+    expr.ra.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_unary_expression<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    expr: &mut UnaryExpressionState,
+    arg: V::Arg,
+) -> V::Output {
+    expr.op.accept(visitor, arg.clone());
+    expr.expr.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_vname_expression<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    expr: &mut VnameExpressionState,
+    arg: V::Arg,
+) -> V::Output {
+    expr.vname.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_single_array_aggregate<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    agg: &mut SingleArrayAggregateState,
+    arg: V::Arg,
+) -> V::Output {
+    agg.expr.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_multiple_array_aggregate<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    agg: &mut MultipleArrayAggregateState,
+    arg: V::Arg,
+) -> V::Output {
+    agg.expr.accept(visitor, arg.clone());
+    agg.aa.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_single_record_aggregate<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    agg: &mut SingleRecordAggregateState,
+    arg: V::Arg,
+) -> V::Output {
+    agg.id.accept(visitor, arg.clone());
+    agg.expr.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_multiple_record_aggregate<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    agg: &mut MultipleRecordAggregateState,
+    arg: V::Arg,
+) -> V::Output {
+    agg.id.accept(visitor, arg.clone());
+    agg.expr.accept(visitor, arg.clone());
+    agg.ra.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_binary_operator_declaration<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    decl: &mut BinaryOperatorDeclarationState,
+    arg: V::Arg,
+) -> V::Output {
+    decl.arg1_type.accept(visitor, arg.clone());
+    decl.op.accept(visitor, arg.clone());
+    decl.arg2_type.accept(visitor, arg.clone());
+    decl.res_type.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_const_declaration<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    decl: &mut ConstDeclarationState,
+    arg: V::Arg,
+) -> V::Output {
+    decl.id.accept(visitor, arg.clone());
+    decl.expr.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_func_declaration<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    decl: &mut FuncDeclarationState,
+    arg: V::Arg,
+) -> V::Output {
+    decl.id.accept(visitor, arg.clone());
+    decl.fps.accept(visitor, arg.clone());
+    decl.td.accept(visitor, arg.clone());
+    decl.expr.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_proc_declaration<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    decl: &mut ProcDeclarationState,
+    arg: V::Arg,
+) -> V::Output {
+    decl.id.accept(visitor, arg.clone());
+    decl.fps.accept(visitor, arg.clone());
+    decl.cmd.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_sequential_declaration<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    decl: &mut SequentialDeclarationState,
+    arg: V::Arg,
+) -> V::Output {
+    decl.decl1.accept(visitor, arg.clone());
+    decl.decl2.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_type_declaration<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    decl: &mut TypeDeclarationState,
+    arg: V::Arg,
+) -> V::Output {
+    decl.id.accept(visitor, arg.clone());
+    decl.td.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_unary_operator_declaration<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    decl: &mut UnaryOperatorDeclarationState,
+    arg: V::Arg,
+) -> V::Output {
+    decl.op.accept(visitor, arg.clone());
+    decl.argtype.accept(visitor, arg.clone());
+    decl.res_type.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_var_declaration<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    decl: &mut VarDeclarationState,
+    arg: V::Arg,
+) -> V::Output {
+    decl.id.accept(visitor, arg.clone());
+    decl.td.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_array_type_denoter<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    td: &mut ArrayTypeDenoterState,
+    arg: V::Arg,
+) -> V::Output {
+    td.il.accept(visitor, arg.clone());
+    td.td.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_record_type_denoter<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    td: &mut RecordTypeDenoterState,
+    arg: V::Arg,
+) -> V::Output {
+    td.ftd.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_simple_type_denoter<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    td: &mut SimpleTypeDenoterState,
+    arg: V::Arg,
+) -> V::Output {
+    td.id.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_single_field_type_denoter<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    td: &mut SingleFieldTypeDenoterState,
+    arg: V::Arg,
+) -> V::Output {
+    td.id.accept(visitor, arg.clone());
+    td.td.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_multiple_field_type_denoter<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    td: &mut MultipleFieldTypeDenoterState,
+    arg: V::Arg,
+) -> V::Output {
+    td.id.accept(visitor, arg.clone());
+    td.td.accept(visitor, arg.clone());
+    td.ftd.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_single_formal_parameter_sequence<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    fps: &mut SingleFormalParameterSequenceState,
+    arg: V::Arg,
+) -> V::Output {
+    fps.fp.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_multiple_formal_parameter_sequence<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    fps: &mut MultipleFormalParameterSequenceState,
+    arg: V::Arg,
+) -> V::Output {
+    fps.fp.accept(visitor, arg.clone());
+    fps.fps.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_const_formal_parameter<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    fp: &mut ConstFormalParameterState,
+    arg: V::Arg,
+) -> V::Output {
+    fp.id.accept(visitor, arg.clone());
+    fp.td.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_func_formal_parameter<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    fp: &mut FuncFormalParameterState,
+    arg: V::Arg,
+) -> V::Output {
+    fp.id.accept(visitor, arg.clone());
+    fp.fps.accept(visitor, arg.clone());
+    fp.td.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_proc_formal_parameter<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    fp: &mut ProcFormalParameterState,
+    arg: V::Arg,
+) -> V::Output {
+    fp.id.accept(visitor, arg.clone());
+    fp.fps.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_var_formal_parameter<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    fp: &mut VarFormalParameterState,
+    arg: V::Arg,
+) -> V::Output {
+    fp.id.accept(visitor, arg.clone());
+    fp.td.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_single_actual_parameter_sequence<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    aps: &mut SingleActualParameterSequenceState,
+    arg: V::Arg,
+) -> V::Output {
+    aps.ap.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_multiple_actual_parameter_sequence<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    aps: &mut MultipleActualParameterSequenceState,
+    arg: V::Arg,
+) -> V::Output {
+    aps.ap.accept(visitor, arg.clone());
+    aps.aps.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_const_actual_parameter<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    ap: &mut ConstActualParameterState,
+    arg: V::Arg,
+) -> V::Output {
+    ap.expr.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_func_actual_parameter<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    ap: &mut FuncActualParameterState,
+    arg: V::Arg,
+) -> V::Output {
+    ap.id.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_proc_actual_parameter<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    ap: &mut ProcActualParameterState,
+    arg: V::Arg,
+) -> V::Output {
+    ap.id.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_var_actual_parameter<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    ap: &mut VarActualParameterState,
+    arg: V::Arg,
+) -> V::Output {
+    ap.vname.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_dot_vname<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    vname: &mut DotVnameState,
+    arg: V::Arg,
+) -> V::Output {
+    vname.vname.accept(visitor, arg.clone());
+    vname.id.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_simple_vname<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    vname: &mut SimpleVnameState,
+    arg: V::Arg,
+) -> V::Output {
+    vname.id.accept(visitor, arg);
+    V::Output::default()
+}
+
+pub fn walk_subscript_vname<V: AstVisitor + ?Sized>(
+    visitor: &mut V,
+    vname: &mut SubscriptVnameState,
+    arg: V::Arg,
+) -> V::Output {
+    vname.vname.accept(visitor, arg.clone());
+    vname.expr.accept(visitor, arg);
+    V::Output::default()
+}
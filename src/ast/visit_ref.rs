@@ -0,0 +1,1260 @@
+//! A read-only visitor for the Triangle Ast
+//!
+//! The AstRefVisitor is the same as the AstVisitor, except that it only borrows the Ast, along
+//! with the arena that its nodes are stored in, so it can be used to analyse a program that is
+//! shared, or that has already been checked. Every method walks the children of its node by
+//! default, passing each of them the argument it was given, and returns the default of its
+//! Output. There is also a method for each kind of node (`visit_command`, `visit_expression` and
+//! so on), which looks the node up in the arena, and passes it on to the method for its variant.
+
+use super::aggregates::*;
+use super::arena::{Child, Nodes};
+use super::commands::*;
+use super::declarations::*;
+use super::expressions::*;
+use super::parameters::*;
+use super::primitives::*;
+use super::typedenoters::*;
+use super::vnames::*;
+use super::Program;
+
+/// Visitor for a shared Triangle Ast. Every node is borrowed for as long as the Ast is, so a
+/// visitor can keep references to the nodes that it has visited.
+pub trait AstRefVisitor<'a> {
+    /// what each visit is given by its parent
+    type Arg: Clone;
+
+    /// what each visit hands back to its parent
+    type Output: Default;
+
+    /// the arena that the nodes being visited are stored in
+    fn nodes(&self) -> &'a Nodes;
+
+    fn visit_program(&mut self, program: &'a Program, arg: Self::Arg) -> Self::Output {
+        walk_program(self, program, arg)
+    }
+
+    fn visit_command(&mut self, cmd: &'a Child<Command>, arg: Self::Arg) -> Self::Output {
+        walk_command(self, cmd.get(self.nodes()), arg)
+    }
+
+    fn visit_assign_command(
+        &mut self,
+        cmd: &'a AssignCommandState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_assign_command(self, cmd, arg)
+    }
+
+    fn visit_call_command(&mut self, cmd: &'a CallCommandState, arg: Self::Arg) -> Self::Output {
+        walk_call_command(self, cmd, arg)
+    }
+
+    fn visit_empty_command(
+        &mut self,
+        _cmd: &'a EmptyCommandState,
+        _arg: Self::Arg,
+    ) -> Self::Output {
+        Self::Output::default()
+    }
+
+    fn visit_if_command(&mut self, cmd: &'a IfCommandState, arg: Self::Arg) -> Self::Output {
+        walk_if_command(self, cmd, arg)
+    }
+
+    fn visit_let_command(&mut self, cmd: &'a LetCommandState, arg: Self::Arg) -> Self::Output {
+        walk_let_command(self, cmd, arg)
+    }
+
+    fn visit_sequential_command(
+        &mut self,
+        cmd: &'a SequentialCommandState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_sequential_command(self, cmd, arg)
+    }
+
+    fn visit_while_command(&mut self, cmd: &'a WhileCommandState, arg: Self::Arg) -> Self::Output {
+        walk_while_command(self, cmd, arg)
+    }
+
+    fn visit_expression(&mut self, expr: &'a Child<Expression>, arg: Self::Arg) -> Self::Output {
+        walk_expression(self, expr.get(self.nodes()), arg)
+    }
+
+    fn visit_array_expression(
+        &mut self,
+        expr: &'a ArrayExpressionState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_array_expression(self, expr, arg)
+    }
+
+    fn visit_binary_expression(
+        &mut self,
+        expr: &'a BinaryExpressionState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_binary_expression(self, expr, arg)
+    }
+
+    fn visit_call_expression(
+        &mut self,
+        expr: &'a CallExpressionState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_call_expression(self, expr, arg)
+    }
+
+    fn visit_character_expression(
+        &mut self,
+        expr: &'a CharacterExpressionState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_character_expression(self, expr, arg)
+    }
+
+    fn visit_empty_expression(
+        &mut self,
+        _expr: &'a EmptyExpressionState,
+        _arg: Self::Arg,
+    ) -> Self::Output {
+        Self::Output::default()
+    }
+
+    fn visit_if_expression(&mut self, expr: &'a IfExpressionState, arg: Self::Arg) -> Self::Output {
+        walk_if_expression(self, expr, arg)
+    }
+
+    fn visit_integer_expression(
+        &mut self,
+        expr: &'a IntegerExpressionState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_integer_expression(self, expr, arg)
+    }
+
+    fn visit_let_expression(
+        &mut self,
+        expr: &'a LetExpressionState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_let_expression(self, expr, arg)
+    }
+
+    fn visit_record_expression(
+        &mut self,
+        expr: &'a RecordExpressionState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_record_expression(self, expr, arg)
+    }
+
+    fn visit_unary_expression(
+        &mut self,
+        expr: &'a UnaryExpressionState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_unary_expression(self, expr, arg)
+    }
+
+    fn visit_vname_expression(
+        &mut self,
+        expr: &'a VnameExpressionState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_vname_expression(self, expr, arg)
+    }
+
+    fn visit_array_aggregate(
+        &mut self,
+        agg: &'a Child<ArrayAggregate>,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_array_aggregate(self, agg.get(self.nodes()), arg)
+    }
+
+    fn visit_single_array_aggregate(
+        &mut self,
+        agg: &'a SingleArrayAggregateState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_single_array_aggregate(self, agg, arg)
+    }
+
+    fn visit_multiple_array_aggregate(
+        &mut self,
+        agg: &'a MultipleArrayAggregateState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_multiple_array_aggregate(self, agg, arg)
+    }
+
+    fn visit_record_aggregate(
+        &mut self,
+        agg: &'a Child<RecordAggregate>,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_record_aggregate(self, agg.get(self.nodes()), arg)
+    }
+
+    fn visit_single_record_aggregate(
+        &mut self,
+        agg: &'a SingleRecordAggregateState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_single_record_aggregate(self, agg, arg)
+    }
+
+    fn visit_multiple_record_aggregate(
+        &mut self,
+        agg: &'a MultipleRecordAggregateState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_multiple_record_aggregate(self, agg, arg)
+    }
+
+    fn visit_declaration(&mut self, decl: &'a Child<Declaration>, arg: Self::Arg) -> Self::Output {
+        walk_declaration(self, decl.get(self.nodes()), arg)
+    }
+
+    fn visit_binary_operator_declaration(
+        &mut self,
+        decl: &'a BinaryOperatorDeclarationState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_binary_operator_declaration(self, decl, arg)
+    }
+
+    fn visit_const_declaration(
+        &mut self,
+        decl: &'a ConstDeclarationState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_const_declaration(self, decl, arg)
+    }
+
+    fn visit_func_declaration(
+        &mut self,
+        decl: &'a FuncDeclarationState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_func_declaration(self, decl, arg)
+    }
+
+    fn visit_proc_declaration(
+        &mut self,
+        decl: &'a ProcDeclarationState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_proc_declaration(self, decl, arg)
+    }
+
+    fn visit_sequential_declaration(
+        &mut self,
+        decl: &'a SequentialDeclarationState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_sequential_declaration(self, decl, arg)
+    }
+
+    fn visit_type_declaration(
+        &mut self,
+        decl: &'a TypeDeclarationState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_type_declaration(self, decl, arg)
+    }
+
+    fn visit_unary_operator_declaration(
+        &mut self,
+        decl: &'a UnaryOperatorDeclarationState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_unary_operator_declaration(self, decl, arg)
+    }
+
+    fn visit_var_declaration(
+        &mut self,
+        decl: &'a VarDeclarationState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_var_declaration(self, decl, arg)
+    }
+
+    fn visit_type_denoter(&mut self, td: &'a TypeDenoter, arg: Self::Arg) -> Self::Output {
+        walk_type_denoter(self, td, arg)
+    }
+
+    fn visit_any_type_denoter(
+        &mut self,
+        _td: &'a AnyTypeDenoterState,
+        _arg: Self::Arg,
+    ) -> Self::Output {
+        Self::Output::default()
+    }
+
+    fn visit_array_type_denoter(
+        &mut self,
+        td: &'a ArrayTypeDenoterState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_array_type_denoter(self, td, arg)
+    }
+
+    fn visit_bool_type_denoter(
+        &mut self,
+        _td: &'a BoolTypeDenoterState,
+        _arg: Self::Arg,
+    ) -> Self::Output {
+        Self::Output::default()
+    }
+
+    fn visit_char_type_denoter(
+        &mut self,
+        _td: &'a CharTypeDenoterState,
+        _arg: Self::Arg,
+    ) -> Self::Output {
+        Self::Output::default()
+    }
+
+    fn visit_error_type_denoter(
+        &mut self,
+        _td: &'a ErrorTypeDenoterState,
+        _arg: Self::Arg,
+    ) -> Self::Output {
+        Self::Output::default()
+    }
+
+    fn visit_int_type_denoter(
+        &mut self,
+        _td: &'a IntTypeDenoterState,
+        _arg: Self::Arg,
+    ) -> Self::Output {
+        Self::Output::default()
+    }
+
+    fn visit_record_type_denoter(
+        &mut self,
+        td: &'a RecordTypeDenoterState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_record_type_denoter(self, td, arg)
+    }
+
+    fn visit_simple_type_denoter(
+        &mut self,
+        td: &'a SimpleTypeDenoterState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_simple_type_denoter(self, td, arg)
+    }
+
+    fn visit_field_type_denoter(
+        &mut self,
+        td: &'a FieldTypeDenoter,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_field_type_denoter(self, td, arg)
+    }
+
+    fn visit_single_field_type_denoter(
+        &mut self,
+        td: &'a SingleFieldTypeDenoterState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_single_field_type_denoter(self, td, arg)
+    }
+
+    fn visit_multiple_field_type_denoter(
+        &mut self,
+        td: &'a MultipleFieldTypeDenoterState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_multiple_field_type_denoter(self, td, arg)
+    }
+
+    fn visit_formal_parameter_sequence(
+        &mut self,
+        fps: &'a Child<FormalParameterSequence>,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_formal_parameter_sequence(self, fps.get(self.nodes()), arg)
+    }
+
+    fn visit_empty_formal_parameter_sequence(
+        &mut self,
+        _fps: &'a EmptyFormalParameterSequenceState,
+        _arg: Self::Arg,
+    ) -> Self::Output {
+        Self::Output::default()
+    }
+
+    fn visit_single_formal_parameter_sequence(
+        &mut self,
+        fps: &'a SingleFormalParameterSequenceState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_single_formal_parameter_sequence(self, fps, arg)
+    }
+
+    fn visit_multiple_formal_parameter_sequence(
+        &mut self,
+        fps: &'a MultipleFormalParameterSequenceState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_multiple_formal_parameter_sequence(self, fps, arg)
+    }
+
+    fn visit_formal_parameter(
+        &mut self,
+        fp: &'a Child<FormalParameter>,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_formal_parameter(self, fp.get(self.nodes()), arg)
+    }
+
+    fn visit_const_formal_parameter(
+        &mut self,
+        fp: &'a ConstFormalParameterState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_const_formal_parameter(self, fp, arg)
+    }
+
+    fn visit_func_formal_parameter(
+        &mut self,
+        fp: &'a FuncFormalParameterState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_func_formal_parameter(self, fp, arg)
+    }
+
+    fn visit_proc_formal_parameter(
+        &mut self,
+        fp: &'a ProcFormalParameterState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_proc_formal_parameter(self, fp, arg)
+    }
+
+    fn visit_var_formal_parameter(
+        &mut self,
+        fp: &'a VarFormalParameterState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_var_formal_parameter(self, fp, arg)
+    }
+
+    fn visit_actual_parameter_sequence(
+        &mut self,
+        aps: &'a Child<ActualParameterSequence>,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_actual_parameter_sequence(self, aps.get(self.nodes()), arg)
+    }
+
+    fn visit_empty_actual_parameter_sequence(
+        &mut self,
+        _aps: &'a EmptyActualParameterSequenceState,
+        _arg: Self::Arg,
+    ) -> Self::Output {
+        Self::Output::default()
+    }
+
+    fn visit_single_actual_parameter_sequence(
+        &mut self,
+        aps: &'a SingleActualParameterSequenceState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_single_actual_parameter_sequence(self, aps, arg)
+    }
+
+    fn visit_multiple_actual_parameter_sequence(
+        &mut self,
+        aps: &'a MultipleActualParameterSequenceState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_multiple_actual_parameter_sequence(self, aps, arg)
+    }
+
+    fn visit_actual_parameter(
+        &mut self,
+        ap: &'a Child<ActualParameter>,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_actual_parameter(self, ap.get(self.nodes()), arg)
+    }
+
+    fn visit_const_actual_parameter(
+        &mut self,
+        ap: &'a ConstActualParameterState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_const_actual_parameter(self, ap, arg)
+    }
+
+    fn visit_func_actual_parameter(
+        &mut self,
+        ap: &'a FuncActualParameterState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_func_actual_parameter(self, ap, arg)
+    }
+
+    fn visit_proc_actual_parameter(
+        &mut self,
+        ap: &'a ProcActualParameterState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_proc_actual_parameter(self, ap, arg)
+    }
+
+    fn visit_var_actual_parameter(
+        &mut self,
+        ap: &'a VarActualParameterState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_var_actual_parameter(self, ap, arg)
+    }
+
+    fn visit_vname(&mut self, vname: &'a Child<Vname>, arg: Self::Arg) -> Self::Output {
+        walk_vname(self, vname.get(self.nodes()), arg)
+    }
+
+    fn visit_dot_vname(&mut self, vname: &'a DotVnameState, arg: Self::Arg) -> Self::Output {
+        walk_dot_vname(self, vname, arg)
+    }
+
+    fn visit_simple_vname(&mut self, vname: &'a SimpleVnameState, arg: Self::Arg) -> Self::Output {
+        walk_simple_vname(self, vname, arg)
+    }
+
+    fn visit_subscript_vname(
+        &mut self,
+        vname: &'a SubscriptVnameState,
+        arg: Self::Arg,
+    ) -> Self::Output {
+        walk_subscript_vname(self, vname, arg)
+    }
+
+    fn visit_identifier(&mut self, _id: &'a Identifier, _arg: Self::Arg) -> Self::Output {
+        Self::Output::default()
+    }
+
+    fn visit_operator(&mut self, _op: &'a Operator, _arg: Self::Arg) -> Self::Output {
+        Self::Output::default()
+    }
+
+    fn visit_integer_literal(&mut self, _il: &'a IntegerLiteral, _arg: Self::Arg) -> Self::Output {
+        Self::Output::default()
+    }
+
+    fn visit_character_literal(
+        &mut self,
+        _cl: &'a CharacterLiteral,
+        _arg: Self::Arg,
+    ) -> Self::Output {
+        Self::Output::default()
+    }
+}
+
+pub fn walk_program<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    program: &'a Program,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_command(&program.cmd, arg);
+    V::Output::default()
+}
+
+pub fn walk_command<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    cmd: &'a Command,
+    arg: V::Arg,
+) -> V::Output {
+    match *cmd {
+        Command::AssignCommand(ref state) => visitor.visit_assign_command(state, arg),
+        Command::CallCommand(ref state) => visitor.visit_call_command(state, arg),
+        Command::EmptyCommand(ref state) => visitor.visit_empty_command(state, arg),
+        Command::IfCommand(ref state) => visitor.visit_if_command(state, arg),
+        Command::LetCommand(ref state) => visitor.visit_let_command(state, arg),
+        Command::SequentialCommand(ref state) => visitor.visit_sequential_command(state, arg),
+        Command::WhileCommand(ref state) => visitor.visit_while_command(state, arg),
+    }
+}
+
+pub fn walk_assign_command<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    cmd: &'a AssignCommandState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_vname(&cmd.vname, arg.clone());
+    visitor.visit_expression(&cmd.expr, arg);
+    V::Output::default()
+}
+
+pub fn walk_call_command<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    cmd: &'a CallCommandState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_identifier(&cmd.id, arg.clone());
+    visitor.visit_actual_parameter_sequence(&cmd.aps, arg);
+    V::Output::default()
+}
+
+pub fn walk_if_command<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    cmd: &'a IfCommandState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_expression(&cmd.expr, arg.clone());
+    visitor.visit_command(&cmd.cmd1, arg.clone());
+    visitor.visit_command(&cmd.cmd2, arg);
+    V::Output::default()
+}
+
+pub fn walk_let_command<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    cmd: &'a LetCommandState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_declaration(&cmd.decl, arg.clone());
+    visitor.visit_command(&cmd.cmd, arg);
+    V::Output::default()
+}
+
+pub fn walk_sequential_command<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    cmd: &'a SequentialCommandState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_command(&cmd.cmd1, arg.clone());
+    visitor.visit_command(&cmd.cmd2, arg);
+    V::Output::default()
+}
+
+pub fn walk_while_command<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    cmd: &'a WhileCommandState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_expression(&cmd.expr, arg.clone());
+    visitor.visit_command(&cmd.cmd, arg);
+    V::Output::default()
+}
+
+pub fn walk_expression<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    expr: &'a Expression,
+    arg: V::Arg,
+) -> V::Output {
+    match *expr {
+        Expression::ArrayExpression(ref state) => visitor.visit_array_expression(state, arg),
+        Expression::BinaryExpression(ref state) => visitor.visit_binary_expression(state, arg),
+        Expression::CallExpression(ref state) => visitor.visit_call_expression(state, arg),
+        Expression::CharacterExpression(ref state) => {
+            visitor.visit_character_expression(state, arg)
+        }
+        Expression::EmptyExpression(ref state) => visitor.visit_empty_expression(state, arg),
+        Expression::IfExpression(ref state) => visitor.visit_if_expression(state, arg),
+        Expression::IntegerExpression(ref state) => visitor.visit_integer_expression(state, arg),
+        Expression::LetExpression(ref state) => visitor.visit_let_expression(state, arg),
+        Expression::RecordExpression(ref state) => visitor.visit_record_expression(state, arg),
+        Expression::UnaryExpression(ref state) => visitor.visit_unary_expression(state, arg),
+        Expression::VnameExpression(ref state) => visitor.visit_vname_expression(state, arg),
+    }
+}
+
+pub fn walk_array_expression<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    expr: &'a ArrayExpressionState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_array_aggregate(&expr.aa, arg);
+    V::Output::default()
+}
+
+pub fn walk_binary_expression<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    expr: &'a BinaryExpressionState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_expression(&expr.expr1, arg.clone());
+    visitor.visit_operator(&expr.op, arg.clone());
+    visitor.visit_expression(&expr.expr2, arg);
+    V::Output::default()
+}
+
+pub fn walk_call_expression<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    expr: &'a CallExpressionState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_identifier(&expr.id, arg.clone());
+    visitor.visit_actual_parameter_sequence(&expr.aps, arg);
+    V::Output::default()
+}
+
+pub fn walk_character_expression<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    expr: &'a CharacterExpressionState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_character_literal(&expr.cl, arg);
+    V::Output::default()
+}
+
+pub fn walk_if_expression<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    expr: &'a IfExpressionState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_expression(&expr.expr1, arg.clone());
+    visitor.visit_expression(&expr.expr2, arg.clone());
+    visitor.visit_expression(&expr.expr3, arg);
+    V::Output::default()
+}
+
+pub fn walk_integer_expression<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    expr: &'a IntegerExpressionState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_integer_literal(&expr.il, arg);
+    V::Output::default()
+}
+
+pub fn walk_let_expression<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    expr: &'a LetExpressionState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_declaration(&expr.decl, arg.clone());
+    visitor.visit_expression(&expr.expr, arg);
+    V::Output::default()
+}
+
+pub fn walk_record_expression<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    expr: &'a RecordExpressionState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_record_aggregate(&expr.ra, arg);
+    V::Output::default()
+}
+
+pub fn walk_unary_expression<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    expr: &'a UnaryExpressionState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_operator(&expr.op, arg.clone());
+    visitor.visit_expression(&expr.expr, arg);
+    V::Output::default()
+}
+
+pub fn walk_vname_expression<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    expr: &'a VnameExpressionState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_vname(&expr.vname, arg);
+    V::Output::default()
+}
+
+pub fn walk_array_aggregate<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    agg: &'a ArrayAggregate,
+    arg: V::Arg,
+) -> V::Output {
+    match *agg {
+        ArrayAggregate::SingleArrayAggregate(ref state) => {
+            visitor.visit_single_array_aggregate(state, arg)
+        }
+        ArrayAggregate::MultipleArrayAggregate(ref state) => {
+            visitor.visit_multiple_array_aggregate(state, arg)
+        }
+    }
+}
+
+pub fn walk_single_array_aggregate<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    agg: &'a SingleArrayAggregateState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_expression(&agg.expr, arg);
+    V::Output::default()
+}
+
+pub fn walk_multiple_array_aggregate<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    agg: &'a MultipleArrayAggregateState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_expression(&agg.expr, arg.clone());
+    visitor.visit_array_aggregate(&agg.aa, arg);
+    V::Output::default()
+}
+
+pub fn walk_record_aggregate<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    agg: &'a RecordAggregate,
+    arg: V::Arg,
+) -> V::Output {
+    match *agg {
+        RecordAggregate::SingleRecordAggregate(ref state) => {
+            visitor.visit_single_record_aggregate(state, arg)
+        }
+        RecordAggregate::MultipleRecordAggregate(ref state) => {
+            visitor.visit_multiple_record_aggregate(state, arg)
+        }
+    }
+}
+
+pub fn walk_single_record_aggregate<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    agg: &'a SingleRecordAggregateState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_identifier(&agg.id, arg.clone());
+    visitor.visit_expression(&agg.expr, arg);
+    V::Output::default()
+}
+
+pub fn walk_multiple_record_aggregate<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    agg: &'a MultipleRecordAggregateState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_identifier(&agg.id, arg.clone());
+    visitor.visit_expression(&agg.expr, arg.clone());
+    visitor.visit_record_aggregate(&agg.ra, arg);
+    V::Output::default()
+}
+
+pub fn walk_declaration<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    decl: &'a Declaration,
+    arg: V::Arg,
+) -> V::Output {
+    match *decl {
+        Declaration::BinaryOperatorDeclaration(ref state) => {
+            visitor.visit_binary_operator_declaration(state, arg)
+        }
+        Declaration::ConstDeclaration(ref state) => visitor.visit_const_declaration(state, arg),
+        Declaration::FormalParameterDeclaration(ref fp) => walk_formal_parameter(visitor, fp, arg),
+        Declaration::FuncDeclaration(ref state) => visitor.visit_func_declaration(state, arg),
+        Declaration::ProcDeclaration(ref state) => visitor.visit_proc_declaration(state, arg),
+        Declaration::SequentialDeclaration(ref state) => {
+            visitor.visit_sequential_declaration(state, arg)
+        }
+        Declaration::TypeDeclaration(ref state) => visitor.visit_type_declaration(state, arg),
+        Declaration::UnaryOperatorDeclaration(ref state) => {
+            visitor.visit_unary_operator_declaration(state, arg)
+        }
+        Declaration::VarDeclaration(ref state) => visitor.visit_var_declaration(state, arg),
+    }
+}
+
+pub fn walk_binary_operator_declaration<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    decl: &'a BinaryOperatorDeclarationState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_type_denoter(&decl.arg1_type, arg.clone());
+    visitor.visit_operator(&decl.op, arg.clone());
+    visitor.visit_type_denoter(&decl.arg2_type, arg.clone());
+    visitor.visit_type_denoter(&decl.res_type, arg);
+    V::Output::default()
+}
+
+pub fn walk_const_declaration<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    decl: &'a ConstDeclarationState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_identifier(&decl.id, arg.clone());
+    visitor.visit_expression(&decl.expr, arg);
+    V::Output::default()
+}
+
+pub fn walk_func_declaration<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    decl: &'a FuncDeclarationState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_identifier(&decl.id, arg.clone());
+    visitor.visit_formal_parameter_sequence(&decl.fps, arg.clone());
+    visitor.visit_type_denoter(&decl.td, arg.clone());
+    visitor.visit_expression(&decl.expr, arg);
+    V::Output::default()
+}
+
+pub fn walk_proc_declaration<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    decl: &'a ProcDeclarationState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_identifier(&decl.id, arg.clone());
+    visitor.visit_formal_parameter_sequence(&decl.fps, arg.clone());
+    visitor.visit_command(&decl.cmd, arg);
+    V::Output::default()
+}
+
+pub fn walk_sequential_declaration<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    decl: &'a SequentialDeclarationState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_declaration(&decl.decl1, arg.clone());
+    visitor.visit_declaration(&decl.decl2, arg);
+    V::Output::default()
+}
+
+pub fn walk_type_declaration<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    decl: &'a TypeDeclarationState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_identifier(&decl.id, arg.clone());
+    visitor.visit_type_denoter(&decl.td, arg);
+    V::Output::default()
+}
+
+pub fn walk_unary_operator_declaration<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    decl: &'a UnaryOperatorDeclarationState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_operator(&decl.op, arg.clone());
+    visitor.visit_type_denoter(&decl.argtype, arg.clone());
+    visitor.visit_type_denoter(&decl.res_type, arg);
+    V::Output::default()
+}
+
+pub fn walk_var_declaration<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    decl: &'a VarDeclarationState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_identifier(&decl.id, arg.clone());
+    visitor.visit_type_denoter(&decl.td, arg);
+    V::Output::default()
+}
+
+pub fn walk_type_denoter<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    td: &'a TypeDenoter,
+    arg: V::Arg,
+) -> V::Output {
+    match *td {
+        TypeDenoter::AnyTypeDenoter(ref state) => visitor.visit_any_type_denoter(state, arg),
+        TypeDenoter::ArrayTypeDenoter(ref state) => visitor.visit_array_type_denoter(state, arg),
+        TypeDenoter::BoolTypeDenoter(ref state) => visitor.visit_bool_type_denoter(state, arg),
+        TypeDenoter::CharTypeDenoter(ref state) => visitor.visit_char_type_denoter(state, arg),
+        TypeDenoter::ErrorTypeDenoter(ref state) => visitor.visit_error_type_denoter(state, arg),
+        TypeDenoter::IntTypeDenoter(ref state) => visitor.visit_int_type_denoter(state, arg),
+        TypeDenoter::RecordTypeDenoter(ref state) => visitor.visit_record_type_denoter(state, arg),
+        TypeDenoter::SimpleTypeDenoter(ref state) => visitor.visit_simple_type_denoter(state, arg),
+    }
+}
+
+pub fn walk_array_type_denoter<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    td: &'a ArrayTypeDenoterState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_integer_literal(&td.il, arg.clone());
+    visitor.visit_type_denoter(&td.td, arg);
+    V::Output::default()
+}
+
+pub fn walk_record_type_denoter<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    td: &'a RecordTypeDenoterState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_field_type_denoter(&td.ftd, arg);
+    V::Output::default()
+}
+
+pub fn walk_simple_type_denoter<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    td: &'a SimpleTypeDenoterState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_identifier(&td.id, arg);
+    V::Output::default()
+}
+
+pub fn walk_field_type_denoter<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    td: &'a FieldTypeDenoter,
+    arg: V::Arg,
+) -> V::Output {
+    match *td {
+        FieldTypeDenoter::SingleFieldTypeDenoter(ref state) => {
+            visitor.visit_single_field_type_denoter(state, arg)
+        }
+        FieldTypeDenoter::MultipleFieldTypeDenoter(ref state) => {
+            visitor.visit_multiple_field_type_denoter(state, arg)
+        }
+    }
+}
+
+pub fn walk_single_field_type_denoter<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    td: &'a SingleFieldTypeDenoterState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_identifier(&td.id, arg.clone());
+    visitor.visit_type_denoter(&td.td, arg);
+    V::Output::default()
+}
+
+pub fn walk_multiple_field_type_denoter<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    td: &'a MultipleFieldTypeDenoterState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_identifier(&td.id, arg.clone());
+    visitor.visit_type_denoter(&td.td, arg.clone());
+    visitor.visit_field_type_denoter(&td.ftd, arg);
+    V::Output::default()
+}
+
+pub fn walk_formal_parameter_sequence<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    fps: &'a FormalParameterSequence,
+    arg: V::Arg,
+) -> V::Output {
+    match *fps {
+        FormalParameterSequence::EmptyFormalParameterSequence(ref state) => {
+            visitor.visit_empty_formal_parameter_sequence(state, arg)
+        }
+        FormalParameterSequence::SingleFormalParameterSequence(ref state) => {
+            visitor.visit_single_formal_parameter_sequence(state, arg)
+        }
+        FormalParameterSequence::MultipleFormalParameterSequence(ref state) => {
+            visitor.visit_multiple_formal_parameter_sequence(state, arg)
+        }
+    }
+}
+
+pub fn walk_single_formal_parameter_sequence<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    fps: &'a SingleFormalParameterSequenceState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_formal_parameter(&fps.fp, arg);
+    V::Output::default()
+}
+
+pub fn walk_multiple_formal_parameter_sequence<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    fps: &'a MultipleFormalParameterSequenceState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_formal_parameter(&fps.fp, arg.clone());
+    visitor.visit_formal_parameter_sequence(&fps.fps, arg);
+    V::Output::default()
+}
+
+pub fn walk_formal_parameter<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    fp: &'a FormalParameter,
+    arg: V::Arg,
+) -> V::Output {
+    match *fp {
+        FormalParameter::ConstFormalParameter(ref state) => {
+            visitor.visit_const_formal_parameter(state, arg)
+        }
+        FormalParameter::FuncFormalParameter(ref state) => {
+            visitor.visit_func_formal_parameter(state, arg)
+        }
+        FormalParameter::ProcFormalParameter(ref state) => {
+            visitor.visit_proc_formal_parameter(state, arg)
+        }
+        FormalParameter::VarFormalParameter(ref state) => {
+            visitor.visit_var_formal_parameter(state, arg)
+        }
+    }
+}
+
+pub fn walk_const_formal_parameter<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    fp: &'a ConstFormalParameterState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_identifier(&fp.id, arg.clone());
+    visitor.visit_type_denoter(&fp.td, arg);
+    V::Output::default()
+}
+
+pub fn walk_func_formal_parameter<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    fp: &'a FuncFormalParameterState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_identifier(&fp.id, arg.clone());
+    visitor.visit_formal_parameter_sequence(&fp.fps, arg.clone());
+    visitor.visit_type_denoter(&fp.td, arg);
+    V::Output::default()
+}
+
+pub fn walk_proc_formal_parameter<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    fp: &'a ProcFormalParameterState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_identifier(&fp.id, arg.clone());
+    visitor.visit_formal_parameter_sequence(&fp.fps, arg);
+    V::Output::default()
+}
+
+pub fn walk_var_formal_parameter<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    fp: &'a VarFormalParameterState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_identifier(&fp.id, arg.clone());
+    visitor.visit_type_denoter(&fp.td, arg);
+    V::Output::default()
+}
+
+pub fn walk_actual_parameter_sequence<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    aps: &'a ActualParameterSequence,
+    arg: V::Arg,
+) -> V::Output {
+    match *aps {
+        ActualParameterSequence::EmptyActualParameterSequence(ref state) => {
+            visitor.visit_empty_actual_parameter_sequence(state, arg)
+        }
+        ActualParameterSequence::SingleActualParameterSequence(ref state) => {
+            visitor.visit_single_actual_parameter_sequence(state, arg)
+        }
+        ActualParameterSequence::MultipleActualParameterSequence(ref state) => {
+            visitor.visit_multiple_actual_parameter_sequence(state, arg)
+        }
+    }
+}
+
+pub fn walk_single_actual_parameter_sequence<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    aps: &'a SingleActualParameterSequenceState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_actual_parameter(&aps.ap, arg);
+    V::Output::default()
+}
+
+pub fn walk_multiple_actual_parameter_sequence<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    aps: &'a MultipleActualParameterSequenceState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_actual_parameter(&aps.ap, arg.clone());
+    visitor.visit_actual_parameter_sequence(&aps.aps, arg);
+    V::Output::default()
+}
+
+pub fn walk_actual_parameter<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    ap: &'a ActualParameter,
+    arg: V::Arg,
+) -> V::Output {
+    match *ap {
+        ActualParameter::ConstActualParameter(ref state) => {
+            visitor.visit_const_actual_parameter(state, arg)
+        }
+        ActualParameter::FuncActualParameter(ref state) => {
+            visitor.visit_func_actual_parameter(state, arg)
+        }
+        ActualParameter::ProcActualParameter(ref state) => {
+            visitor.visit_proc_actual_parameter(state, arg)
+        }
+        ActualParameter::VarActualParameter(ref state) => {
+            visitor.visit_var_actual_parameter(state, arg)
+        }
+    }
+}
+
+pub fn walk_const_actual_parameter<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    ap: &'a ConstActualParameterState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_expression(&ap.expr, arg);
+    V::Output::default()
+}
+
+pub fn walk_func_actual_parameter<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    ap: &'a FuncActualParameterState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_identifier(&ap.id, arg);
+    V::Output::default()
+}
+
+pub fn walk_proc_actual_parameter<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    ap: &'a ProcActualParameterState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_identifier(&ap.id, arg);
+    V::Output::default()
+}
+
+pub fn walk_var_actual_parameter<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    ap: &'a VarActualParameterState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_vname(&ap.vname, arg);
+    V::Output::default()
+}
+
+pub fn walk_vname<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    vname: &'a Vname,
+    arg: V::Arg,
+) -> V::Output {
+    match *vname {
+        Vname::DotVname(ref state) => visitor.visit_dot_vname(state, arg),
+        Vname::SimpleVname(ref state) => visitor.visit_simple_vname(state, arg),
+        Vname::SubscriptVname(ref state) => visitor.visit_subscript_vname(state, arg),
+    }
+}
+
+pub fn walk_dot_vname<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    vname: &'a DotVnameState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_vname(&vname.vname, arg.clone());
+    visitor.visit_identifier(&vname.id, arg);
+    V::Output::default()
+}
+
+pub fn walk_simple_vname<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    vname: &'a SimpleVnameState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_identifier(&vname.id, arg);
+    V::Output::default()
+}
+
+pub fn walk_subscript_vname<'a, V: AstRefVisitor<'a> + ?Sized>(
+    visitor: &mut V,
+    vname: &'a SubscriptVnameState,
+    arg: V::Arg,
+) -> V::Output {
+    visitor.visit_vname(&vname.vname, arg.clone());
+    visitor.visit_expression(&vname.expr, arg);
+    V::Output::default()
+}
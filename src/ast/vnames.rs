@@ -1,8 +1,9 @@
 //! vname asts
 
+use super::arena::Child;
 use super::expressions::Expression;
 use super::primitives::Identifier;
-use super::{Ast, AstObject, AstVisitor, CommonState};
+use super::{Ast, AstVisitor, CommonState};
 use crate::scanner::SourcePosition;
 use std::fmt;
 
@@ -14,6 +15,16 @@ pub enum Vname {
 }
 
 impl Vname {
+    pub fn common_state(&self) -> &CommonState {
+        use Vname::*;
+
+        match *self {
+            DotVname(ref state) => &state.common_state,
+            SimpleVname(ref state) => &state.common_state,
+            SubscriptVname(ref state) => &state.common_state,
+        }
+    }
+
     pub fn get_dot_vname(&self) -> Option<&DotVnameState> {
         match *self {
             Vname::DotVname(ref dot) => Some(&dot),
@@ -64,7 +75,7 @@ impl fmt::Display for Vname {
 }
 
 impl Ast for Vname {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         use Vname::*;
 
         match *self {
@@ -78,6 +89,8 @@ impl Ast for Vname {
 #[derive(Debug, Clone)]
 pub struct SimpleVnameState {
     pub id: Identifier,
+    pub offset: usize,
+    pub indexed: bool,
     pub common_state: CommonState,
 }
 
@@ -85,6 +98,8 @@ impl SimpleVnameState {
     pub fn new(id: Identifier) -> Self {
         SimpleVnameState {
             id: id,
+            offset: 0,
+            indexed: false,
             common_state: CommonState::default(),
         }
     }
@@ -111,22 +126,26 @@ impl fmt::Display for SimpleVnameState {
 }
 
 impl Ast for SimpleVnameState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_simple_vname(self, arg)
     }
 }
 #[derive(Debug, Clone)]
 pub struct SubscriptVnameState {
-    pub vname: Box<Vname>,
-    pub expr: Box<Expression>,
+    pub vname: Child<Vname>,
+    pub expr: Child<Expression>,
+    pub offset: usize,
+    pub indexed: bool,
     pub common_state: CommonState,
 }
 
 impl SubscriptVnameState {
     pub fn new(vname: Vname, expr: Expression) -> Self {
         SubscriptVnameState {
-            vname: Box::new(vname),
-            expr: Box::new(expr),
+            vname: Child::new(vname),
+            expr: Child::new(expr),
+            offset: 0,
+            indexed: false,
             common_state: CommonState::default(),
         }
     }
@@ -153,23 +172,27 @@ impl fmt::Display for SubscriptVnameState {
 }
 
 impl Ast for SubscriptVnameState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_subscript_vname(self, arg)
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct DotVnameState {
-    pub vname: Box<Vname>,
+    pub vname: Child<Vname>,
     pub id: Identifier,
+    pub offset: usize,
+    pub indexed: bool,
     pub common_state: CommonState,
 }
 
 impl DotVnameState {
     pub fn new(vname: Vname, id: Identifier) -> Self {
         DotVnameState {
-            vname: Box::new(vname),
+            vname: Child::new(vname),
             id: id,
+            offset: 0,
+            indexed: false,
             common_state: CommonState::default(),
         }
     }
@@ -196,7 +219,7 @@ impl fmt::Display for DotVnameState {
 }
 
 impl Ast for DotVnameState {
-    fn accept(&mut self, visitor: &mut dyn AstVisitor, arg: AstObject) -> AstObject {
+    fn accept<V: AstVisitor + ?Sized>(&mut self, visitor: &mut V, arg: V::Arg) -> V::Output {
         visitor.visit_dot_vname(self, arg)
     }
 }
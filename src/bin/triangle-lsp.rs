@@ -0,0 +1,19 @@
+//! The `triangle-lsp` language server
+//!
+//! Serves an editor over stdin and stdout, publishing the errors in the Triangle source files
+//! that it has open. See the `lsp` module for the details.
+
+use triangle_rs::error;
+use triangle_rs::lsp;
+
+use std::io;
+use std::process;
+
+fn main() {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    match lsp::serve(stdin.lock(), stdout.lock()) {
+        Ok(code) => process::exit(code),
+        Err(err) => error::report_error_and_exit(err),
+    }
+}
@@ -1,82 +1,192 @@
-use crate::ast::declarations::Declaration;
-use crate::error;
-use std::collections::HashMap;
+//! The Identification Table
+//!
+//! A stack of scopes, mapping each identifier that is declared in a scope to the NodeId of its
+//! declaration.
+//! The scope at level 0 holds the standard environment, and each let, procedure, and function
+//! opens a scope of its own on top of it. Every entry records the level and the position that
+//! it was declared at.
+//!
+//! Scopes are kept when they are closed, along with the span of source that they cover, so that
+//! once a program has been checked the table can still tell what was visible at any position
+//! in it.
+
+use crate::ast::NodeId;
+use crate::error::{CheckerError, GenError, GenResult};
+use crate::scanner::{Position, SourcePosition};
+
+use std::cmp::Reverse;
+use std::collections::HashSet;
 use std::fmt;
 
-type Level = u8;
+pub type Level = usize;
+
+/// an identifier along with the declaration that it is bound to
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub id: String,
+    pub decl: NodeId,
+    pub level: Level,
+    /// where the identifier was declared - positions before line 1 mean that it is a part of
+    /// the standard environment
+    pub position: SourcePosition,
+}
+
+/// the identifiers declared within a span of source, in the order that they were declared
+#[derive(Debug, Clone)]
+pub struct Scope {
+    pub level: Level,
+    pub span: SourcePosition,
+    pub entries: Vec<Entry>,
+}
+
+impl Scope {
+    fn new(level: Level, span: SourcePosition) -> Self {
+        Scope {
+            level,
+            span,
+            entries: Vec::new(),
+        }
+    }
+
+    fn get(&self, id: &str) -> Option<&Entry> {
+        self.entries.iter().find(|entry| entry.id == id)
+    }
+}
 
 /// The Identification Table contains metadats about all the
 /// applied occurrences in a program - it maps a given applied occurrence
 /// to its declaration in the AST, if present.
 pub struct IdentificationTable {
-    table: HashMap<Level, HashMap<String, Box<Declaration>>>,
-    curr_level: Level,
+    /// the open scopes, innermost last. The standard environment is always at the bottom.
+    scopes: Vec<Scope>,
+    /// the scopes that have been closed, in the order that they were closed
+    closed: Vec<Scope>,
 }
 
 impl IdentificationTable {
     pub fn new() -> Self {
         IdentificationTable {
-            table: HashMap::new(),
-            curr_level: 0,
+            scopes: vec![Scope::new(0, SourcePosition::default())],
+            closed: Vec::new(),
         }
     }
 
-    pub fn enter(&mut self, id: &str, val: Declaration) {
-        if !self.table.contains_key(&self.curr_level) {
-            self.table.insert(self.curr_level, HashMap::new());
-        }
+    /// the level of the innermost open scope
+    pub fn level(&self) -> Level {
+        self.scopes.len() - 1
+    }
 
-        if self.table.get(&self.curr_level).unwrap().contains_key(id) {
-            error::report_error_and_exit(error::GenError::from(error::CheckerError::new(
-                &format!(
-                    "identifier {} is already defined at level {}",
-                    id, self.curr_level
-                ),
-            )));
+    /// declare the identifier at the position in the innermost scope. An identifier can only be
+    /// declared once in each scope.
+    pub fn enter(&mut self, id: &str, decl: NodeId, position: SourcePosition) -> GenResult<()> {
+        let level = self.level();
+        let scope = self.scopes.last_mut().unwrap();
+
+        if let Some(prev) = scope.get(id) {
+            let err = CheckerError::new(
+                &format!("identifier {} is already defined at level {}", id, level),
+                position,
+            )
+            .with_note(&format!("{} was first defined here", id), prev.position);
+            return Err(GenError::from(err));
         }
 
-        self.table
-            .get_mut(&self.curr_level)
-            .unwrap()
-            .insert(id.to_owned(), Box::new(val));
+        scope.entries.push(Entry {
+            id: id.to_owned(),
+            decl,
+            level,
+            position,
+        });
+        Ok(())
     }
 
-    pub fn retrieve(&self, id: &String) -> Option<&Box<Declaration>> {
-        let mut level = self.curr_level;
+    /// the declaration that the identifier is bound to in the innermost scope declaring it
+    pub fn retrieve(&self, id: &str) -> Option<NodeId> {
+        self.lookup(id).map(|entry| entry.decl)
+    }
 
-        loop {
-            if let Some(mapping) = self.table.get(&level) {
-                if mapping.contains_key(id) {
-                    return self.table.get(&level).unwrap().get(id);
-                }
-            }
-            level -= 1;
+    /// look the identifier up in the current scope, and then in each enclosing scope down to the
+    /// standard environment at level 0
+    pub fn lookup(&self, id: &str) -> Option<&Entry> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(id))
+    }
 
-            if level == 0 {
-                return None;
-            }
-        }
+    /// the entry in an enclosing scope that a declaration of the identifier in the current scope
+    /// would hide, if there is one
+    pub fn shadowed(&self, id: &str) -> Option<&Entry> {
+        let enclosing = self.scopes.len() - 1;
+        self.scopes[..enclosing]
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(id))
     }
 
-    pub fn open_scope(&mut self) {
-        self.curr_level += 1;
+    /// open a scope that covers the given span of source
+    pub fn open_scope(&mut self, span: SourcePosition) {
+        let level = self.level() + 1;
+        self.scopes.push(Scope::new(level, span));
     }
 
+    /// close the innermost scope. The standard environment is never closed.
     pub fn close_scope(&mut self) {
-        self.table.remove_entry(&self.curr_level);
-        self.curr_level -= 1;
+        if self.scopes.len() > 1 {
+            let scope = self.scopes.pop().unwrap();
+            self.closed.push(scope);
+        }
+    }
+
+    /// every entry that is visible at this point of the check, innermost first. Entries hidden
+    /// by one of the same identifier in an inner scope are left out.
+    pub fn snapshot(&self) -> Vec<&Entry> {
+        visible(self.scopes.iter().rev(), |_| true)
+    }
+
+    /// every entry that is visible at the position in the source, innermost first, as it was
+    /// when the position was checked. Identifiers are visible from their declaration until
+    /// the end of the scope that they are declared in.
+    pub fn visible_at(&self, position: Position) -> Vec<&Entry> {
+        let mut scopes = self
+            .scopes
+            .iter()
+            .chain(self.closed.iter())
+            .filter(|scope| scope.level == 0 || scope.span.contains(position))
+            .collect::<Vec<_>>();
+        scopes.sort_by_key(|scope| Reverse(scope.level));
+
+        visible(scopes.into_iter(), |entry| {
+            entry.level == 0 || entry.position.start <= position
+        })
+    }
+}
+
+/// the entries of the scopes, innermost first, that pass the filter and are not hidden by an
+/// entry that comes before them
+fn visible<'a, I, F>(scopes: I, filter: F) -> Vec<&'a Entry>
+where
+    I: Iterator<Item = &'a Scope>,
+    F: Fn(&Entry) -> bool,
+{
+    let mut seen = HashSet::new();
+    let mut entries = Vec::new();
+    for scope in scopes {
+        for entry in scope.entries.iter().rev() {
+            if filter(entry) && seen.insert(entry.id.as_str()) {
+                entries.push(entry);
+            }
+        }
     }
+    entries
 }
 
 impl fmt::Display for IdentificationTable {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        for (level, entries) in &self.table {
-            let _ = writeln!(f, "Level {}", level);
+        for scope in &self.scopes {
+            writeln!(f, "Level {}", scope.level)?;
 
-            for (key, val) in entries {
-                let _ = writeln!(f, "{} => {}", key, *val);
+            for entry in &scope.entries {
+                writeln!(f, "{} => {}", entry.id, entry.decl)?;
             }
         }
-        writeln!(f, "{}", "")
+        writeln!(f)
     }
 }
@@ -1,709 +1,1617 @@
 //! The Checker module.
 //! This module traverses the AST generated by the Parser, checks that the AST is well-formed,
 //! and decorates it with links from applied occurrences of identfiers to their corresponding declarations,
-//! as well as expressions, type-denoters, and vnames with their type.
+//! as well as expressions, type-denoters, and vnames with their type. The decorations are kept in
+//! the side tables of the program, keyed by the NodeId of the node that they decorate.
 
 use crate::ast::aggregates::*;
+use crate::ast::arena::{Child, Nodes};
 use crate::ast::commands::*;
 use crate::ast::declarations::*;
+use crate::ast::decorations::Decorations;
 use crate::ast::expressions::*;
 use crate::ast::parameters::*;
 use crate::ast::primitives::*;
 use crate::ast::typedenoters::*;
+use crate::ast::visit_ref::AstRefVisitor;
 use crate::ast::vnames::*;
 use crate::ast::*;
-use crate::error::{report_error_and_exit, CheckerError, GenError};
+use crate::diagnostics::Diagnostics;
+use crate::error::{CheckerError, CheckerWarning, GenError, GenResult};
+use crate::scanner::SourcePosition;
+use crate::tam::MAX_INT;
 
-mod id_table;
+pub mod id_table;
 pub mod std_env;
 
 use id_table::IdentificationTable;
-use std_env::STANDARD_ENVIRONMENT;
+use std_env::StdEnvironment;
+
+use std::mem;
+use std::sync::Arc;
+
+/// what the Checker passes down to a node - the formal parameters that an actual parameter
+/// sequence, or an actual parameter, is checked against
+#[derive(Debug, Copy, Clone)]
+pub enum Formals<'a> {
+    None,
+    Sequence(&'a FormalParameterSequence),
+    Parameter(&'a FormalParameter),
+}
 
 pub struct Checker {
     id_table: IdentificationTable,
+    diagnostics: Diagnostics,
+    warnings: Diagnostics,
+    std_env: Arc<StdEnvironment>,
 }
 
 impl Checker {
+    /// a checker over the shared default standard environment
     pub fn new() -> Self {
-        let mut checker = Checker {
+        Checker::with_environment(StdEnvironment::shared())
+    }
+
+    /// a checker over the given standard environment. The environment is only ever read, so one
+    /// environment can be shared by checkers running on any number of threads.
+    pub fn with_environment(std_env: Arc<StdEnvironment>) -> Self {
+        Checker {
             id_table: IdentificationTable::new(),
-        };
-        checker.establish_standard_environment();
-        checker
+            diagnostics: Diagnostics::new(),
+            warnings: Diagnostics::new(),
+            std_env,
+        }
     }
 
-    /// set up the standard environment (the prelude)
-    fn establish_standard_environment(&mut self) {
-        self.id_table.enter(
-            "Integer",
-            STANDARD_ENVIRONMENT.lock().unwrap().int_decl.clone(),
-        );
+    /// the standard environment that this checker declares its programs in
+    pub fn std_env(&self) -> &StdEnvironment {
+        &self.std_env
+    }
 
-        self.id_table.enter(
-            "Char",
-            STANDARD_ENVIRONMENT.lock().unwrap().char_decl.clone(),
-        );
+    /// the symbol table, which still knows every scope of the last program once it has been
+    /// checked
+    pub fn symbols(&self) -> &IdentificationTable {
+        &self.id_table
+    }
 
-        self.id_table.enter(
-            "Boolean",
-            STANDARD_ENVIRONMENT.lock().unwrap().bool_decl.clone(),
-        );
-        self.id_table.enter(
-            "false",
-            STANDARD_ENVIRONMENT.lock().unwrap().false_decl.clone(),
-        );
-        self.id_table.enter(
-            "true",
-            STANDARD_ENVIRONMENT.lock().unwrap().true_decl.clone(),
-        );
+    /// take the warnings about declarations that hide a declaration of the same identifier in
+    /// an enclosing scope. These are legal Triangle, so they are not reported as errors.
+    pub fn take_warnings(&mut self) -> Diagnostics {
+        mem::take(&mut self.warnings)
+    }
 
-        self.id_table
-            .enter("id", STANDARD_ENVIRONMENT.lock().unwrap().id_decl.clone());
-        self.id_table
-            .enter("\\", STANDARD_ENVIRONMENT.lock().unwrap().not_decl.clone());
-        self.id_table
-            .enter("/\\", STANDARD_ENVIRONMENT.lock().unwrap().and_decl.clone());
-        self.id_table
-            .enter("\\/", STANDARD_ENVIRONMENT.lock().unwrap().or_decl.clone());
-        self.id_table.enter(
-            "succ",
-            STANDARD_ENVIRONMENT.lock().unwrap().succ_decl.clone(),
-        );
-        self.id_table.enter(
-            "pred",
-            STANDARD_ENVIRONMENT.lock().unwrap().pred_decl.clone(),
-        );
-        self.id_table
-            .enter("neg", STANDARD_ENVIRONMENT.lock().unwrap().neg_decl.clone());
-        self.id_table
-            .enter("+", STANDARD_ENVIRONMENT.lock().unwrap().add_decl.clone());
-        self.id_table
-            .enter("-", STANDARD_ENVIRONMENT.lock().unwrap().sub_decl.clone());
-        self.id_table
-            .enter("*", STANDARD_ENVIRONMENT.lock().unwrap().mult_decl.clone());
-        self.id_table
-            .enter("/", STANDARD_ENVIRONMENT.lock().unwrap().div_decl.clone());
-        self.id_table
-            .enter("//", STANDARD_ENVIRONMENT.lock().unwrap().mod_decl.clone());
-        self.id_table
-            .enter("<", STANDARD_ENVIRONMENT.lock().unwrap().lt_decl.clone());
-        self.id_table
-            .enter("<=", STANDARD_ENVIRONMENT.lock().unwrap().le_decl.clone());
-        self.id_table
-            .enter(">=", STANDARD_ENVIRONMENT.lock().unwrap().ge_decl.clone());
-        self.id_table
-            .enter(">", STANDARD_ENVIRONMENT.lock().unwrap().gt_decl.clone());
-        self.id_table
-            .enter("=", STANDARD_ENVIRONMENT.lock().unwrap().eq_decl.clone());
-        self.id_table
-            .enter("/=", STANDARD_ENVIRONMENT.lock().unwrap().ne_decl.clone());
-        self.id_table
-            .enter("eol", STANDARD_ENVIRONMENT.lock().unwrap().eol_decl.clone());
-        self.id_table
-            .enter("eof", STANDARD_ENVIRONMENT.lock().unwrap().eof_decl.clone());
-        self.id_table
-            .enter("get", STANDARD_ENVIRONMENT.lock().unwrap().get_decl.clone());
-        self.id_table
-            .enter("put", STANDARD_ENVIRONMENT.lock().unwrap().put_decl.clone());
-        self.id_table.enter(
-            "geteol",
-            STANDARD_ENVIRONMENT.lock().unwrap().geteol_decl.clone(),
-        );
-        self.id_table.enter(
-            "puteol",
-            STANDARD_ENVIRONMENT.lock().unwrap().puteol_decl.clone(),
-        );
-        self.id_table.enter(
-            "getint",
-            STANDARD_ENVIRONMENT.lock().unwrap().getint_decl.clone(),
-        );
-        self.id_table.enter(
-            "putint",
-            STANDARD_ENVIRONMENT.lock().unwrap().putint_decl.clone(),
-        );
-        self.id_table
-            .enter("chr", STANDARD_ENVIRONMENT.lock().unwrap().chr_decl.clone());
-        self.id_table
-            .enter("ord", STANDARD_ENVIRONMENT.lock().unwrap().ord_decl.clone());
-        self.id_table
-            .enter("new", STANDARD_ENVIRONMENT.lock().unwrap().new_decl.clone());
-        self.id_table.enter(
-            "dispose",
-            STANDARD_ENVIRONMENT.lock().unwrap().dispose_decl.clone(),
-        );
+    /// set up the standard environment (the prelude), including any host primitives that it has
+    /// been extended with
+    fn establish_standard_environment(&mut self) {
+        for (name, decl) in self.std_env.declarations() {
+            let state = decl.common_state();
+            if let Err(err) = self.id_table.enter(name, state.id, state.position) {
+                self.diagnostics.report(err);
+            }
+        }
     }
 
     /// Check that the AST is well-formed, link all applied occurrences of identifiers and
     /// operators to their declarations, check that all expressions and typedenoters have
-    /// proper types.
-    pub fn check(&mut self, program: &mut Program) {
-        program.accept(self, AstObject::Null);
+    /// proper types. Every error in the program is reported - several are returned together.
+    pub fn check(&mut self, program: &mut Program) -> GenResult<()> {
+        let mut diagnostics = Diagnostics::new();
+        self.check_with(program, &mut diagnostics);
+        diagnostics.into_result()
+    }
+
+    /// check the program, reporting every error into the diagnostics - usually the same ones
+    /// that the Parser reported into
+    pub fn check_with(&mut self, program: &mut Program, diagnostics: &mut Diagnostics) {
+        // every program is checked in a table of its own, so that the scopes of the programs
+        // checked before it are forgotten
+        self.id_table = IdentificationTable::new();
+        self.establish_standard_environment();
+
+        let std_env = Arc::clone(&self.std_env);
+        program.nodes.link_environment(Arc::clone(&std_env.nodes));
+
+        let mut checking = Checking {
+            std_env: &std_env,
+            nodes: &program.nodes,
+            id_table: mem::replace(&mut self.id_table, IdentificationTable::new()),
+            diagnostics: mem::take(&mut self.diagnostics),
+            warnings: mem::take(&mut self.warnings),
+            decorations: std_env.decorations.clone(),
+            ids: mem::take(&mut program.ids),
+        };
+        checking.visit_program(program, Formals::None);
+
+        let Checking {
+            id_table,
+            diagnostics: mut errors,
+            warnings,
+            decorations,
+            ids,
+            ..
+        } = checking;
+        self.id_table = id_table;
+        self.warnings = warnings;
+        program.decorations = decorations;
+        program.ids = ids;
+        diagnostics.append(&mut errors);
     }
 }
 
-impl AstVisitor for Checker {
-    /// check the program by recursively checking all its components. For semantic analysis,
-    /// the AstObject parameter is not particularly used apart from a few instances - return
-    /// Null.
-    fn visit_program(&mut self, program: &mut Program, arg: AstObject) -> AstObject {
-        program.cmd.accept(self, arg.clone());
-        AstObject::Null
+/// A check of one program. The declarations that the identifiers of the program are linked to
+/// are borrowed from the arena of the program, which is linked to the arena of the standard
+/// environment, for as long as the check lasts.
+struct Checking<'a> {
+    std_env: &'a StdEnvironment,
+    nodes: &'a Nodes,
+    id_table: IdentificationTable,
+    diagnostics: Diagnostics,
+    warnings: Diagnostics,
+    decorations: Decorations,
+    /// the NodeIds of the program, which the types that the check makes up for aggregates are
+    /// numbered from
+    ids: NodeIds,
+}
+
+impl<'a> Checking<'a> {
+    fn report(&mut self, message: &str, position: SourcePosition) {
+        self.diagnostics
+            .report(GenError::from(CheckerError::new(message, position)));
+    }
+
+    /// report an error unless the actual type is equivalent to the expected type
+    fn expect_type(
+        &mut self,
+        expected: &TypeDenoter,
+        actual: &TypeDenoter,
+        position: SourcePosition,
+    ) {
+        if !equivalent(&self.decorations, expected, actual) {
+            self.diagnostics
+                .report(GenError::from(type_error(expected, actual, position)));
+        }
     }
 
-    /// check the empty command - trivially checked, return Null
-    fn visit_empty_command(&mut self, cmd: &mut EmptyCommandState, arg: AstObject) -> AstObject {
-        AstObject::Null
+    /// report an actual parameter that is not of the same kind as its formal parameter
+    fn report_parameter_mismatch(&mut self, fp: &FormalParameter, position: SourcePosition) {
+        let err = CheckerError::new(
+            &format!("a {} actual parameter is expected here", parameter_kind(fp)),
+            position,
+        );
+        let err = declared_here(err, &fp.id().spelling, fp.common_state().position);
+        self.diagnostics.report(GenError::from(err));
+    }
+
+    /// declare the formal parameters in the current scope - the scope of the body of their routine
+    fn enter_formal_parameters(&mut self, fps: &'a FormalParameterSequence) {
+        match *fps {
+            FormalParameterSequence::EmptyFormalParameterSequence(_) => {}
+            FormalParameterSequence::SingleFormalParameterSequence(ref sfps) => {
+                let fp = sfps.fp.get(self.nodes);
+                self.enter(&fp.id().spelling, Definition::Parameter(fp));
+            }
+            FormalParameterSequence::MultipleFormalParameterSequence(ref mfps) => {
+                let fp = mfps.fp.get(self.nodes);
+                self.enter(&fp.id().spelling, Definition::Parameter(fp));
+                self.enter_formal_parameters(mfps.fps.get(self.nodes));
+            }
+        }
+    }
+
+    /// link the applied occurrence of an identifier or operator to the declaration that it is
+    /// bound to, and return that declaration
+    fn identify(&mut self, spelling: &str, node: NodeId) -> Option<Definition<'a>> {
+        let decl = self.id_table.lookup(spelling)?.decl;
+        self.decorations.link(node, decl);
+        self.nodes.definition(decl)
+    }
+
+    /// the declaration that the node has been linked to
+    fn definition_of(&self, node: NodeId) -> Option<Definition<'a>> {
+        let decl = self.decorations.linked(node)?;
+        self.nodes.definition(decl)
+    }
+
+    /// declare the identifier in the current scope, warning if it hides a declaration in an
+    /// enclosing scope other than the standard environment
+    fn enter(&mut self, id: &str, def: Definition<'a>) {
+        let state = def.common_state();
+
+        let shadowed = match self.id_table.shadowed(id) {
+            Some(entry) if entry.level > 0 => Some(entry.position),
+            _ => None,
+        };
+
+        match self.id_table.enter(id, state.id, state.position) {
+            Ok(()) => {
+                if let Some(prev) = shadowed {
+                    let warning = CheckerWarning::new(
+                        &format!("\"{}\" hides a declaration in an enclosing scope", id),
+                        state.position,
+                    )
+                    .with_note(&format!("\"{}\" is declared here", id), prev);
+                    self.warnings.report(GenError::from(warning));
+                }
+            }
+            Err(err) => self.diagnostics.report(err),
+        }
+    }
+
+    /// give a node of a type that the check makes up a NodeId of its own, so that the Encoder can
+    /// tell the type apart from the others
+    fn number(&mut self, common_state: &mut CommonState) {
+        common_state.id = self.ids.allocate();
+    }
+
+    /// a record type of the fields of an aggregate
+    fn record_type(&mut self, ftd: FieldTypeDenoter) -> TypeDenoter {
+        let mut record = RecordTypeDenoterState::new(ftd);
+        self.number(&mut record.common_state);
+        TypeDenoter::RecordTypeDenoter(record)
+    }
+}
+
+impl<'a> AstRefVisitor<'a> for Checking<'a> {
+    type Arg = Formals<'a>;
+    type Output = Option<TypeDenoter>;
+
+    fn nodes(&self) -> &'a Nodes {
+        self.nodes
     }
 
     /// check the assign command - check the vname, check the expression, and confirm that the
-    /// types are compatible. Return Null.
-    fn visit_assign_command(&mut self, cmd: &mut AssignCommandState, arg: AstObject) -> AstObject {
-        AstObject::Null
+    /// vname is a variable rather than a constant, and that the types are compatible. Return
+    /// None.
+    fn visit_assign_command(
+        &mut self,
+        cmd: &'a AssignCommandState,
+        _arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        let vname_td = type_of(self.visit_vname(&cmd.vname, Formals::None));
+        let expr_td = type_of(self.visit_expression(&cmd.expr, Formals::None));
+
+        if let Some(decl) =
+            self.definition_of(vname_identifier(self.nodes, &cmd.vname).common_state.id)
+        {
+            if !is_variable(decl) {
+                let id = vname_identifier(self.nodes, &cmd.vname);
+                let err = CheckerError::new(
+                    &format!("\"{}\" is not a variable", id.spelling),
+                    vname_position(cmd.vname.get(self.nodes)),
+                );
+                let err = declared_here(err, &id.spelling, decl.common_state().position);
+                self.diagnostics.report(GenError::from(err));
+            }
+        }
+        self.expect_type(
+            &vname_td,
+            &expr_td,
+            expression_position(cmd.expr.get(self.nodes)),
+        );
+        None
     }
 
-    /// check that the identifier represents a procedure declaration, and then pass the formal
-    /// parameter sequence downstream so that the actual parameter sequence can be validated against
+    /// check that the identifier represents a procedure (declared, or a procedural parameter), and
+    /// then pass the formal parameter sequence downstream so that the actual parameter sequence can be validated against
     /// it.
-    fn visit_call_command(&mut self, cmd: &mut CallCommandState, arg: AstObject) -> AstObject {
-        let id_decl_ast = cmd.id.accept(self, arg.clone());
-
-        if let Some(id_decl) = id_decl_ast.get_declaration() {
-            if let Some(proc_decl) = id_decl.get_proc_declaration() {
-                let fps = proc_decl.fps.clone();
-                cmd.aps
-                    .accept(self, AstObject::FormalParameterSequence(fps));
+    fn visit_call_command(
+        &mut self,
+        cmd: &'a CallCommandState,
+        _arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        let id_decl = self.identify(&cmd.id.spelling, cmd.id.common_state.id);
+
+        if let Some(id_decl) = id_decl {
+            if let Some(fps) = procedure_parameters(self.nodes, id_decl) {
+                self.visit_actual_parameter_sequence(&cmd.aps, Formals::Sequence(fps));
             } else {
-                report_error_and_exit(GenError::from(CheckerError::new(
-                    "expected a procedure here",
-                )));
+                let err = CheckerError::new(
+                    &format!("\"{}\" is not a procedure", cmd.id.spelling),
+                    cmd.id.common_state.position,
+                );
+                let err = declared_here(err, &cmd.id.spelling, id_decl.common_state().position);
+                self.diagnostics.report(GenError::from(err));
             }
         } else {
-            report_error_and_exit(GenError::from(CheckerError::new(
-                "expected a declaration here",
-            )));
+            self.report(
+                &format!("\"{}\" is not declared", cmd.id.spelling),
+                cmd.id.common_state.position,
+            );
         }
 
-        AstObject::Null
+        None
     }
 
     /// open a new scope, expand the declarations, and check the command in the context of the
     /// declarations, and finally close the scope.
-    fn visit_let_command(&mut self, cmd: &mut LetCommandState, arg: AstObject) -> AstObject {
-        self.id_table.open_scope();
-        cmd.decl.accept(self, AstObject::Null);
-        cmd.cmd.accept(self, AstObject::Null);
+    fn visit_let_command(
+        &mut self,
+        cmd: &'a LetCommandState,
+        _arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        self.id_table.open_scope(cmd.common_state.position);
+        self.visit_declaration(&cmd.decl, Formals::None);
+        self.visit_command(&cmd.cmd, Formals::None);
         self.id_table.close_scope();
-        AstObject::Null
+        None
     }
 
-    fn visit_if_command(&mut self, cmd: &mut IfCommandState, arg: AstObject) -> AstObject {
-        AstObject::Null
-    }
+    /// check that the condition is a Boolean, and then check both branches
+    fn visit_if_command(
+        &mut self,
+        cmd: &'a IfCommandState,
+        _arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        let cond_td = type_of(self.visit_expression(&cmd.expr, Formals::None));
+        self.expect_type(
+            &bool_type(),
+            &cond_td,
+            expression_position(cmd.expr.get(self.nodes)),
+        );
 
-    fn visit_while_command(&mut self, cmd: &mut WhileCommandState, arg: AstObject) -> AstObject {
-        AstObject::Null
+        self.visit_command(&cmd.cmd1, Formals::None);
+        self.visit_command(&cmd.cmd2, Formals::None);
+        None
     }
 
-    /// check the first command, and then check the second command
-    fn visit_sequential_command(
+    /// check that the condition is a Boolean, and then check the body
+    fn visit_while_command(
         &mut self,
-        cmd: &mut SequentialCommandState,
-        arg: AstObject,
-    ) -> AstObject {
-        cmd.cmd1.accept(self, AstObject::Null);
-        cmd.cmd2.accept(self, AstObject::Null);
-        AstObject::Null
-    }
+        cmd: &'a WhileCommandState,
+        _arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        let cond_td = type_of(self.visit_expression(&cmd.expr, Formals::None));
+        self.expect_type(
+            &bool_type(),
+            &cond_td,
+            expression_position(cmd.expr.get(self.nodes)),
+        );
 
-    fn visit_empty_expression(
-        &mut self,
-        expr: &mut EmptyExpressionState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
+        self.visit_command(&cmd.cmd, Formals::None);
+        None
     }
 
     /// annotate the integer expression with its type
     fn visit_integer_expression(
         &mut self,
-        expr: &mut IntegerExpressionState,
-        arg: AstObject,
-    ) -> AstObject {
-        if let Some(td) = expr.il.accept(self, arg).get_type_denoter() {
-            expr.td = Some(td.clone());
-        } else {
-            report_error_and_exit(GenError::from(CheckerError::new(
-                "could not resolve the type of an integer expression",
-            )));
-        }
-        AstObject::TypeDenoter(expr.td.clone().unwrap())
+        expr: &'a IntegerExpressionState,
+        arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        let td = type_of(self.visit_integer_literal(&expr.il, arg));
+        self.decorations.set_type(expr.common_state.id, td.clone());
+        Some(td)
     }
 
+    /// annotate the character expression with its type
     fn visit_character_expression(
         &mut self,
-        expr: &mut CharacterExpressionState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
+        expr: &'a CharacterExpressionState,
+        arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        let td = type_of(self.visit_character_literal(&expr.cl, arg));
+        self.decorations.set_type(expr.common_state.id, td.clone());
+        Some(td)
     }
 
+    /// the expression has the type of the vname
     fn visit_vname_expression(
         &mut self,
-        expr: &mut VnameExpressionState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
+        expr: &'a VnameExpressionState,
+        arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        let td = type_of(self.visit_vname(&expr.vname, arg));
+        self.decorations.set_type(expr.common_state.id, td.clone());
+        Some(td)
     }
 
-    fn visit_let_expression(&mut self, expr: &mut LetExpressionState, arg: AstObject) -> AstObject {
-        AstObject::Null
+    /// open a new scope, expand the declarations, and check the expression in the context of
+    /// the declarations. The let expression has the type of its body.
+    fn visit_let_expression(
+        &mut self,
+        expr: &'a LetExpressionState,
+        arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        self.id_table.open_scope(expr.common_state.position);
+        self.visit_declaration(&expr.decl, Formals::None);
+        let td = type_of(self.visit_expression(&expr.expr, arg));
+        self.id_table.close_scope();
+
+        self.decorations.set_type(expr.common_state.id, td.clone());
+        Some(td)
     }
 
+    /// check that the identifier represents a function (declared, or a functional parameter),
+    /// validate the actual parameter
+    /// sequence against its formal parameter sequence, and annotate the call with the result type
+    /// of the function.
     fn visit_call_expression(
         &mut self,
-        expr: &mut CallExpressionState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
+        expr: &'a CallExpressionState,
+        _arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        let id_decl = self.identify(&expr.id.spelling, expr.id.common_state.id);
+
+        let td = if let Some(id_decl) = id_decl {
+            if let Some((fps, td)) = function_signature(self.nodes, id_decl) {
+                let td = resolve_type(&self.decorations, td);
+                self.visit_actual_parameter_sequence(&expr.aps, Formals::Sequence(fps));
+                td
+            } else {
+                let err = CheckerError::new(
+                    &format!("\"{}\" is not a function", expr.id.spelling),
+                    expr.id.common_state.position,
+                );
+                let err = declared_here(err, &expr.id.spelling, id_decl.common_state().position);
+                self.diagnostics.report(GenError::from(err));
+                error_type()
+            }
+        } else {
+            self.report(
+                &format!("\"{}\" is not declared", expr.id.spelling),
+                expr.id.common_state.position,
+            );
+            error_type()
+        };
+
+        self.decorations.set_type(expr.common_state.id, td.clone());
+        Some(td)
     }
 
-    fn visit_if_expression(&mut self, expr: &mut IfExpressionState, arg: AstObject) -> AstObject {
-        AstObject::Null
+    /// the condition must be a Boolean, and both branches must have the same type, which is the
+    /// type of the if expression
+    fn visit_if_expression(
+        &mut self,
+        expr: &'a IfExpressionState,
+        arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        let cond_td = type_of(self.visit_expression(&expr.expr1, arg));
+        self.expect_type(
+            &bool_type(),
+            &cond_td,
+            expression_position(expr.expr1.get(self.nodes)),
+        );
+
+        let td = type_of(self.visit_expression(&expr.expr2, arg));
+        let else_td = type_of(self.visit_expression(&expr.expr3, arg));
+        self.expect_type(
+            &td,
+            &else_td,
+            expression_position(expr.expr3.get(self.nodes)),
+        );
+
+        self.decorations.set_type(expr.common_state.id, td.clone());
+        Some(td)
     }
 
+    /// check that the operator represents a unary operator declaration, that the operand has the
+    /// type of its argument, and annotate the expression with the result type of the operator
     fn visit_unary_expression(
         &mut self,
-        expr: &mut UnaryExpressionState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
+        expr: &'a UnaryExpressionState,
+        arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        let expr_td = type_of(self.visit_expression(&expr.expr, arg));
+        let op_decl = self.identify(&expr.op.spelling, expr.op.common_state.id);
+
+        let td = if let Some(op_decl) = op_decl {
+            if let Definition::UnaryOperator(unary_decl) = op_decl {
+                self.expect_type(
+                    &resolve_type(&self.decorations, &unary_decl.argtype),
+                    &expr_td,
+                    expression_position(expr.expr.get(self.nodes)),
+                );
+                resolve_type(&self.decorations, &unary_decl.res_type)
+            } else {
+                self.report(
+                    &format!("\"{}\" is not a unary operator", expr.op.spelling),
+                    expr.op.common_state.position,
+                );
+                error_type()
+            }
+        } else {
+            self.report(
+                &format!("operator \"{}\" is not declared", expr.op.spelling),
+                expr.op.common_state.position,
+            );
+            error_type()
+        };
+
+        self.decorations.set_type(expr.common_state.id, td.clone());
+        Some(td)
     }
 
+    /// check that the operator represents a binary operator declaration, that the operands have
+    /// the types of its arguments, and annotate the expression with the result type of the
+    /// operator. The operands of a polymorphic operator (`=` and `\\=`, whose arguments are of
+    /// any type) only need to have the same type as each other.
     fn visit_binary_expression(
         &mut self,
-        expr: &mut BinaryExpressionState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
+        expr: &'a BinaryExpressionState,
+        arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        let expr1_td = type_of(self.visit_expression(&expr.expr1, arg));
+        let expr2_td = type_of(self.visit_expression(&expr.expr2, arg));
+        let op_decl = self.identify(&expr.op.spelling, expr.op.common_state.id);
+
+        let td = if let Some(op_decl) = op_decl {
+            if let Definition::BinaryOperator(binary_decl) = op_decl {
+                let arg1_td = resolve_type(&self.decorations, &binary_decl.arg1_type);
+                if arg1_td.get_any_type_denoter().is_some() {
+                    self.expect_type(
+                        &expr1_td,
+                        &expr2_td,
+                        expression_position(expr.expr2.get(self.nodes)),
+                    );
+                } else {
+                    let arg2_td = resolve_type(&self.decorations, &binary_decl.arg2_type);
+                    self.expect_type(
+                        &arg1_td,
+                        &expr1_td,
+                        expression_position(expr.expr1.get(self.nodes)),
+                    );
+                    self.expect_type(
+                        &arg2_td,
+                        &expr2_td,
+                        expression_position(expr.expr2.get(self.nodes)),
+                    );
+                }
+                resolve_type(&self.decorations, &binary_decl.res_type)
+            } else {
+                self.report(
+                    &format!("\"{}\" is not a binary operator", expr.op.spelling),
+                    expr.op.common_state.position,
+                );
+                error_type()
+            }
+        } else {
+            self.report(
+                &format!("operator \"{}\" is not declared", expr.op.spelling),
+                expr.op.common_state.position,
+            );
+            error_type()
+        };
+
+        self.decorations.set_type(expr.common_state.id, td.clone());
+        Some(td)
     }
 
+    /// the array expression has an array type of as many elements as the aggregate has, of the
+    /// type of its elements
     fn visit_array_expression(
         &mut self,
-        expr: &mut ArrayExpressionState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
+        expr: &'a ArrayExpressionState,
+        arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        let elem_td = type_of(self.visit_array_aggregate(&expr.aa, arg));
+        let mut array =
+            ArrayTypeDenoterState::new(IntegerLiteral::new(&expr.elem_count.to_string()), elem_td);
+        self.number(&mut array.il.common_state);
+        self.number(&mut array.common_state);
+
+        let td = TypeDenoter::ArrayTypeDenoter(array);
+        self.decorations.set_type(expr.common_state.id, td.clone());
+        Some(td)
     }
 
+    /// the record expression has a record type with the fields of the aggregate
     fn visit_record_expression(
         &mut self,
-        expr: &mut RecordExpressionState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
+        expr: &'a RecordExpressionState,
+        arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        let td = type_of(self.visit_record_aggregate(&expr.ra, arg));
+        self.decorations.set_type(expr.common_state.id, td.clone());
+        Some(td)
     }
 
+    /// the type of the elements of the aggregate is the type of its only expression
     fn visit_single_array_aggregate(
         &mut self,
-        agg: &mut SingleArrayAggregateState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
+        agg: &'a SingleArrayAggregateState,
+        arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        Some(type_of(self.visit_expression(&agg.expr, arg)))
     }
 
+    /// every element of the aggregate must have the same type
     fn visit_multiple_array_aggregate(
         &mut self,
-        agg: &mut MultipleArrayAggregateState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
+        agg: &'a MultipleArrayAggregateState,
+        arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        let td = type_of(self.visit_expression(&agg.expr, arg));
+        let rest_td = type_of(self.visit_array_aggregate(&agg.aa, arg));
+        self.expect_type(
+            &td,
+            &rest_td,
+            array_aggregate_position(agg.aa.get(self.nodes)),
+        );
+        Some(td)
     }
 
+    /// the aggregate has a record type with a single field, of the type of its expression
     fn visit_single_record_aggregate(
         &mut self,
-        agg: &mut SingleRecordAggregateState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
+        agg: &'a SingleRecordAggregateState,
+        arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        let td = type_of(self.visit_expression(&agg.expr, arg));
+        let mut field = SingleFieldTypeDenoterState::new(agg.id.clone(), td);
+        self.number(&mut field.common_state);
+        Some(self.record_type(FieldTypeDenoter::SingleFieldTypeDenoter(field)))
     }
 
+    /// the aggregate has a record type with a field for each of its expressions, and no field
+    /// may be given twice
     fn visit_multiple_record_aggregate(
         &mut self,
-        agg: &mut MultipleRecordAggregateState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
+        agg: &'a MultipleRecordAggregateState,
+        arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        let td = type_of(self.visit_expression(&agg.expr, arg));
+        let rest_td = type_of(self.visit_record_aggregate(&agg.ra, arg));
+
+        let rest_ftd = match rest_td {
+            TypeDenoter::RecordTypeDenoter(record) => *record.ftd,
+            _ => return Some(error_type()),
+        };
+        if field_type(&rest_ftd, &agg.id.spelling).is_some() {
+            self.report(
+                &format!(
+                    "duplicate field \"{}\" in the record aggregate",
+                    agg.id.spelling
+                ),
+                agg.id.common_state.position,
+            );
+        }
+
+        let mut field = MultipleFieldTypeDenoterState::new(agg.id.clone(), td, rest_ftd);
+        self.number(&mut field.common_state);
+        Some(self.record_type(FieldTypeDenoter::MultipleFieldTypeDenoter(field)))
     }
 
+    /// check the expression, and then enter the identifier against the declaration into the id
+    /// table
     fn visit_const_declaration(
         &mut self,
-        decl: &mut ConstDeclarationState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
+        decl: &'a ConstDeclarationState,
+        _arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        self.visit_expression(&decl.expr, Formals::None);
+        self.enter(&decl.id.spelling, Definition::Const(decl));
+        None
     }
 
     /// check all the components of the declaration, and then enter entries for the identifier
     /// representing this declaration against the declaration itself, into the id table.
     fn visit_var_declaration(
         &mut self,
-        decl: &mut VarDeclarationState,
-        arg: AstObject,
-    ) -> AstObject {
-        decl.id.accept(self, AstObject::Null);
-        decl.td.accept(self, AstObject::Null);
-        self.id_table
-            .enter(&decl.id.spelling, Declaration::VarDeclaration(decl.clone()));
-        AstObject::Null
+        decl: &'a VarDeclarationState,
+        _arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        self.visit_type_denoter(&decl.td, Formals::None);
+        self.enter(&decl.id.spelling, Definition::Var(decl));
+        None
     }
 
+    /// check the formal parameters, and enter the procedure into the id table before its body is
+    /// checked, so that the procedure can call itself. The body is checked in a new scope, in
+    /// which the formal parameters are declared.
     fn visit_proc_declaration(
         &mut self,
-        decl: &mut ProcDeclarationState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
+        decl: &'a ProcDeclarationState,
+        _arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        self.visit_formal_parameter_sequence(&decl.fps, Formals::None);
+        self.enter(&decl.id.spelling, Definition::Proc(decl));
+
+        self.id_table.open_scope(decl.common_state.position);
+        self.enter_formal_parameters(decl.fps.get(self.nodes));
+        self.visit_command(&decl.cmd, Formals::None);
+        self.id_table.close_scope();
+        None
     }
 
+    /// check the formal parameters and the result type, and enter the function into the id table
+    /// before its body is checked, so that the function can call itself. The body is checked in a
+    /// new scope, in which the formal parameters are declared, and must have the result type.
     fn visit_func_declaration(
         &mut self,
-        decl: &mut FuncDeclarationState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
-    }
-
-    fn visit_type_declaration(
-        &mut self,
-        decl: &mut TypeDeclarationState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
-    }
-
-    fn visit_unary_operator_declaration(
-        &mut self,
-        decl: &mut UnaryOperatorDeclarationState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
-    }
+        decl: &'a FuncDeclarationState,
+        _arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        self.visit_formal_parameter_sequence(&decl.fps, Formals::None);
+        let td = type_of(self.visit_type_denoter(&decl.td, Formals::None));
+        self.enter(&decl.id.spelling, Definition::Func(decl));
+
+        self.id_table.open_scope(decl.common_state.position);
+        self.enter_formal_parameters(decl.fps.get(self.nodes));
+        let expr_td = type_of(self.visit_expression(&decl.expr, Formals::None));
+        self.id_table.close_scope();
 
-    fn visit_binary_operator_declaration(
-        &mut self,
-        decl: &mut BinaryOperatorDeclarationState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
+        self.expect_type(
+            &td,
+            &expr_td,
+            expression_position(decl.expr.get(self.nodes)),
+        );
+        None
     }
 
-    /// check the first declaration, and then check the second declaration.
-    fn visit_sequential_declaration(
+    /// check the type-denoter, and then enter the identifier against the declaration into the id
+    /// table
+    fn visit_type_declaration(
         &mut self,
-        decl: &mut SequentialDeclarationState,
-        arg: AstObject,
-    ) -> AstObject {
-        decl.decl1.accept(self, AstObject::Null);
-        decl.decl2.accept(self, AstObject::Null);
-        AstObject::Null
+        decl: &'a TypeDeclarationState,
+        _arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        self.visit_type_denoter(&decl.td, Formals::None);
+        self.enter(&decl.id.spelling, Definition::Type(decl));
+        None
     }
 
     fn visit_any_type_denoter(
         &mut self,
-        td: &mut AnyTypeDenoterState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
+        td: &'a AnyTypeDenoterState,
+        _arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        Some(TypeDenoter::AnyTypeDenoter(td.clone()))
     }
 
     fn visit_error_type_denoter(
         &mut self,
-        td: &mut ErrorTypeDenoterState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
+        td: &'a ErrorTypeDenoterState,
+        _arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        Some(TypeDenoter::ErrorTypeDenoter(td.clone()))
     }
 
     fn visit_bool_type_denoter(
         &mut self,
-        td: &mut BoolTypeDenoterState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
+        td: &'a BoolTypeDenoterState,
+        _arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        Some(TypeDenoter::BoolTypeDenoter(td.clone()))
     }
 
     fn visit_char_type_denoter(
         &mut self,
-        td: &mut CharTypeDenoterState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
+        td: &'a CharTypeDenoterState,
+        _arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        Some(TypeDenoter::CharTypeDenoter(td.clone()))
     }
 
     fn visit_int_type_denoter(
         &mut self,
-        td: &mut IntTypeDenoterState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
+        td: &'a IntTypeDenoterState,
+        _arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        Some(TypeDenoter::IntTypeDenoter(td.clone()))
     }
 
+    /// check the number of elements and the element type
     fn visit_array_type_denoter(
         &mut self,
-        td: &mut ArrayTypeDenoterState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
+        td: &'a ArrayTypeDenoterState,
+        _arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        self.visit_integer_literal(&td.il, Formals::None);
+        self.visit_type_denoter(&td.td, Formals::None);
+        Some(TypeDenoter::ArrayTypeDenoter(td.clone()))
     }
 
+    /// link the type identifier to its type declaration, and record and return the type that it
+    /// denotes
     fn visit_simple_type_denoter(
         &mut self,
-        td: &mut SimpleTypeDenoterState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
+        td: &'a SimpleTypeDenoterState,
+        _arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        let id_decl = self.identify(&td.id.spelling, td.id.common_state.id);
+
+        if let Some(id_decl) = id_decl {
+            if let Definition::Type(type_decl) = id_decl {
+                let resolved = resolve_type(&self.decorations, &type_decl.td);
+                self.decorations
+                    .set_type(td.common_state.id, resolved.clone());
+                return Some(resolved);
+            }
+            self.report(
+                &format!("\"{}\" is not a type identifier", td.id.spelling),
+                td.id.common_state.position,
+            );
+        } else {
+            self.report(
+                &format!("\"{}\" is not declared", td.id.spelling),
+                td.id.common_state.position,
+            );
+        }
+        Some(error_type())
     }
 
     fn visit_single_field_type_denoter(
         &mut self,
-        td: &mut SingleFieldTypeDenoterState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
+        td: &'a SingleFieldTypeDenoterState,
+        _arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        self.visit_type_denoter(&td.td, Formals::None);
+        None
     }
 
+    /// check the type of each field, and that no field is declared twice
     fn visit_multiple_field_type_denoter(
         &mut self,
-        td: &mut MultipleFieldTypeDenoterState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
+        td: &'a MultipleFieldTypeDenoterState,
+        _arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        self.visit_type_denoter(&td.td, Formals::None);
+        self.visit_field_type_denoter(&td.ftd, Formals::None);
+        if field_type(&td.ftd, &td.id.spelling).is_some() {
+            self.report(
+                &format!("duplicate field \"{}\" in the record type", td.id.spelling),
+                td.id.common_state.position,
+            );
+        }
+        None
     }
 
+    /// check the fields
     fn visit_record_type_denoter(
         &mut self,
-        td: &mut RecordTypeDenoterState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
-    }
-
-    fn visit_empty_formal_parameter_sequence(
-        &mut self,
-        fps: &mut EmptyFormalParameterSequenceState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
-    }
-
-    fn visit_single_formal_parameter_sequence(
-        &mut self,
-        fps: &mut SingleFormalParameterSequenceState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
-    }
-
-    fn visit_multiple_formal_parameter_sequence(
-        &mut self,
-        fps: &mut MultipleFormalParameterSequenceState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
+        td: &'a RecordTypeDenoterState,
+        _arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        self.visit_field_type_denoter(&td.ftd, Formals::None);
+        Some(TypeDenoter::RecordTypeDenoter(td.clone()))
     }
 
+    /// check the type of the parameter. Formal parameters are entered into the id table by the
+    /// routine that they belong to.
     fn visit_const_formal_parameter(
         &mut self,
-        fp: &mut ConstFormalParameterState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
+        fp: &'a ConstFormalParameterState,
+        _arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        self.visit_type_denoter(&fp.td, Formals::None);
+        None
     }
 
     fn visit_var_formal_parameter(
         &mut self,
-        fp: &mut VarFormalParameterState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
+        fp: &'a VarFormalParameterState,
+        _arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        self.visit_type_denoter(&fp.td, Formals::None);
+        None
     }
 
     fn visit_proc_formal_parameter(
         &mut self,
-        fp: &mut ProcFormalParameterState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
+        fp: &'a ProcFormalParameterState,
+        _arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        self.visit_formal_parameter_sequence(&fp.fps, Formals::None);
+        None
     }
 
     fn visit_func_formal_parameter(
         &mut self,
-        fp: &mut FuncFormalParameterState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
+        fp: &'a FuncFormalParameterState,
+        _arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        self.visit_formal_parameter_sequence(&fp.fps, Formals::None);
+        self.visit_type_denoter(&fp.td, Formals::None);
+        None
     }
 
+    /// check that the formal parameter sequence passed in via arg is empty as well
     fn visit_empty_actual_parameter_sequence(
         &mut self,
-        aps: &mut EmptyActualParameterSequenceState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
+        aps: &'a EmptyActualParameterSequenceState,
+        arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        if let Formals::Sequence(fps) = arg {
+            if fps.get_empty_formal_parameter_sequence().is_none() {
+                self.report("too few actual parameters", aps.common_state.position);
+            }
+        }
+        None
     }
 
-    /// check that the formal parameter sequence passed in via arg is a single formal parameter
-    /// sequence, extract the formal parameter, and pass that downstream to validate the actual
-    /// parameter against.
+    /// check that the formal parameter sequence passed in via arg has a single formal parameter,
+    /// and pass that downstream to validate the actual parameter against.
     fn visit_single_actual_parameter_sequence(
         &mut self,
-        aps: &mut SingleActualParameterSequenceState,
-        arg: AstObject,
-    ) -> AstObject {
-        if let Some(fps) = arg.get_formal_parameter_sequence() {
-            if let Some(sfps) = fps.get_single_formal_parameter_sequence() {
-                aps.ap
-                    .accept(self, AstObject::FormalParameter(sfps.fp.clone()));
-            } else {
-                report_error_and_exit(GenError::from(CheckerError::new(
-                    "expected a single formal parameter sequence here",
-                )));
+        aps: &'a SingleActualParameterSequenceState,
+        arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        if let Formals::Sequence(fps) = arg {
+            match fps {
+                FormalParameterSequence::SingleFormalParameterSequence(ref sfps) => {
+                    self.visit_actual_parameter(
+                        &aps.ap,
+                        Formals::Parameter(sfps.fp.get(self.nodes)),
+                    );
+                }
+                FormalParameterSequence::MultipleFormalParameterSequence(ref mfps) => {
+                    self.visit_actual_parameter(
+                        &aps.ap,
+                        Formals::Parameter(mfps.fp.get(self.nodes)),
+                    );
+                    self.report("too few actual parameters", aps.common_state.position);
+                }
+                FormalParameterSequence::EmptyFormalParameterSequence(_) => {
+                    self.report("too many actual parameters", aps.common_state.position);
+                }
             }
-        } else {
-            report_error_and_exit(GenError::from(CheckerError::new(
-                "expected a formal parameter sequence here",
-            )));
         }
-
-        AstObject::Null
+        None
     }
 
-    /// check that the formal parameter sequence passed in via arg is a multiple formal parameter
-    /// sequence, extract the formal parameter, pass that downstream to validate against the actual
-    /// parameter, and then check the remaining formal parameter sequence as well.
+    /// check the first actual parameter against the first formal parameter of the sequence
+    /// passed in via arg, and then check the remaining actual parameters against the remaining
+    /// formal parameters.
     fn visit_multiple_actual_parameter_sequence(
         &mut self,
-        aps: &mut MultipleActualParameterSequenceState,
-        arg: AstObject,
-    ) -> AstObject {
-        if let Some(fps) = arg.get_formal_parameter_sequence() {
-            if let Some(mfps) = fps.get_multiple_formal_parameter_sequence() {
-                aps.ap
-                    .accept(self, AstObject::FormalParameter(mfps.fp.clone()));
-                aps.aps
-                    .accept(self, AstObject::FormalParameterSequence(mfps.fps.clone()));
-            } else {
-                report_error_and_exit(GenError::from(CheckerError::new(
-                    "expected a multiple formal parameter sequence here",
-                )));
+        aps: &'a MultipleActualParameterSequenceState,
+        arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        if let Formals::Sequence(fps) = arg {
+            match fps {
+                FormalParameterSequence::MultipleFormalParameterSequence(ref mfps) => {
+                    self.visit_actual_parameter(
+                        &aps.ap,
+                        Formals::Parameter(mfps.fp.get(self.nodes)),
+                    );
+                    self.visit_actual_parameter_sequence(
+                        &aps.aps,
+                        Formals::Sequence(mfps.fps.get(self.nodes)),
+                    );
+                }
+                FormalParameterSequence::SingleFormalParameterSequence(ref sfps) => {
+                    self.visit_actual_parameter(
+                        &aps.ap,
+                        Formals::Parameter(sfps.fp.get(self.nodes)),
+                    );
+                    self.report(
+                        "too many actual parameters",
+                        actual_parameters_position(aps.aps.get(self.nodes)),
+                    );
+                }
+                FormalParameterSequence::EmptyFormalParameterSequence(_) => {
+                    self.report("too many actual parameters", aps.common_state.position);
+                }
             }
-        } else {
-            report_error_and_exit(GenError::from(CheckerError::new(
-                "expected a formal parameter sequence here",
-            )));
         }
-        AstObject::Null
+        None
     }
 
     /// check that the formal parameter passed in via arg is a const formal parameter,
     /// and check that its type matches that of the const actual parameter.
     fn visit_const_actual_parameter(
         &mut self,
-        ap: &mut ConstActualParameterState,
-        arg: AstObject,
-    ) -> AstObject {
-        if let Some(fp) = arg.get_formal_parameter() {
+        ap: &'a ConstActualParameterState,
+        arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        let actual_td = type_of(self.visit_expression(&ap.expr, Formals::None));
+
+        if let Formals::Parameter(fp) = arg {
             if let Some(cfp) = fp.get_const_formal_parameter() {
-                let expected_td = cfp.td.clone();
-                let actual_td = ap
-                    .expr
-                    .accept(self, AstObject::Null)
-                    .get_type_denoter()
-                    .unwrap()
-                    .clone();
-
-                if *expected_td != *actual_td {
-                    report_error_and_exit(GenError::from(CheckerError::new(&format!(
-                        "expected type was {}, actual type was {}",
-                        expected_td, actual_td
-                    ))));
+                let expected_td = resolve_type(&self.decorations, &cfp.td);
+                if !equivalent(&self.decorations, &expected_td, &actual_td) {
+                    let err = type_error(&expected_td, &actual_td, ap.common_state.position);
+                    let err = declared_here(err, &cfp.id.spelling, cfp.common_state.position);
+                    self.diagnostics.report(GenError::from(err));
                 }
             } else {
-                report_error_and_exit(GenError::from(CheckerError::new(
-                    "expected a const formal parameter here",
-                )));
+                self.report_parameter_mismatch(fp, ap.common_state.position);
             }
-        } else {
-            report_error_and_exit(GenError::from(CheckerError::new(
-                "expected a formal parameter here",
-            )));
         }
-        AstObject::Null
+        None
     }
 
+    /// check that the formal parameter passed in via arg is a var formal parameter, that the
+    /// vname is a variable, and that their types match.
     fn visit_var_actual_parameter(
         &mut self,
-        ap: &mut VarActualParameterState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
+        ap: &'a VarActualParameterState,
+        arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        let actual_td = type_of(self.visit_vname(&ap.vname, Formals::None));
+
+        if let Formals::Parameter(fp) = arg {
+            if let Some(vfp) = fp.get_var_formal_parameter() {
+                if let Some(decl) =
+                    self.definition_of(vname_identifier(self.nodes, &ap.vname).common_state.id)
+                {
+                    if !is_variable(decl) {
+                        self.report(
+                            &format!(
+                                "\"{}\" is not a variable",
+                                vname_identifier(self.nodes, &ap.vname).spelling
+                            ),
+                            ap.common_state.position,
+                        );
+                    }
+                }
+
+                let expected_td = resolve_type(&self.decorations, &vfp.td);
+                if !equivalent(&self.decorations, &expected_td, &actual_td) {
+                    let err = type_error(&expected_td, &actual_td, ap.common_state.position);
+                    let err = declared_here(err, &vfp.id.spelling, vfp.common_state.position);
+                    self.diagnostics.report(GenError::from(err));
+                }
+            } else {
+                self.report_parameter_mismatch(fp, ap.common_state.position);
+            }
+        }
+        None
     }
 
+    /// check that the formal parameter passed in via arg is a proc formal parameter, and that the
+    /// identifier represents a procedure with the same formal parameters.
     fn visit_proc_actual_parameter(
         &mut self,
-        ap: &mut ProcActualParameterState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
+        ap: &'a ProcActualParameterState,
+        arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        let id_decl = self.identify(&ap.id.spelling, ap.id.common_state.id);
+
+        if let Formals::Parameter(fp) = arg {
+            if let Some(pfp) = fp.get_proc_formal_parameter() {
+                match id_decl {
+                    Some(id_decl) => match procedure_parameters(self.nodes, id_decl) {
+                        Some(fps)
+                            if !equivalent_parameters(
+                                self.nodes,
+                                &self.decorations,
+                                pfp.fps.get(self.nodes),
+                                fps,
+                            ) =>
+                        {
+                            self.report(
+                                &format!(
+                                    "the formal parameters of \"{}\" do not match those of \"{}\"",
+                                    ap.id.spelling, pfp.id.spelling
+                                ),
+                                ap.common_state.position,
+                            )
+                        }
+                        Some(_) => {}
+                        None => self.report(
+                            &format!("\"{}\" is not a procedure", ap.id.spelling),
+                            ap.common_state.position,
+                        ),
+                    },
+                    None => self.report(
+                        &format!("\"{}\" is not declared", ap.id.spelling),
+                        ap.id.common_state.position,
+                    ),
+                }
+            } else {
+                self.report_parameter_mismatch(fp, ap.common_state.position);
+            }
+        }
+        None
     }
 
+    /// check that the formal parameter passed in via arg is a func formal parameter, and that the
+    /// identifier represents a function with the same formal parameters and result type.
     fn visit_func_actual_parameter(
         &mut self,
-        ap: &mut FuncActualParameterState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
+        ap: &'a FuncActualParameterState,
+        arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        let id_decl = self.identify(&ap.id.spelling, ap.id.common_state.id);
+
+        if let Formals::Parameter(fp) = arg {
+            if let Some(ffp) = fp.get_func_formal_parameter() {
+                match id_decl {
+                    Some(id_decl) => match function_signature(self.nodes, id_decl) {
+                        Some((fps, td))
+                            if !equivalent_parameters(
+                                self.nodes,
+                                &self.decorations,
+                                ffp.fps.get(self.nodes),
+                                fps,
+                            ) || !equivalent(
+                                &self.decorations,
+                                &resolve_type(&self.decorations, &ffp.td),
+                                &resolve_type(&self.decorations, td),
+                            ) =>
+                        {
+                            self.report(
+                                &format!(
+                                    "the signature of \"{}\" does not match that of \"{}\"",
+                                    ap.id.spelling, ffp.id.spelling
+                                ),
+                                ap.common_state.position,
+                            )
+                        }
+                        Some(_) => {}
+                        None => self.report(
+                            &format!("\"{}\" is not a function", ap.id.spelling),
+                            ap.common_state.position,
+                        ),
+                    },
+                    None => self.report(
+                        &format!("\"{}\" is not declared", ap.id.spelling),
+                        ap.id.common_state.position,
+                    ),
+                }
+            } else {
+                self.report_parameter_mismatch(fp, ap.common_state.position);
+            }
+        }
+        None
     }
 
-    fn visit_simple_vname(&mut self, vname: &mut SimpleVnameState, arg: AstObject) -> AstObject {
-        AstObject::Null
+    /// check that the identifier represents a constant or a variable, and annotate the vname with
+    /// its type
+    fn visit_simple_vname(
+        &mut self,
+        vname: &'a SimpleVnameState,
+        _arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        let id_decl = self.identify(&vname.id.spelling, vname.id.common_state.id);
+
+        let td = if let Some(id_decl) = id_decl {
+            match id_decl {
+                Definition::Const(decl) => resolve_type(
+                    &self.decorations,
+                    &expression_type(&self.decorations, decl.expr.get(self.nodes)),
+                ),
+                Definition::Var(decl) => resolve_type(&self.decorations, &decl.td),
+                Definition::Parameter(FormalParameter::ConstFormalParameter(fp)) => {
+                    resolve_type(&self.decorations, &fp.td)
+                }
+                Definition::Parameter(FormalParameter::VarFormalParameter(fp)) => {
+                    resolve_type(&self.decorations, &fp.td)
+                }
+                _ => {
+                    self.report(
+                        &format!("\"{}\" is not a constant or variable", vname.id.spelling),
+                        vname.id.common_state.position,
+                    );
+                    error_type()
+                }
+            }
+        } else {
+            self.report(
+                &format!("\"{}\" is not declared", vname.id.spelling),
+                vname.id.common_state.position,
+            );
+            error_type()
+        };
+
+        self.decorations.set_type(vname.common_state.id, td.clone());
+        Some(td)
     }
 
-    fn visit_dot_vname(&mut self, vname: &mut DotVnameState, arg: AstObject) -> AstObject {
-        AstObject::Null
+    /// check that the vname is a record with the named field, and annotate the vname with the
+    /// type of the field
+    fn visit_dot_vname(
+        &mut self,
+        vname: &'a DotVnameState,
+        arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        let record_td = type_of(self.visit_vname(&vname.vname, arg));
+
+        let td = match record_td {
+            TypeDenoter::RecordTypeDenoter(ref record) => {
+                match field_type(&record.ftd, &vname.id.spelling) {
+                    Some(td) => resolve_type(&self.decorations, td),
+                    None => {
+                        self.report(
+                            &format!("no field \"{}\" in this record type", vname.id.spelling),
+                            vname.id.common_state.position,
+                        );
+                        error_type()
+                    }
+                }
+            }
+            TypeDenoter::ErrorTypeDenoter(_) => error_type(),
+            _ => {
+                self.report(
+                    "a record is expected here",
+                    vname_position(vname.vname.get(self.nodes)),
+                );
+                error_type()
+            }
+        };
+
+        self.decorations.set_type(vname.common_state.id, td.clone());
+        Some(td)
     }
 
+    /// check that the vname is an array and that the index is an Integer, and annotate the vname
+    /// with the type of the elements
     fn visit_subscript_vname(
         &mut self,
-        vname: &mut SubscriptVnameState,
-        arg: AstObject,
-    ) -> AstObject {
-        AstObject::Null
+        vname: &'a SubscriptVnameState,
+        arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        let array_td = type_of(self.visit_vname(&vname.vname, arg));
+        let index_td = type_of(self.visit_expression(&vname.expr, arg));
+        self.expect_type(
+            &int_type(),
+            &index_td,
+            expression_position(vname.expr.get(self.nodes)),
+        );
+
+        let td = match array_td {
+            TypeDenoter::ArrayTypeDenoter(ref array) => resolve_type(&self.decorations, &array.td),
+            TypeDenoter::ErrorTypeDenoter(_) => error_type(),
+            _ => {
+                self.report(
+                    "an array is expected here",
+                    vname_position(vname.vname.get(self.nodes)),
+                );
+                error_type()
+            }
+        };
+
+        self.decorations.set_type(vname.common_state.id, td.clone());
+        Some(td)
+    }
+
+    /// identifiers are linked to their declarations by the nodes that they are a part of
+    fn visit_identifier(&mut self, _id: &'a Identifier, _arg: Formals<'a>) -> Option<TypeDenoter> {
+        None
+    }
+
+    /// this is the standard int type. The literal must fit in a word of the TAM.
+    fn visit_integer_literal(
+        &mut self,
+        il: &'a IntegerLiteral,
+        _arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        if il.spelling.parse::<i16>().is_err() {
+            self.report(
+                &format!("integer literal {} is larger than {}", il.spelling, MAX_INT),
+                il.common_state.position,
+            );
+        }
+        Some(self.std_env.int_type.clone())
+    }
+
+    fn visit_character_literal(
+        &mut self,
+        _cl: &'a CharacterLiteral,
+        _arg: Formals<'a>,
+    ) -> Option<TypeDenoter> {
+        Some(self.std_env.char_type.clone())
+    }
+
+    /// operators are linked to their declarations by the expressions that they are a part of
+    fn visit_operator(&mut self, _op: &'a Operator, _arg: Formals<'a>) -> Option<TypeDenoter> {
+        None
+    }
+}
+
+// the primitive types carry no state of their own, so these are the same as the types of the
+// standard environment
+
+fn int_type() -> TypeDenoter {
+    TypeDenoter::IntTypeDenoter(IntTypeDenoterState::new())
+}
+
+fn bool_type() -> TypeDenoter {
+    TypeDenoter::BoolTypeDenoter(BoolTypeDenoterState::new())
+}
+
+fn error_type() -> TypeDenoter {
+    TypeDenoter::ErrorTypeDenoter(ErrorTypeDenoterState::new())
+}
+
+/// the type that a visit returned, or the error type if there was none
+fn type_of(td: Option<TypeDenoter>) -> TypeDenoter {
+    td.unwrap_or_else(error_type)
+}
+
+/// replace type identifiers with the type denoters that they stand for, throughout the type, so
+/// that the result only contains primitive, array and record types. A type identifier stands for
+/// the type that it was resolved to when it was checked, and one that does not stand for a type
+/// denotes the error type.
+fn resolve_type(decorations: &Decorations, td: &TypeDenoter) -> TypeDenoter {
+    use TypeDenoter::*;
+
+    match *td {
+        SimpleTypeDenoter(ref simple) => match decorations.type_of(simple.common_state.id) {
+            Some(td) => td.clone(),
+            None => error_type(),
+        },
+        ArrayTypeDenoter(ref array) => {
+            let mut array = array.clone();
+            array.td = Box::new(resolve_type(decorations, &array.td));
+            ArrayTypeDenoter(array)
+        }
+        RecordTypeDenoter(ref record) => {
+            let mut record = record.clone();
+            record.ftd = Box::new(resolve_fields(decorations, &record.ftd));
+            RecordTypeDenoter(record)
+        }
+        _ => td.clone(),
+    }
+}
+
+fn resolve_fields(decorations: &Decorations, ftd: &FieldTypeDenoter) -> FieldTypeDenoter {
+    match *ftd {
+        FieldTypeDenoter::SingleFieldTypeDenoter(ref single) => {
+            let mut single = single.clone();
+            single.td = Box::new(resolve_type(decorations, &single.td));
+            FieldTypeDenoter::SingleFieldTypeDenoter(single)
+        }
+        FieldTypeDenoter::MultipleFieldTypeDenoter(ref multiple) => {
+            let mut multiple = multiple.clone();
+            multiple.td = Box::new(resolve_type(decorations, &multiple.td));
+            multiple.ftd = Box::new(resolve_fields(decorations, &multiple.ftd));
+            FieldTypeDenoter::MultipleFieldTypeDenoter(multiple)
+        }
+    }
+}
+
+/// whether a value of the actual type can be used where the expected type is needed. Triangle
+/// uses structural equivalence: arrays are equivalent if they have the same number of equivalent
+/// elements, and records if they have the same field names, in the same order, with equivalent
+/// types. The any type (of the arguments of polymorphic routines) and the error type (of
+/// expressions that have already been reported) are equivalent to every type, so that an error
+/// is only reported once.
+fn equivalent(decorations: &Decorations, expected: &TypeDenoter, actual: &TypeDenoter) -> bool {
+    use TypeDenoter::*;
+
+    match (
+        &resolve_type(decorations, expected),
+        &resolve_type(decorations, actual),
+    ) {
+        (AnyTypeDenoter(_), _) | (_, AnyTypeDenoter(_)) => true,
+        (ErrorTypeDenoter(_), _) | (_, ErrorTypeDenoter(_)) => true,
+        (BoolTypeDenoter(_), BoolTypeDenoter(_))
+        | (CharTypeDenoter(_), CharTypeDenoter(_))
+        | (IntTypeDenoter(_), IntTypeDenoter(_)) => true,
+        (ArrayTypeDenoter(ref array1), ArrayTypeDenoter(ref array2)) => {
+            equal_literals(&array1.il, &array2.il)
+                && equivalent(decorations, &array1.td, &array2.td)
+        }
+        (RecordTypeDenoter(ref record1), RecordTypeDenoter(ref record2)) => {
+            equivalent_fields(decorations, &record1.ftd, &record2.ftd)
+        }
+        _ => false,
+    }
+}
+
+fn equivalent_fields(
+    decorations: &Decorations,
+    ftd1: &FieldTypeDenoter,
+    ftd2: &FieldTypeDenoter,
+) -> bool {
+    use FieldTypeDenoter::*;
+
+    match (ftd1, ftd2) {
+        (SingleFieldTypeDenoter(ref field1), SingleFieldTypeDenoter(ref field2)) => {
+            field1.id.spelling == field2.id.spelling
+                && equivalent(decorations, &field1.td, &field2.td)
+        }
+        (MultipleFieldTypeDenoter(ref field1), MultipleFieldTypeDenoter(ref field2)) => {
+            field1.id.spelling == field2.id.spelling
+                && equivalent(decorations, &field1.td, &field2.td)
+                && equivalent_fields(decorations, &field1.ftd, &field2.ftd)
+        }
+        _ => false,
+    }
+}
+
+/// whether two integer literals have the same value, however they are spelled
+fn equal_literals(il1: &IntegerLiteral, il2: &IntegerLiteral) -> bool {
+    match (il1.spelling.parse::<i64>(), il2.spelling.parse::<i64>()) {
+        (Ok(value1), Ok(value2)) => value1 == value2,
+        _ => il1.spelling == il2.spelling,
+    }
+}
+
+/// point a note at the declaration of the identifier, unless it is one from the standard
+/// environment, which has no place in the source
+fn declared_here(err: CheckerError, spelling: &str, position: SourcePosition) -> CheckerError {
+    if position.start.line > 0 {
+        err.with_note(&format!("\"{}\" is declared here", spelling), position)
+    } else {
+        err
+    }
+}
+
+fn type_error(
+    expected: &TypeDenoter,
+    actual: &TypeDenoter,
+    position: SourcePosition,
+) -> CheckerError {
+    CheckerError::new(
+        &format!(
+            "expected type was {}, actual type was {}",
+            expected.name(),
+            actual.name()
+        ),
+        position,
+    )
+}
+
+/// the type of the named field of a record
+fn field_type<'a>(ftd: &'a FieldTypeDenoter, field: &str) -> Option<&'a TypeDenoter> {
+    match *ftd {
+        FieldTypeDenoter::SingleFieldTypeDenoter(ref single) if single.id.spelling == field => {
+            Some(&single.td)
+        }
+        FieldTypeDenoter::MultipleFieldTypeDenoter(ref multiple) => {
+            if multiple.id.spelling == field {
+                Some(&multiple.td)
+            } else {
+                field_type(&multiple.ftd, field)
+            }
+        }
+        _ => None,
+    }
+}
+
+/// the type that the expression has been annotated with
+fn expression_type(decorations: &Decorations, expr: &Expression) -> TypeDenoter {
+    match decorations.type_of(expr.common_state().id) {
+        Some(td) => td.clone(),
+        None => error_type(),
     }
+}
+
+fn expression_position(expr: &Expression) -> SourcePosition {
+    use Expression::*;
+
+    match *expr {
+        ArrayExpression(ref expr) => expr.common_state.position,
+        BinaryExpression(ref expr) => expr.common_state.position,
+        CallExpression(ref expr) => expr.common_state.position,
+        CharacterExpression(ref expr) => expr.common_state.position,
+        EmptyExpression(ref expr) => expr.common_state.position,
+        IfExpression(ref expr) => expr.common_state.position,
+        IntegerExpression(ref expr) => expr.common_state.position,
+        LetExpression(ref expr) => expr.common_state.position,
+        RecordExpression(ref expr) => expr.common_state.position,
+        UnaryExpression(ref expr) => expr.common_state.position,
+        VnameExpression(ref expr) => expr.common_state.position,
+    }
+}
 
-    fn visit_identifier(&mut self, id: &mut Identifier, arg: AstObject) -> AstObject {
-        if let Some(decl) = self.id_table.retrieve(&id.spelling) {
-            id.decl = Some(Box::new(*decl.clone()));
-            return AstObject::Declaration(id.decl.clone().unwrap());
+fn vname_position(vname: &Vname) -> SourcePosition {
+    match *vname {
+        Vname::SimpleVname(ref vname) => vname.common_state.position,
+        Vname::DotVname(ref vname) => vname.common_state.position,
+        Vname::SubscriptVname(ref vname) => vname.common_state.position,
+    }
+}
+
+/// the formal parameters of the procedure that the declaration represents, whether it is a
+/// procedure declaration or a procedural parameter
+fn procedure_parameters<'a>(
+    nodes: &'a Nodes,
+    decl: Definition<'a>,
+) -> Option<&'a FormalParameterSequence> {
+    match decl {
+        Definition::Proc(decl) => Some(decl.fps.get(nodes)),
+        Definition::Parameter(FormalParameter::ProcFormalParameter(fp)) => Some(fp.fps.get(nodes)),
+        _ => None,
+    }
+}
+
+/// the formal parameters and result type of the function that the declaration represents,
+/// whether it is a function declaration or a functional parameter
+fn function_signature<'a>(
+    nodes: &'a Nodes,
+    decl: Definition<'a>,
+) -> Option<(&'a FormalParameterSequence, &'a TypeDenoter)> {
+    match decl {
+        Definition::Func(decl) => Some((decl.fps.get(nodes), &decl.td)),
+        Definition::Parameter(FormalParameter::FuncFormalParameter(fp)) => {
+            Some((fp.fps.get(nodes), &fp.td))
         }
-        AstObject::Null
+        _ => None,
     }
+}
+
+/// whether the declaration represents a variable, which can be assigned to or passed as a var
+/// parameter
+fn is_variable(decl: Definition) -> bool {
+    matches!(
+        decl,
+        Definition::Var(_) | Definition::Parameter(FormalParameter::VarFormalParameter(_))
+    )
+}
 
-    /// this is the standard int type
-    fn visit_integer_literal(&mut self, il: &mut IntegerLiteral, arg: AstObject) -> AstObject {
-        AstObject::TypeDenoter(Box::new(
-            STANDARD_ENVIRONMENT.lock().unwrap().int_type.clone(),
-        ))
+fn parameter_kind(fp: &FormalParameter) -> &'static str {
+    match *fp {
+        FormalParameter::ConstFormalParameter(_) => "const",
+        FormalParameter::FuncFormalParameter(_) => "func",
+        FormalParameter::ProcFormalParameter(_) => "proc",
+        FormalParameter::VarFormalParameter(_) => "var",
     }
+}
 
-    fn visit_character_literal(&mut self, cl: &mut CharacterLiteral, arg: AstObject) -> AstObject {
-        AstObject::TypeDenoter(Box::new(
-            STANDARD_ENVIRONMENT.lock().unwrap().char_type.clone(),
-        ))
+/// whether the two formal parameter sequences have parameters of the same kinds and types, in
+/// the same order
+fn equivalent_parameters(
+    nodes: &Nodes,
+    decorations: &Decorations,
+    fps1: &FormalParameterSequence,
+    fps2: &FormalParameterSequence,
+) -> bool {
+    use FormalParameterSequence::*;
+
+    match (fps1, fps2) {
+        (EmptyFormalParameterSequence(_), EmptyFormalParameterSequence(_)) => true,
+        (SingleFormalParameterSequence(ref sfps1), SingleFormalParameterSequence(ref sfps2)) => {
+            equivalent_parameter(nodes, decorations, sfps1.fp.get(nodes), sfps2.fp.get(nodes))
+        }
+        (
+            MultipleFormalParameterSequence(ref mfps1),
+            MultipleFormalParameterSequence(ref mfps2),
+        ) => {
+            equivalent_parameter(nodes, decorations, mfps1.fp.get(nodes), mfps2.fp.get(nodes))
+                && equivalent_parameters(
+                    nodes,
+                    decorations,
+                    mfps1.fps.get(nodes),
+                    mfps2.fps.get(nodes),
+                )
+        }
+        (_, _) => false,
     }
+}
+
+fn equivalent_parameter(
+    nodes: &Nodes,
+    decorations: &Decorations,
+    fp1: &FormalParameter,
+    fp2: &FormalParameter,
+) -> bool {
+    use FormalParameter::*;
+
+    match (fp1, fp2) {
+        (ConstFormalParameter(ref fp1), ConstFormalParameter(ref fp2)) => {
+            equivalent(decorations, &fp1.td, &fp2.td)
+        }
+        (VarFormalParameter(ref fp1), VarFormalParameter(ref fp2)) => {
+            equivalent(decorations, &fp1.td, &fp2.td)
+        }
+        (ProcFormalParameter(ref fp1), ProcFormalParameter(ref fp2)) => {
+            equivalent_parameters(nodes, decorations, fp1.fps.get(nodes), fp2.fps.get(nodes))
+        }
+        (FuncFormalParameter(ref fp1), FuncFormalParameter(ref fp2)) => {
+            equivalent_parameters(nodes, decorations, fp1.fps.get(nodes), fp2.fps.get(nodes))
+                && equivalent(decorations, &fp1.td, &fp2.td)
+        }
+        (_, _) => false,
+    }
+}
+
+fn actual_parameters_position(aps: &ActualParameterSequence) -> SourcePosition {
+    match *aps {
+        ActualParameterSequence::EmptyActualParameterSequence(ref aps) => aps.common_state.position,
+        ActualParameterSequence::SingleActualParameterSequence(ref aps) => {
+            aps.common_state.position
+        }
+        ActualParameterSequence::MultipleActualParameterSequence(ref aps) => {
+            aps.common_state.position
+        }
+    }
+}
+
+/// the identifier that the vname is ultimately based on
+fn vname_identifier<'a>(nodes: &'a Nodes, vname: &'a Child<Vname>) -> &'a Identifier {
+    match *vname.get(nodes) {
+        Vname::SimpleVname(ref vname) => &vname.id,
+        Vname::DotVname(ref vname) => vname_identifier(nodes, &vname.vname),
+        Vname::SubscriptVname(ref vname) => vname_identifier(nodes, &vname.vname),
+    }
+}
 
-    fn visit_operator(&mut self, op: &mut Operator, arg: AstObject) -> AstObject {
-        AstObject::Null
+fn array_aggregate_position(aa: &ArrayAggregate) -> SourcePosition {
+    match *aa {
+        ArrayAggregate::SingleArrayAggregate(ref agg) => agg.common_state.position,
+        ArrayAggregate::MultipleArrayAggregate(ref agg) => agg.common_state.position,
     }
 }
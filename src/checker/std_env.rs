@@ -1,22 +1,54 @@
+use crate::ast::arena::{Child, Nodes, Tree};
 use crate::ast::commands::*;
 use crate::ast::declarations::*;
+use crate::ast::decorations::Decorations;
 use crate::ast::expressions::*;
 use crate::ast::parameters::*;
 use crate::ast::primitives::*;
+use crate::ast::runtime_entities::*;
 use crate::ast::typedenoters::*;
+use crate::ast::NodeIds;
+use crate::error::{CompilerError, GenError, GenResult};
+use crate::tam::host::{HostRoutine, HOST_PRIMITIVE_BASE};
+use crate::tam::{
+    Primitive, BOOLEAN_SIZE, CHARACTER_SIZE, CLOSURE_SIZE, FALSE_REP, INTEGER_SIZE, TRUE_REP,
+};
 use std::default::Default;
-use std::sync::Mutex;
+use std::fmt;
+use std::sync::Arc;
 
 use lazy_static::lazy_static;
 
 lazy_static! {
-    pub static ref STANDARD_ENVIRONMENT: Mutex<StdEnvironment> =
-        Mutex::new(StdEnvironment::default());
+    /// the environment that checkers share unless they are given one of their own. It is never
+    /// changed once it has been built, so checkers on different threads can use it without a lock.
+    static ref SHARED_ENVIRONMENT: Arc<StdEnvironment> = Arc::new(StdEnvironment::default());
 }
 
 /// The Standard Environment provides pre-defined functionality in the form of constants, types,
 /// operators, procedures, and function declarations. It is what is known as the "Prelude" in some
-/// other languages
+/// other languages.
+///
+/// It can be extended with host primitives - procedures and functions implemented in Rust - which
+/// programs checked in the environment can call like any standard routine:
+///
+/// ```
+/// use std::sync::Arc;
+/// use triangle_rs::ast::parameters::*;
+/// use triangle_rs::checker::std_env::StdEnvironment;
+/// use triangle_rs::checker::Checker;
+///
+/// let mut std_env = StdEnvironment::default();
+/// let fps = FormalParameterSequence::EmptyFormalParameterSequence(
+///     EmptyFormalParameterSequenceState::new(),
+/// );
+/// let result = std_env.int_type.clone();
+/// std_env
+///     .register_primitive("clock", fps, Some(result), Arc::new(|machine| machine.push(42)))
+///     .unwrap();
+///
+/// let checker = Checker::with_environment(Arc::new(std_env));
+/// ```
 #[derive(Debug)]
 pub struct StdEnvironment {
     pub any_type: TypeDenoter,
@@ -25,73 +57,224 @@ pub struct StdEnvironment {
     pub char_type: TypeDenoter,
     pub bool_type: TypeDenoter,
 
-    pub int_decl: Declaration,
-    pub char_decl: Declaration,
-    pub bool_decl: Declaration,
-    pub false_decl: Declaration,
-    pub true_decl: Declaration,
-
-    pub id_decl: Declaration,
-    pub not_decl: Declaration,
-    pub and_decl: Declaration,
-    pub or_decl: Declaration,
-    pub succ_decl: Declaration,
-    pub pred_decl: Declaration,
-    pub neg_decl: Declaration,
-    pub add_decl: Declaration,
-    pub sub_decl: Declaration,
-    pub mult_decl: Declaration,
-    pub div_decl: Declaration,
-    pub mod_decl: Declaration,
-    pub lt_decl: Declaration,
-    pub le_decl: Declaration,
-    pub ge_decl: Declaration,
-    pub gt_decl: Declaration,
-    pub eq_decl: Declaration,
-    pub ne_decl: Declaration,
-    pub eol_decl: Declaration,
-    pub eof_decl: Declaration,
-    pub get_decl: Declaration,
-    pub put_decl: Declaration,
-    pub geteol_decl: Declaration,
-    pub puteol_decl: Declaration,
-    pub getint_decl: Declaration,
-    pub putint_decl: Declaration,
-    pub chr_decl: Declaration,
-    pub ord_decl: Declaration,
-    pub new_decl: Declaration,
-    pub dispose_decl: Declaration,
+    pub int_decl: Child<Declaration>,
+    pub char_decl: Child<Declaration>,
+    pub bool_decl: Child<Declaration>,
+    pub false_decl: Child<Declaration>,
+    pub true_decl: Child<Declaration>,
+
+    pub id_decl: Child<Declaration>,
+    pub not_decl: Child<Declaration>,
+    pub and_decl: Child<Declaration>,
+    pub or_decl: Child<Declaration>,
+    pub succ_decl: Child<Declaration>,
+    pub pred_decl: Child<Declaration>,
+    pub neg_decl: Child<Declaration>,
+    pub add_decl: Child<Declaration>,
+    pub sub_decl: Child<Declaration>,
+    pub mult_decl: Child<Declaration>,
+    pub div_decl: Child<Declaration>,
+    pub mod_decl: Child<Declaration>,
+    pub lt_decl: Child<Declaration>,
+    pub le_decl: Child<Declaration>,
+    pub ge_decl: Child<Declaration>,
+    pub gt_decl: Child<Declaration>,
+    pub eq_decl: Child<Declaration>,
+    pub ne_decl: Child<Declaration>,
+    pub eol_decl: Child<Declaration>,
+    pub eof_decl: Child<Declaration>,
+    pub get_decl: Child<Declaration>,
+    pub put_decl: Child<Declaration>,
+    pub geteol_decl: Child<Declaration>,
+    pub puteol_decl: Child<Declaration>,
+    pub getint_decl: Child<Declaration>,
+    pub putint_decl: Child<Declaration>,
+    pub chr_decl: Child<Declaration>,
+    pub ord_decl: Child<Declaration>,
+    pub new_decl: Child<Declaration>,
+    pub dispose_decl: Child<Declaration>,
+
+    /// the host primitives, in the order of their slots in the primitive segment
+    pub host_primitives: Vec<HostPrimitive>,
+
+    /// the runtime entities of the declarations, and the types of the constants
+    pub decorations: Decorations,
+
+    /// the arena that the declarations are stored in, which the arena of every program checked
+    /// in the environment is linked to
+    pub nodes: Arc<Nodes>,
+
+    /// the NodeIds that the environment is numbered with, and that host primitives are numbered
+    /// from when they are registered
+    ids: NodeIds,
+}
+
+/// a procedure or function that is declared in the standard environment, and implemented by a
+/// Rust routine that the interpreter runs when it is called
+#[derive(Clone)]
+pub struct HostPrimitive {
+    pub name: String,
+    pub decl: Child<Declaration>,
+    pub routine: HostRoutine,
+}
+
+impl fmt::Debug for HostPrimitive {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("HostPrimitive")
+            .field("name", &self.name)
+            .field("decl", &self.decl)
+            .finish()
+    }
 }
 
 impl StdEnvironment {
-    fn declare_std_type(id: &str, td: TypeDenoter) -> Declaration {
-        Declaration::TypeDeclaration(TypeDeclarationState::new(Identifier::new(id), td))
+    /// a handle on the default standard environment, built once and shared by every Checker
+    /// that is created with `Checker::new`
+    pub fn shared() -> Arc<StdEnvironment> {
+        Arc::clone(&SHARED_ENVIRONMENT)
+    }
+
+    /// every declaration of the environment, along with the name it is declared under - the
+    /// standard declarations first, followed by the host primitives
+    pub fn declarations(&self) -> Vec<(&str, &Declaration)> {
+        let mut decls = vec![
+            ("Integer", &self.int_decl),
+            ("Char", &self.char_decl),
+            ("Boolean", &self.bool_decl),
+            ("false", &self.false_decl),
+            ("true", &self.true_decl),
+            ("id", &self.id_decl),
+            ("\\", &self.not_decl),
+            ("/\\", &self.and_decl),
+            ("\\/", &self.or_decl),
+            ("succ", &self.succ_decl),
+            ("pred", &self.pred_decl),
+            ("neg", &self.neg_decl),
+            ("+", &self.add_decl),
+            ("-", &self.sub_decl),
+            ("*", &self.mult_decl),
+            ("/", &self.div_decl),
+            ("//", &self.mod_decl),
+            ("<", &self.lt_decl),
+            ("<=", &self.le_decl),
+            (">=", &self.ge_decl),
+            (">", &self.gt_decl),
+            ("=", &self.eq_decl),
+            ("\\=", &self.ne_decl),
+            ("eol", &self.eol_decl),
+            ("eof", &self.eof_decl),
+            ("get", &self.get_decl),
+            ("put", &self.put_decl),
+            ("geteol", &self.geteol_decl),
+            ("puteol", &self.puteol_decl),
+            ("getint", &self.getint_decl),
+            ("putint", &self.putint_decl),
+            ("chr", &self.chr_decl),
+            ("ord", &self.ord_decl),
+            ("new", &self.new_decl),
+            ("dispose", &self.dispose_decl),
+        ];
+
+        for primitive in &self.host_primitives {
+            decls.push((primitive.name.as_str(), &primitive.decl));
+        }
+        decls
+            .into_iter()
+            .map(|(name, decl)| (name, decl.get(&self.nodes)))
+            .collect()
+    }
+
+    /// declare a host primitive - a function returning `result`, or a procedure if there is no
+    /// result type - which takes the formal parameters `fps`, and is carried out by `routine`.
+    /// The name must not already be declared in the environment.
+    pub fn register_primitive(
+        &mut self,
+        name: &str,
+        fps: FormalParameterSequence,
+        result: Option<TypeDenoter>,
+        routine: HostRoutine,
+    ) -> GenResult<()> {
+        if self
+            .declarations()
+            .iter()
+            .any(|(declared, _)| *declared == name)
+        {
+            return Err(GenError::from(CompilerError::new(&format!(
+                "{} is already declared in the standard environment",
+                name
+            ))));
+        }
+
+        let mut decl = match result {
+            Some(td) => StdEnvironment::declare_std_function(
+                name,
+                fps,
+                td,
+                Expression::EmptyExpression(EmptyExpressionState::new()),
+            ),
+            None => StdEnvironment::declare_std_procedure(
+                name,
+                fps,
+                Command::EmptyCommand(EmptyCommandState::new()),
+            ),
+        };
+
+        decl.adopt(Arc::make_mut(&mut self.nodes), &mut self.ids);
+
+        let displacement = HOST_PRIMITIVE_BASE + self.host_primitives.len() as isize;
+        set_entity(
+            &mut self.decorations,
+            &decl,
+            RuntimeEntity::PrimitiveRoutine(PrimitiveRoutineState::new(CLOSURE_SIZE, displacement)),
+        );
+
+        self.host_primitives.push(HostPrimitive {
+            name: name.to_owned(),
+            decl,
+            routine,
+        });
+        Ok(())
+    }
+
+    /// the names of the host primitives, for the disassembler to label calls to them with
+    pub fn host_names(&self) -> Vec<&str> {
+        self.host_primitives
+            .iter()
+            .map(|primitive| primitive.name.as_str())
+            .collect()
+    }
+
+    /// the routines of the host primitives, for the interpreter that runs the compiled program
+    pub fn host_routines(&self) -> Vec<HostRoutine> {
+        self.host_primitives
+            .iter()
+            .map(|primitive| Arc::clone(&primitive.routine))
+            .collect()
     }
 
-    fn declare_std_const(id: &str, val: &str) -> Declaration {
-        Declaration::ConstDeclaration(ConstDeclarationState::new(
+    fn declare_std_type(id: &str, td: TypeDenoter) -> Child<Declaration> {
+        Child::new(Declaration::TypeDeclaration(TypeDeclarationState::new(
             Identifier::new(id),
-            Expression::IntegerExpression(IntegerExpressionState::new(IntegerLiteral::new(val))),
-        ))
+            td,
+        )))
+    }
+
+    fn declare_std_const(id: &str, val: &str) -> Child<Declaration> {
+        let expr = IntegerExpressionState::new(IntegerLiteral::new(val));
+        Child::new(Declaration::ConstDeclaration(ConstDeclarationState::new(
+            Identifier::new(id),
+            Expression::IntegerExpression(expr),
+        )))
     }
 
     fn declare_std_unary_operator(
         id: &str,
         arg_type: TypeDenoter,
         res_type: TypeDenoter,
-    ) -> Declaration {
-        let mut decl = Declaration::UnaryOperatorDeclaration(UnaryOperatorDeclarationState::new(
-            Operator::new(id),
-            arg_type,
-            res_type,
-        ));
-
-        let decl_clone = decl.clone();
-        if let Declaration::UnaryOperatorDeclaration(ref mut state) = decl {
-            state.op.decl = Some(Box::new(decl_clone));
-        }
-
-        decl
+    ) -> Child<Declaration> {
+        Child::new(Declaration::UnaryOperatorDeclaration(
+            UnaryOperatorDeclarationState::new(Operator::new(id), arg_type, res_type),
+        ))
     }
 
     fn declare_std_binary_operator(
@@ -99,32 +282,22 @@ impl StdEnvironment {
         arg1_type: TypeDenoter,
         arg2_type: TypeDenoter,
         res_type: TypeDenoter,
-    ) -> Declaration {
-        let mut decl = Declaration::BinaryOperatorDeclaration(BinaryOperatorDeclarationState::new(
-            arg1_type,
-            Operator::new(id),
-            arg2_type,
-            res_type,
-        ));
-
-        let decl_clone = decl.clone();
-        if let Declaration::BinaryOperatorDeclaration(ref mut state) = decl {
-            state.op.decl = Some(Box::new(decl_clone));
-        }
-
-        decl
+    ) -> Child<Declaration> {
+        Child::new(Declaration::BinaryOperatorDeclaration(
+            BinaryOperatorDeclarationState::new(arg1_type, Operator::new(id), arg2_type, res_type),
+        ))
     }
 
-    fn declare_std_procedure(id: &str, fps: FormalParameterSequence, cmd: Command) -> Declaration {
-        let mut decl =
-            Declaration::ProcDeclaration(ProcDeclarationState::new(Identifier::new(id), fps, cmd));
-
-        let decl_clone = decl.clone();
-        if let Declaration::ProcDeclaration(ref mut state) = decl {
-            state.id.decl = Some(Box::new(decl_clone));
-        }
-
-        decl
+    fn declare_std_procedure(
+        id: &str,
+        fps: FormalParameterSequence,
+        cmd: Command,
+    ) -> Child<Declaration> {
+        Child::new(Declaration::ProcDeclaration(ProcDeclarationState::new(
+            Identifier::new(id),
+            fps,
+            cmd,
+        )))
     }
 
     fn declare_std_function(
@@ -132,23 +305,283 @@ impl StdEnvironment {
         fps: FormalParameterSequence,
         td: TypeDenoter,
         expr: Expression,
-    ) -> Declaration {
-        let mut decl = Declaration::FuncDeclaration(FuncDeclarationState::new(
+    ) -> Child<Declaration> {
+        Child::new(Declaration::FuncDeclaration(FuncDeclarationState::new(
             Identifier::new(id),
             fps,
             td,
             expr,
-        ));
+        )))
+    }
+}
 
-        let decl_clone = decl.clone();
-        if let Declaration::FuncDeclaration(ref mut state) = decl {
-            state.id.decl = Some(Box::new(decl_clone));
+impl StdEnvironment {
+    /// number the types and the declarations of the environment, and store the declarations in
+    /// its arena
+    fn number(&mut self) {
+        let nodes = Arc::make_mut(&mut self.nodes);
+        let ids = &mut self.ids;
+        let types = [
+            &mut self.any_type,
+            &mut self.error_type,
+            &mut self.int_type,
+            &mut self.char_type,
+            &mut self.bool_type,
+        ];
+        for td in types {
+            td.adopt(nodes, ids);
         }
 
-        decl
+        let decls = [
+            &mut self.int_decl,
+            &mut self.char_decl,
+            &mut self.bool_decl,
+            &mut self.false_decl,
+            &mut self.true_decl,
+            &mut self.id_decl,
+            &mut self.not_decl,
+            &mut self.and_decl,
+            &mut self.or_decl,
+            &mut self.succ_decl,
+            &mut self.pred_decl,
+            &mut self.neg_decl,
+            &mut self.add_decl,
+            &mut self.sub_decl,
+            &mut self.mult_decl,
+            &mut self.div_decl,
+            &mut self.mod_decl,
+            &mut self.lt_decl,
+            &mut self.le_decl,
+            &mut self.ge_decl,
+            &mut self.gt_decl,
+            &mut self.eq_decl,
+            &mut self.ne_decl,
+            &mut self.eol_decl,
+            &mut self.eof_decl,
+            &mut self.get_decl,
+            &mut self.put_decl,
+            &mut self.geteol_decl,
+            &mut self.puteol_decl,
+            &mut self.getint_decl,
+            &mut self.putint_decl,
+            &mut self.chr_decl,
+            &mut self.ord_decl,
+            &mut self.new_decl,
+            &mut self.dispose_decl,
+        ];
+        for decl in decls {
+            decl.adopt(nodes, ids);
+        }
+    }
+
+    /// record the runtime entities of the standard declarations, and the types of the standard
+    /// constants, in the decorations that every Checker starts out with
+    fn elaborate(&mut self) {
+        for decl in &[&self.false_decl, &self.true_decl] {
+            if let Some(const_decl) = decl.get(&self.nodes).get_const_declaration() {
+                let id = const_decl.expr.id();
+                self.decorations.set_type(id, self.bool_type.clone());
+            }
+        }
+
+        fn primitive(prim: Primitive) -> RuntimeEntity {
+            RuntimeEntity::PrimitiveRoutine(PrimitiveRoutineState::new(
+                CLOSURE_SIZE,
+                prim.displacement(),
+            ))
+        }
+
+        fn equality(prim: Primitive) -> RuntimeEntity {
+            RuntimeEntity::EqualityRoutine(EqualityRoutineState::new(
+                CLOSURE_SIZE,
+                prim.displacement(),
+            ))
+        }
+
+        set_entity(
+            &mut self.decorations,
+            &self.int_decl,
+            RuntimeEntity::TypeRepresentation(TypeRepresentationState::new(INTEGER_SIZE)),
+        );
+        set_entity(
+            &mut self.decorations,
+            &self.char_decl,
+            RuntimeEntity::TypeRepresentation(TypeRepresentationState::new(CHARACTER_SIZE)),
+        );
+        set_entity(
+            &mut self.decorations,
+            &self.bool_decl,
+            RuntimeEntity::TypeRepresentation(TypeRepresentationState::new(BOOLEAN_SIZE)),
+        );
+        set_entity(
+            &mut self.decorations,
+            &self.false_decl,
+            RuntimeEntity::KnownValue(KnownValueState::new(BOOLEAN_SIZE, FALSE_REP as i32)),
+        );
+        set_entity(
+            &mut self.decorations,
+            &self.true_decl,
+            RuntimeEntity::KnownValue(KnownValueState::new(BOOLEAN_SIZE, TRUE_REP as i32)),
+        );
+
+        set_entity(
+            &mut self.decorations,
+            &self.id_decl,
+            primitive(Primitive::Id),
+        );
+        set_entity(
+            &mut self.decorations,
+            &self.not_decl,
+            primitive(Primitive::Not),
+        );
+        set_entity(
+            &mut self.decorations,
+            &self.and_decl,
+            primitive(Primitive::And),
+        );
+        set_entity(
+            &mut self.decorations,
+            &self.or_decl,
+            primitive(Primitive::Or),
+        );
+        set_entity(
+            &mut self.decorations,
+            &self.succ_decl,
+            primitive(Primitive::Succ),
+        );
+        set_entity(
+            &mut self.decorations,
+            &self.pred_decl,
+            primitive(Primitive::Pred),
+        );
+        set_entity(
+            &mut self.decorations,
+            &self.neg_decl,
+            primitive(Primitive::Neg),
+        );
+        set_entity(
+            &mut self.decorations,
+            &self.add_decl,
+            primitive(Primitive::Add),
+        );
+        set_entity(
+            &mut self.decorations,
+            &self.sub_decl,
+            primitive(Primitive::Sub),
+        );
+        set_entity(
+            &mut self.decorations,
+            &self.mult_decl,
+            primitive(Primitive::Mult),
+        );
+        set_entity(
+            &mut self.decorations,
+            &self.div_decl,
+            primitive(Primitive::Div),
+        );
+        set_entity(
+            &mut self.decorations,
+            &self.mod_decl,
+            primitive(Primitive::Mod),
+        );
+        set_entity(
+            &mut self.decorations,
+            &self.lt_decl,
+            primitive(Primitive::Lt),
+        );
+        set_entity(
+            &mut self.decorations,
+            &self.le_decl,
+            primitive(Primitive::Le),
+        );
+        set_entity(
+            &mut self.decorations,
+            &self.ge_decl,
+            primitive(Primitive::Ge),
+        );
+        set_entity(
+            &mut self.decorations,
+            &self.gt_decl,
+            primitive(Primitive::Gt),
+        );
+        set_entity(
+            &mut self.decorations,
+            &self.eq_decl,
+            equality(Primitive::Eq),
+        );
+        set_entity(
+            &mut self.decorations,
+            &self.ne_decl,
+            equality(Primitive::Ne),
+        );
+        set_entity(
+            &mut self.decorations,
+            &self.eol_decl,
+            primitive(Primitive::Eol),
+        );
+        set_entity(
+            &mut self.decorations,
+            &self.eof_decl,
+            primitive(Primitive::Eof),
+        );
+        set_entity(
+            &mut self.decorations,
+            &self.get_decl,
+            primitive(Primitive::Get),
+        );
+        set_entity(
+            &mut self.decorations,
+            &self.put_decl,
+            primitive(Primitive::Put),
+        );
+        set_entity(
+            &mut self.decorations,
+            &self.geteol_decl,
+            primitive(Primitive::GetEol),
+        );
+        set_entity(
+            &mut self.decorations,
+            &self.puteol_decl,
+            primitive(Primitive::PutEol),
+        );
+        set_entity(
+            &mut self.decorations,
+            &self.getint_decl,
+            primitive(Primitive::GetInt),
+        );
+        set_entity(
+            &mut self.decorations,
+            &self.putint_decl,
+            primitive(Primitive::PutInt),
+        );
+        // chr and ord do not change the representation of their argument
+        set_entity(
+            &mut self.decorations,
+            &self.chr_decl,
+            primitive(Primitive::Id),
+        );
+        set_entity(
+            &mut self.decorations,
+            &self.ord_decl,
+            primitive(Primitive::Id),
+        );
+        set_entity(
+            &mut self.decorations,
+            &self.new_decl,
+            primitive(Primitive::New),
+        );
+        set_entity(
+            &mut self.decorations,
+            &self.dispose_decl,
+            primitive(Primitive::Dispose),
+        );
     }
 }
 
+fn set_entity(decorations: &mut Decorations, decl: &Child<Declaration>, entity: RuntimeEntity) {
+    decorations.set_entity(decl.id(), entity);
+}
+
 impl Default for StdEnvironment {
     fn default() -> Self {
         // primitive types
@@ -267,36 +700,38 @@ impl Default for StdEnvironment {
         );
 
         let ne_decl = StdEnvironment::declare_std_binary_operator(
-            "/=",
+            "\\=",
             any_type.clone(),
             any_type.clone(),
             bool_type.clone(),
         );
 
-        // procedures
+        // functions on integers
 
         let succ_decl = {
             let dummy_id = Identifier::default();
             let fps = FormalParameterSequence::SingleFormalParameterSequence(
-                SingleFormalParameterSequenceState::new(FormalParameter::VarFormalParameter(
-                    VarFormalParameterState::new(dummy_id, int_type.clone()),
+                SingleFormalParameterSequenceState::new(FormalParameter::ConstFormalParameter(
+                    ConstFormalParameterState::new(dummy_id, int_type.clone()),
                 )),
             );
-            let cmd = Command::EmptyCommand(EmptyCommandState::new());
-            StdEnvironment::declare_std_procedure("succ", fps, cmd)
+            let expr = Expression::EmptyExpression(EmptyExpressionState::new());
+            StdEnvironment::declare_std_function("succ", fps, int_type.clone(), expr)
         };
 
         let pred_decl = {
             let dummy_id = Identifier::default();
             let fps = FormalParameterSequence::SingleFormalParameterSequence(
-                SingleFormalParameterSequenceState::new(FormalParameter::VarFormalParameter(
-                    VarFormalParameterState::new(dummy_id, int_type.clone()),
+                SingleFormalParameterSequenceState::new(FormalParameter::ConstFormalParameter(
+                    ConstFormalParameterState::new(dummy_id, int_type.clone()),
                 )),
             );
-            let cmd = Command::EmptyCommand(EmptyCommandState::new());
-            StdEnvironment::declare_std_procedure("pred", fps, cmd)
+            let expr = Expression::EmptyExpression(EmptyExpressionState::new());
+            StdEnvironment::declare_std_function("pred", fps, int_type.clone(), expr)
         };
 
+        // procedures
+
         let put_decl = {
             let dummy_id = Identifier::default();
             let fps = FormalParameterSequence::SingleFormalParameterSequence(
@@ -364,8 +799,8 @@ impl Default for StdEnvironment {
                     ConstFormalParameterState::new(dummy_id, int_type.clone()),
                 )),
             );
-            let cmd = Command::EmptyCommand(EmptyCommandState::new());
-            StdEnvironment::declare_std_procedure("new", fps, cmd)
+            let expr = Expression::EmptyExpression(EmptyExpressionState::new());
+            StdEnvironment::declare_std_function("new", fps, int_type.clone(), expr)
         };
 
         let dispose_decl = {
@@ -443,7 +878,7 @@ impl Default for StdEnvironment {
             StdEnvironment::declare_std_function("eof", fps, bool_type.clone(), expr)
         };
 
-        StdEnvironment {
+        let mut env = StdEnvironment {
             any_type,
             error_type,
             int_type,
@@ -486,16 +921,49 @@ impl Default for StdEnvironment {
             ord_decl,
             new_decl,
             dispose_decl,
-        }
+
+            host_primitives: Vec::new(),
+            decorations: Decorations::new(),
+            nodes: Arc::new(Nodes::environment()),
+            ids: NodeIds::environment(),
+        };
+
+        env.number();
+        env.elaborate();
+        env
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::STANDARD_ENVIRONMENT;
+    use super::StdEnvironment;
+    use crate::ast::parameters::*;
+
+    use std::sync::Arc;
 
     #[test]
     fn test_print_default_std_env() {
-        println!("{:#?}", unsafe { STANDARD_ENVIRONMENT.lock().unwrap() });
+        println!("{:#?}", StdEnvironment::shared());
+    }
+
+    #[test]
+    fn test_register_primitive() {
+        let mut std_env = StdEnvironment::default();
+        let no_fps = || {
+            FormalParameterSequence::EmptyFormalParameterSequence(
+                EmptyFormalParameterSequenceState::new(),
+            )
+        };
+
+        assert!(std_env
+            .register_primitive("tick", no_fps(), None, Arc::new(|_| Ok(())))
+            .is_ok());
+        assert!(std_env
+            .register_primitive("tick", no_fps(), None, Arc::new(|_| Ok(())))
+            .is_err());
+        assert!(std_env
+            .register_primitive("putint", no_fps(), None, Arc::new(|_| Ok(())))
+            .is_err());
+        assert_eq!(1, std_env.host_routines().len());
     }
 }
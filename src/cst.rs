@@ -0,0 +1,516 @@
+//! The concrete syntax tree
+//!
+//! A lossless view of a Triangle program - the nodes of the abstract syntax tree, each holding
+//! the tokens that it was parsed from, and every token holding the whitespace and comments
+//! before it. Nothing of the source is left out, so the text of the tree is exactly the text
+//! that was parsed, which is what a tool that rewrites part of a program needs to leave the
+//! rest of it as it was.
+//!
+//! Each token belongs to the innermost node whose position covers it. The tokens that the
+//! abstract syntax tree has no node for - keywords, punctuation and the parentheses around an
+//! expression - belong to the node that they are part of, and the end of the text belongs to
+//! the program.
+
+use crate::ast::aggregates::*;
+use crate::ast::arena::{Child, Nodes};
+use crate::ast::commands::*;
+use crate::ast::declarations::*;
+use crate::ast::expressions::*;
+use crate::ast::parameters::*;
+use crate::ast::primitives::*;
+use crate::ast::typedenoters::*;
+use crate::ast::visit_ref::{self, AstRefVisitor};
+use crate::ast::vnames::*;
+use crate::ast::{CommonState, NodeId, Program};
+use crate::error::GenResult;
+use crate::parser::Parser;
+use crate::scanner::{Scanner, SourcePosition, Token, TokenType};
+
+use std::collections::VecDeque;
+use std::fmt;
+
+/// the kinds of node, one for each variant of the abstract syntax tree
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SyntaxKind {
+    Program,
+
+    AssignCommand,
+    CallCommand,
+    EmptyCommand,
+    IfCommand,
+    LetCommand,
+    SequentialCommand,
+    WhileCommand,
+
+    ArrayExpression,
+    BinaryExpression,
+    CallExpression,
+    CharacterExpression,
+    EmptyExpression,
+    IfExpression,
+    IntegerExpression,
+    LetExpression,
+    RecordExpression,
+    UnaryExpression,
+    VnameExpression,
+
+    SingleArrayAggregate,
+    MultipleArrayAggregate,
+    SingleRecordAggregate,
+    MultipleRecordAggregate,
+
+    BinaryOperatorDeclaration,
+    ConstDeclaration,
+    FuncDeclaration,
+    ProcDeclaration,
+    SequentialDeclaration,
+    TypeDeclaration,
+    UnaryOperatorDeclaration,
+    VarDeclaration,
+
+    AnyTypeDenoter,
+    ArrayTypeDenoter,
+    BoolTypeDenoter,
+    CharTypeDenoter,
+    ErrorTypeDenoter,
+    IntTypeDenoter,
+    RecordTypeDenoter,
+    SimpleTypeDenoter,
+    SingleFieldTypeDenoter,
+    MultipleFieldTypeDenoter,
+
+    EmptyFormalParameterSequence,
+    SingleFormalParameterSequence,
+    MultipleFormalParameterSequence,
+    ConstFormalParameter,
+    FuncFormalParameter,
+    ProcFormalParameter,
+    VarFormalParameter,
+
+    EmptyActualParameterSequence,
+    SingleActualParameterSequence,
+    MultipleActualParameterSequence,
+    ConstActualParameter,
+    FuncActualParameter,
+    ProcActualParameter,
+    VarActualParameter,
+
+    DotVname,
+    SimpleVname,
+    SubscriptVname,
+
+    Identifier,
+    Operator,
+    IntegerLiteral,
+    CharacterLiteral,
+}
+
+impl From<&Command> for SyntaxKind {
+    fn from(cmd: &Command) -> Self {
+        use Command::*;
+
+        match *cmd {
+            AssignCommand(_) => SyntaxKind::AssignCommand,
+            CallCommand(_) => SyntaxKind::CallCommand,
+            EmptyCommand(_) => SyntaxKind::EmptyCommand,
+            IfCommand(_) => SyntaxKind::IfCommand,
+            LetCommand(_) => SyntaxKind::LetCommand,
+            SequentialCommand(_) => SyntaxKind::SequentialCommand,
+            WhileCommand(_) => SyntaxKind::WhileCommand,
+        }
+    }
+}
+
+impl From<&Expression> for SyntaxKind {
+    fn from(expr: &Expression) -> Self {
+        use Expression::*;
+
+        match *expr {
+            ArrayExpression(_) => SyntaxKind::ArrayExpression,
+            BinaryExpression(_) => SyntaxKind::BinaryExpression,
+            CallExpression(_) => SyntaxKind::CallExpression,
+            CharacterExpression(_) => SyntaxKind::CharacterExpression,
+            EmptyExpression(_) => SyntaxKind::EmptyExpression,
+            IfExpression(_) => SyntaxKind::IfExpression,
+            IntegerExpression(_) => SyntaxKind::IntegerExpression,
+            LetExpression(_) => SyntaxKind::LetExpression,
+            RecordExpression(_) => SyntaxKind::RecordExpression,
+            UnaryExpression(_) => SyntaxKind::UnaryExpression,
+            VnameExpression(_) => SyntaxKind::VnameExpression,
+        }
+    }
+}
+
+impl From<&ArrayAggregate> for SyntaxKind {
+    fn from(agg: &ArrayAggregate) -> Self {
+        match *agg {
+            ArrayAggregate::SingleArrayAggregate(_) => SyntaxKind::SingleArrayAggregate,
+            ArrayAggregate::MultipleArrayAggregate(_) => SyntaxKind::MultipleArrayAggregate,
+        }
+    }
+}
+
+impl From<&RecordAggregate> for SyntaxKind {
+    fn from(agg: &RecordAggregate) -> Self {
+        match *agg {
+            RecordAggregate::SingleRecordAggregate(_) => SyntaxKind::SingleRecordAggregate,
+            RecordAggregate::MultipleRecordAggregate(_) => SyntaxKind::MultipleRecordAggregate,
+        }
+    }
+}
+
+impl From<&Declaration> for SyntaxKind {
+    fn from(decl: &Declaration) -> Self {
+        use Declaration::*;
+
+        match *decl {
+            BinaryOperatorDeclaration(_) => SyntaxKind::BinaryOperatorDeclaration,
+            ConstDeclaration(_) => SyntaxKind::ConstDeclaration,
+            FormalParameterDeclaration(ref fp) => SyntaxKind::from(fp),
+            FuncDeclaration(_) => SyntaxKind::FuncDeclaration,
+            ProcDeclaration(_) => SyntaxKind::ProcDeclaration,
+            SequentialDeclaration(_) => SyntaxKind::SequentialDeclaration,
+            TypeDeclaration(_) => SyntaxKind::TypeDeclaration,
+            UnaryOperatorDeclaration(_) => SyntaxKind::UnaryOperatorDeclaration,
+            VarDeclaration(_) => SyntaxKind::VarDeclaration,
+        }
+    }
+}
+
+impl From<&TypeDenoter> for SyntaxKind {
+    fn from(td: &TypeDenoter) -> Self {
+        use TypeDenoter::*;
+
+        match *td {
+            AnyTypeDenoter(_) => SyntaxKind::AnyTypeDenoter,
+            ArrayTypeDenoter(_) => SyntaxKind::ArrayTypeDenoter,
+            BoolTypeDenoter(_) => SyntaxKind::BoolTypeDenoter,
+            CharTypeDenoter(_) => SyntaxKind::CharTypeDenoter,
+            ErrorTypeDenoter(_) => SyntaxKind::ErrorTypeDenoter,
+            IntTypeDenoter(_) => SyntaxKind::IntTypeDenoter,
+            RecordTypeDenoter(_) => SyntaxKind::RecordTypeDenoter,
+            SimpleTypeDenoter(_) => SyntaxKind::SimpleTypeDenoter,
+        }
+    }
+}
+
+impl From<&FieldTypeDenoter> for SyntaxKind {
+    fn from(td: &FieldTypeDenoter) -> Self {
+        match *td {
+            FieldTypeDenoter::SingleFieldTypeDenoter(_) => SyntaxKind::SingleFieldTypeDenoter,
+            FieldTypeDenoter::MultipleFieldTypeDenoter(_) => SyntaxKind::MultipleFieldTypeDenoter,
+        }
+    }
+}
+
+impl From<&FormalParameterSequence> for SyntaxKind {
+    fn from(fps: &FormalParameterSequence) -> Self {
+        use FormalParameterSequence::*;
+
+        match *fps {
+            EmptyFormalParameterSequence(_) => SyntaxKind::EmptyFormalParameterSequence,
+            SingleFormalParameterSequence(_) => SyntaxKind::SingleFormalParameterSequence,
+            MultipleFormalParameterSequence(_) => SyntaxKind::MultipleFormalParameterSequence,
+        }
+    }
+}
+
+impl From<&FormalParameter> for SyntaxKind {
+    fn from(fp: &FormalParameter) -> Self {
+        use FormalParameter::*;
+
+        match *fp {
+            ConstFormalParameter(_) => SyntaxKind::ConstFormalParameter,
+            FuncFormalParameter(_) => SyntaxKind::FuncFormalParameter,
+            ProcFormalParameter(_) => SyntaxKind::ProcFormalParameter,
+            VarFormalParameter(_) => SyntaxKind::VarFormalParameter,
+        }
+    }
+}
+
+impl From<&ActualParameterSequence> for SyntaxKind {
+    fn from(aps: &ActualParameterSequence) -> Self {
+        use ActualParameterSequence::*;
+
+        match *aps {
+            EmptyActualParameterSequence(_) => SyntaxKind::EmptyActualParameterSequence,
+            SingleActualParameterSequence(_) => SyntaxKind::SingleActualParameterSequence,
+            MultipleActualParameterSequence(_) => SyntaxKind::MultipleActualParameterSequence,
+        }
+    }
+}
+
+impl From<&ActualParameter> for SyntaxKind {
+    fn from(ap: &ActualParameter) -> Self {
+        use ActualParameter::*;
+
+        match *ap {
+            ConstActualParameter(_) => SyntaxKind::ConstActualParameter,
+            FuncActualParameter(_) => SyntaxKind::FuncActualParameter,
+            ProcActualParameter(_) => SyntaxKind::ProcActualParameter,
+            VarActualParameter(_) => SyntaxKind::VarActualParameter,
+        }
+    }
+}
+
+impl From<&Vname> for SyntaxKind {
+    fn from(vname: &Vname) -> Self {
+        match *vname {
+            Vname::DotVname(_) => SyntaxKind::DotVname,
+            Vname::SimpleVname(_) => SyntaxKind::SimpleVname,
+            Vname::SubscriptVname(_) => SyntaxKind::SubscriptVname,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SyntaxNode {
+    pub kind: SyntaxKind,
+    /// the node of the abstract syntax tree that this one stands for
+    pub node: NodeId,
+    pub position: SourcePosition,
+    /// the nodes and tokens that make up this one, in the order that they appear
+    pub children: Vec<SyntaxElement>,
+}
+
+#[derive(Clone, Debug)]
+pub enum SyntaxElement {
+    Node(SyntaxNode),
+    Token(Token),
+}
+
+impl SyntaxNode {
+    /// every token of the node, in order
+    pub fn tokens(&self) -> Vec<&Token> {
+        let mut tokens = Vec::new();
+        for child in &self.children {
+            match *child {
+                SyntaxElement::Node(ref node) => tokens.extend(node.tokens()),
+                SyntaxElement::Token(ref token) => tokens.push(token),
+            }
+        }
+        tokens
+    }
+
+    /// the source text of the node, starting with the whitespace and comments before its first
+    /// token
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        for token in self.tokens() {
+            for trivia in &token.leading {
+                text.push_str(&trivia.text);
+            }
+            text.push_str(&token.text());
+        }
+        text
+    }
+
+    /// the node that stands for the node of the abstract syntax tree, if it is this one or
+    /// inside it
+    pub fn find(&self, node: NodeId) -> Option<&SyntaxNode> {
+        if self.node == node {
+            return Some(self);
+        }
+        self.children.iter().find_map(|child| match *child {
+            SyntaxElement::Node(ref child) => child.find(node),
+            SyntaxElement::Token(_) => None,
+        })
+    }
+}
+
+impl fmt::Display for SyntaxNode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.text())
+    }
+}
+
+/// parse the source text, returning the program along with its concrete syntax tree
+pub fn parse(text: &str) -> GenResult<(Program, SyntaxNode)> {
+    let program = Parser::new(Scanner::from_text(text)).parse_program()?;
+    let tokens = Scanner::from_text(text).with_trivia().scan_all()?;
+    let root = build(&program, tokens);
+    Ok((program, root))
+}
+
+/// build the concrete syntax tree of the program from every token of its source, trivia and all
+pub fn build(program: &Program, tokens: Vec<Token>) -> SyntaxNode {
+    let mut builder = Builder {
+        nodes: &program.nodes,
+        tokens: tokens.into(),
+        stack: vec![SyntaxNode {
+            kind: SyntaxKind::Program,
+            node: program.common_state.id,
+            position: program.common_state.position,
+            children: Vec::new(),
+        }],
+    };
+    builder.visit_command(&program.cmd, ());
+
+    let mut root = builder.stack.pop().unwrap();
+    root.children
+        .extend(builder.tokens.into_iter().map(SyntaxElement::Token));
+    root
+}
+
+struct Builder<'a> {
+    /// the arena of the program that the tree is built for
+    nodes: &'a Nodes,
+    /// the tokens that have not been given to a node yet
+    tokens: VecDeque<Token>,
+    /// the nodes that have been entered and not yet left, innermost last
+    stack: Vec<SyntaxNode>,
+}
+
+impl<'a> Builder<'a> {
+    fn enter(&mut self, kind: SyntaxKind, state: &CommonState) {
+        let start = state.position.start;
+        self.take(|token| token.position.start < start);
+        self.stack.push(SyntaxNode {
+            kind,
+            node: state.id,
+            position: state.position,
+            children: Vec::new(),
+        });
+    }
+
+    fn leave(&mut self) {
+        let finish = self.stack[self.stack.len() - 1].position.finish;
+        self.take(|token| token.position.start <= finish);
+        let node = self.stack.pop().unwrap();
+        let parent = self.stack.len() - 1;
+        self.stack[parent].children.push(SyntaxElement::Node(node));
+    }
+
+    /// give the innermost node the tokens that belong to it. The end of the text is left for
+    /// the program.
+    fn take<F: Fn(&Token) -> bool>(&mut self, belongs: F) {
+        let node = self.stack.len() - 1;
+        while let Some(token) = self.tokens.front() {
+            if token.kind == TokenType::Eot || !belongs(token) {
+                break;
+            }
+            let token = self.tokens.pop_front().unwrap();
+            self.stack[node].children.push(SyntaxElement::Token(token));
+        }
+    }
+
+    fn leaf(&mut self, kind: SyntaxKind, state: &CommonState) {
+        self.enter(kind, state);
+        self.leave();
+    }
+}
+
+impl<'a> AstRefVisitor<'a> for Builder<'a> {
+    type Arg = ();
+    type Output = ();
+
+    fn nodes(&self) -> &'a Nodes {
+        self.nodes
+    }
+
+    fn visit_command(&mut self, cmd: &'a Child<Command>, arg: ()) {
+        let cmd = cmd.get(self.nodes);
+        self.enter(SyntaxKind::from(cmd), cmd.common_state());
+        visit_ref::walk_command(self, cmd, arg);
+        self.leave();
+    }
+
+    fn visit_expression(&mut self, expr: &'a Child<Expression>, arg: ()) {
+        let expr = expr.get(self.nodes);
+        self.enter(SyntaxKind::from(expr), expr.common_state());
+        visit_ref::walk_expression(self, expr, arg);
+        self.leave();
+    }
+
+    fn visit_array_aggregate(&mut self, agg: &'a Child<ArrayAggregate>, arg: ()) {
+        let agg = agg.get(self.nodes);
+        self.enter(SyntaxKind::from(agg), agg.common_state());
+        visit_ref::walk_array_aggregate(self, agg, arg);
+        self.leave();
+    }
+
+    fn visit_record_aggregate(&mut self, agg: &'a Child<RecordAggregate>, arg: ()) {
+        let agg = agg.get(self.nodes);
+        self.enter(SyntaxKind::from(agg), agg.common_state());
+        visit_ref::walk_record_aggregate(self, agg, arg);
+        self.leave();
+    }
+
+    fn visit_declaration(&mut self, decl: &'a Child<Declaration>, arg: ()) {
+        let decl = decl.get(self.nodes);
+        self.enter(SyntaxKind::from(decl), decl.common_state());
+        visit_ref::walk_declaration(self, decl, arg);
+        self.leave();
+    }
+
+    fn visit_type_denoter(&mut self, td: &'a TypeDenoter, arg: ()) {
+        self.enter(SyntaxKind::from(td), td.common_state());
+        visit_ref::walk_type_denoter(self, td, arg);
+        self.leave();
+    }
+
+    fn visit_field_type_denoter(&mut self, td: &'a FieldTypeDenoter, arg: ()) {
+        self.enter(SyntaxKind::from(td), td.common_state());
+        visit_ref::walk_field_type_denoter(self, td, arg);
+        self.leave();
+    }
+
+    fn visit_formal_parameter_sequence(
+        &mut self,
+        fps: &'a Child<FormalParameterSequence>,
+        arg: (),
+    ) {
+        let fps = fps.get(self.nodes);
+        self.enter(SyntaxKind::from(fps), fps.common_state());
+        visit_ref::walk_formal_parameter_sequence(self, fps, arg);
+        self.leave();
+    }
+
+    fn visit_formal_parameter(&mut self, fp: &'a Child<FormalParameter>, arg: ()) {
+        let fp = fp.get(self.nodes);
+        self.enter(SyntaxKind::from(fp), fp.common_state());
+        visit_ref::walk_formal_parameter(self, fp, arg);
+        self.leave();
+    }
+
+    fn visit_actual_parameter_sequence(
+        &mut self,
+        aps: &'a Child<ActualParameterSequence>,
+        arg: (),
+    ) {
+        let aps = aps.get(self.nodes);
+        self.enter(SyntaxKind::from(aps), aps.common_state());
+        visit_ref::walk_actual_parameter_sequence(self, aps, arg);
+        self.leave();
+    }
+
+    fn visit_actual_parameter(&mut self, ap: &'a Child<ActualParameter>, arg: ()) {
+        let ap = ap.get(self.nodes);
+        self.enter(SyntaxKind::from(ap), ap.common_state());
+        visit_ref::walk_actual_parameter(self, ap, arg);
+        self.leave();
+    }
+
+    fn visit_vname(&mut self, vname: &'a Child<Vname>, arg: ()) {
+        let vname = vname.get(self.nodes);
+        self.enter(SyntaxKind::from(vname), vname.common_state());
+        visit_ref::walk_vname(self, vname, arg);
+        self.leave();
+    }
+
+    fn visit_identifier(&mut self, id: &'a Identifier, _arg: ()) {
+        self.leaf(SyntaxKind::Identifier, &id.common_state);
+    }
+
+    fn visit_operator(&mut self, op: &'a Operator, _arg: ()) {
+        self.leaf(SyntaxKind::Operator, &op.common_state);
+    }
+
+    fn visit_integer_literal(&mut self, il: &'a IntegerLiteral, _arg: ()) {
+        self.leaf(SyntaxKind::IntegerLiteral, &il.common_state);
+    }
+
+    fn visit_character_literal(&mut self, cl: &'a CharacterLiteral, _arg: ()) {
+        self.leaf(SyntaxKind::CharacterLiteral, &cl.common_state);
+    }
+}
@@ -0,0 +1,245 @@
+//! The Diagnostics module.
+//!
+//! Collects the errors found while compiling a program, so that the Parser and the Checker can
+//! each report every error that they find in one run, rather than stopping at the first one.
+//!
+//! Errors that carry a source position can be rendered along with the offending source lines,
+//! with the span of the error underlined, and any notes pointing at related locations:
+//!
+//! ```text
+//! error: identifier x is already defined at level 1
+//!  --> sample.t:3:3
+//!   |
+//! 3 |   var x : Char
+//!   |   ^~~~~~~~~~~~
+//! note: x was first defined here
+//!  --> sample.t:2:3
+//!   |
+//! 2 |   var x : Integer;
+//!   |   ^~~~~~~~~~~~~~~
+//! ```
+
+use crate::error::{self, CheckerWarning, GenError, GenResult};
+use crate::scanner::SourcePosition;
+
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Default)]
+pub struct Diagnostics {
+    errors: Vec<GenError>,
+}
+
+impl Diagnostics {
+    pub fn new() -> Self {
+        Diagnostics { errors: Vec::new() }
+    }
+
+    pub fn report(&mut self, error: GenError) {
+        self.errors.push(error);
+    }
+
+    /// move all the errors collected by another phase into this collection
+    pub fn append(&mut self, other: &mut Diagnostics) {
+        self.errors.append(&mut other.errors);
+    }
+
+    pub fn has_errors(&self) -> bool {
+        !self.errors.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    pub fn errors(&self) -> &[GenError] {
+        &self.errors
+    }
+
+    /// render every error in turn, against the source text of `file`
+    pub fn render(&self, file: &str, source: &str, colour: bool) -> String {
+        self.errors
+            .iter()
+            .map(|error| render(error, file, source, colour))
+            .collect()
+    }
+
+    /// render every entry in turn as a warning, rather than an error
+    pub fn render_warnings(&self, file: &str, source: &str, colour: bool) -> String {
+        self.errors
+            .iter()
+            .map(|error| render_warning(error, file, source, colour))
+            .collect()
+    }
+
+    /// Ok if nothing was reported. A single error is returned as it is, and several errors are
+    /// returned together as the Diagnostics themselves.
+    pub fn into_result(mut self) -> GenResult<()> {
+        match self.errors.len() {
+            0 => Ok(()),
+            1 => Err(self.errors.remove(0)),
+            _ => Err(GenError::from(self)),
+        }
+    }
+}
+
+impl Error for Diagnostics {}
+
+/// each error on a line of its own
+impl fmt::Display for Diagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (idx, error) in self.errors.iter().enumerate() {
+            if idx > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", error)?;
+        }
+        Ok(())
+    }
+}
+
+const RED: &str = "\x1b[1;31m";
+const GREEN: &str = "\x1b[1;32m";
+const YELLOW: &str = "\x1b[1;33m";
+const BLUE: &str = "\x1b[1;34m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+/// render an error as its message followed by the source lines that it refers to, underlined
+/// from the start to the finish of its position. Errors without a position are rendered as their
+/// message alone. `colour` adds ANSI colour codes, for writing to a terminal. A warning is
+/// rendered as one, even among errors.
+pub fn render(error: &GenError, file: &str, source: &str, colour: bool) -> String {
+    if error.is::<CheckerWarning>() {
+        return render_warning(error, file, source, colour);
+    }
+    render_labelled(error, "error", RED, file, source, colour)
+}
+
+/// render an error in the same way, but labelled as a warning
+pub fn render_warning(error: &GenError, file: &str, source: &str, colour: bool) -> String {
+    render_labelled(error, "warning", YELLOW, file, source, colour)
+}
+
+fn render_labelled(
+    error: &GenError,
+    label: &str,
+    code: &str,
+    file: &str,
+    source: &str,
+    colour: bool,
+) -> String {
+    if let Some(diagnostics) = error.downcast_ref::<Diagnostics>() {
+        return diagnostics
+            .errors
+            .iter()
+            .map(|error| render_labelled(error, label, code, file, source, colour))
+            .collect();
+    }
+
+    let mut out = String::new();
+    let paint = Paint { colour };
+    match error::locate(error) {
+        Some(located) => {
+            out += &format!(
+                "{}: {}\n",
+                paint.with(code, label),
+                paint.with(BOLD, located.message())
+            );
+            out += &snippet(located.position(), file, source, code, &paint);
+            for note in located.notes() {
+                out += &format!("{}: {}\n", paint.with(GREEN, "note"), note.message);
+                out += &snippet(note.position, file, source, GREEN, &paint);
+            }
+        }
+        None => out += &format!("{}: {}\n", paint.with(code, label), error),
+    }
+    out
+}
+
+struct Paint {
+    colour: bool,
+}
+
+impl Paint {
+    fn with(&self, code: &str, text: &str) -> String {
+        if self.colour {
+            format!("{}{}{}", code, text, RESET)
+        } else {
+            text.to_owned()
+        }
+    }
+}
+
+/// the location line, and the source lines spanned by the position with their underlines.
+/// Nothing but the location is shown for positions outside the source.
+fn snippet(
+    position: SourcePosition,
+    file: &str,
+    source: &str,
+    underline: &str,
+    paint: &Paint,
+) -> String {
+    let (start, finish) = (position.start, position.finish);
+    let lines = source.lines().collect::<Vec<_>>();
+    if start.line < 1 || start.line as usize > lines.len() {
+        return format!(
+            "{} {}:{}:{}\n",
+            paint.with(BLUE, " -->"),
+            file,
+            start.line,
+            start.column
+        );
+    }
+
+    let last = if finish.line < start.line || finish.line as usize > lines.len() {
+        start.line
+    } else {
+        finish.line
+    };
+    let width = last.to_string().len();
+    let gutter = |label: &str| paint.with(BLUE, &format!("{:>1$} |", label, width));
+
+    let mut out = format!(
+        "{}{} {}:{}:{}\n{}\n",
+        " ".repeat(width),
+        paint.with(BLUE, "-->"),
+        file,
+        start.line,
+        start.column,
+        gutter("")
+    );
+    for line in start.line..=last {
+        let text = lines[line as usize - 1].trim_end();
+        let len = text.chars().count() as isize;
+        let from = if line == start.line { start.column } else { 1 };
+        let to = if line == last && last == finish.line {
+            finish.column
+        } else {
+            len
+        };
+        let from = from.max(1);
+        let to = to.max(from).min(len.max(from));
+
+        let prefix = text
+            .chars()
+            .take(from as usize - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect::<String>();
+        let head = if line == start.line { "^" } else { "~" };
+        let marks = format!("{}{}", head, "~".repeat((to - from) as usize));
+
+        out += &format!("{} {}\n", gutter(&line.to_string()), text);
+        out += &format!(
+            "{} {}{}\n",
+            gutter(""),
+            prefix,
+            paint.with(underline, &marks)
+        );
+    }
+    out
+}
@@ -1 +1,1207 @@
 //! The Encoder module
+//!
+//! This module traverses the AST that has been decorated by the Checker, and generates TAM
+//! (Triangle Abstract Machine) code for it following the code templates described in
+//! `docs/codegen_specification.md`. Along the way, every declaration (and formal parameter) is
+//! decorated with the runtime entity that it elaborates to - a value, an address, a routine, or a
+//! type representation - in the side tables of the program, against the NodeId of the
+//! declaration.
+
+use crate::ast::aggregates::*;
+use crate::ast::arena::{Child, Nodes};
+use crate::ast::commands::*;
+use crate::ast::declarations::*;
+use crate::ast::decorations::Decorations;
+use crate::ast::expressions::*;
+use crate::ast::parameters::*;
+use crate::ast::primitives::*;
+use crate::ast::runtime_entities::*;
+use crate::ast::typedenoters::*;
+use crate::ast::vnames::*;
+use crate::ast::*;
+use crate::diagnostics::Diagnostics;
+use crate::error::{EncoderError, GenError, GenResult};
+use crate::scanner::SourcePosition;
+use crate::tam::*;
+
+use std::mem;
+
+pub struct Encoder {
+    code: Vec<Instruction>,
+    positions: Vec<SourcePosition>,
+    position: SourcePosition,
+    decorations: Decorations,
+    /// the arena of the program while it is being encoded
+    nodes: Nodes,
+    diagnostics: Diagnostics,
+}
+
+impl Encoder {
+    pub fn new() -> Self {
+        Encoder {
+            code: Vec::new(),
+            positions: Vec::new(),
+            position: SourcePosition::default(),
+            decorations: Decorations::new(),
+            nodes: Nodes::new(),
+            diagnostics: Diagnostics::new(),
+        }
+    }
+
+    /// Generate the TAM code for a checked program. The code is meant to be loaded at the
+    /// beginning of the code store (CB = 0). Every restriction of the TAM that the program
+    /// runs into is reported - several are returned together.
+    pub fn encode(&mut self, program: &mut Program) -> GenResult<Vec<Instruction>> {
+        self.code.clear();
+        self.positions.clear();
+        self.position = SourcePosition::default();
+        self.decorations = mem::take(&mut program.decorations);
+        program.accept(self, Frame::new(0, 0));
+        program.decorations = mem::take(&mut self.decorations);
+        mem::take(&mut self.diagnostics).into_result()?;
+        Ok(mem::take(&mut self.code))
+    }
+
+    /// the source position of the command or declaration that each instruction of the last
+    /// encoded program was generated for
+    pub fn positions(&self) -> &[SourcePosition] {
+        &self.positions
+    }
+
+    /// the address of the next instruction to be emitted
+    fn next_instr_addr(&self) -> usize {
+        self.code.len()
+    }
+
+    /// append an instruction to the code store
+    fn emit(&mut self, op: Opcode, n: usize, r: Register, d: isize) {
+        if n > 255 {
+            self.report_restriction("length of operand can't exceed 255 words");
+        }
+
+        if d < i16::MIN as isize || d > i16::MAX as isize {
+            self.report_restriction("operand can't exceed 32767 words");
+        }
+
+        // the code is still emitted, so that the addresses of the jumps remain right
+        if self.next_instr_addr() == PB {
+            self.report_restriction("program is too large to fit in the code store");
+        }
+
+        self.code.push(Instruction::new(op, n as u8, r, d as i16));
+        self.positions.push(self.position);
+    }
+
+    /// attribute the instructions emitted from now on to the given source position, returning
+    /// the position to restore afterwards
+    fn locate(&mut self, position: SourcePosition) -> SourcePosition {
+        mem::replace(&mut self.position, position)
+    }
+
+    /// backpatch the displacement of the (jump) instruction at `addr`
+    fn patch(&mut self, addr: usize, d: usize) {
+        self.code[addr].d = d as i16;
+    }
+
+    /// the register that must be used to access an entity declared at `object_level` from
+    /// code at `current_level`
+    fn display_register(&mut self, current_level: usize, object_level: usize) -> Register {
+        if object_level == 0 {
+            Register::SB
+        } else if current_level - object_level <= 6 {
+            Register::from_u8(Register::LB as u8 + (current_level - object_level) as u8).unwrap()
+        } else {
+            self.report_restriction("can't access data more than 6 levels out");
+            Register::LB
+        }
+    }
+
+    /// record the entity that a declaration (or vname) elaborates to
+    fn elaborate(&mut self, node: NodeId, entity: RuntimeEntity) {
+        self.decorations.set_entity(node, entity);
+    }
+
+    /// the entity of the declaration that an applied occurrence with the given spelling is
+    /// linked to
+    fn entity_of(&mut self, spelling: &str, node: NodeId) -> RuntimeEntity {
+        let decl = match self.decorations.linked(node) {
+            Some(decl) => decl,
+            None => {
+                self.report(&format!(
+                    "{} has no declaration; was the program checked?",
+                    spelling
+                ));
+                return RuntimeEntity::None;
+            }
+        };
+
+        match self.decorations.entity_of(decl) {
+            Some(RuntimeEntity::None) | None => {
+                self.report(&format!(
+                    "no runtime entity has been elaborated for {}",
+                    spelling
+                ));
+                RuntimeEntity::None
+            }
+            Some(entity) => entity.clone(),
+        }
+    }
+
+    /// the size of the type of the expression or vname, or an error if the type has not been
+    /// determined
+    fn type_size(&mut self, node: NodeId) -> usize {
+        match self.decorations.type_of(node) {
+            Some(td) => {
+                let mut td = td.clone();
+                td.accept(self, layout(0))
+            }
+            None => {
+                self.report("expression has no type; was the program checked?");
+                0
+            }
+        }
+    }
+
+    /// the type that a type identifier stands for, as the Checker resolved it
+    fn resolve_type(&mut self, td: &TypeDenoter) -> TypeDenoter {
+        if let TypeDenoter::SimpleTypeDenoter(ref simple) = *td {
+            if let Some(resolved) = self.decorations.type_of(simple.common_state.id) {
+                return resolved.clone();
+            }
+            self.report(&format!(
+                "type identifier {} has not been resolved; was the program checked?",
+                simple.id.spelling
+            ));
+        }
+        td.clone()
+    }
+
+    /// the base entity, the offset, and whether the vname is indexed, as decorated by the Encoder
+    fn vname_details(&self, vname: &Child<Vname>) -> (RuntimeEntity, usize, bool) {
+        let entity = self
+            .decorations
+            .entity_of(vname.id())
+            .cloned()
+            .unwrap_or(RuntimeEntity::None);
+
+        match *vname.get(&self.nodes) {
+            Vname::SimpleVname(ref simple) => (entity, simple.offset, simple.indexed),
+            Vname::DotVname(ref dot) => (entity, dot.offset, dot.indexed),
+            Vname::SubscriptVname(ref subscript) => (entity, subscript.offset, subscript.indexed),
+        }
+    }
+
+    /// the offset of the named field within a value of the record type of the vname
+    fn field_offset(&mut self, node: NodeId, field: &str) -> usize {
+        let td = match self.decorations.type_of(node) {
+            Some(td) => td.clone(),
+            None => {
+                self.report("record v-name has no type; was the program checked?");
+                return 0;
+            }
+        };
+
+        match self.resolve_type(&td).get_record_type_denoter() {
+            Some(record) => {
+                let mut ftd = record.ftd.clone();
+                self.find_field(&mut ftd, field, 0)
+            }
+            None => {
+                self.report(&format!("{} is not a field of a record type", field));
+                0
+            }
+        }
+    }
+
+    fn find_field(&mut self, ftd: &mut FieldTypeDenoter, field: &str, offset: usize) -> usize {
+        match *ftd {
+            FieldTypeDenoter::SingleFieldTypeDenoter(ref single) if single.id.spelling == field => {
+                offset
+            }
+            FieldTypeDenoter::MultipleFieldTypeDenoter(ref mut multiple) => {
+                if multiple.id.spelling == field {
+                    offset
+                } else {
+                    let field_size = multiple.td.accept(self, layout(0));
+                    self.find_field(&mut multiple.ftd, field, offset + field_size)
+                }
+            }
+            _ => {
+                self.report(&format!("no such field {} in the record type", field));
+                offset
+            }
+        }
+    }
+
+    /// emit code to fetch the value of the vname (of size `val_size`) onto the stack
+    fn encode_fetch(&mut self, vname: &mut Child<Vname>, frame: &Frame, val_size: usize) {
+        vname.accept(self, *frame);
+        let (base, offset, indexed) = self.vname_details(vname);
+
+        match base {
+            RuntimeEntity::KnownValue(ref value) => {
+                self.emit(Opcode::LoadL, 0, Register::CB, value.value as isize);
+            }
+
+            RuntimeEntity::UnknownValue(UnknownValueState { ref address, .. })
+            | RuntimeEntity::KnownAddress(KnownAddressState { ref address, .. }) => {
+                let reg = self.display_register(frame.level, address.level);
+                if indexed {
+                    self.emit(
+                        Opcode::LoadA,
+                        0,
+                        reg,
+                        address.displacement + offset as isize,
+                    );
+                    self.emit_primitive_call(Primitive::Add);
+                    self.emit(Opcode::LoadI, val_size, Register::CB, 0);
+                } else {
+                    self.emit(
+                        Opcode::Load,
+                        val_size,
+                        reg,
+                        address.displacement + offset as isize,
+                    );
+                }
+            }
+
+            RuntimeEntity::UnknownAddress(ref unknown) => {
+                let reg = self.display_register(frame.level, unknown.address.level);
+                self.emit(
+                    Opcode::Load,
+                    ADDRESS_SIZE,
+                    reg,
+                    unknown.address.displacement,
+                );
+                self.encode_address_offset(offset, indexed);
+                self.emit(Opcode::LoadI, val_size, Register::CB, 0);
+            }
+
+            _ => self.report("expected a value or a variable here"),
+        }
+    }
+
+    /// emit code to store the value (of size `val_size`) on top of the stack into the vname
+    fn encode_assign(&mut self, vname: &mut Child<Vname>, frame: &Frame, val_size: usize) {
+        vname.accept(self, *frame);
+        let (base, offset, indexed) = self.vname_details(vname);
+
+        match base {
+            RuntimeEntity::KnownAddress(ref known) => {
+                let reg = self.display_register(frame.level, known.address.level);
+                if indexed {
+                    self.emit(
+                        Opcode::LoadA,
+                        0,
+                        reg,
+                        known.address.displacement + offset as isize,
+                    );
+                    self.emit_primitive_call(Primitive::Add);
+                    self.emit(Opcode::StoreI, val_size, Register::CB, 0);
+                } else {
+                    self.emit(
+                        Opcode::Store,
+                        val_size,
+                        reg,
+                        known.address.displacement + offset as isize,
+                    );
+                }
+            }
+
+            RuntimeEntity::UnknownAddress(ref unknown) => {
+                let reg = self.display_register(frame.level, unknown.address.level);
+                self.emit(
+                    Opcode::Load,
+                    ADDRESS_SIZE,
+                    reg,
+                    unknown.address.displacement,
+                );
+                self.encode_address_offset(offset, indexed);
+                self.emit(Opcode::StoreI, val_size, Register::CB, 0);
+            }
+
+            _ => self.report("expected a variable here"),
+        }
+    }
+
+    /// emit code to push the address of the vname onto the stack
+    fn encode_fetch_address(&mut self, vname: &mut Child<Vname>, frame: &Frame) {
+        vname.accept(self, *frame);
+        let (base, offset, indexed) = self.vname_details(vname);
+
+        match base {
+            RuntimeEntity::KnownAddress(ref known) => {
+                let reg = self.display_register(frame.level, known.address.level);
+                self.emit(
+                    Opcode::LoadA,
+                    0,
+                    reg,
+                    known.address.displacement + offset as isize,
+                );
+                if indexed {
+                    self.emit_primitive_call(Primitive::Add);
+                }
+            }
+
+            RuntimeEntity::UnknownAddress(ref unknown) => {
+                let reg = self.display_register(frame.level, unknown.address.level);
+                self.emit(
+                    Opcode::Load,
+                    ADDRESS_SIZE,
+                    reg,
+                    unknown.address.displacement,
+                );
+                self.encode_address_offset(offset, indexed);
+            }
+
+            _ => self.report("expected a variable here"),
+        }
+    }
+
+    /// with an address on top of the stack (and the index below it, if any), add the index and
+    /// the static offset to it
+    fn encode_address_offset(&mut self, offset: usize, indexed: bool) {
+        if indexed {
+            self.emit_primitive_call(Primitive::Add);
+        }
+
+        if offset != 0 {
+            self.emit(Opcode::LoadL, 0, Register::CB, offset as isize);
+            self.emit_primitive_call(Primitive::Add);
+        }
+    }
+
+    fn emit_primitive_call(&mut self, prim: Primitive) {
+        self.emit(
+            Opcode::Call,
+            Register::SB as usize,
+            Register::PB,
+            prim.displacement(),
+        );
+    }
+
+    /// emit code to call the routine the entity represents, with the arguments (of total size
+    /// `frame.size`) already on the stack
+    fn encode_call(&mut self, entity: &RuntimeEntity, frame: &Frame) {
+        match *entity {
+            RuntimeEntity::KnownRoutine(ref known) => {
+                let reg = self.display_register(frame.level, known.address.level);
+                self.emit(
+                    Opcode::Call,
+                    reg as usize,
+                    Register::CB,
+                    known.address.displacement,
+                );
+            }
+
+            RuntimeEntity::UnknownRoutine(ref unknown) => {
+                let reg = self.display_register(frame.level, unknown.address.level);
+                self.emit(
+                    Opcode::Load,
+                    CLOSURE_SIZE,
+                    reg,
+                    unknown.address.displacement,
+                );
+                self.emit(Opcode::CallI, 0, Register::CB, 0);
+            }
+
+            RuntimeEntity::PrimitiveRoutine(ref primitive) => {
+                if primitive.displacement != Primitive::Id.displacement() {
+                    self.emit(
+                        Opcode::Call,
+                        Register::SB as usize,
+                        Register::PB,
+                        primitive.displacement,
+                    );
+                }
+            }
+
+            RuntimeEntity::EqualityRoutine(ref equality) => {
+                self.emit(Opcode::LoadL, 0, Register::CB, (frame.size / 2) as isize);
+                self.emit(
+                    Opcode::Call,
+                    Register::SB as usize,
+                    Register::PB,
+                    equality.displacement,
+                );
+            }
+
+            _ => self.report("expected a routine here"),
+        }
+    }
+
+    /// emit code to push the closure (static link and code address) of the routine that the
+    /// entity represents
+    fn encode_closure(&mut self, entity: &RuntimeEntity, frame: &Frame) {
+        match *entity {
+            RuntimeEntity::KnownRoutine(ref known) => {
+                let reg = self.display_register(frame.level, known.address.level);
+                self.emit(Opcode::LoadA, 0, reg, 0);
+                self.emit(Opcode::LoadA, 0, Register::CB, known.address.displacement);
+            }
+
+            RuntimeEntity::UnknownRoutine(ref unknown) => {
+                let reg = self.display_register(frame.level, unknown.address.level);
+                self.emit(
+                    Opcode::Load,
+                    CLOSURE_SIZE,
+                    reg,
+                    unknown.address.displacement,
+                );
+            }
+
+            RuntimeEntity::PrimitiveRoutine(PrimitiveRoutineState {
+                ref displacement, ..
+            })
+            | RuntimeEntity::EqualityRoutine(EqualityRoutineState {
+                ref displacement, ..
+            }) => {
+                self.emit(Opcode::LoadA, 0, Register::SB, 0);
+                self.emit(Opcode::LoadA, 0, Register::PB, *displacement);
+            }
+
+            _ => self.report("expected a routine here"),
+        }
+    }
+
+    /// report an error at the command or declaration being encoded
+    fn report(&mut self, message: &str) {
+        self.diagnostics
+            .report(GenError::from(EncoderError::new(message, self.position)));
+    }
+
+    fn report_restriction(&mut self, message: &str) {
+        self.report(&format!("restriction: {}", message));
+    }
+
+    fn integer_valuation(&mut self, il: &IntegerLiteral) -> isize {
+        match il.spelling.parse::<i16>() {
+            Ok(value) => value as isize,
+            Err(_) => {
+                self.diagnostics.report(GenError::from(EncoderError::new(
+                    &format!(
+                        "restriction: integer literal {} is larger than {}",
+                        il.spelling, MAX_INT
+                    ),
+                    il.common_state.position,
+                )));
+                0
+            }
+        }
+    }
+}
+
+impl Default for Encoder {
+    fn default() -> Self {
+        Encoder::new()
+    }
+}
+
+fn character_valuation(cl: &CharacterLiteral) -> isize {
+    cl.spelling.chars().next().map_or(0, |c| c as isize)
+}
+
+/// Types are elaborated outside of any frame. The fields of a record are laid out one after the
+/// other like the variables of a frame, so a field type denoter is given the layout of the
+/// fields before it, and the size of that layout is the offset of the field.
+fn layout(offset: usize) -> Frame {
+    Frame::new(0, offset)
+}
+
+impl AstVisitor for Encoder {
+    type Arg = Frame;
+    type Output = usize;
+
+    fn nodes(&mut self) -> &mut Nodes {
+        &mut self.nodes
+    }
+
+    /// encode the program's command in the global frame, and then halt
+    fn visit_program(&mut self, program: &mut Program, arg: Frame) -> usize {
+        program.cmd.accept(self, arg);
+        self.emit(Opcode::Halt, 0, Register::CB, 0);
+        0
+    }
+
+    /// evaluate the expression, and store its value into the vname
+    fn visit_assign_command(&mut self, cmd: &mut AssignCommandState, frame: Frame) -> usize {
+        let saved = self.locate(cmd.common_state.position);
+        let val_size = cmd.expr.accept(self, frame);
+        self.encode_assign(&mut cmd.vname, &frame.expand(val_size), val_size);
+        self.position = saved;
+        0
+    }
+
+    /// push the arguments, and then call the procedure
+    fn visit_call_command(&mut self, cmd: &mut CallCommandState, frame: Frame) -> usize {
+        let saved = self.locate(cmd.common_state.position);
+        let args_size = cmd.aps.accept(self, frame);
+        cmd.id.accept(self, Frame::new(frame.level, args_size));
+        self.position = saved;
+        0
+    }
+
+    /// elaborate the declarations, execute the command, and pop the storage the declarations
+    /// occupied
+    fn visit_let_command(&mut self, cmd: &mut LetCommandState, frame: Frame) -> usize {
+        let saved = self.locate(cmd.common_state.position);
+        let extra_size = cmd.decl.accept(self, frame);
+        cmd.cmd.accept(self, frame.expand(extra_size));
+
+        if extra_size > 0 {
+            self.emit(Opcode::Pop, 0, Register::CB, extra_size as isize);
+        }
+        self.position = saved;
+        0
+    }
+
+    ///       evaluate E
+    ///       JUMPIF(0) g
+    ///       execute C1
+    ///       JUMP h
+    ///    g: execute C2
+    ///    h:
+    fn visit_if_command(&mut self, cmd: &mut IfCommandState, arg: Frame) -> usize {
+        let saved = self.locate(cmd.common_state.position);
+        cmd.expr.accept(self, arg);
+        let jumpif_addr = self.next_instr_addr();
+        self.emit(Opcode::JumpIf, FALSE_REP as usize, Register::CB, 0);
+
+        cmd.cmd1.accept(self, arg);
+        let jump_addr = self.next_instr_addr();
+        self.emit(Opcode::Jump, 0, Register::CB, 0);
+
+        let g = self.next_instr_addr();
+        self.patch(jumpif_addr, g);
+        cmd.cmd2.accept(self, arg);
+
+        let h = self.next_instr_addr();
+        self.patch(jump_addr, h);
+        self.position = saved;
+        0
+    }
+
+    ///       JUMP h
+    ///    g: execute C
+    ///    h: evaluate E
+    ///       JUMPIF(1) g
+    fn visit_while_command(&mut self, cmd: &mut WhileCommandState, arg: Frame) -> usize {
+        let saved = self.locate(cmd.common_state.position);
+        let jump_addr = self.next_instr_addr();
+        self.emit(Opcode::Jump, 0, Register::CB, 0);
+
+        let g = self.next_instr_addr();
+        cmd.cmd.accept(self, arg);
+
+        let h = self.next_instr_addr();
+        self.patch(jump_addr, h);
+        cmd.expr.accept(self, arg);
+        self.emit(Opcode::JumpIf, TRUE_REP as usize, Register::CB, g as isize);
+        self.position = saved;
+        0
+    }
+
+    fn visit_empty_expression(&mut self, _expr: &mut EmptyExpressionState, _arg: Frame) -> usize {
+        0
+    }
+
+    fn visit_integer_expression(
+        &mut self,
+        expr: &mut IntegerExpressionState,
+        _arg: Frame,
+    ) -> usize {
+        let value = self.integer_valuation(&expr.il);
+        self.emit(Opcode::LoadL, 0, Register::CB, value);
+        INTEGER_SIZE
+    }
+
+    fn visit_character_expression(
+        &mut self,
+        expr: &mut CharacterExpressionState,
+        _arg: Frame,
+    ) -> usize {
+        let value = character_valuation(&expr.cl);
+        self.emit(Opcode::LoadL, 0, Register::CB, value);
+        CHARACTER_SIZE
+    }
+
+    fn visit_vname_expression(&mut self, expr: &mut VnameExpressionState, frame: Frame) -> usize {
+        let val_size = self.type_size(expr.common_state.id);
+        self.encode_fetch(&mut expr.vname, &frame, val_size);
+        val_size
+    }
+
+    /// elaborate the declarations, evaluate the expression, and pop the storage the
+    /// declarations occupied from underneath the result
+    fn visit_let_expression(&mut self, expr: &mut LetExpressionState, frame: Frame) -> usize {
+        let val_size = self.type_size(expr.common_state.id);
+        let extra_size = expr.decl.accept(self, frame);
+        expr.expr.accept(self, frame.expand(extra_size));
+
+        if extra_size > 0 {
+            self.emit(Opcode::Pop, val_size, Register::CB, extra_size as isize);
+        }
+        val_size
+    }
+
+    fn visit_call_expression(&mut self, expr: &mut CallExpressionState, frame: Frame) -> usize {
+        let val_size = self.type_size(expr.common_state.id);
+        let args_size = expr.aps.accept(self, frame);
+        expr.id.accept(self, Frame::new(frame.level, args_size));
+        val_size
+    }
+
+    fn visit_if_expression(&mut self, expr: &mut IfExpressionState, arg: Frame) -> usize {
+        expr.expr1.accept(self, arg);
+        let jumpif_addr = self.next_instr_addr();
+        self.emit(Opcode::JumpIf, FALSE_REP as usize, Register::CB, 0);
+
+        expr.expr2.accept(self, arg);
+        let jump_addr = self.next_instr_addr();
+        self.emit(Opcode::Jump, 0, Register::CB, 0);
+
+        let g = self.next_instr_addr();
+        self.patch(jumpif_addr, g);
+        let val_size = expr.expr3.accept(self, arg);
+
+        let h = self.next_instr_addr();
+        self.patch(jump_addr, h);
+        val_size
+    }
+
+    fn visit_unary_expression(&mut self, expr: &mut UnaryExpressionState, frame: Frame) -> usize {
+        let val_size = self.type_size(expr.common_state.id);
+        let arg_size = expr.expr.accept(self, frame);
+        expr.op.accept(self, Frame::new(frame.level, arg_size));
+        val_size
+    }
+
+    fn visit_binary_expression(&mut self, expr: &mut BinaryExpressionState, frame: Frame) -> usize {
+        let val_size = self.type_size(expr.common_state.id);
+        let val_size1 = expr.expr1.accept(self, frame);
+        let val_size2 = expr.expr2.accept(self, frame.expand(val_size1));
+        expr.op
+            .accept(self, Frame::new(frame.level, val_size1 + val_size2));
+        val_size
+    }
+
+    fn visit_array_expression(&mut self, expr: &mut ArrayExpressionState, arg: Frame) -> usize {
+        expr.aa.accept(self, arg)
+    }
+
+    fn visit_record_expression(&mut self, expr: &mut RecordExpressionState, arg: Frame) -> usize {
+        expr.ra.accept(self, arg)
+    }
+
+    fn visit_single_array_aggregate(
+        &mut self,
+        agg: &mut SingleArrayAggregateState,
+        arg: Frame,
+    ) -> usize {
+        agg.expr.accept(self, arg)
+    }
+
+    fn visit_multiple_array_aggregate(
+        &mut self,
+        agg: &mut MultipleArrayAggregateState,
+        frame: Frame,
+    ) -> usize {
+        let elem_size = agg.expr.accept(self, frame);
+        let rest_size = agg.aa.accept(self, frame.expand(elem_size));
+        elem_size + rest_size
+    }
+
+    fn visit_single_record_aggregate(
+        &mut self,
+        agg: &mut SingleRecordAggregateState,
+        arg: Frame,
+    ) -> usize {
+        agg.expr.accept(self, arg)
+    }
+
+    fn visit_multiple_record_aggregate(
+        &mut self,
+        agg: &mut MultipleRecordAggregateState,
+        frame: Frame,
+    ) -> usize {
+        let field_size = agg.expr.accept(self, frame);
+        let rest_size = agg.ra.accept(self, frame.expand(field_size));
+        field_size + rest_size
+    }
+
+    /// constants bound to literals are known values and occupy no storage; any other constant
+    /// is evaluated onto the stack
+    fn visit_const_declaration(&mut self, decl: &mut ConstDeclarationState, frame: Frame) -> usize {
+        let saved = self.locate(decl.common_state.position);
+
+        let (entity, extra_size) = match *decl.expr.get(&self.nodes) {
+            Expression::CharacterExpression(ref expr) => (
+                RuntimeEntity::KnownValue(KnownValueState::new(
+                    CHARACTER_SIZE,
+                    character_valuation(&expr.cl) as i32,
+                )),
+                0,
+            ),
+            Expression::IntegerExpression(ref expr) => {
+                let il = expr.il.clone();
+                (
+                    RuntimeEntity::KnownValue(KnownValueState::new(
+                        INTEGER_SIZE,
+                        self.integer_valuation(&il) as i32,
+                    )),
+                    0,
+                )
+            }
+            _ => {
+                let val_size = decl.expr.accept(self, frame);
+                (
+                    RuntimeEntity::UnknownValue(UnknownValueState::new(
+                        val_size,
+                        frame.level,
+                        frame.size as isize,
+                    )),
+                    val_size,
+                )
+            }
+        };
+
+        self.elaborate(decl.common_state.id, entity);
+        self.position = saved;
+        extra_size
+    }
+
+    /// reserve storage for the variable on top of the stack
+    fn visit_var_declaration(&mut self, decl: &mut VarDeclarationState, frame: Frame) -> usize {
+        let saved = self.locate(decl.common_state.position);
+        let extra_size = decl.td.accept(self, layout(0));
+        self.emit(Opcode::Push, 0, Register::CB, extra_size as isize);
+
+        let entity = RuntimeEntity::KnownAddress(KnownAddressState::new(
+            ADDRESS_SIZE,
+            frame.level,
+            frame.size as isize,
+        ));
+        self.elaborate(decl.common_state.id, entity);
+        self.position = saved;
+        extra_size
+    }
+
+    ///       JUMP g
+    ///    e: execute C
+    ///       RETURN(0) d   where d is the size of the parameters
+    ///    g:
+    fn visit_proc_declaration(&mut self, decl: &mut ProcDeclarationState, frame: Frame) -> usize {
+        let saved = self.locate(decl.common_state.position);
+        let jump_addr = self.next_instr_addr();
+        self.emit(Opcode::Jump, 0, Register::CB, 0);
+
+        // the routine is entered before its body is encoded, so that it can call itself
+        let entity = RuntimeEntity::KnownRoutine(KnownRoutineState::new(
+            CLOSURE_SIZE,
+            frame.level,
+            self.next_instr_addr() as isize,
+        ));
+        self.elaborate(decl.common_state.id, entity);
+
+        if frame.level == MAX_ROUTINE_LEVEL {
+            self.report_restriction("can't nest routines so deeply");
+        }
+
+        let args_size = decl.fps.accept(self, Frame::new(frame.level + 1, 0));
+        decl.cmd
+            .accept(self, Frame::new(frame.level + 1, LINK_DATA_SIZE));
+        self.emit(Opcode::Return, 0, Register::CB, args_size as isize);
+
+        let g = self.next_instr_addr();
+        self.patch(jump_addr, g);
+        self.position = saved;
+        0
+    }
+
+    ///       JUMP g
+    ///    e: evaluate E
+    ///       RETURN(n) d   where n is the size of the result, d the size of the parameters
+    ///    g:
+    fn visit_func_declaration(&mut self, decl: &mut FuncDeclarationState, frame: Frame) -> usize {
+        let saved = self.locate(decl.common_state.position);
+        let jump_addr = self.next_instr_addr();
+        self.emit(Opcode::Jump, 0, Register::CB, 0);
+
+        // the routine is entered before its body is encoded, so that it can call itself
+        let entity = RuntimeEntity::KnownRoutine(KnownRoutineState::new(
+            CLOSURE_SIZE,
+            frame.level,
+            self.next_instr_addr() as isize,
+        ));
+        self.elaborate(decl.common_state.id, entity);
+
+        if frame.level == MAX_ROUTINE_LEVEL {
+            self.report_restriction("can't nest routines so deeply");
+        }
+
+        let args_size = decl.fps.accept(self, Frame::new(frame.level + 1, 0));
+        let val_size = decl
+            .expr
+            .accept(self, Frame::new(frame.level + 1, LINK_DATA_SIZE));
+        self.emit(Opcode::Return, val_size, Register::CB, args_size as isize);
+
+        let g = self.next_instr_addr();
+        self.patch(jump_addr, g);
+        self.position = saved;
+        0
+    }
+
+    fn visit_type_declaration(&mut self, decl: &mut TypeDeclarationState, _arg: Frame) -> usize {
+        let size = decl.td.accept(self, layout(0));
+        let entity = RuntimeEntity::TypeRepresentation(TypeRepresentationState::new(size));
+        self.elaborate(decl.common_state.id, entity);
+        0
+    }
+
+    fn visit_unary_operator_declaration(
+        &mut self,
+        _decl: &mut UnaryOperatorDeclarationState,
+        _arg: Frame,
+    ) -> usize {
+        0
+    }
+
+    fn visit_binary_operator_declaration(
+        &mut self,
+        _decl: &mut BinaryOperatorDeclarationState,
+        _arg: Frame,
+    ) -> usize {
+        0
+    }
+
+    fn visit_sequential_declaration(
+        &mut self,
+        decl: &mut SequentialDeclarationState,
+        frame: Frame,
+    ) -> usize {
+        let extra_size1 = decl.decl1.accept(self, frame);
+        let extra_size2 = decl.decl2.accept(self, frame.expand(extra_size1));
+        extra_size1 + extra_size2
+    }
+
+    fn visit_any_type_denoter(&mut self, _td: &mut AnyTypeDenoterState, _arg: Frame) -> usize {
+        0
+    }
+
+    fn visit_error_type_denoter(&mut self, _td: &mut ErrorTypeDenoterState, _arg: Frame) -> usize {
+        0
+    }
+
+    fn visit_bool_type_denoter(&mut self, td: &mut BoolTypeDenoterState, _arg: Frame) -> usize {
+        self.elaborate(
+            td.common_state.id,
+            RuntimeEntity::TypeRepresentation(TypeRepresentationState::new(BOOLEAN_SIZE)),
+        );
+        BOOLEAN_SIZE
+    }
+
+    fn visit_char_type_denoter(&mut self, td: &mut CharTypeDenoterState, _arg: Frame) -> usize {
+        self.elaborate(
+            td.common_state.id,
+            RuntimeEntity::TypeRepresentation(TypeRepresentationState::new(CHARACTER_SIZE)),
+        );
+        CHARACTER_SIZE
+    }
+
+    fn visit_int_type_denoter(&mut self, td: &mut IntTypeDenoterState, _arg: Frame) -> usize {
+        self.elaborate(
+            td.common_state.id,
+            RuntimeEntity::TypeRepresentation(TypeRepresentationState::new(INTEGER_SIZE)),
+        );
+        INTEGER_SIZE
+    }
+
+    fn visit_array_type_denoter(&mut self, td: &mut ArrayTypeDenoterState, _arg: Frame) -> usize {
+        if let Some(RuntimeEntity::TypeRepresentation(repr)) =
+            self.decorations.entity_of(td.common_state.id)
+        {
+            return repr.size;
+        }
+
+        let elem_size = td.td.accept(self, layout(0));
+        let size = self.integer_valuation(&td.il) as usize * elem_size;
+        self.elaborate(
+            td.common_state.id,
+            RuntimeEntity::TypeRepresentation(TypeRepresentationState::new(size)),
+        );
+        size
+    }
+
+    /// a type identifier has the size of the type it was declared to denote
+    fn visit_simple_type_denoter(&mut self, td: &mut SimpleTypeDenoterState, _arg: Frame) -> usize {
+        let mut resolved = self.resolve_type(&TypeDenoter::SimpleTypeDenoter(td.clone()));
+        resolved.accept(self, layout(0))
+    }
+
+    /// the offset of the field within the record is the size of the layout passed in via arg
+    fn visit_single_field_type_denoter(
+        &mut self,
+        td: &mut SingleFieldTypeDenoterState,
+        arg: Frame,
+    ) -> usize {
+        let offset = arg.size;
+        let field_size = td.td.accept(self, layout(0));
+        self.elaborate(
+            td.common_state.id,
+            RuntimeEntity::Field(FieldState::new(field_size, offset as isize)),
+        );
+        field_size
+    }
+
+    /// the offset of the first field within the record is the size of the layout passed in via
+    /// arg
+    fn visit_multiple_field_type_denoter(
+        &mut self,
+        td: &mut MultipleFieldTypeDenoterState,
+        arg: Frame,
+    ) -> usize {
+        let offset = arg.size;
+        let field_size = td.td.accept(self, layout(0));
+        self.elaborate(
+            td.common_state.id,
+            RuntimeEntity::Field(FieldState::new(field_size, offset as isize)),
+        );
+        let rest_size = td.ftd.accept(self, layout(offset + field_size));
+        field_size + rest_size
+    }
+
+    fn visit_record_type_denoter(&mut self, td: &mut RecordTypeDenoterState, _arg: Frame) -> usize {
+        if let Some(RuntimeEntity::TypeRepresentation(repr)) =
+            self.decorations.entity_of(td.common_state.id)
+        {
+            return repr.size;
+        }
+
+        let size = td.ftd.accept(self, layout(0));
+        self.elaborate(
+            td.common_state.id,
+            RuntimeEntity::TypeRepresentation(TypeRepresentationState::new(size)),
+        );
+        size
+    }
+
+    fn visit_empty_formal_parameter_sequence(
+        &mut self,
+        _fps: &mut EmptyFormalParameterSequenceState,
+        _arg: Frame,
+    ) -> usize {
+        0
+    }
+
+    fn visit_single_formal_parameter_sequence(
+        &mut self,
+        fps: &mut SingleFormalParameterSequenceState,
+        arg: Frame,
+    ) -> usize {
+        fps.fp.accept(self, arg)
+    }
+
+    /// parameters are addressed at negative displacements from LB, so the last parameter is
+    /// elaborated first
+    fn visit_multiple_formal_parameter_sequence(
+        &mut self,
+        fps: &mut MultipleFormalParameterSequenceState,
+        frame: Frame,
+    ) -> usize {
+        let args_size1 = fps.fps.accept(self, frame);
+        let args_size2 = fps.fp.accept(self, frame.expand(args_size1));
+        args_size1 + args_size2
+    }
+
+    fn visit_const_formal_parameter(
+        &mut self,
+        fp: &mut ConstFormalParameterState,
+        frame: Frame,
+    ) -> usize {
+        let val_size = fp.td.accept(self, layout(0));
+        let entity = RuntimeEntity::UnknownValue(UnknownValueState::new(
+            val_size,
+            frame.level,
+            -(frame.size as isize) - val_size as isize,
+        ));
+        self.elaborate(fp.common_state.id, entity);
+        val_size
+    }
+
+    fn visit_var_formal_parameter(
+        &mut self,
+        fp: &mut VarFormalParameterState,
+        frame: Frame,
+    ) -> usize {
+        fp.td.accept(self, layout(0));
+        let entity = RuntimeEntity::UnknownAddress(UnknownAddressState::new(
+            ADDRESS_SIZE,
+            frame.level,
+            -(frame.size as isize) - ADDRESS_SIZE as isize,
+        ));
+        self.elaborate(fp.common_state.id, entity);
+        ADDRESS_SIZE
+    }
+
+    fn visit_proc_formal_parameter(
+        &mut self,
+        fp: &mut ProcFormalParameterState,
+        frame: Frame,
+    ) -> usize {
+        let entity = RuntimeEntity::UnknownRoutine(UnknownRoutineState::new(
+            CLOSURE_SIZE,
+            frame.level,
+            -(frame.size as isize) - CLOSURE_SIZE as isize,
+        ));
+        self.elaborate(fp.common_state.id, entity);
+        CLOSURE_SIZE
+    }
+
+    fn visit_func_formal_parameter(
+        &mut self,
+        fp: &mut FuncFormalParameterState,
+        frame: Frame,
+    ) -> usize {
+        let entity = RuntimeEntity::UnknownRoutine(UnknownRoutineState::new(
+            CLOSURE_SIZE,
+            frame.level,
+            -(frame.size as isize) - CLOSURE_SIZE as isize,
+        ));
+        self.elaborate(fp.common_state.id, entity);
+        CLOSURE_SIZE
+    }
+
+    fn visit_empty_actual_parameter_sequence(
+        &mut self,
+        _aps: &mut EmptyActualParameterSequenceState,
+        _arg: Frame,
+    ) -> usize {
+        0
+    }
+
+    fn visit_single_actual_parameter_sequence(
+        &mut self,
+        aps: &mut SingleActualParameterSequenceState,
+        arg: Frame,
+    ) -> usize {
+        aps.ap.accept(self, arg)
+    }
+
+    fn visit_multiple_actual_parameter_sequence(
+        &mut self,
+        aps: &mut MultipleActualParameterSequenceState,
+        frame: Frame,
+    ) -> usize {
+        let args_size1 = aps.ap.accept(self, frame);
+        let args_size2 = aps.aps.accept(self, frame.expand(args_size1));
+        args_size1 + args_size2
+    }
+
+    fn visit_const_actual_parameter(
+        &mut self,
+        ap: &mut ConstActualParameterState,
+        arg: Frame,
+    ) -> usize {
+        ap.expr.accept(self, arg)
+    }
+
+    fn visit_var_actual_parameter(
+        &mut self,
+        ap: &mut VarActualParameterState,
+        frame: Frame,
+    ) -> usize {
+        self.encode_fetch_address(&mut ap.vname, &frame);
+        ADDRESS_SIZE
+    }
+
+    fn visit_proc_actual_parameter(
+        &mut self,
+        ap: &mut ProcActualParameterState,
+        frame: Frame,
+    ) -> usize {
+        let entity = self.entity_of(&ap.id.spelling, ap.id.common_state.id);
+        self.encode_closure(&entity, &frame);
+        CLOSURE_SIZE
+    }
+
+    fn visit_func_actual_parameter(
+        &mut self,
+        ap: &mut FuncActualParameterState,
+        frame: Frame,
+    ) -> usize {
+        let entity = self.entity_of(&ap.id.spelling, ap.id.common_state.id);
+        self.encode_closure(&entity, &frame);
+        CLOSURE_SIZE
+    }
+
+    /// decorate the vname with the entity of its identifier
+    fn visit_simple_vname(&mut self, vname: &mut SimpleVnameState, _arg: Frame) -> usize {
+        let entity = self.entity_of(&vname.id.spelling, vname.id.common_state.id);
+        self.elaborate(vname.common_state.id, entity);
+        vname.offset = 0;
+        vname.indexed = false;
+        0
+    }
+
+    /// a field is found at a fixed offset from the start of its record
+    fn visit_dot_vname(&mut self, vname: &mut DotVnameState, arg: Frame) -> usize {
+        vname.vname.accept(self, arg);
+        let (base, offset, indexed) = self.vname_details(&vname.vname);
+        let field_offset = self.field_offset(vname.vname.id(), &vname.id.spelling);
+
+        self.elaborate(vname.common_state.id, base);
+        vname.offset = offset + field_offset;
+        vname.indexed = indexed;
+        0
+    }
+
+    /// a literal index adds to the static offset, any other index is evaluated (and scaled by
+    /// the element size) onto the stack
+    fn visit_subscript_vname(&mut self, vname: &mut SubscriptVnameState, frame: Frame) -> usize {
+        vname.vname.accept(self, frame);
+        let (base, offset, indexed) = self.vname_details(&vname.vname);
+        let elem_size = self.type_size(vname.common_state.id);
+
+        self.elaborate(vname.common_state.id, base);
+        vname.offset = offset;
+        vname.indexed = indexed;
+
+        if let Expression::IntegerExpression(ref expr) = *vname.expr.get(&self.nodes) {
+            let il = expr.il.clone();
+            vname.offset += self.integer_valuation(&il) as usize * elem_size;
+        } else {
+            let frame = if vname.indexed {
+                frame.expand(INTEGER_SIZE)
+            } else {
+                frame
+            };
+            vname.expr.accept(self, frame);
+
+            if elem_size != 1 {
+                self.emit(Opcode::LoadL, 0, Register::CB, elem_size as isize);
+                self.emit_primitive_call(Primitive::Mult);
+            }
+
+            if vname.indexed {
+                self.emit_primitive_call(Primitive::Add);
+            } else {
+                vname.indexed = true;
+            }
+        }
+        0
+    }
+
+    /// an identifier is only visited as the callee of a call command or expression
+    fn visit_identifier(&mut self, id: &mut Identifier, frame: Frame) -> usize {
+        let entity = self.entity_of(&id.spelling, id.common_state.id);
+        self.encode_call(&entity, &frame);
+        0
+    }
+
+    /// an operator is applied like a call to the routine it denotes
+    fn visit_operator(&mut self, op: &mut Operator, frame: Frame) -> usize {
+        let entity = self.entity_of(&op.spelling, op.common_state.id);
+        self.encode_call(&entity, &frame);
+        0
+    }
+}
@@ -1,5 +1,7 @@
 //! Error-handling module.
 
+use crate::scanner::SourcePosition;
+
 use std::error::Error;
 use std::io::{stderr, Write};
 
@@ -7,33 +9,72 @@ pub type GenError = Box<dyn Error>;
 pub type GenResult<T> = Result<T, GenError>;
 
 macro_rules! impl_errors_with_position {
-    ( $( $error_type:ident )* ) => {
-        $(
-            #[derive(Debug)]
-            pub struct $error_type {
-                message: String,
-                position: $crate::scanner::SourcePosition,
-            }
+    ( $( $error_type:ident )* ; $( $warning_type:ident )* ) => {
+        $( impl_errors_with_position!(@located $error_type, concat!(stringify!($error_type), " error")); )*
+        $( impl_errors_with_position!(@located $warning_type, stringify!($warning_type)); )*
 
-            impl $error_type {
-                pub fn new(message: &str, position: $crate::scanner::SourcePosition) -> Self {
-                    $error_type {
-                        message: String::from(message),
-                        position: position,
-                    }
+        /// the error as one with a position in the source, if it is one
+        pub fn locate(error: &GenError) -> Option<&dyn Located> {
+            $(
+                if let Some(err) = error.downcast_ref::<$error_type>() {
+                    return Some(err);
                 }
-            }
+            )*
+            $(
+                if let Some(warning) = error.downcast_ref::<$warning_type>() {
+                    return Some(warning);
+                }
+            )*
+            None
+        }
+    };
 
-            impl std::error::Error for $error_type {}
+    (@located $error_type:ident, $label:expr) => {
+        #[derive(Debug)]
+        pub struct $error_type {
+            message: String,
+            position: $crate::scanner::SourcePosition,
+            notes: Vec<Note>,
+        }
 
-            impl std::fmt::Display for $error_type {
-                fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-                    write!(f, "{}",
-                        &format!(concat!(stringify!($error_type), " error at line {} and column {}: {}"),
-                            self.position.start.line, self.position.start.column, self.message))
+        impl $error_type {
+            pub fn new(message: &str, position: $crate::scanner::SourcePosition) -> Self {
+                $error_type {
+                    message: String::from(message),
+                    position: position,
+                    notes: Vec::new(),
                 }
             }
-          )*
+
+            /// attach a note that points at a related location, e.g. an earlier declaration
+            pub fn with_note(mut self, message: &str, position: $crate::scanner::SourcePosition) -> Self {
+                self.notes.push(Note::new(message, position));
+                self
+            }
+        }
+
+        impl Located for $error_type {
+            fn message(&self) -> &str {
+                &self.message
+            }
+
+            fn position(&self) -> $crate::scanner::SourcePosition {
+                self.position
+            }
+
+            fn notes(&self) -> &[Note] {
+                &self.notes
+            }
+        }
+
+        impl std::error::Error for $error_type {}
+
+        impl std::fmt::Display for $error_type {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "{} at line {} and column {}: {}", $label,
+                    self.position.start.line, self.position.start.column, self.message)
+            }
+        }
     };
 }
 
@@ -66,8 +107,31 @@ macro_rules! impl_errors_with_no_position {
     };
 }
 
-impl_errors_with_position!(ScannerError ParserError);
-impl_errors_with_no_position!(CheckerError EncoderError CompilerError);
+/// An error that can be traced back to a position in the source
+pub trait Located {
+    fn message(&self) -> &str;
+    fn position(&self) -> SourcePosition;
+    fn notes(&self) -> &[Note];
+}
+
+/// A remark about a location related to an error
+#[derive(Debug, Clone)]
+pub struct Note {
+    pub message: String,
+    pub position: SourcePosition,
+}
+
+impl Note {
+    pub fn new(message: &str, position: SourcePosition) -> Self {
+        Note {
+            message: String::from(message),
+            position,
+        }
+    }
+}
+
+impl_errors_with_position!(ScannerError ParserError CheckerError EncoderError AssemblerError; CheckerWarning);
+impl_errors_with_no_position!(CompilerError TamError JsonError LspError);
 
 pub fn report_error_and_exit(error: GenError) -> ! {
     let _ = writeln!(stderr(), "{}", error);
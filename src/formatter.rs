@@ -0,0 +1,740 @@
+//! The formatter module
+//!
+//! Lays a Triangle program out afresh from its abstract syntax tree - one declaration or command
+//! to a line, with the bodies of `let ... in`, `begin ... end`, `if ... then ... else` and
+//! `while ... do` indented by two spaces. The tree does not hold the comments, so they are taken
+//! from the trivia that the Scanner keeps with the tokens, and put back before the first
+//! declaration, command or keyword that followed them, or at the end of the line that they
+//! trailed. A single blank line is kept wherever the source separated two declarations or
+//! commands with blank lines.
+//!
+//! The tree does not record parentheses either. Operators in Triangle all bind equally tightly
+//! and associate to the left, so an operand is put in parentheses whenever it would otherwise be
+//! parsed differently, or when a binary operand has an operator different from its parent's.
+//! Formatting the output again leaves it as it is, and it parses to the same program.
+
+use crate::ast::aggregates::*;
+use crate::ast::arena::Nodes;
+use crate::ast::commands::*;
+use crate::ast::declarations::*;
+use crate::ast::expressions::*;
+use crate::ast::parameters::*;
+use crate::ast::primitives::Identifier;
+use crate::ast::typedenoters::*;
+use crate::ast::vnames::*;
+use crate::ast::Program;
+use crate::error::GenResult;
+use crate::parser::Parser;
+use crate::scanner::{Position, Scanner, Token, TokenType, TriviaKind};
+
+use std::collections::VecDeque;
+use std::mem;
+
+const INDENT: &str = "  ";
+
+/// lay out the program in the source text, which must be free of syntax errors
+pub fn format(text: &str) -> GenResult<String> {
+    let program = Parser::new(Scanner::from_text(text)).parse_program()?;
+
+    let tokens = Scanner::from_text(text).with_trivia().scan_all()?;
+    let source = text.lines().collect::<Vec<_>>();
+
+    let formatter = Formatter {
+        comments: comments(&source, &tokens),
+        source,
+        nodes: &program.nodes,
+        tokens,
+        lines: Vec::new(),
+        line: String::new(),
+        commented: false,
+        indent: 0,
+    };
+    Ok(formatter.program(&program))
+}
+
+/// a `!` comment, running to the end of its line
+#[derive(Debug)]
+struct Comment {
+    position: Position,
+    text: String,
+    /// whether there is code before the comment on its line
+    trailing: bool,
+}
+
+/// the comments that the tokens were preceded by, in order
+fn comments(source: &[&str], tokens: &[Token]) -> VecDeque<Comment> {
+    tokens
+        .iter()
+        .flat_map(|token| &token.leading)
+        .filter(|trivia| trivia.kind == TriviaKind::Comment)
+        .map(|trivia| {
+            let position = trivia.position.start;
+            let line = source[position.line as usize - 1];
+            Comment {
+                position,
+                text: trivia.text.trim_end().to_owned(),
+                trailing: line
+                    .chars()
+                    .take(position.column as usize - 1)
+                    .any(|c| !c.is_whitespace()),
+            }
+        })
+        .collect()
+}
+
+struct Formatter<'a> {
+    source: Vec<&'a str>,
+    /// the arena of the program being laid out
+    nodes: &'a Nodes,
+    tokens: Vec<Token>,
+    /// the comments that have not been written yet
+    comments: VecDeque<Comment>,
+    lines: Vec<String>,
+    /// the line being written
+    line: String,
+    /// whether the last line written already ends with a comment
+    commented: bool,
+    indent: usize,
+}
+
+impl<'a> Formatter<'a> {
+    fn program(mut self, program: &Program) -> String {
+        if !is_empty_command(program.cmd.get(self.nodes)) {
+            self.command_item(program.cmd.get(self.nodes));
+        }
+        // whatever comments are left follow the last line of the program
+        self.start(Position::new(isize::MAX, isize::MAX), true);
+        self.newline();
+
+        if self.lines.is_empty() {
+            String::new()
+        } else {
+            self.lines.join("\n") + "\n"
+        }
+    }
+
+    fn write(&mut self, text: &str) {
+        if self.line.is_empty() {
+            self.line = INDENT.repeat(self.indent);
+        }
+        self.line.push_str(text);
+    }
+
+    fn newline(&mut self) {
+        if !self.line.trim().is_empty() {
+            self.lines.push(mem::take(&mut self.line));
+            self.commented = false;
+        }
+        self.line.clear();
+    }
+
+    /// start a new line for whatever is at the position in the source, writing the comments
+    /// before it first. A sibling of the declaration or command above it keeps a blank line that
+    /// separated them in the source.
+    fn start(&mut self, position: Position, sibling: bool) {
+        self.newline();
+
+        let mut sibling = sibling;
+        while self.comment_before(position) {
+            let comment = self.comments.pop_front().unwrap();
+            match self.lines.last_mut() {
+                Some(last) if comment.trailing && !self.commented => {
+                    last.push(' ');
+                    last.push_str(&comment.text);
+                    self.commented = true;
+                }
+                _ => {
+                    self.blank_line(comment.position, sibling);
+                    self.write(&comment.text);
+                    self.newline();
+                    sibling = true;
+                }
+            }
+        }
+        self.blank_line(position, sibling);
+    }
+
+    fn blank_line(&mut self, position: Position, sibling: bool) {
+        let follows_blank = |source: &[&str]| {
+            let line = position.line as usize;
+            if line < 2 || line > source.len() {
+                return false;
+            }
+            let before = source[line - 1]
+                .chars()
+                .take(position.column as usize - 1)
+                .all(char::is_whitespace);
+            before && source[line - 2].trim().is_empty()
+        };
+
+        if sibling
+            && self.lines.last().is_some_and(|last| !last.is_empty())
+            && follows_blank(&self.source)
+        {
+            self.lines.push(String::new());
+            self.commented = false;
+        }
+    }
+
+    /// whether a comment comes before the position
+    fn comment_before(&self, position: Position) -> bool {
+        self.comments
+            .front()
+            .is_some_and(|comment| comment.position < position)
+    }
+
+    /// the position of the first keyword of the kind after the position
+    fn after(&self, kind: TokenType, position: Position) -> Position {
+        self.tokens
+            .iter()
+            .find(|token| token.kind == kind && token.position.start > position)
+            .map_or(position, |token| token.position.start)
+    }
+
+    /// the position of the last keyword of the kind before the position
+    fn before(&self, kind: TokenType, position: Position) -> Position {
+        self.tokens
+            .iter()
+            .rev()
+            .find(|token| token.kind == kind && token.position.start < position)
+            .map_or(position, |token| token.position.start)
+    }
+
+    /// write the command on a line of its own
+    fn command_item(&mut self, cmd: &Command) {
+        let mut position = cmd.common_state().position.start;
+        if let Command::SequentialCommand(_) = *cmd {
+            position = self.before(TokenType::Begin, position);
+        }
+        self.start(position, false);
+        self.command(cmd);
+    }
+
+    fn command(&mut self, cmd: &Command) {
+        use Command::*;
+
+        match *cmd {
+            AssignCommand(ref cmd) => {
+                self.vname(cmd.vname.get(self.nodes));
+                self.write(" := ");
+                self.expression(cmd.expr.get(self.nodes));
+            }
+
+            CallCommand(ref cmd) => {
+                self.write(&cmd.id.spelling);
+                self.write("(");
+                self.actual_parameters(cmd.aps.get(self.nodes));
+                self.write(")");
+            }
+
+            // a bracketed empty command can stand wherever a single command is needed
+            EmptyCommand(_) => self.write("begin end"),
+
+            IfCommand(ref cmd) => {
+                self.write("if ");
+                self.expression(cmd.expr.get(self.nodes));
+                self.indent += 1;
+                let then = self.after(
+                    TokenType::Then,
+                    cmd.expr.get(self.nodes).common_state().position.finish,
+                );
+                self.command_branch("then", then, cmd.cmd1.get(self.nodes));
+                let els = self.after(
+                    TokenType::Else,
+                    cmd.cmd1.get(self.nodes).common_state().position.finish,
+                );
+                self.command_branch("else", els, cmd.cmd2.get(self.nodes));
+                self.indent -= 1;
+            }
+
+            LetCommand(ref cmd) => {
+                self.write("let");
+                self.declarations(cmd.decl.get(self.nodes));
+                let position = self.after(
+                    TokenType::In,
+                    cmd.decl.get(self.nodes).common_state().position.finish,
+                );
+                self.start(position, false);
+                self.write("in");
+                self.indent += 1;
+                self.command_item(cmd.cmd.get(self.nodes));
+                self.indent -= 1;
+            }
+
+            SequentialCommand(_) => {
+                let cmds = sequence(self.nodes, cmd);
+                self.write("begin");
+                self.indent += 1;
+                for (i, cmd) in cmds.iter().enumerate() {
+                    let last = i + 1 == cmds.len();
+                    // an empty command at the end is written as the semicolon before it
+                    if last && is_empty_command(cmd) {
+                        break;
+                    }
+                    self.start(cmd.common_state().position.start, i > 0);
+                    self.command(cmd);
+                    if !last {
+                        self.write(";");
+                    }
+                }
+                self.indent -= 1;
+
+                let finish = cmds[cmds.len() - 1].common_state().position.finish;
+                let position = self.after(TokenType::End, finish);
+                self.start(position, false);
+                self.write("end");
+            }
+
+            WhileCommand(ref cmd) => {
+                self.write("while ");
+                self.expression(cmd.expr.get(self.nodes));
+                self.write(" do");
+                self.indent += 1;
+                self.command_item(cmd.cmd.get(self.nodes));
+                self.indent -= 1;
+            }
+        }
+    }
+
+    /// write the `then` or `else` branch of an if command, keeping a simple command on the same
+    /// line as the keyword
+    fn command_branch(&mut self, keyword: &str, position: Position, cmd: &Command) {
+        self.start(position, false);
+        self.write(keyword);
+
+        let simple = matches!(
+            *cmd,
+            Command::AssignCommand(_) | Command::CallCommand(_) | Command::EmptyCommand(_)
+        );
+        if simple && !self.comment_before(cmd.common_state().position.start) {
+            self.write(" ");
+            self.command(cmd);
+        } else {
+            self.indent += 1;
+            self.command_item(cmd);
+            self.indent -= 1;
+        }
+    }
+
+    /// write the declarations of a let command or expression, one to a line
+    fn declarations(&mut self, decl: &Declaration) {
+        let decls = declaration_sequence(self.nodes, decl);
+        self.indent += 1;
+        for (i, decl) in decls.iter().enumerate() {
+            self.start(decl.common_state().position.start, i > 0);
+            self.declaration(decl);
+            if i + 1 < decls.len() {
+                self.write(";");
+            }
+        }
+        self.indent -= 1;
+    }
+
+    fn declaration(&mut self, decl: &Declaration) {
+        use Declaration::*;
+
+        match *decl {
+            ConstDeclaration(ref decl) => {
+                self.write("const ");
+                self.write(&decl.id.spelling);
+                self.write(" ~");
+                let own_line = matches!(
+                    *decl.expr.get(self.nodes),
+                    Expression::IfExpression(_) | Expression::LetExpression(_)
+                );
+                self.body(decl.expr.get(self.nodes), own_line);
+            }
+
+            FuncDeclaration(ref decl) => {
+                self.write("func ");
+                self.write(&decl.id.spelling);
+                self.write("(");
+                self.formal_parameters(decl.fps.get(self.nodes));
+                self.write("): ");
+                self.write(&decl.td.name());
+                self.write(" ~");
+                self.body(decl.expr.get(self.nodes), true);
+            }
+
+            ProcDeclaration(ref decl) => {
+                self.write("proc ");
+                self.write(&decl.id.spelling);
+                self.write("(");
+                self.formal_parameters(decl.fps.get(self.nodes));
+                self.write(") ~");
+                self.indent += 1;
+                self.command_item(decl.cmd.get(self.nodes));
+                self.indent -= 1;
+            }
+
+            TypeDeclaration(ref decl) => {
+                self.write("type ");
+                self.write(&decl.id.spelling);
+                self.write(" ~");
+                match *decl.td {
+                    TypeDenoter::RecordTypeDenoter(ref record) => {
+                        self.indent += 1;
+                        self.start(record.common_state.position.start, false);
+                        self.record(record);
+                        self.indent -= 1;
+                    }
+                    ref td => {
+                        self.write(" ");
+                        self.write(&td.name());
+                    }
+                }
+            }
+
+            VarDeclaration(ref decl) => {
+                self.write("var ");
+                self.write(&decl.id.spelling);
+                self.write(": ");
+                self.write(&decl.td.name());
+            }
+
+            // only the standard environment declares operators and formal parameters, and
+            // sequences have been taken apart by `declarations`
+            BinaryOperatorDeclaration(_)
+            | FormalParameterDeclaration(_)
+            | SequentialDeclaration(_)
+            | UnaryOperatorDeclaration(_) => {}
+        }
+    }
+
+    /// write the expression of a constant or function, on a line of its own if asked
+    fn body(&mut self, expr: &Expression, own_line: bool) {
+        if own_line {
+            self.indent += 1;
+            self.start(expr.common_state().position.start, false);
+            self.expression(expr);
+            self.indent -= 1;
+        } else {
+            self.write(" ");
+            self.expression(expr);
+        }
+    }
+
+    /// write the record type of a type declaration, one field to a line
+    fn record(&mut self, record: &RecordTypeDenoterState) {
+        let mut fields = Vec::new();
+        let mut ftd = &*record.ftd;
+        loop {
+            match *ftd {
+                FieldTypeDenoter::SingleFieldTypeDenoter(ref field) => {
+                    fields.push((&field.id, &field.td));
+                    break;
+                }
+                FieldTypeDenoter::MultipleFieldTypeDenoter(ref field) => {
+                    fields.push((&field.id, &field.td));
+                    ftd = &field.ftd;
+                }
+            }
+        }
+
+        self.write("record");
+        self.indent += 1;
+        for (i, &(id, td)) in fields.iter().enumerate() {
+            self.start(id.common_state.position.start, i > 0);
+            self.write(&id.spelling);
+            self.write(": ");
+            self.write(&td.name());
+            if i + 1 < fields.len() {
+                self.write(",");
+            }
+        }
+        self.indent -= 1;
+
+        let position = self.after(TokenType::End, record.ftd.common_state().position.finish);
+        self.start(position, false);
+        self.write("end");
+    }
+
+    fn formal_parameters(&mut self, fps: &FormalParameterSequence) {
+        use FormalParameterSequence::*;
+
+        match *fps {
+            EmptyFormalParameterSequence(_) => {}
+            SingleFormalParameterSequence(ref fps) => self.formal_parameter(fps.fp.get(self.nodes)),
+            MultipleFormalParameterSequence(ref fps) => {
+                self.formal_parameter(fps.fp.get(self.nodes));
+                self.write(", ");
+                self.formal_parameters(fps.fps.get(self.nodes));
+            }
+        }
+    }
+
+    fn formal_parameter(&mut self, fp: &FormalParameter) {
+        use FormalParameter::*;
+
+        match *fp {
+            ConstFormalParameter(ref fp) => {
+                self.write(&fp.id.spelling);
+                self.write(": ");
+                self.write(&fp.td.name());
+            }
+            VarFormalParameter(ref fp) => {
+                self.write("var ");
+                self.write(&fp.id.spelling);
+                self.write(": ");
+                self.write(&fp.td.name());
+            }
+            ProcFormalParameter(ref fp) => {
+                self.write("proc ");
+                self.write(&fp.id.spelling);
+                self.write("(");
+                self.formal_parameters(fp.fps.get(self.nodes));
+                self.write(")");
+            }
+            FuncFormalParameter(ref fp) => {
+                self.write("func ");
+                self.write(&fp.id.spelling);
+                self.write("(");
+                self.formal_parameters(fp.fps.get(self.nodes));
+                self.write("): ");
+                self.write(&fp.td.name());
+            }
+        }
+    }
+
+    fn actual_parameters(&mut self, aps: &ActualParameterSequence) {
+        use ActualParameterSequence::*;
+
+        match *aps {
+            EmptyActualParameterSequence(_) => {}
+            SingleActualParameterSequence(ref aps) => self.actual_parameter(aps.ap.get(self.nodes)),
+            MultipleActualParameterSequence(ref aps) => {
+                self.actual_parameter(aps.ap.get(self.nodes));
+                self.write(", ");
+                self.actual_parameters(aps.aps.get(self.nodes));
+            }
+        }
+    }
+
+    fn actual_parameter(&mut self, ap: &ActualParameter) {
+        use ActualParameter::*;
+
+        match *ap {
+            ConstActualParameter(ref ap) => self.expression(ap.expr.get(self.nodes)),
+            VarActualParameter(ref ap) => {
+                self.write("var ");
+                self.vname(ap.vname.get(self.nodes));
+            }
+            ProcActualParameter(ref ap) => self.named("proc ", &ap.id),
+            FuncActualParameter(ref ap) => self.named("func ", &ap.id),
+        }
+    }
+
+    fn named(&mut self, keyword: &str, id: &Identifier) {
+        self.write(keyword);
+        self.write(&id.spelling);
+    }
+
+    fn expression(&mut self, expr: &Expression) {
+        use Expression::*;
+
+        match *expr {
+            ArrayExpression(ref expr) => {
+                self.write("[");
+                self.array_aggregate(expr.aa.get(self.nodes));
+                self.write("]");
+            }
+
+            BinaryExpression(ref expr) => {
+                let parenthesise = match *expr.expr1.get(self.nodes) {
+                    BinaryExpression(ref left) => left.op.spelling != expr.op.spelling,
+                    ref left => !is_primary(left),
+                };
+                self.operand(expr.expr1.get(self.nodes), parenthesise);
+                self.write(" ");
+                self.write(&expr.op.spelling);
+                self.write(" ");
+                self.operand(
+                    expr.expr2.get(self.nodes),
+                    !is_primary(expr.expr2.get(self.nodes)),
+                );
+            }
+
+            CallExpression(ref expr) => {
+                self.write(&expr.id.spelling);
+                self.write("(");
+                self.actual_parameters(expr.aps.get(self.nodes));
+                self.write(")");
+            }
+
+            CharacterExpression(ref expr) => {
+                self.write("'");
+                self.write(&expr.cl.spelling);
+                self.write("'");
+            }
+
+            EmptyExpression(_) => {}
+
+            IfExpression(ref expr) => {
+                self.write("if ");
+                self.expression(expr.expr1.get(self.nodes));
+                self.indent += 1;
+                let then = self.after(
+                    TokenType::Then,
+                    expr.expr1.get(self.nodes).common_state().position.finish,
+                );
+                self.expression_branch("then", then, expr.expr2.get(self.nodes));
+                let els = self.after(
+                    TokenType::Else,
+                    expr.expr2.get(self.nodes).common_state().position.finish,
+                );
+                self.expression_branch("else", els, expr.expr3.get(self.nodes));
+                self.indent -= 1;
+            }
+
+            IntegerExpression(ref expr) => self.write(&expr.il.spelling),
+
+            LetExpression(ref expr) => {
+                self.write("let");
+                self.declarations(expr.decl.get(self.nodes));
+                let position = self.after(
+                    TokenType::In,
+                    expr.decl.get(self.nodes).common_state().position.finish,
+                );
+                self.start(position, false);
+                self.write("in");
+                self.body(expr.expr.get(self.nodes), true);
+            }
+
+            RecordExpression(ref expr) => {
+                self.write("{ ");
+                self.record_aggregate(expr.ra.get(self.nodes));
+                self.write(" }");
+            }
+
+            UnaryExpression(ref expr) => {
+                self.write(&expr.op.spelling);
+                // keep the operators apart, so that they are not scanned as one
+                if let UnaryExpression(_) = *expr.expr.get(self.nodes) {
+                    self.write(" ");
+                }
+                // the operand would take in a binary expression without them, but they make
+                // that plain to see
+                let parenthesise = matches!(
+                    *expr.expr.get(self.nodes),
+                    BinaryExpression(_) | IfExpression(_) | LetExpression(_)
+                );
+                self.operand(expr.expr.get(self.nodes), parenthesise);
+            }
+
+            VnameExpression(ref expr) => self.vname(expr.vname.get(self.nodes)),
+        }
+    }
+
+    fn operand(&mut self, expr: &Expression, parenthesise: bool) {
+        if parenthesise {
+            self.write("(");
+        }
+        self.expression(expr);
+        if parenthesise {
+            self.write(")");
+        }
+    }
+
+    /// write the `then` or `else` branch of an if expression, keeping a simple expression on the
+    /// same line as the keyword
+    fn expression_branch(&mut self, keyword: &str, position: Position, expr: &Expression) {
+        self.start(position, false);
+        self.write(keyword);
+
+        let simple = !matches!(
+            *expr,
+            Expression::IfExpression(_) | Expression::LetExpression(_)
+        );
+        if simple && !self.comment_before(expr.common_state().position.start) {
+            self.write(" ");
+            self.expression(expr);
+        } else {
+            self.body(expr, true);
+        }
+    }
+
+    fn array_aggregate(&mut self, aa: &ArrayAggregate) {
+        match *aa {
+            ArrayAggregate::SingleArrayAggregate(ref aa) => {
+                self.expression(aa.expr.get(self.nodes))
+            }
+            ArrayAggregate::MultipleArrayAggregate(ref aa) => {
+                self.expression(aa.expr.get(self.nodes));
+                self.write(", ");
+                self.array_aggregate(aa.aa.get(self.nodes));
+            }
+        }
+    }
+
+    fn record_aggregate(&mut self, ra: &RecordAggregate) {
+        match *ra {
+            RecordAggregate::SingleRecordAggregate(ref ra) => {
+                self.write(&ra.id.spelling);
+                self.write(" ~ ");
+                self.expression(ra.expr.get(self.nodes));
+            }
+            RecordAggregate::MultipleRecordAggregate(ref ra) => {
+                self.write(&ra.id.spelling);
+                self.write(" ~ ");
+                self.expression(ra.expr.get(self.nodes));
+                self.write(", ");
+                self.record_aggregate(ra.ra.get(self.nodes));
+            }
+        }
+    }
+
+    fn vname(&mut self, vname: &Vname) {
+        match *vname {
+            Vname::SimpleVname(ref vname) => self.write(&vname.id.spelling),
+            Vname::DotVname(ref vname) => {
+                self.vname(vname.vname.get(self.nodes));
+                self.write(".");
+                self.write(&vname.id.spelling);
+            }
+            Vname::SubscriptVname(ref vname) => {
+                self.vname(vname.vname.get(self.nodes));
+                self.write("[");
+                self.expression(vname.expr.get(self.nodes));
+                self.write("]");
+            }
+        }
+    }
+}
+
+fn is_empty_command(cmd: &Command) -> bool {
+    matches!(*cmd, Command::EmptyCommand(_))
+}
+
+/// whether the expression is a primary expression, which needs no parentheses as an operand
+fn is_primary(expr: &Expression) -> bool {
+    use Expression::*;
+
+    !matches!(
+        *expr,
+        BinaryExpression(_) | IfExpression(_) | LetExpression(_) | UnaryExpression(_)
+    )
+}
+
+/// the commands of a sequence, in order
+fn sequence<'a>(nodes: &'a Nodes, cmd: &'a Command) -> Vec<&'a Command> {
+    match *cmd {
+        Command::SequentialCommand(ref cmd) => {
+            let mut cmds = sequence(nodes, cmd.cmd1.get(nodes));
+            cmds.extend(sequence(nodes, cmd.cmd2.get(nodes)));
+            cmds
+        }
+        ref cmd => vec![cmd],
+    }
+}
+
+/// the declarations of a sequence, in order
+fn declaration_sequence<'a>(nodes: &'a Nodes, decl: &'a Declaration) -> Vec<&'a Declaration> {
+    match *decl {
+        Declaration::SequentialDeclaration(ref decl) => {
+            let mut decls = declaration_sequence(nodes, decl.decl1.get(nodes));
+            decls.extend(declaration_sequence(nodes, decl.decl2.get(nodes)));
+            decls
+        }
+        ref decl => vec![decl],
+    }
+}
@@ -1,6 +1,12 @@
 pub mod ast;
 pub mod checker;
+pub mod cst;
+pub mod diagnostics;
 pub mod encoder;
 pub mod error;
+pub mod formatter;
+pub mod json;
+pub mod lsp;
 pub mod parser;
 pub mod scanner;
+pub mod tam;
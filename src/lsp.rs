@@ -0,0 +1,591 @@
+//! The Language Server
+//!
+//! Speaks the Language Server Protocol - JSON-RPC messages, each preceded by a `Content-Length`
+//! header - so that editors can show the errors in a Triangle source file as it is edited. Every
+//! time a document is opened or changed it is scanned, parsed and checked afresh, and everything
+//! that was found is published back to the editor as diagnostics, with the notes of an error
+//! as its related information. Once a document has been checked, the server can also go to the
+//! declaration of an identifier, show its type on hover, and find its references.
+//!
+//! Triangle positions count lines and columns from 1, by character. LSP positions count both from
+//! 0, with the character measured in UTF-16 code units, and the end of an LSP range lies just
+//! past its last character.
+
+use crate::ast::Program;
+use crate::checker::Checker;
+use crate::diagnostics::Diagnostics;
+use crate::error::{self, GenError, GenResult, LspError};
+use crate::json::Json;
+use crate::parser::Parser;
+use crate::scanner::{Position, Scanner, SourcePosition};
+
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// the longest message body that the server will read - far longer than any source file that it
+/// could be asked to check
+const MAX_CONTENT_LENGTH: usize = 1 << 26;
+
+const SEVERITY_ERROR: i64 = 1;
+const SEVERITY_WARNING: i64 = 2;
+
+/// an open document, and the program in it as it was last checked
+struct Document {
+    text: String,
+    /// the checked program, unless the text did not parse
+    program: Option<Program>,
+}
+
+/// the documents that the editor has open, and where the conversation with it has got to
+pub struct Server {
+    /// each open document, by URI
+    documents: HashMap<String, Document>,
+    shutdown: bool,
+    exited: bool,
+}
+
+impl Server {
+    pub fn new() -> Self {
+        Server {
+            documents: HashMap::new(),
+            shutdown: false,
+            exited: false,
+        }
+    }
+
+    /// whether the editor has asked the server to exit
+    pub fn exited(&self) -> bool {
+        self.exited
+    }
+
+    /// the status that the server should exit with - success only if it was shut down first
+    pub fn exit_code(&self) -> i32 {
+        if self.shutdown {
+            0
+        } else {
+            1
+        }
+    }
+
+    /// the text of an open document
+    pub fn document(&self, uri: &str) -> Option<&str> {
+        self.documents
+            .get(uri)
+            .map(|document| document.text.as_str())
+    }
+
+    /// handle a single message from the editor, returning the messages to send back to it - the
+    /// response to a request, and any notifications
+    pub fn handle(&mut self, message: &Json) -> Vec<Json> {
+        let id = message.get("id").cloned();
+        let params = message.get("params").unwrap_or(&Json::Null);
+        let method = match message.get("method").and_then(Json::as_str) {
+            Some(method) => method,
+            None => {
+                // a response to a request of ours - the server does not make any
+                if id.is_none() || message.get("result").is_some() || message.get("error").is_some()
+                {
+                    return Vec::new();
+                }
+                return vec![error_response(id, INVALID_REQUEST, "no method given")];
+            }
+        };
+
+        // once shut down, the server only waits to be told to exit
+        if self.shutdown && method != "exit" {
+            return match id {
+                Some(id) => vec![error_response(
+                    Some(id),
+                    INVALID_REQUEST,
+                    "the server has been shut down",
+                )],
+                None => Vec::new(),
+            };
+        }
+
+        match (method, id) {
+            ("initialize", Some(id)) => vec![response(id, capabilities())],
+            ("shutdown", Some(id)) => {
+                self.shutdown = true;
+                vec![response(id, Json::Null)]
+            }
+            ("exit", None) => {
+                self.exited = true;
+                Vec::new()
+            }
+            ("textDocument/didOpen", None) => {
+                let document = params.get("textDocument").unwrap_or(&Json::Null);
+                match (string_at(document, "uri"), string_at(document, "text")) {
+                    (Some(uri), Some(text)) => self.update(uri, text),
+                    _ => Vec::new(),
+                }
+            }
+            ("textDocument/didChange", None) => {
+                // the server asks for the full text of the document on every change
+                let uri = params
+                    .get("textDocument")
+                    .and_then(|doc| string_at(doc, "uri"));
+                let text = params
+                    .get("contentChanges")
+                    .and_then(Json::as_array)
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| string_at(change, "text"));
+                match (uri, text) {
+                    (Some(uri), Some(text)) => self.update(uri, text),
+                    _ => Vec::new(),
+                }
+            }
+            ("textDocument/didClose", None) => {
+                match params
+                    .get("textDocument")
+                    .and_then(|doc| string_at(doc, "uri"))
+                {
+                    Some(uri) => {
+                        self.documents.remove(uri);
+                        vec![publish(uri, Vec::new())]
+                    }
+                    None => Vec::new(),
+                }
+            }
+            ("textDocument/definition", Some(id)) => vec![response(id, self.definition(params))],
+            ("textDocument/hover", Some(id)) => vec![response(id, self.hover(params))],
+            ("textDocument/references", Some(id)) => vec![response(id, self.references(params))],
+            (_, Some(id)) => vec![error_response(
+                Some(id),
+                METHOD_NOT_FOUND,
+                &format!("unknown method {}", method),
+            )],
+            // notifications that the server has no use for, such as `initialized`
+            (_, None) => Vec::new(),
+        }
+    }
+
+    /// record the new text of the document, and publish what is wrong with it
+    fn update(&mut self, uri: &str, text: &str) -> Vec<Json> {
+        let (program, errors, warnings) = analyse(text);
+        let diagnostics = diagnostics(uri, text, &errors, &warnings);
+        self.documents.insert(
+            uri.to_owned(),
+            Document {
+                text: text.to_owned(),
+                program,
+            },
+        );
+        vec![publish(uri, diagnostics)]
+    }
+
+    /// the document, its checked program, and the position in it, that a request is about
+    fn target<'a>(
+        &'a self,
+        params: &'a Json,
+    ) -> Option<(&'a str, &'a Document, &'a Program, Position)> {
+        let uri = params
+            .get("textDocument")
+            .and_then(|doc| string_at(doc, "uri"))?;
+        let document = self.documents.get(uri)?;
+        let program = document.program.as_ref()?;
+        let position = params.get("position")?;
+        let line = position.get("line").and_then(Json::as_i64)?;
+        let character = position.get("character").and_then(Json::as_i64)?;
+        Some((
+            uri,
+            document,
+            program,
+            from_lsp(&document.text, line, character),
+        ))
+    }
+
+    /// the location of the identifier that declares what is at the position. Declarations of
+    /// the standard environment have no location.
+    fn definition(&self, params: &Json) -> Json {
+        let (uri, document, program, position) = match self.target(params) {
+            Some(target) => target,
+            None => return Json::Null,
+        };
+        program
+            .definition_at(position)
+            .and_then(|decl| {
+                program
+                    .references_of(decl)
+                    .into_iter()
+                    .find(|occurrence| occurrence.defining)
+            })
+            .map_or(Json::Null, |occurrence| {
+                location(uri, &document.text, occurrence.position)
+            })
+    }
+
+    /// what the identifier at the position is declared as, or else the type of the expression
+    /// there
+    fn hover(&self, params: &Json) -> Json {
+        let (_, document, program, position) = match self.target(params) {
+            Some(target) => target,
+            None => return Json::Null,
+        };
+        let occurrence = program.occurrence_at(position);
+        let value = match (program.definition_at(position), program.type_at(position)) {
+            (Some(decl), _) => program.describe(decl),
+            (None, Some(td)) => td.name(),
+            (None, None) => return Json::Null,
+        };
+
+        let mut members = vec![(
+            "contents",
+            Json::object(vec![
+                ("kind", Json::from("plaintext")),
+                ("value", Json::from(value)),
+            ]),
+        )];
+        if let Some(occurrence) = occurrence {
+            members.push(("range", range(&document.text, occurrence.position)));
+        }
+        Json::object(members)
+    }
+
+    /// the locations of every use of the declaration of what is at the position, along with
+    /// the declaration itself if the editor asks for it
+    fn references(&self, params: &Json) -> Json {
+        let (uri, document, program, position) = match self.target(params) {
+            Some(target) => target,
+            None => return Json::Null,
+        };
+        let include_declaration = params
+            .get("context")
+            .and_then(|context| context.get("includeDeclaration"))
+            .and_then(Json::as_bool)
+            .unwrap_or(false);
+
+        let locations = match program.definition_at(position) {
+            Some(decl) => program
+                .references_of(decl)
+                .into_iter()
+                .filter(|occurrence| include_declaration || !occurrence.defining)
+                .map(|occurrence| location(uri, &document.text, occurrence.position))
+                .collect(),
+            None => Vec::new(),
+        };
+        Json::from(locations)
+    }
+}
+
+impl Default for Server {
+    fn default() -> Self {
+        Server::new()
+    }
+}
+
+/// serve the editor on the other end of the reader and writer until it asks the server to exit,
+/// or closes the connection. Returns the status that the server should exit with.
+pub fn serve<R: BufRead, W: Write>(mut reader: R, mut writer: W) -> GenResult<i32> {
+    let mut server = Server::new();
+    while let Some(body) = read_message(&mut reader)? {
+        let replies = match Json::parse(&body) {
+            Ok(message) => server.handle(&message),
+            Err(err) => vec![error_response(
+                Some(Json::Null),
+                PARSE_ERROR,
+                &err.to_string(),
+            )],
+        };
+        for reply in replies {
+            write_message(&mut writer, &reply)?;
+        }
+        if server.exited() {
+            return Ok(server.exit_code());
+        }
+    }
+    Ok(server.exit_code())
+}
+
+/// read the body of the next message, or None if the input has ended. A message without a
+/// `Content-Length` header, or with one longer than the server will read, is an error.
+pub fn read_message<R: BufRead>(reader: &mut R) -> GenResult<Option<String>> {
+    let mut length = None;
+    let mut headers = false;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return if headers {
+                Err(lsp_error("the input ended in the middle of a message"))
+            } else {
+                Ok(None)
+            };
+        }
+
+        let line = line.trim_end();
+        if line.is_empty() {
+            if headers {
+                break;
+            }
+            continue;
+        }
+        headers = true;
+        if let Some(value) = header_value(line, "content-length") {
+            length = Some(
+                value
+                    .parse::<usize>()
+                    .map_err(|_| lsp_error(&format!("bad Content-Length {:?}", value)))?,
+            );
+        }
+    }
+
+    let length = length.ok_or_else(|| lsp_error("a message has no Content-Length header"))?;
+    if length > MAX_CONTENT_LENGTH {
+        return Err(lsp_error(&format!(
+            "Content-Length {} is longer than the server will read",
+            length
+        )));
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    String::from_utf8(body)
+        .map(Some)
+        .map_err(|_| lsp_error("a message is not valid UTF-8"))
+}
+
+/// write the message, preceded by its header
+pub fn write_message<W: Write>(writer: &mut W, message: &Json) -> GenResult<()> {
+    let body = message.to_string();
+    write!(writer, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// the value of the header on this line, if the line holds the named header
+fn header_value<'a>(line: &'a str, name: &str) -> Option<&'a str> {
+    let colon = line.find(':')?;
+    if line[..colon].trim().eq_ignore_ascii_case(name) {
+        Some(line[colon + 1..].trim())
+    } else {
+        None
+    }
+}
+
+/// scan, parse and check the text of the document, and return everything that was found as LSP
+/// diagnostics
+pub fn diagnose(uri: &str, text: &str) -> Vec<Json> {
+    let (_, errors, warnings) = analyse(text);
+    diagnostics(uri, text, &errors, &warnings)
+}
+
+/// scan, parse and check the text, returning the checked program along with the errors and the
+/// warnings. The program is only checked, and returned, if it parsed cleanly.
+fn analyse(text: &str) -> (Option<Program>, Diagnostics, Diagnostics) {
+    let mut errors = Diagnostics::new();
+    let mut warnings = Diagnostics::new();
+    let mut program = Parser::new(Scanner::from_text(text)).parse_program_with(&mut errors);
+    if errors.has_errors() {
+        return (None, errors, warnings);
+    }
+
+    let mut checker = Checker::new();
+    checker.check_with(&mut program, &mut errors);
+    warnings.append(&mut checker.take_warnings());
+    (Some(program), errors, warnings)
+}
+
+fn diagnostics(uri: &str, text: &str, errors: &Diagnostics, warnings: &Diagnostics) -> Vec<Json> {
+    let mut diagnostics = Vec::new();
+    for error in errors.errors() {
+        diagnostic(error, SEVERITY_ERROR, uri, text, &mut diagnostics);
+    }
+    for warning in warnings.errors() {
+        diagnostic(warning, SEVERITY_WARNING, uri, text, &mut diagnostics);
+    }
+    diagnostics
+}
+
+/// add the error as an LSP diagnostic, or each of the errors if it holds several. An error without
+/// a position is shown at the start of the document.
+fn diagnostic(error: &GenError, severity: i64, uri: &str, text: &str, out: &mut Vec<Json>) {
+    if let Some(diagnostics) = error.downcast_ref::<Diagnostics>() {
+        for error in diagnostics.errors() {
+            diagnostic(error, severity, uri, text, out);
+        }
+        return;
+    }
+
+    let (message, position, notes) = match error::locate(error) {
+        Some(located) => (
+            located.message().to_owned(),
+            located.position(),
+            located.notes().to_vec(),
+        ),
+        None => (error.to_string(), SourcePosition::default(), Vec::new()),
+    };
+
+    let mut members = vec![
+        ("range", range(text, position)),
+        ("severity", Json::from(severity)),
+        ("source", Json::from("triangle")),
+        ("message", Json::from(message)),
+    ];
+    if !notes.is_empty() {
+        let related = notes
+            .iter()
+            .map(|note| {
+                Json::object(vec![
+                    ("location", location(uri, text, note.position)),
+                    ("message", Json::from(note.message.as_str())),
+                ])
+            })
+            .collect::<Vec<_>>();
+        members.push(("relatedInformation", Json::from(related)));
+    }
+    out.push(Json::object(members));
+}
+
+/// the LSP location of a position in the document
+pub fn location(uri: &str, text: &str, position: SourcePosition) -> Json {
+    Json::object(vec![
+        ("uri", Json::from(uri)),
+        ("range", range(text, position)),
+    ])
+}
+
+/// the LSP range covering a source position in the text, from its start to just past its finish.
+/// Positions outside the source cover the start of the text.
+pub fn range(text: &str, position: SourcePosition) -> Json {
+    let (start, finish) = (position.start, position.finish);
+    if start.line < 1 || start.column < 1 {
+        return Json::object(vec![
+            ("start", lsp_position(0, 0)),
+            ("end", lsp_position(0, 0)),
+        ]);
+    }
+
+    let (start_line, start_char) = to_lsp(text, start);
+    let (end_line, end_char) = if finish < start {
+        (start_line, start_char + 1)
+    } else {
+        let (line, character) = to_lsp(text, finish);
+        let width = char_at(text, finish).map_or(1, char::len_utf16) as i64;
+        (line, character + width)
+    };
+    Json::object(vec![
+        ("start", lsp_position(start_line, start_char)),
+        ("end", lsp_position(end_line, end_char)),
+    ])
+}
+
+/// the LSP line and character of a source position in the text
+pub fn to_lsp(text: &str, position: Position) -> (i64, i64) {
+    let line = (position.line - 1).max(0) as i64;
+    let column = (position.column - 1).max(0) as usize;
+    let character = match text.lines().nth(line as usize) {
+        Some(source) => {
+            source
+                .chars()
+                .take(column)
+                .map(char::len_utf16)
+                .sum::<usize>()
+                + column.saturating_sub(source.chars().count())
+        }
+        None => column,
+    };
+    (line, character as i64)
+}
+
+/// the source position of an LSP line and character in the text
+pub fn from_lsp(text: &str, line: i64, character: i64) -> Position {
+    let mut column = 0;
+    if let Some(source) = text.lines().nth(line.max(0) as usize) {
+        let mut units = 0;
+        for c in source.chars() {
+            if units >= character {
+                break;
+            }
+            units += c.len_utf16() as i64;
+            column += 1;
+        }
+    }
+    Position::new(line as isize + 1, column + 1)
+}
+
+fn char_at(text: &str, position: Position) -> Option<char> {
+    text.lines()
+        .nth((position.line - 1) as usize)?
+        .chars()
+        .nth((position.column - 1) as usize)
+}
+
+fn lsp_position(line: i64, character: i64) -> Json {
+    Json::object(vec![
+        ("line", Json::from(line)),
+        ("character", Json::from(character)),
+    ])
+}
+
+fn capabilities() -> Json {
+    Json::object(vec![
+        (
+            "capabilities",
+            Json::object(vec![
+                // the full text of the document is sent on every change
+                ("textDocumentSync", Json::from(1i64)),
+                ("definitionProvider", Json::from(true)),
+                ("hoverProvider", Json::from(true)),
+                ("referencesProvider", Json::from(true)),
+            ]),
+        ),
+        (
+            "serverInfo",
+            Json::object(vec![
+                ("name", Json::from("triangle-lsp")),
+                ("version", Json::from(env!("CARGO_PKG_VERSION"))),
+            ]),
+        ),
+    ])
+}
+
+fn publish(uri: &str, diagnostics: Vec<Json>) -> Json {
+    notification(
+        "textDocument/publishDiagnostics",
+        Json::object(vec![
+            ("uri", Json::from(uri)),
+            ("diagnostics", Json::from(diagnostics)),
+        ]),
+    )
+}
+
+fn notification(method: &str, params: Json) -> Json {
+    Json::object(vec![
+        ("jsonrpc", Json::from("2.0")),
+        ("method", Json::from(method)),
+        ("params", params),
+    ])
+}
+
+fn response(id: Json, result: Json) -> Json {
+    Json::object(vec![
+        ("jsonrpc", Json::from("2.0")),
+        ("id", id),
+        ("result", result),
+    ])
+}
+
+fn error_response(id: Option<Json>, code: i64, message: &str) -> Json {
+    Json::object(vec![
+        ("jsonrpc", Json::from("2.0")),
+        ("id", id.unwrap_or(Json::Null)),
+        (
+            "error",
+            Json::object(vec![
+                ("code", Json::from(code)),
+                ("message", Json::from(message)),
+            ]),
+        ),
+    ])
+}
+
+fn string_at<'a>(value: &'a Json, key: &str) -> Option<&'a str> {
+    value.get(key).and_then(Json::as_str)
+}
+
+fn lsp_error(message: &str) -> GenError {
+    GenError::from(LspError::new(message))
+}
@@ -1,3 +1,492 @@
+//! The `triangle` command-line driver
+//!
+//! Runs any part of the compiler pipeline over a source file - scanning, parsing, checking,
+//! compiling to a TAM object file, or compiling and running the program on the TAM interpreter.
+
+use triangle_rs::ast::Program;
+use triangle_rs::checker::Checker;
+use triangle_rs::diagnostics::{self, Diagnostics};
+use triangle_rs::encoder::Encoder;
+use triangle_rs::error::{CompilerError, GenError, GenResult};
+use triangle_rs::formatter;
+use triangle_rs::parser::Parser;
+use triangle_rs::scanner::{Position, Scanner, SourcePosition, TokenType};
+use triangle_rs::tam::assembler;
+use triangle_rs::tam::disassembler;
+use triangle_rs::tam::interpreter::{Interpreter, Status};
+use triangle_rs::tam::object::TamObject;
+use triangle_rs::tam::Instruction;
+
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, IsTerminal, Read, Write};
+use std::path::Path;
+use std::process;
+
+/// the file name that stands for stdin
+const STDIN: &str = "-";
+
+const USAGE: &str = "\
+usage: triangle <command> [options] <file>
+
+commands:
+    tokens        print the tokens of a Triangle source file
+    parse         print the abstract syntax tree of a Triangle source file
+    check         parse and check a Triangle source file
+    compile       compile a Triangle source file (or assemble a .tasm file) into a TAM object
+    run           run a Triangle source file, a .tasm file, or a TAM object on the interpreter
+    disassemble   print the TAM listing of a Triangle source file, a .tasm file, or a TAM object
+    fmt           lay out a Triangle source file afresh, keeping its comments
+    where         show what the identifier at <file>:<line>:<col> stands for, its type, and
+                  where it is used
+
+options:
+    -o, --output <path>   write the output to <path> (compile defaults to <file>.tam)
+    --tree                print the syntax tree (parse does by default; check adds the types)
+    --json                print the syntax tree as JSON instead
+    -v, --verbose         report what is being done; repeat for more detail
+    -h, --help            print this message
+
+A source <file> of \"-\" is read from stdin.";
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Command {
+    Tokens,
+    Parse,
+    Check,
+    Compile,
+    Run,
+    Disassemble,
+    Fmt,
+    Where,
+}
+
+/// how `parse` and `check` show the program
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Dump {
+    Tree,
+    Json,
+}
+
+struct Options {
+    command: Command,
+    file: String,
+    output: Option<String>,
+    verbosity: usize,
+    dump: Option<Dump>,
+    /// the position that `where` asks about
+    position: Option<Position>,
+    /// the text of the input file, unless it is a TAM object
+    text: String,
+}
+
 fn main() {
-    println!("Welcome to the Triangle programming language!");
+    let mut options = match parse_args(env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return;
+        }
+        Err(err) => {
+            eprintln!("{}\n\n{}", err, USAGE);
+            process::exit(2);
+        }
+    };
+
+    if let Err(err) = read_text(&mut options).and_then(|_| drive(&options)) {
+        report(&options, err);
+    }
+}
+
+/// print the error, showing the lines of the input that it refers to, and exit
+fn report(options: &Options, err: GenError) -> ! {
+    let colour = io::stderr().is_terminal();
+    eprint!(
+        "{}",
+        diagnostics::render(&err, file_name(options), &options.text, colour)
+    );
+    process::exit(1);
+}
+
+/// the name of the input file, as it is shown in diagnostics
+fn file_name(options: &Options) -> &str {
+    if options.file == STDIN {
+        "<stdin>"
+    } else {
+        &options.file
+    }
+}
+
+/// parse the command line, returning None if help was asked for
+fn parse_args<I: Iterator<Item = String>>(mut args: I) -> GenResult<Option<Options>> {
+    let mut command = None;
+    let mut file = None;
+    let mut output = None;
+    let mut verbosity = 0;
+    let mut dump = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--verbose" => verbosity += 1,
+            "--tree" => dump = Some(Dump::Tree),
+            "--json" => dump = Some(Dump::Json),
+            _ if arg.len() > 1 && arg.starts_with('-') && arg[1..].chars().all(|c| c == 'v') => {
+                verbosity += arg.len() - 1
+            }
+            "-o" | "--output" => match args.next() {
+                Some(path) => output = Some(path),
+                None => return Err(usage_error(&format!("{} needs a path", arg))),
+            },
+            _ if arg.starts_with('-') && arg != STDIN => {
+                return Err(usage_error(&format!("unknown option {}", arg)))
+            }
+            _ if command.is_none() => command = Some(parse_command(&arg)?),
+            _ if file.is_none() => file = Some(arg),
+            _ => return Err(usage_error(&format!("unexpected argument {}", arg))),
+        }
+    }
+
+    match (command, file) {
+        (Some(Command::Where), Some(location)) => {
+            let (file, position) = parse_location(&location)?;
+            Ok(Some(Options {
+                command: Command::Where,
+                file,
+                output,
+                verbosity,
+                dump,
+                position: Some(position),
+                text: String::new(),
+            }))
+        }
+        (Some(command), Some(file)) => Ok(Some(Options {
+            command,
+            file,
+            output,
+            verbosity,
+            dump,
+            position: None,
+            text: String::new(),
+        })),
+        (None, _) => Err(usage_error("no command given")),
+        (_, None) => Err(usage_error("no file given")),
+    }
+}
+
+fn parse_command(arg: &str) -> GenResult<Command> {
+    match arg {
+        "tokens" => Ok(Command::Tokens),
+        "parse" => Ok(Command::Parse),
+        "check" => Ok(Command::Check),
+        "compile" => Ok(Command::Compile),
+        "run" => Ok(Command::Run),
+        "disassemble" => Ok(Command::Disassemble),
+        "fmt" => Ok(Command::Fmt),
+        "where" => Ok(Command::Where),
+        _ => Err(usage_error(&format!("unknown command {}", arg))),
+    }
+}
+
+/// split a `<file>:<line>:<col>` argument into the file and the position
+fn parse_location(arg: &str) -> GenResult<(String, Position)> {
+    let mut parts = arg.rsplitn(3, ':');
+    let column = parts.next().and_then(|column| column.parse().ok());
+    let line = parts.next().and_then(|line| line.parse().ok());
+    match (parts.next(), line, column) {
+        (Some(file), Some(line), Some(column)) if line > 0 && column > 0 => {
+            Ok((file.to_owned(), Position::new(line, column)))
+        }
+        _ => Err(usage_error(&format!(
+            "expected <file>:<line>:<col>, but found {}",
+            arg
+        ))),
+    }
+}
+
+fn usage_error(message: &str) -> GenError {
+    GenError::from(CompilerError::new(message))
+}
+
+fn drive(options: &Options) -> GenResult<()> {
+    match options.command {
+        Command::Tokens => tokens(options),
+        Command::Parse => parse(options),
+        Command::Check => check(options),
+        Command::Compile => compile(options),
+        Command::Run => run(options),
+        Command::Disassemble => disassemble(options),
+        Command::Fmt => fmt(options),
+        Command::Where => where_is(options),
+    }
+}
+
+fn tokens(options: &Options) -> GenResult<()> {
+    let mut out = open_output(&options.output)?;
+    let mut scanner = Scanner::from_text(&options.text);
+
+    loop {
+        let token = scanner.scan_token()?;
+        writeln!(
+            out,
+            "{}:{}\t{:?}\t{:?}",
+            token.position.start.line, token.position.start.column, token.kind, token.spelling
+        )?;
+
+        if token.kind == TokenType::Eot {
+            break;
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
+fn parse(options: &Options) -> GenResult<()> {
+    let program = Parser::new(Scanner::from_text(&options.text)).parse_program()?;
+    dump(options, &program, options.dump.unwrap_or(Dump::Tree))
+}
+
+fn check(options: &Options) -> GenResult<()> {
+    let program = checked(options)?;
+
+    if options.verbosity > 0 {
+        eprintln!("{}: no errors found", options.file);
+    }
+    match options.dump {
+        Some(how) => dump(options, &program, how),
+        None => Ok(()),
+    }
+}
+
+/// print the program as a tree or as JSON
+fn dump(options: &Options, program: &Program, how: Dump) -> GenResult<()> {
+    let mut out = open_output(&options.output)?;
+    match how {
+        Dump::Tree => write!(out, "{}", program.tree())?,
+        Dump::Json => writeln!(out, "{}", program.to_json().pretty())?,
+    }
+    out.flush()?;
+    Ok(())
+}
+
+fn compile(options: &Options) -> GenResult<()> {
+    let object = load(options)?;
+    let output = match &options.output {
+        Some(path) => path.clone(),
+        None if options.file == STDIN => {
+            return Err(usage_error("compiling from stdin needs an output path"))
+        }
+        None => Path::new(&options.file)
+            .with_extension("tam")
+            .to_string_lossy()
+            .into_owned(),
+    };
+
+    let mut writer = BufWriter::new(File::create(&output)?);
+    object.write_to(&mut writer)?;
+
+    if options.verbosity > 0 {
+        eprintln!("{}: wrote {}", options.file, output);
+    }
+    Ok(())
+}
+
+fn run(options: &Options) -> GenResult<()> {
+    let object = load(options)?;
+
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    let mut interpreter = Interpreter::new(stdin.lock(), stdout.lock());
+    interpreter.load(&object.code);
+
+    match interpreter.run() {
+        Status::Halted => Ok(()),
+        status => {
+            eprintln!("{}", status);
+            process::exit(1)
+        }
+    }
+}
+
+/// list the code, interleaved with the lines of the source if the input is a Triangle program
+fn disassemble(options: &Options) -> GenResult<()> {
+    let mut out = open_output(&options.output)?;
+    if is_source(&options.file) {
+        let (code, positions) = compile_program(checked(options)?)?;
+        write!(out, "{}", listing(options, &code, &positions))?;
+    } else {
+        let object = load(options)?;
+        write!(out, "{}", disassembler::disassemble(&object.code))?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+fn fmt(options: &Options) -> GenResult<()> {
+    let mut out = open_output(&options.output)?;
+    write!(out, "{}", formatter::format(&options.text)?)?;
+    out.flush()?;
+    Ok(())
+}
+
+/// show the declaration that the identifier or operator at the position stands for, the type of
+/// the innermost expression there, and every occurrence of the declaration
+fn where_is(options: &Options) -> GenResult<()> {
+    let position = options.position.unwrap_or_default();
+    let location = |position: SourcePosition| {
+        format!(
+            "{}:{}:{}",
+            file_name(options),
+            position.start.line,
+            position.start.column
+        )
+    };
+
+    let program = checked(options)?;
+    let occurrence = program.occurrence_at(position);
+    let td = program.type_at(position);
+    if occurrence.is_none() && td.is_none() {
+        return Err(usage_error(&format!(
+            "there is nothing to show at {}:{}:{}",
+            file_name(options),
+            position.line,
+            position.column
+        )));
+    }
+
+    let mut out = open_output(&options.output)?;
+    if let Some(ref occurrence) = occurrence {
+        match program.definition_at(position) {
+            Some(decl) if decl.common_state().position.start.line < 1 => writeln!(
+                out,
+                "\"{}\" is {}, from the standard environment",
+                occurrence.spelling,
+                program.describe(decl)
+            )?,
+            Some(decl) => writeln!(
+                out,
+                "\"{}\" is {}",
+                occurrence.spelling,
+                program.describe(decl)
+            )?,
+            None => writeln!(out, "\"{}\" is not declared", occurrence.spelling)?,
+        }
+    }
+    if let Some(td) = td {
+        writeln!(out, "type: {}", td.name())?;
+    }
+    if let Some(decl) = program.definition_at(position) {
+        for reference in program.references_of(decl) {
+            let verb = if reference.defining {
+                "declared"
+            } else {
+                "used"
+            };
+            writeln!(out, "{} at {}", verb, location(reference.position))?;
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// produce the code for the input file - read it as an object file, assemble it, or compile it
+/// from source, depending on its extension
+fn load(options: &Options) -> GenResult<TamObject> {
+    let path = Path::new(&options.file);
+    let object = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("tam") => TamObject::read_from(&mut File::open(path)?)?,
+        Some("tasm") => TamObject::new(assembler::assemble(&options.text)?),
+        _ => {
+            let (code, positions) = compile_program(checked(options)?)?;
+            if options.verbosity > 1 {
+                eprint!("{}", listing(options, &code, &positions));
+            }
+            TamObject::new(code)
+        }
+    };
+
+    if options.verbosity > 0 {
+        eprintln!("{}: {} instructions", options.file, object.code.len());
+    }
+    Ok(object)
+}
+
+fn is_source(file: &str) -> bool {
+    !matches!(
+        Path::new(file).extension().and_then(|ext| ext.to_str()),
+        Some("tam") | Some("tasm")
+    )
+}
+
+/// read the text of the input file, so that errors can be shown against it. TAM objects are
+/// binary, and are read when they are loaded.
+fn read_text(options: &mut Options) -> GenResult<()> {
+    if Path::new(&options.file)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        != Some("tam")
+    {
+        options.text = read_source(&options.file)?;
+    }
+    Ok(())
+}
+
+/// read the source text from the file, or from stdin if the file is "-"
+fn read_source(file: &str) -> GenResult<String> {
+    if file == STDIN {
+        let mut source = String::new();
+        io::stdin().read_to_string(&mut source)?;
+        Ok(source)
+    } else {
+        Ok(fs::read_to_string(file)?)
+    }
+}
+
+/// parse and check the Triangle program, reporting every error found by either. The program is
+/// only checked if it parsed cleanly. Anything the checker warns about is added to `warnings`.
+fn analyse(source: &str, warnings: &mut Diagnostics) -> GenResult<Program> {
+    let mut diagnostics = Diagnostics::new();
+    let mut program = Parser::new(Scanner::from_text(source)).parse_program_with(&mut diagnostics);
+    if !diagnostics.has_errors() {
+        let mut checker = Checker::new();
+        checker.check_with(&mut program, &mut diagnostics);
+        warnings.append(&mut checker.take_warnings());
+    }
+
+    diagnostics.into_result()?;
+    Ok(program)
+}
+
+/// parse and check the input file, printing anything the checker warns about to stderr
+fn checked(options: &Options) -> GenResult<Program> {
+    let mut warnings = Diagnostics::new();
+    let program = analyse(&options.text, &mut warnings)?;
+    eprint!(
+        "{}",
+        warnings.render_warnings(
+            file_name(options),
+            &options.text,
+            io::stderr().is_terminal()
+        )
+    );
+    Ok(program)
+}
+
+/// compile the checked Triangle program, returning its code along with the source position of
+/// every instruction
+fn compile_program(mut program: Program) -> GenResult<(Vec<Instruction>, Vec<SourcePosition>)> {
+    let mut encoder = Encoder::new();
+    let code = encoder.encode(&mut program)?;
+    Ok((code, encoder.positions().to_vec()))
+}
+
+fn listing(options: &Options, code: &[Instruction], positions: &[SourcePosition]) -> String {
+    disassembler::disassemble_with_source(code, positions, &options.text)
+}
+
+fn open_output(output: &Option<String>) -> GenResult<Box<dyn Write>> {
+    match output {
+        Some(path) => Ok(Box::new(BufWriter::new(File::create(path)?))),
+        None => Ok(Box::new(io::stdout())),
+    }
 }
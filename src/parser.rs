@@ -24,21 +24,45 @@ use crate::ast::typedenoters::*;
 use crate::ast::vnames::Vname::*;
 use crate::ast::vnames::*;
 use crate::ast::Program;
-use crate::error::{self, GenError, ParserError};
+use crate::diagnostics::Diagnostics;
+use crate::error::{GenError, GenResult, ParserError};
 use crate::scanner::{Scanner, SourcePosition, Token, TokenType};
 
+/// the tokens that may follow a command, a declaration, and the condition of an if or while
+/// command - on a syntax error, the Parser skips ahead to one of these before carrying on
+const COMMAND_FOLLOWERS: &[TokenType] = &[
+    TokenType::Semicolon,
+    TokenType::End,
+    TokenType::Else,
+    TokenType::In,
+    TokenType::Eot,
+];
+const DECLARATION_FOLLOWERS: &[TokenType] = &[TokenType::Semicolon, TokenType::In, TokenType::Eot];
+const CONDITION_FOLLOWERS: &[TokenType] = &[
+    TokenType::Then,
+    TokenType::Do,
+    TokenType::Else,
+    TokenType::Semicolon,
+    TokenType::End,
+    TokenType::Eot,
+];
+
 pub struct Parser {
     scanner: Scanner,
     current_token: Token,
+    previous_token_position: SourcePosition,
+    diagnostics: Diagnostics,
 }
 
 impl Parser {
-    pub fn new(mut scanner: Scanner) -> Self {
-        let initial_token = scanner.scan_token();
-
+    /// the first token is not scanned until parsing begins, so that any error in it can be
+    /// reported by parse_program
+    pub fn new(scanner: Scanner) -> Self {
         Parser {
             scanner: scanner,
-            current_token: initial_token,
+            current_token: Token::new(TokenType::Eot, "", SourcePosition::default()),
+            previous_token_position: SourcePosition::default(),
+            diagnostics: Diagnostics::new(),
         }
     }
 
@@ -47,82 +71,148 @@ impl Parser {
         position.start.column = self.current_token.position.start.column;
     }
 
+    /// a phrase finishes with the last token accepted as part of it
     fn finish(&mut self, position: &mut SourcePosition) {
-        position.finish.line = self.current_token.position.finish.line;
-        position.finish.column = self.current_token.position.finish.column;
+        position.finish.line = self.previous_token_position.finish.line;
+        position.finish.column = self.previous_token_position.finish.column;
     }
 
-    fn accept(&mut self, tt: TokenType) {
+    fn accept(&mut self, tt: TokenType) -> GenResult<()> {
         if self.current_token.kind == tt {
-            self.current_token = self.scanner.scan_token();
+            self.accept_it();
+            Ok(())
         } else {
-            error::report_error_and_exit(GenError::from(ParserError::new(
+            Err(GenError::from(ParserError::new(
                 &format!(
                     "expected to accept token of kind {:?}, but got kind {:}",
                     tt, self.current_token.kind
                 ),
                 self.current_token.position,
-            )));
+            )))
         }
     }
 
+    /// move on to the next token. A lexical error is reported, and the offending character
+    /// skipped, without disturbing the parse.
     fn accept_it(&mut self) {
-        self.current_token = self.scanner.scan_token();
+        self.previous_token_position = self.current_token.position;
+        loop {
+            match self.scanner.scan_token() {
+                Ok(token) => {
+                    self.current_token = token;
+                    return;
+                }
+                Err(err) => self.diagnostics.report(err),
+            }
+        }
+    }
+
+    /// panic-mode error recovery - record the syntax error, skip ahead to the next token that may
+    /// follow the construct that failed to parse, and carry on with a placeholder in its place
+    fn recover<T, F>(&mut self, result: GenResult<T>, followers: &[TokenType], placeholder: F) -> T
+    where
+        F: FnOnce(SourcePosition) -> T,
+    {
+        match result {
+            Ok(val) => val,
+            Err(err) => {
+                self.diagnostics.report(err);
+                let position = self.current_token.position;
+                self.synchronise(followers);
+                placeholder(position)
+            }
+        }
     }
 
-    fn parse_identifier(&mut self) -> Identifier {
+    fn synchronise(&mut self, followers: &[TokenType]) {
+        while !followers.contains(&self.current_token.kind)
+            && self.current_token.kind != TokenType::Eot
+        {
+            self.accept_it();
+        }
+    }
+
+    fn parse_identifier(&mut self) -> GenResult<Identifier> {
+        if self.current_token.kind != TokenType::Identifier {
+            return Err(GenError::from(ParserError::new(
+                &format!(
+                    "expected an identifier, but got kind {}",
+                    self.current_token.kind
+                ),
+                self.current_token.position,
+            )));
+        }
+
         let id = Identifier::new_with_position(
             &self.current_token.spelling,
             self.current_token.position,
         );
         self.accept_it();
-        id
+        Ok(id)
     }
 
-    fn parse_integer_literal(&mut self) -> IntegerLiteral {
+    fn parse_integer_literal(&mut self) -> GenResult<IntegerLiteral> {
+        if self.current_token.kind != TokenType::IntegerLiteral {
+            return Err(GenError::from(ParserError::new(
+                &format!(
+                    "expected an integer literal, but got kind {}",
+                    self.current_token.kind
+                ),
+                self.current_token.position,
+            )));
+        }
+
         let il = IntegerLiteral::new_with_position(
             &self.current_token.spelling,
             self.current_token.position,
         );
         self.accept_it();
-        il
+        Ok(il)
     }
 
-    fn parse_character_literal(&mut self) -> CharacterLiteral {
+    fn parse_character_literal(&mut self) -> GenResult<CharacterLiteral> {
         let cl = CharacterLiteral::new_with_position(
             &self.current_token.spelling,
             self.current_token.position,
         );
         self.accept_it();
-        cl
+        Ok(cl)
     }
 
-    fn parse_operator(&mut self) -> Operator {
+    fn parse_operator(&mut self) -> GenResult<Operator> {
         let op =
             Operator::new_with_position(&self.current_token.spelling, self.current_token.position);
         self.accept_it();
-        op
+        Ok(op)
     }
 
     ///
     /// Command ::= single-Command | Command ; single-Command
     ///
-    fn parse_command(&mut self) -> Command {
+    fn parse_command(&mut self) -> GenResult<Command> {
         let mut cmd_pos = SourcePosition::default();
         self.start(&mut cmd_pos);
-        let mut cmd = self.parse_single_command();
+        let mut cmd = self.parse_single_command()?;
         self.finish(&mut cmd_pos);
 
         while self.current_token.kind == TokenType::Semicolon {
             self.accept_it();
-            let cmd1 = self.parse_single_command();
+            let cmd1 = self.parse_single_command()?;
             self.finish(&mut cmd_pos);
             cmd = Command::SequentialCommand(SequentialCommandState::new_with_position(
                 cmd, cmd1, cmd_pos,
             ));
         }
 
-        cmd
+        Ok(cmd)
+    }
+
+    /// parse a single command, recovering from any syntax error in it
+    fn parse_single_command(&mut self) -> GenResult<Command> {
+        let result = self.try_parse_single_command();
+        Ok(self.recover(result, COMMAND_FOLLOWERS, |position| {
+            EmptyCommand(EmptyCommandState::new_with_position(position))
+        }))
     }
 
     ///
@@ -134,99 +224,103 @@ impl Parser {
     ///                  | LetCommand
     ///                  | WhileCommand
     ///
-    fn parse_single_command(&mut self) -> Command {
+    fn try_parse_single_command(&mut self) -> GenResult<Command> {
         let mut cmd_pos = SourcePosition::default();
         self.start(&mut cmd_pos);
 
         match self.current_token.kind {
             TokenType::Identifier => {
-                let id = self.parse_identifier();
+                let id = self.parse_identifier()?;
 
                 if self.current_token.kind == TokenType::LeftParen {
                     self.accept_it();
-                    let aps = self.parse_actual_parameter_sequence();
-                    self.accept(TokenType::RightParen);
+                    let aps = self.parse_actual_parameter_sequence()?;
+                    self.accept(TokenType::RightParen)?;
                     self.finish(&mut cmd_pos);
-                    CallCommand(CallCommandState::new_with_position(id, aps, cmd_pos))
+                    Ok(CallCommand(CallCommandState::new_with_position(
+                        id, aps, cmd_pos,
+                    )))
                 } else {
-                    let vname = self.parse_vname(id);
+                    let vname = self.parse_vname(id)?;
 
                     if self.current_token.kind == TokenType::Becomes {
                         self.accept_it();
-                        let expr = self.parse_expression();
+                        let expr = self.parse_expression()?;
                         self.finish(&mut cmd_pos);
-                        AssignCommand(AssignCommandState::new_with_position(vname, expr, cmd_pos))
+                        Ok(AssignCommand(AssignCommandState::new_with_position(
+                            vname, expr, cmd_pos,
+                        )))
                     } else {
-                        error::report_error_and_exit(GenError::from(ParserError::new(
+                        Err(GenError::from(ParserError::new(
                             &format!(
                                 "expected to find `:=`, found `{:?}` instead",
                                 self.current_token.spelling
                             ),
                             self.current_token.position,
-                        )));
+                        )))
                     }
                 }
             }
 
             TokenType::Let => {
                 self.accept_it();
-                let decl = self.parse_declaration();
-                self.accept(TokenType::In);
-                let cmd1 = self.parse_single_command();
+                let decl = self.parse_declaration()?;
+                self.accept(TokenType::In)?;
+                let cmd1 = self.parse_single_command()?;
                 self.finish(&mut cmd_pos);
-                LetCommand(LetCommandState::new_with_position(decl, cmd1, cmd_pos))
+                Ok(LetCommand(LetCommandState::new_with_position(
+                    decl, cmd1, cmd_pos,
+                )))
             }
 
             TokenType::If => {
                 self.accept_it();
-                let expr = self.parse_expression();
-                self.accept(TokenType::Then);
-                let cmd1 = self.parse_single_command();
-                self.accept(TokenType::Else);
-                let cmd2 = self.parse_single_command();
+                let expr = self.parse_condition();
+                self.accept(TokenType::Then)?;
+                let cmd1 = self.parse_single_command()?;
+                self.accept(TokenType::Else)?;
+                let cmd2 = self.parse_single_command()?;
                 self.finish(&mut cmd_pos);
-                IfCommand(IfCommandState::new_with_position(expr, cmd1, cmd2, cmd_pos))
+                Ok(IfCommand(IfCommandState::new_with_position(
+                    expr, cmd1, cmd2, cmd_pos,
+                )))
             }
 
             TokenType::While => {
                 self.accept_it();
-                let expr = self.parse_expression();
-                self.accept(TokenType::Do);
-                let cmd1 = self.parse_single_command();
+                let expr = self.parse_condition();
+                self.accept(TokenType::Do)?;
+                let cmd1 = self.parse_single_command()?;
                 self.finish(&mut cmd_pos);
-                WhileCommand(WhileCommandState::new_with_position(expr, cmd1, cmd_pos))
+                Ok(WhileCommand(WhileCommandState::new_with_position(
+                    expr, cmd1, cmd_pos,
+                )))
             }
 
             TokenType::Begin => {
                 self.accept_it();
-                let cmd = self.parse_command();
-                self.accept(TokenType::End);
-                cmd
+                let cmd = self.parse_command()?;
+                self.accept(TokenType::End)?;
+                Ok(cmd)
             }
 
             TokenType::End => {
                 self.finish(&mut cmd_pos);
-                EmptyCommand(EmptyCommandState::new_with_position(cmd_pos))
+                Ok(EmptyCommand(EmptyCommandState::new_with_position(cmd_pos)))
             }
 
             TokenType::Semicolon | TokenType::Eot => {
                 self.accept_it();
                 self.finish(&mut cmd_pos);
-                EmptyCommand(EmptyCommandState::new_with_position(cmd_pos))
-            }
-            _ => {
-                println!(
-                    "curr token = {:?}, not implemented or error",
-                    self.current_token
-                );
-                error::report_error_and_exit(GenError::from(ParserError::new(
-                    &format!(
-                        "a command cannot begin with a {:?}",
-                        self.current_token.kind
-                    ),
-                    self.current_token.position,
-                )));
+                Ok(EmptyCommand(EmptyCommandState::new_with_position(cmd_pos)))
             }
+            _ => Err(GenError::from(ParserError::new(
+                &format!(
+                    "a command cannot begin with a {:?}",
+                    self.current_token.kind
+                ),
+                self.current_token.position,
+            ))),
         }
     }
 
@@ -234,21 +328,34 @@ impl Parser {
     /// Declaration ::= single-Declaration
     ///             | Declaration ; single-Declaration
     ///
-    fn parse_declaration(&mut self) -> Declaration {
+    fn parse_declaration(&mut self) -> GenResult<Declaration> {
         let mut decl_pos = SourcePosition::default();
         self.start(&mut decl_pos);
 
-        let mut decl = self.parse_single_declaration();
+        let mut decl = self.parse_single_declaration()?;
         while self.current_token.kind == TokenType::Semicolon {
             self.accept_it();
+            let decl1 = self.parse_single_declaration()?;
             self.finish(&mut decl_pos);
-            let decl1 = self.parse_single_declaration();
             decl = SequentialDeclaration(SequentialDeclarationState::new_with_position(
                 decl, decl1, decl_pos,
             ));
         }
 
-        decl
+        Ok(decl)
+    }
+
+    /// parse a single declaration, recovering from any syntax error in it. The placeholder for a
+    /// declaration that could not be parsed declares nothing that can be referred to.
+    fn parse_single_declaration(&mut self) -> GenResult<Declaration> {
+        let result = self.try_parse_single_declaration();
+        Ok(self.recover(result, DECLARATION_FOLLOWERS, |position| {
+            ConstDeclaration(ConstDeclarationState::new_with_position(
+                Identifier::new_with_position("", position),
+                EmptyExpression(EmptyExpressionState::new_with_position(position)),
+                position,
+            ))
+        }))
     }
 
     ///
@@ -258,69 +365,75 @@ impl Parser {
     ///                 | FuncDeclaration
     ///                 | TypeDeclaration
     ///
-    fn parse_single_declaration(&mut self) -> Declaration {
+    fn try_parse_single_declaration(&mut self) -> GenResult<Declaration> {
         let mut decl_pos = SourcePosition::default();
         self.start(&mut decl_pos);
 
         match self.current_token.kind {
             TokenType::Const => {
                 self.accept_it();
-                let id = self.parse_identifier();
-                self.accept(TokenType::Is);
-                let expr = self.parse_expression();
+                let id = self.parse_identifier()?;
+                self.accept(TokenType::Is)?;
+                let expr = self.parse_expression()?;
                 self.finish(&mut decl_pos);
-                ConstDeclaration(ConstDeclarationState::new_with_position(id, expr, decl_pos))
+                Ok(ConstDeclaration(ConstDeclarationState::new_with_position(
+                    id, expr, decl_pos,
+                )))
             }
 
             TokenType::Var => {
                 self.accept_it();
-                let id = self.parse_identifier();
-                self.accept(TokenType::Colon);
-                let td = self.parse_type_denoter();
+                let id = self.parse_identifier()?;
+                self.accept(TokenType::Colon)?;
+                let td = self.parse_type_denoter()?;
                 self.finish(&mut decl_pos);
-                VarDeclaration(VarDeclarationState::new_with_position(id, td, decl_pos))
+                Ok(VarDeclaration(VarDeclarationState::new_with_position(
+                    id, td, decl_pos,
+                )))
             }
 
             TokenType::Procedure => {
                 self.accept_it();
-                let id = self.parse_identifier();
-                self.accept(TokenType::LeftParen);
-                let fps = self.parse_formal_parameter_sequence();
-                self.accept(TokenType::RightParen);
-                self.accept(TokenType::Is);
-                let cmd = self.parse_single_command();
+                let id = self.parse_identifier()?;
+                self.accept(TokenType::LeftParen)?;
+                let fps = self.parse_formal_parameter_sequence()?;
+                self.accept(TokenType::RightParen)?;
+                self.accept(TokenType::Is)?;
+                let cmd = self.parse_single_command()?;
                 self.finish(&mut decl_pos);
-                ProcDeclaration(ProcDeclarationState::new_with_position(
+                Ok(ProcDeclaration(ProcDeclarationState::new_with_position(
                     id, fps, cmd, decl_pos,
-                ))
+                )))
             }
 
             TokenType::Function => {
                 self.accept_it();
-                let id = self.parse_identifier();
-                self.accept(TokenType::LeftParen);
-                let fps = self.parse_formal_parameter_sequence();
-                self.accept(TokenType::RightParen);
-                self.accept(TokenType::Colon);
-                let td = self.parse_type_denoter();
-                self.accept(TokenType::Is);
-                let expr = self.parse_expression();
+                let id = self.parse_identifier()?;
+                self.accept(TokenType::LeftParen)?;
+                let fps = self.parse_formal_parameter_sequence()?;
+                self.accept(TokenType::RightParen)?;
+                self.accept(TokenType::Colon)?;
+                let td = self.parse_type_denoter()?;
+                self.accept(TokenType::Is)?;
+                let expr = self.parse_expression()?;
                 self.finish(&mut decl_pos);
-                FuncDeclaration(FuncDeclarationState::new_with_position(
+                Ok(FuncDeclaration(FuncDeclarationState::new_with_position(
                     id, fps, td, expr, decl_pos,
-                ))
+                )))
             }
 
             TokenType::Type => {
                 self.accept_it();
-                let id = self.parse_identifier();
-                self.accept(TokenType::Is);
-                let td = self.parse_type_denoter();
+                let id = self.parse_identifier()?;
+                self.accept(TokenType::Is)?;
+                let td = self.parse_type_denoter()?;
                 self.finish(&mut decl_pos);
-                TypeDeclaration(TypeDeclarationState::new_with_position(id, td, decl_pos))
+                Ok(TypeDeclaration(TypeDeclarationState::new_with_position(
+                    id, td, decl_pos,
+                )))
             }
 
-            _ => error::report_error_and_exit(GenError::from(ParserError::new(
+            _ => Err(GenError::from(ParserError::new(
                 &format!("{:?} cannot start a declaration", self.current_token.kind),
                 self.current_token.position,
             ))),
@@ -332,35 +445,41 @@ impl Parser {
     ///               | ArrayTypeDenoter
     ///               | RecordTypeDenoter
     ///
-    fn parse_type_denoter(&mut self) -> TypeDenoter {
+    fn parse_type_denoter(&mut self) -> GenResult<TypeDenoter> {
         let mut td_pos = SourcePosition::default();
         self.start(&mut td_pos);
 
         match self.current_token.kind {
             TokenType::Identifier => {
-                let id = self.parse_identifier();
+                let id = self.parse_identifier()?;
                 self.finish(&mut td_pos);
-                SimpleTypeDenoter(SimpleTypeDenoterState::new_with_position(id, td_pos))
+                Ok(SimpleTypeDenoter(
+                    SimpleTypeDenoterState::new_with_position(id, td_pos),
+                ))
             }
 
             TokenType::Array => {
                 self.accept_it();
-                let il = self.parse_integer_literal();
-                self.accept(TokenType::Of);
-                let td1 = self.parse_type_denoter();
+                let il = self.parse_integer_literal()?;
+                self.accept(TokenType::Of)?;
+                let td1 = self.parse_type_denoter()?;
                 self.finish(&mut td_pos);
-                ArrayTypeDenoter(ArrayTypeDenoterState::new_with_position(il, td1, td_pos))
+                Ok(ArrayTypeDenoter(ArrayTypeDenoterState::new_with_position(
+                    il, td1, td_pos,
+                )))
             }
 
             TokenType::Record => {
-                self.accept(TokenType::Record);
-                let ftd = self.parse_field_type_denoter();
-                self.accept(TokenType::End);
+                self.accept(TokenType::Record)?;
+                let ftd = self.parse_field_type_denoter()?;
+                self.accept(TokenType::End)?;
                 self.finish(&mut td_pos);
-                RecordTypeDenoter(RecordTypeDenoterState::new_with_position(ftd, td_pos))
+                Ok(RecordTypeDenoter(
+                    RecordTypeDenoterState::new_with_position(ftd, td_pos),
+                ))
             }
 
-            _ => error::report_error_and_exit(GenError::from(ParserError::new(
+            _ => Err(GenError::from(ParserError::new(
                 &format!("{:?} cannot start a type denoter", self.current_token.kind),
                 self.current_token.position,
             ))),
@@ -374,25 +493,25 @@ impl Parser {
     /// SingleFieldTypeDenoter ::= Identifier : Type-Denoter
     /// MultipleFieldTypeDenoter ::= Identifier : Type-Denoter , FieldTypeDenoter
     ///
-    fn parse_field_type_denoter(&mut self) -> FieldTypeDenoter {
+    fn parse_field_type_denoter(&mut self) -> GenResult<FieldTypeDenoter> {
         let mut ftd_pos = SourcePosition::default();
         self.start(&mut ftd_pos);
 
-        let id = self.parse_identifier();
-        self.accept(TokenType::Colon);
-        let td = self.parse_type_denoter();
+        let id = self.parse_identifier()?;
+        self.accept(TokenType::Colon)?;
+        let td = self.parse_type_denoter()?;
 
         if self.current_token.kind == TokenType::Comma {
             self.accept_it();
-            let ftd = self.parse_field_type_denoter();
+            let ftd = self.parse_field_type_denoter()?;
             self.finish(&mut ftd_pos);
-            MultipleFieldTypeDenoter(MultipleFieldTypeDenoterState::new_with_position(
-                id, td, ftd, ftd_pos,
+            Ok(MultipleFieldTypeDenoter(
+                MultipleFieldTypeDenoterState::new_with_position(id, td, ftd, ftd_pos),
             ))
         } else {
             self.finish(&mut ftd_pos);
-            SingleFieldTypeDenoter(SingleFieldTypeDenoterState::new_with_position(
-                id, td, ftd_pos,
+            Ok(SingleFieldTypeDenoter(
+                SingleFieldTypeDenoterState::new_with_position(id, td, ftd_pos),
             ))
         }
     }
@@ -402,29 +521,29 @@ impl Parser {
     ///                         | SingleFormalParameterSequence
     ///                         | MultipleFormalParameterSequence
     ///
-    fn parse_formal_parameter_sequence(&mut self) -> FormalParameterSequence {
+    fn parse_formal_parameter_sequence(&mut self) -> GenResult<FormalParameterSequence> {
         let mut fps_pos = SourcePosition::default();
         self.start(&mut fps_pos);
 
         if self.current_token.kind == TokenType::RightParen {
             self.finish(&mut fps_pos);
-            EmptyFormalParameterSequence(EmptyFormalParameterSequenceState::new_with_position(
-                fps_pos,
+            Ok(EmptyFormalParameterSequence(
+                EmptyFormalParameterSequenceState::new_with_position(fps_pos),
             ))
         } else {
-            let fp = self.parse_formal_parameter();
+            let fp = self.parse_formal_parameter()?;
             if self.current_token.kind == TokenType::Comma {
                 self.accept_it();
-                let fps = self.parse_formal_parameter_sequence();
+                let fps = self.parse_formal_parameter_sequence()?;
                 self.finish(&mut fps_pos);
-                MultipleFormalParameterSequence(
+                Ok(MultipleFormalParameterSequence(
                     MultipleFormalParameterSequenceState::new_with_position(fp, fps, fps_pos),
-                )
+                ))
             } else {
                 self.finish(&mut fps_pos);
-                SingleFormalParameterSequence(
+                Ok(SingleFormalParameterSequence(
                     SingleFormalParameterSequenceState::new_with_position(fp, fps_pos),
-                )
+                ))
             }
         }
     }
@@ -435,53 +554,59 @@ impl Parser {
     ///                  | ProcFormalParameter
     ///                  | FuncFormalParameter
     ///
-    fn parse_formal_parameter(&mut self) -> FormalParameter {
+    fn parse_formal_parameter(&mut self) -> GenResult<FormalParameter> {
         let mut fp_pos = SourcePosition::default();
         self.start(&mut fp_pos);
 
         match self.current_token.kind {
             TokenType::Identifier => {
-                let id = self.parse_identifier();
-                self.accept(TokenType::Colon);
-                let td = self.parse_type_denoter();
+                let id = self.parse_identifier()?;
+                self.accept(TokenType::Colon)?;
+                let td = self.parse_type_denoter()?;
                 self.finish(&mut fp_pos);
-                ConstFormalParameter(ConstFormalParameterState::new_with_position(id, td, fp_pos))
+                Ok(ConstFormalParameter(
+                    ConstFormalParameterState::new_with_position(id, td, fp_pos),
+                ))
             }
 
             TokenType::Var => {
                 self.accept_it();
-                let id = self.parse_identifier();
-                self.accept(TokenType::Colon);
-                let td = self.parse_type_denoter();
+                let id = self.parse_identifier()?;
+                self.accept(TokenType::Colon)?;
+                let td = self.parse_type_denoter()?;
                 self.finish(&mut fp_pos);
-                VarFormalParameter(VarFormalParameterState::new_with_position(id, td, fp_pos))
+                Ok(VarFormalParameter(
+                    VarFormalParameterState::new_with_position(id, td, fp_pos),
+                ))
             }
 
             TokenType::Procedure => {
                 self.accept_it();
-                let id = self.parse_identifier();
-                self.accept(TokenType::LeftParen);
-                let fps = self.parse_formal_parameter_sequence();
-                self.accept(TokenType::RightParen);
+                let id = self.parse_identifier()?;
+                self.accept(TokenType::LeftParen)?;
+                let fps = self.parse_formal_parameter_sequence()?;
+                self.accept(TokenType::RightParen)?;
                 self.finish(&mut fp_pos);
-                ProcFormalParameter(ProcFormalParameterState::new_with_position(id, fps, fp_pos))
+                Ok(ProcFormalParameter(
+                    ProcFormalParameterState::new_with_position(id, fps, fp_pos),
+                ))
             }
 
             TokenType::Function => {
                 self.accept_it();
-                let id = self.parse_identifier();
-                self.accept(TokenType::LeftParen);
-                let fps = self.parse_formal_parameter_sequence();
-                self.accept(TokenType::RightParen);
-                self.accept(TokenType::Colon);
-                let td = self.parse_type_denoter();
+                let id = self.parse_identifier()?;
+                self.accept(TokenType::LeftParen)?;
+                let fps = self.parse_formal_parameter_sequence()?;
+                self.accept(TokenType::RightParen)?;
+                self.accept(TokenType::Colon)?;
+                let td = self.parse_type_denoter()?;
                 self.finish(&mut fp_pos);
-                FuncFormalParameter(FuncFormalParameterState::new_with_position(
-                    id, fps, td, fp_pos,
+                Ok(FuncFormalParameter(
+                    FuncFormalParameterState::new_with_position(id, fps, td, fp_pos),
                 ))
             }
 
-            _ => error::report_error_and_exit(GenError::from(ParserError::new(
+            _ => Err(GenError::from(ParserError::new(
                 &format!(
                     "{:?} cannot start a formal parameter",
                     self.current_token.kind
@@ -496,29 +621,29 @@ impl Parser {
     ///                          | SingleActualParameterSequenceState
     ///                          | MultipleActualParameterSequence
     ///
-    fn parse_actual_parameter_sequence(&mut self) -> ActualParameterSequence {
+    fn parse_actual_parameter_sequence(&mut self) -> GenResult<ActualParameterSequence> {
         let mut aps_pos = SourcePosition::default();
 
         self.start(&mut aps_pos);
         if self.current_token.kind == TokenType::RightParen {
             self.finish(&mut aps_pos);
-            EmptyActualParameterSequence(EmptyActualParameterSequenceState::new_with_position(
-                aps_pos,
+            Ok(EmptyActualParameterSequence(
+                EmptyActualParameterSequenceState::new_with_position(aps_pos),
             ))
         } else {
-            let ap = self.parse_actual_parameter();
+            let ap = self.parse_actual_parameter()?;
             if self.current_token.kind == TokenType::Comma {
                 self.accept_it();
-                let aps = self.parse_actual_parameter_sequence();
+                let aps = self.parse_actual_parameter_sequence()?;
                 self.finish(&mut aps_pos);
-                MultipleActualParameterSequence(
+                Ok(MultipleActualParameterSequence(
                     MultipleActualParameterSequenceState::new_with_position(ap, aps, aps_pos),
-                )
+                ))
             } else {
                 self.finish(&mut aps_pos);
-                SingleActualParameterSequence(
+                Ok(SingleActualParameterSequence(
                     SingleActualParameterSequenceState::new_with_position(ap, aps_pos),
-                )
+                ))
             }
         }
     }
@@ -529,37 +654,45 @@ impl Parser {
     ///                 | ProcActualParameter
     ///                 | FuncActualParameter
     ///
-    fn parse_actual_parameter(&mut self) -> ActualParameter {
+    fn parse_actual_parameter(&mut self) -> GenResult<ActualParameter> {
         let mut ap_pos = SourcePosition::default();
         self.start(&mut ap_pos);
 
         match self.current_token.kind {
             TokenType::Var => {
                 self.accept_it();
-                let id = self.parse_identifier();
-                let vname = self.parse_vname(id);
+                let id = self.parse_identifier()?;
+                let vname = self.parse_vname(id)?;
                 self.finish(&mut ap_pos);
-                VarActualParameter(VarActualParameterState::new_with_position(vname, ap_pos))
+                Ok(VarActualParameter(
+                    VarActualParameterState::new_with_position(vname, ap_pos),
+                ))
             }
 
             TokenType::Procedure => {
                 self.accept_it();
-                let id = self.parse_identifier();
+                let id = self.parse_identifier()?;
                 self.finish(&mut ap_pos);
-                ProcActualParameter(ProcActualParameterState::new_with_position(id, ap_pos))
+                Ok(ProcActualParameter(
+                    ProcActualParameterState::new_with_position(id, ap_pos),
+                ))
             }
 
             TokenType::Function => {
                 self.accept_it();
-                let id = self.parse_identifier();
+                let id = self.parse_identifier()?;
                 self.finish(&mut ap_pos);
-                FuncActualParameter(FuncActualParameterState::new_with_position(id, ap_pos))
+                Ok(FuncActualParameter(
+                    FuncActualParameterState::new_with_position(id, ap_pos),
+                ))
             }
 
             _ => {
-                let expr = self.parse_expression();
+                let expr = self.parse_expression()?;
                 self.finish(&mut ap_pos);
-                ConstActualParameter(ConstActualParameterState::new_with_position(expr, ap_pos))
+                Ok(ConstActualParameter(
+                    ConstActualParameterState::new_with_position(expr, ap_pos),
+                ))
             }
         }
     }
@@ -569,55 +702,67 @@ impl Parser {
     ///             | LetExpression
     ///             | IfExpression
     ///
-    fn parse_expression(&mut self) -> Expression {
+    fn parse_expression(&mut self) -> GenResult<Expression> {
         let mut expr_pos = SourcePosition::default();
 
         match self.current_token.kind {
             TokenType::If => {
                 self.start(&mut expr_pos);
                 self.accept_it();
-                let expr1 = self.parse_expression();
-                self.accept(TokenType::Then);
-                let expr2 = self.parse_expression();
-                self.accept(TokenType::Else);
-                let expr3 = self.parse_expression();
-                IfExpression(IfExpressionState::new_with_position(
+                let expr1 = self.parse_expression()?;
+                self.accept(TokenType::Then)?;
+                let expr2 = self.parse_expression()?;
+                self.accept(TokenType::Else)?;
+                let expr3 = self.parse_expression()?;
+                self.finish(&mut expr_pos);
+                Ok(IfExpression(IfExpressionState::new_with_position(
                     expr1, expr2, expr3, expr_pos,
-                ))
+                )))
             }
 
             TokenType::Let => {
+                self.start(&mut expr_pos);
                 self.accept_it();
-                let decl = self.parse_declaration();
-                self.accept(TokenType::In);
-                let expr = self.parse_expression();
+                let decl = self.parse_declaration()?;
+                self.accept(TokenType::In)?;
+                let expr = self.parse_expression()?;
                 self.finish(&mut expr_pos);
-                LetExpression(LetExpressionState::new_with_position(decl, expr, expr_pos))
+                Ok(LetExpression(LetExpressionState::new_with_position(
+                    decl, expr, expr_pos,
+                )))
             }
 
             _ => self.parse_secondary_expression(),
         }
     }
 
+    /// parse the condition of an if or while command, recovering from any syntax error in it
+    fn parse_condition(&mut self) -> Expression {
+        let result = self.parse_expression();
+        self.recover(result, CONDITION_FOLLOWERS, |position| {
+            EmptyExpression(EmptyExpressionState::new_with_position(position))
+        })
+    }
+
     ///
     /// secondary-Expression ::= primary-Expression
     ///                     | primary-Expression Operator secondary-Expression
     ///
-    fn parse_secondary_expression(&mut self) -> Expression {
+    fn parse_secondary_expression(&mut self) -> GenResult<Expression> {
         let mut expr_pos = SourcePosition::default();
         self.start(&mut expr_pos);
 
-        let mut expr = self.parse_primary_expression();
+        let mut expr = self.parse_primary_expression()?;
         while self.current_token.kind == TokenType::Operator {
-            let op = self.parse_operator();
+            let op = self.parse_operator()?;
+            let expr1 = self.parse_primary_expression()?;
             self.finish(&mut expr_pos);
-            let expr1 = self.parse_primary_expression();
             expr = BinaryExpression(BinaryExpressionState::new_with_position(
                 expr, op, expr1, expr_pos,
             ));
         }
 
-        expr
+        Ok(expr)
     }
 
     ///
@@ -631,69 +776,83 @@ impl Parser {
     ///                     | RecordExpression
     ///
     ///
-    fn parse_primary_expression(&mut self) -> Expression {
+    fn parse_primary_expression(&mut self) -> GenResult<Expression> {
         let mut expr_pos = SourcePosition::default();
         self.start(&mut expr_pos);
 
         match self.current_token.kind {
             TokenType::IntegerLiteral => {
-                let il = self.parse_integer_literal();
+                let il = self.parse_integer_literal()?;
                 self.finish(&mut expr_pos);
-                IntegerExpression(IntegerExpressionState::new_with_position(il, expr_pos))
+                Ok(IntegerExpression(
+                    IntegerExpressionState::new_with_position(il, expr_pos),
+                ))
             }
 
             TokenType::CharacterLiteral => {
-                let cl = self.parse_character_literal();
+                let cl = self.parse_character_literal()?;
                 self.finish(&mut expr_pos);
-                CharacterExpression(CharacterExpressionState::new_with_position(cl, expr_pos))
+                Ok(CharacterExpression(
+                    CharacterExpressionState::new_with_position(cl, expr_pos),
+                ))
             }
 
             TokenType::Identifier => {
-                let id = self.parse_identifier();
+                let id = self.parse_identifier()?;
                 if self.current_token.kind == TokenType::LeftParen {
                     self.accept_it();
-                    let aps = self.parse_actual_parameter_sequence();
-                    self.accept(TokenType::RightParen);
+                    let aps = self.parse_actual_parameter_sequence()?;
+                    self.accept(TokenType::RightParen)?;
                     self.finish(&mut expr_pos);
-                    CallExpression(CallExpressionState::new_with_position(id, aps, expr_pos))
+                    Ok(CallExpression(CallExpressionState::new_with_position(
+                        id, aps, expr_pos,
+                    )))
                 } else {
-                    let vname = self.parse_vname(id);
+                    let vname = self.parse_vname(id)?;
                     self.finish(&mut expr_pos);
-                    VnameExpression(VnameExpressionState::new_with_position(vname, expr_pos))
+                    Ok(VnameExpression(VnameExpressionState::new_with_position(
+                        vname, expr_pos,
+                    )))
                 }
             }
 
             TokenType::Operator => {
-                let op = self.parse_operator();
-                let expr1 = self.parse_secondary_expression();
+                let op = self.parse_operator()?;
+                let expr1 = self.parse_secondary_expression()?;
                 self.finish(&mut expr_pos);
-                UnaryExpression(UnaryExpressionState::new_with_position(op, expr1, expr_pos))
+                Ok(UnaryExpression(UnaryExpressionState::new_with_position(
+                    op, expr1, expr_pos,
+                )))
             }
 
             TokenType::LeftParen => {
                 self.accept_it();
-                let expr = self.parse_expression();
-                self.accept(TokenType::RightParen);
-                expr
+                let expr = self.parse_expression()?;
+                self.accept(TokenType::RightParen)?;
+                Ok(expr)
             }
 
             TokenType::LeftSquareBracket => {
                 self.accept_it();
-                let aa = self.parse_array_aggregate();
-                self.accept(TokenType::RightSquareBracket);
+                let aa = self.parse_array_aggregate()?;
+                self.accept(TokenType::RightSquareBracket)?;
                 self.finish(&mut expr_pos);
-                ArrayExpression(ArrayExpressionState::new_with_position(aa, expr_pos))
+                Ok(ArrayExpression(ArrayExpressionState::new_with_position(
+                    aa, expr_pos,
+                )))
             }
 
             TokenType::LeftCurlyBracket => {
                 self.accept_it();
-                let ra = self.parse_record_aggregate();
-                self.accept(TokenType::RightCurlyBracket);
+                let ra = self.parse_record_aggregate()?;
+                self.accept(TokenType::RightCurlyBracket)?;
                 self.finish(&mut expr_pos);
-                RecordExpression(RecordExpressionState::new_with_position(ra, expr_pos))
+                Ok(RecordExpression(RecordExpressionState::new_with_position(
+                    ra, expr_pos,
+                )))
             }
 
-            _ => error::report_error_and_exit(GenError::from(ParserError::new(
+            _ => Err(GenError::from(ParserError::new(
                 &format!(
                     "{:?} cannot start a primary expression",
                     self.current_token.kind
@@ -710,22 +869,24 @@ impl Parser {
     /// SingleArrayAggregate ::= Expression
     /// MultipleArrayAggregate ::= Expression , ArrayAggregate
     ///
-    fn parse_array_aggregate(&mut self) -> ArrayAggregate {
+    fn parse_array_aggregate(&mut self) -> GenResult<ArrayAggregate> {
         let mut aa_pos = SourcePosition::default();
         self.start(&mut aa_pos);
 
-        let expr = self.parse_expression();
+        let expr = self.parse_expression()?;
 
         if self.current_token.kind == TokenType::Comma {
             self.accept_it();
-            let aa = self.parse_array_aggregate();
+            let aa = self.parse_array_aggregate()?;
             self.finish(&mut aa_pos);
-            MultipleArrayAggregate(MultipleArrayAggregateState::new_with_position(
-                expr, aa, aa_pos,
+            Ok(MultipleArrayAggregate(
+                MultipleArrayAggregateState::new_with_position(expr, aa, aa_pos),
             ))
         } else {
             self.finish(&mut aa_pos);
-            SingleArrayAggregate(SingleArrayAggregateState::new_with_position(expr, aa_pos))
+            Ok(SingleArrayAggregate(
+                SingleArrayAggregateState::new_with_position(expr, aa_pos),
+            ))
         }
     }
 
@@ -736,25 +897,25 @@ impl Parser {
     /// SingleRecordAggregate ::= Identifier ~ Expression
     /// MultipleRecordAggregate ::= Identifier ~ Expression , RecordAggregate
     ///
-    fn parse_record_aggregate(&mut self) -> RecordAggregate {
+    fn parse_record_aggregate(&mut self) -> GenResult<RecordAggregate> {
         let mut ra_pos = SourcePosition::default();
         self.start(&mut ra_pos);
 
-        let id = self.parse_identifier();
-        self.accept(TokenType::Is);
-        let expr = self.parse_expression();
+        let id = self.parse_identifier()?;
+        self.accept(TokenType::Is)?;
+        let expr = self.parse_expression()?;
 
         if self.current_token.kind == TokenType::Comma {
             self.accept_it();
-            let ra = self.parse_record_aggregate();
+            let ra = self.parse_record_aggregate()?;
             self.finish(&mut ra_pos);
-            MultipleRecordAggregate(MultipleRecordAggregateState::new_with_position(
-                id, expr, ra, ra_pos,
+            Ok(MultipleRecordAggregate(
+                MultipleRecordAggregateState::new_with_position(id, expr, ra, ra_pos),
             ))
         } else {
             self.finish(&mut ra_pos);
-            SingleRecordAggregate(SingleRecordAggregateState::new_with_position(
-                id, expr, ra_pos,
+            Ok(SingleRecordAggregate(
+                SingleRecordAggregateState::new_with_position(id, expr, ra_pos),
             ))
         }
     }
@@ -768,11 +929,9 @@ impl Parser {
     /// DotVname ::= Vname . Identifier
     /// SubscriptVname ::= Vname [ Expression ]
     ///
-    fn parse_vname(&mut self, id: Identifier) -> Vname {
-        let mut vname_pos = SourcePosition::default();
-        self.start(&mut vname_pos);
-
-        self.finish(&mut vname_pos);
+    fn parse_vname(&mut self, id: Identifier) -> GenResult<Vname> {
+        // the identifier has already been accepted, so the vname starts where it does
+        let mut vname_pos = id.common_state.position;
         let mut vname = SimpleVname(SimpleVnameState::new_with_position(id, vname_pos));
 
         while self.current_token.kind == TokenType::LeftSquareBracket
@@ -781,8 +940,8 @@ impl Parser {
             match self.current_token.kind {
                 TokenType::LeftSquareBracket => {
                     self.accept_it();
-                    let expr = self.parse_expression();
-                    self.accept(TokenType::RightSquareBracket);
+                    let expr = self.parse_expression()?;
+                    self.accept(TokenType::RightSquareBracket)?;
                     self.finish(&mut vname_pos);
                     vname = SubscriptVname(SubscriptVnameState::new_with_position(
                         vname, expr, vname_pos,
@@ -791,33 +950,64 @@ impl Parser {
 
                 TokenType::Dot => {
                     self.accept_it();
-                    let id1 = self.parse_identifier();
+                    let id1 = self.parse_identifier()?;
+                    self.finish(&mut vname_pos);
                     vname = DotVname(DotVnameState::new_with_position(vname, id1, vname_pos));
                 }
 
-                _ => error::report_error_and_exit(GenError::from(ParserError::new(
-                    &format!(
-                        "{:?} cannot start or continue a vname",
-                        self.current_token.kind
-                    ),
-                    self.current_token.position,
-                ))),
-            };
+                _ => {
+                    return Err(GenError::from(ParserError::new(
+                        &format!(
+                            "{:?} cannot start or continue a vname",
+                            self.current_token.kind
+                        ),
+                        self.current_token.position,
+                    )))
+                }
+            }
         }
 
-        vname
+        Ok(vname)
     }
 
     ///
     /// Program ::= Command <Eot>
     ///
-    pub fn parse_program(&mut self) -> Program {
+    /// Every syntax error in the program is reported - several errors are returned together.
+    ///
+    pub fn parse_program(&mut self) -> GenResult<Program> {
+        let mut diagnostics = Diagnostics::new();
+        let program = self.parse_program_with(&mut diagnostics);
+        diagnostics.into_result().map(|_| program)
+    }
+
+    /// parse the program, reporting every syntax error into the diagnostics. If there are any,
+    /// the program returned is only the Parser's best effort, with placeholders for the parts
+    /// that could not be parsed, and is not fit to be checked or encoded.
+    pub fn parse_program_with(&mut self, diagnostics: &mut Diagnostics) -> Program {
         let mut pos = SourcePosition::default();
+
+        self.accept_it();
+
         self.start(&mut pos);
-        let cmd = self.parse_command();
+        let result = self.parse_command();
+        let cmd = self.recover(result, &[], |position| {
+            EmptyCommand(EmptyCommandState::new_with_position(position))
+        });
         self.finish(&mut pos);
-        self.accept(TokenType::Eot);
 
+        if self.current_token.kind != TokenType::Eot {
+            self.diagnostics.report(GenError::from(ParserError::new(
+                &format!(
+                    "expected the end of the program, but got kind {}",
+                    self.current_token.kind
+                ),
+                self.current_token.position,
+            )));
+            self.synchronise(&[]);
+        }
+
+        diagnostics.append(&mut self.diagnostics);
         Program::new_with_position(cmd, pos)
     }
 }
@@ -3,13 +3,14 @@
 //! Scan the source file into a stream of tokens that are consumed by the parser, one token at a
 //! time.
 
-use crate::error::{self, GenError, GenResult, ScannerError};
+use crate::error::{GenError, GenResult, ScannerError};
 use phf::phf_map;
 use std::default;
 use std::fmt;
 use std::fs::File;
 use std::io::{BufReader, Read};
 use std::iter::Iterator;
+use std::mem;
 
 pub const NULL: char = '\x00';
 pub const NULL_STR: &'static str = "\x00";
@@ -41,21 +42,52 @@ pub struct Scanner {
     current_char: Char,
     current_spelling: String,
     current_position: SourcePosition,
+    /// whether the whitespace and comments before each token are kept with it
+    keep_trivia: bool,
+    /// the trivia skipped since the last token
+    trivia: Vec<Trivia>,
 }
 
 impl Scanner {
-    pub fn new(source_file: &str) -> Self {
+    /// scan the source file at the given path
+    pub fn new(source_file: &str) -> GenResult<Self> {
+        Ok(Scanner::from_source_file(SourceFile::new(source_file)?))
+    }
+
+    /// scan source text held in memory
+    pub fn from_text(text: &str) -> Self {
+        Scanner::from_source_file(SourceFile::from_text(text))
+    }
+
+    /// scan everything that can be read from the reader, e.g. stdin
+    pub fn from_reader<R: Read>(reader: R) -> GenResult<Self> {
+        Ok(Scanner::from_source_file(SourceFile::from_reader(reader)?))
+    }
+
+    fn from_source_file(source_file: SourceFile) -> Self {
         let mut scanner = Scanner {
-            source_file: SourceFile::new(source_file),
+            source_file,
             current_char: Char::default(),
             current_position: SourcePosition::default(),
             current_spelling: String::new(),
+            keep_trivia: false,
+            trivia: Vec::new(),
         };
 
-        scanner.skip_it();
+        // the source always ends with a NULL character, so there is at least one to skip to
+        if let Some(first_char) = scanner.source_file.next() {
+            scanner.current_char = first_char;
+        }
         scanner
     }
 
+    /// keep the whitespace and comments before each token as its leading trivia, so that the
+    /// tokens hold every character of the source
+    pub fn with_trivia(mut self) -> Self {
+        self.keep_trivia = true;
+        self
+    }
+
     fn start(&mut self) {
         self.current_position.start.line = self.current_char.line;
         self.current_position.start.column = self.current_char.column;
@@ -66,164 +98,202 @@ impl Scanner {
         self.current_position.finish.column = self.current_char.column;
     }
 
-    fn skip_whitespace(&mut self) {
-        match self.current_char.c {
+    fn skip_whitespace(&mut self) -> GenResult<()> {
+        let start = Position::new(self.current_char.line, self.current_char.column);
+        let mut finish = start;
+        let mut text = String::new();
+
+        let kind = match self.current_char.c {
+            // the newline that ends a comment is whitespace
             '!' => {
                 while self.current_char.c != '\n' && self.current_char.c != NULL {
-                    self.skip_it();
-                }
-
-                if self.current_char.c == '\n' {
-                    self.skip('\n');
+                    finish = Position::new(self.current_char.line, self.current_char.column);
+                    text.push(self.current_char.c);
+                    self.skip_it()?;
                 }
+                TriviaKind::Comment
             }
 
             c if c.is_whitespace() => {
                 while self.current_char.c.is_whitespace() {
-                    self.skip_it();
+                    finish = Position::new(self.current_char.line, self.current_char.column);
+                    text.push(self.current_char.c);
+                    self.skip_it()?;
                 }
+                TriviaKind::Whitespace
             }
 
-            _ => {}
+            _ => return Ok(()),
+        };
+
+        if self.keep_trivia {
+            self.trivia.push(Trivia {
+                kind,
+                text,
+                position: SourcePosition::new(start, finish),
+            });
         }
+        Ok(())
     }
 
-    fn skip_it(&mut self) {
+    fn skip_trivia(&mut self) -> GenResult<()> {
+        while self.current_char.c.is_whitespace() || self.current_char.c == '!' {
+            self.skip_whitespace()?;
+        }
+        Ok(())
+    }
+
+    fn skip_it(&mut self) -> GenResult<()> {
         if let Some(next_char) = self.source_file.next() {
             self.current_char = next_char;
+            Ok(())
         } else {
-            error::report_error_and_exit(GenError::from(ScannerError::new(
+            Err(GenError::from(ScannerError::new(
                 "tried to skip character, found no more characters",
                 self.current_position,
-            )));
+            )))
         }
     }
 
-    fn skip(&mut self, expected_char: char) {
+    fn skip(&mut self, expected_char: char) -> GenResult<()> {
         if self.current_char.c == expected_char {
-            self.skip_it();
+            self.skip_it()
         } else {
-            error::report_error_and_exit(GenError::from(ScannerError::new(
+            Err(GenError::from(ScannerError::new(
                 &format!(
                     "expected to skip {}, but found {}",
                     expected_char, self.current_char.c
                 ),
                 self.current_position,
-            )));
+            )))
         }
     }
 
-    fn eat_it(&mut self) {
+    fn eat_it(&mut self) -> GenResult<()> {
         if self.current_char.c == NULL {
             self.current_spelling.push(self.current_char.c);
-            return;
+            return Ok(());
         }
 
         if let Some(next_char) = self.source_file.next() {
             self.current_spelling.push(self.current_char.c);
             self.current_char = next_char;
+            Ok(())
         } else {
-            error::report_error_and_exit(GenError::from(ScannerError::new(
+            Err(GenError::from(ScannerError::new(
                 "expected to eat character, but found no more characters",
                 self.current_position,
-            )));
+            )))
         }
     }
 
-    pub fn scan_token(&mut self) -> Token {
-        while self.current_char.c.is_whitespace() || self.current_char.c == '!' {
-            self.skip_whitespace();
-        }
+    pub fn scan_token(&mut self) -> GenResult<Token> {
+        self.skip_trivia()?;
 
         self.current_spelling = String::new();
-        let current_kind = self.scan();
-        Token::new(current_kind, &self.current_spelling, self.current_position)
+        let current_kind = self.scan()?;
+        let mut token = Token::new(current_kind, &self.current_spelling, self.current_position);
+        token.leading = mem::take(&mut self.trivia);
+        Ok(token)
+    }
+
+    /// scan every token of the source, up to and including the end of the text
+    pub fn scan_all(&mut self) -> GenResult<Vec<Token>> {
+        let mut tokens = Vec::new();
+        loop {
+            let token = self.scan_token()?;
+            let eot = token.kind == TokenType::Eot;
+            tokens.push(token);
+            if eot {
+                return Ok(tokens);
+            }
+        }
     }
 
-    fn scan(&mut self) -> TokenType {
+    fn scan(&mut self) -> GenResult<TokenType> {
         let kind;
         self.current_position = SourcePosition::default();
         self.start();
 
         match self.current_char.c {
             '!' => {
-                self.skip_whitespace();
+                self.skip_trivia()?;
                 return self.scan();
             }
 
             '~' => {
                 self.finish();
-                self.eat_it();
+                self.eat_it()?;
                 kind = TokenType::Is;
             }
 
             '(' => {
                 self.finish();
-                self.eat_it();
+                self.eat_it()?;
                 kind = TokenType::LeftParen;
             }
 
             '{' => {
                 self.finish();
-                self.eat_it();
+                self.eat_it()?;
                 kind = TokenType::LeftCurlyBracket;
             }
 
             '[' => {
                 self.finish();
-                self.eat_it();
+                self.eat_it()?;
                 kind = TokenType::LeftSquareBracket;
             }
 
             ')' => {
                 self.finish();
-                self.eat_it();
+                self.eat_it()?;
                 kind = TokenType::RightParen;
             }
 
             '}' => {
                 self.finish();
-                self.eat_it();
+                self.eat_it()?;
                 kind = TokenType::RightCurlyBracket;
             }
 
             ']' => {
                 self.finish();
-                self.eat_it();
+                self.eat_it()?;
                 kind = TokenType::RightSquareBracket;
             }
 
             '+' | '-' | '*' | '=' | '/' | '\\' | '<' | '>' => {
                 let curr_matched_char = self.current_char.c;
                 self.finish();
-                self.eat_it();
+                self.eat_it()?;
 
                 match curr_matched_char {
                     '/' => {
                         if self.current_char.c == '\\' || self.current_char.c == '/' {
                             self.finish();
-                            self.eat_it();
+                            self.eat_it()?;
                         }
                     }
 
                     '\\' => {
                         if self.current_char.c == '=' || self.current_char.c == '/' {
                             self.finish();
-                            self.eat_it();
+                            self.eat_it()?;
                         }
                     }
 
                     '<' => {
                         if self.current_char.c == '=' {
                             self.finish();
-                            self.eat_it();
+                            self.eat_it()?;
                         }
                     }
 
                     '>' => {
                         if self.current_char.c == '=' {
                             self.finish();
-                            self.eat_it();
+                            self.eat_it()?;
                         }
                     }
 
@@ -235,29 +305,29 @@ impl Scanner {
 
             ',' => {
                 self.finish();
-                self.eat_it();
+                self.eat_it()?;
                 kind = TokenType::Comma;
             }
 
             '.' => {
                 self.finish();
-                self.eat_it();
+                self.eat_it()?;
                 kind = TokenType::Dot;
             }
 
             ';' => {
                 self.finish();
-                self.eat_it();
+                self.eat_it()?;
                 kind = TokenType::Semicolon;
             }
 
             ':' => {
                 self.finish();
-                self.eat_it();
+                self.eat_it()?;
 
                 if self.current_char.c == '=' {
                     self.finish();
-                    self.eat_it();
+                    self.eat_it()?;
                     kind = TokenType::Becomes;
                 } else {
                     kind = TokenType::Colon;
@@ -266,56 +336,61 @@ impl Scanner {
 
             'a'..='z' | 'A'..='Z' | '_' => {
                 self.finish();
-                self.eat_it();
+                self.eat_it()?;
 
                 while self.current_char.c.is_alphanumeric() {
                     self.finish();
-                    self.eat_it();
+                    self.eat_it()?;
                 }
                 kind = TokenType::Identifier;
             }
 
             '0'..='9' => {
                 self.finish();
-                self.eat_it();
+                self.eat_it()?;
 
                 while self.current_char.c.is_numeric() {
                     self.finish();
-                    self.eat_it();
+                    self.eat_it()?;
                 }
                 kind = TokenType::IntegerLiteral;
             }
 
             '\'' => {
-                self.skip_it();
+                self.skip_it()?;
+                self.eat_it()?;
+                // the literal spans up to its closing quote
                 self.finish();
-                self.eat_it();
-                self.skip('\'');
+                self.skip('\'')?;
                 kind = TokenType::CharacterLiteral;
             }
 
             '\x00' => {
                 self.finish();
-                self.eat_it();
+                self.eat_it()?;
                 kind = TokenType::Eot;
             }
 
             _ => {
-                error::report_error_and_exit(GenError::from(ScannerError::new(
-                    &format!("unexpected character {}", self.current_char.c),
+                // skip the character, so that scanning can carry on after the error
+                let unexpected_char = self.current_char.c;
+                self.finish();
+                self.skip_it()?;
+                return Err(GenError::from(ScannerError::new(
+                    &format!("unexpected character {}", unexpected_char),
                     self.current_position,
                 )));
             }
         }
 
-        kind
+        Ok(kind)
     }
 }
 
 /// Represents the start and end of a token in the source code.
 /// Both the beginning and the end of the token are recorded for
 /// error reporting.
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct SourcePosition {
     pub start: Position,
     pub finish: Position,
@@ -325,6 +400,11 @@ impl SourcePosition {
     pub fn new(start: Position, finish: Position) -> Self {
         SourcePosition { start, finish }
     }
+
+    /// whether the position lies between the start and the finish of this one, inclusive
+    pub fn contains(&self, position: Position) -> bool {
+        self.start <= position && position <= self.finish
+    }
 }
 
 impl default::Default for SourcePosition {
@@ -336,7 +416,8 @@ impl default::Default for SourcePosition {
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+/// positions are ordered as they occur in the source - by line, and then by column
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Position {
     pub line: isize,
     pub column: isize,
@@ -397,38 +478,43 @@ pub struct SourceFile {
 }
 
 impl SourceFile {
-    pub fn new(source_file: &str) -> Self {
-        let characters = match SourceFile::load_source_file(source_file) {
-            Err(e) => error::report_error_and_exit(GenError::from(e)),
-            Ok(val) => val,
-        };
-
-        SourceFile {
-            curr_idx: 0,
-            characters: characters,
-        }
+    pub fn new(source_file: &str) -> GenResult<Self> {
+        SourceFile::from_reader(BufReader::new(File::open(source_file)?))
     }
 
-    fn load_source_file(source_file: &str) -> GenResult<Vec<Char>> {
-        let mut reader = BufReader::new(File::open(source_file)?);
-        let mut text = String::new();
-        reader.read_to_string(&mut text)?;
-
+    pub fn from_text(text: &str) -> Self {
         let mut line = 1;
         let mut column = 1;
-        let mut contents = Vec::new();
+        let mut characters = Vec::new();
 
+        // a newline is the last character of the line that it ends
         for c in text.chars() {
+            characters.push(Char::new(c, line, column));
             if c == '\n' {
                 line += 1;
                 column = 1;
+            } else {
+                column += 1;
             }
-            contents.push(Char::new(c, line, column));
-            column += 1;
         }
-        contents.push(Char::new(NULL, -1, -1));
+        // the end of the text is just after its last character, so that errors about it can be
+        // shown on the last line
+        let eot = match characters.last() {
+            Some(last) => Char::new(NULL, last.line, last.column + 1),
+            None => Char::new(NULL, 1, 1),
+        };
+        characters.push(eot);
 
-        Ok(contents)
+        SourceFile {
+            curr_idx: 0,
+            characters,
+        }
+    }
+
+    pub fn from_reader<R: Read>(mut reader: R) -> GenResult<Self> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+        Ok(SourceFile::from_text(&text))
     }
 }
 
@@ -451,6 +537,9 @@ pub struct Token {
     pub kind: TokenType,
     pub spelling: String,
     pub position: SourcePosition,
+    /// the whitespace and comments between the previous token and this one, if the Scanner
+    /// was asked to keep them
+    pub leading: Vec<Trivia>,
 }
 
 impl Token {
@@ -459,6 +548,7 @@ impl Token {
             kind: kind,
             spelling: String::from(spelling),
             position: position,
+            leading: Vec::new(),
         };
 
         if Token::is_keyword(&token.spelling) {
@@ -475,6 +565,16 @@ impl Token {
     pub fn get_token_kind_for_keyword(spelling: &str) -> TokenType {
         *KEYWORDS.get(spelling).unwrap()
     }
+
+    /// the token as it is written in the source - a character literal with its quotes, and the
+    /// end of the text as nothing at all
+    pub fn text(&self) -> String {
+        match self.kind {
+            TokenType::CharacterLiteral => format!("'{}'", self.spelling),
+            TokenType::Eot => String::new(),
+            _ => self.spelling.clone(),
+        }
+    }
 }
 
 impl PartialEq for Token {
@@ -485,6 +585,21 @@ impl PartialEq for Token {
 
 impl Eq for Token {}
 
+/// Whitespace or a comment, which the Parser never sees
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Trivia {
+    pub kind: TriviaKind,
+    pub text: String,
+    pub position: SourcePosition,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TriviaKind {
+    Whitespace,
+    /// a `!` comment, up to but not including the newline that ends it
+    Comment,
+}
+
 /// All the possible kinds of tokens
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum TokenType {
@@ -0,0 +1,435 @@
+//! The TAM assembler
+//!
+//! Assembles a hand-written TAM program, in the same mnemonic syntax that the disassembler
+//! prints, into a code segment. Each line holds at most one instruction, optionally preceded by
+//! labels, and anything after a `;` is a comment:
+//!
+//! ```text
+//!   ; print the numbers from 3 down to 1
+//!           LOADL 3
+//!   loop:   LOAD(1) 0[SB]
+//!           CALL putint
+//!           CALL puteol
+//!           LOAD(1) 0[SB]
+//!           CALL pred
+//!           STORE(1) 0[SB]
+//!           LOAD(1) 0[SB]
+//!           JUMPIF(0) done
+//!           JUMP loop
+//!   done:   HALT
+//! ```
+//!
+//! A label may stand wherever a code address is expected, in which case it denotes that address
+//! relative to CB. A numeric label such as `12:`, as printed in a listing, is checked against
+//! the address of the instruction that follows it. Mnemonics, register names and primitive
+//! names are not case-sensitive.
+
+use super::{Instruction, Opcode, Primitive, Register, PB};
+use crate::error::{AssemblerError, GenError, GenResult};
+use crate::scanner::{Position, SourcePosition};
+
+use std::collections::HashMap;
+
+/// assemble the program text into a code segment
+pub fn assemble(source: &str) -> GenResult<Vec<Instruction>> {
+    let mut assembler = Assembler::new();
+    for (line, text) in source.lines().enumerate() {
+        assembler.assemble_line(line as isize + 1, text)?;
+    }
+    assembler.finish()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    Name(String),
+    Number(i64),
+    Punct(char),
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    position: SourcePosition,
+}
+
+/// a use of a label whose address is not known until the whole program has been read
+struct Fixup {
+    addr: usize,
+    label: String,
+    position: SourcePosition,
+}
+
+struct Assembler {
+    code: Vec<Instruction>,
+    labels: HashMap<String, usize>,
+    fixups: Vec<Fixup>,
+}
+
+impl Assembler {
+    fn new() -> Self {
+        Assembler {
+            code: Vec::new(),
+            labels: HashMap::new(),
+            fixups: Vec::new(),
+        }
+    }
+
+    fn assemble_line(&mut self, line: isize, text: &str) -> GenResult<()> {
+        let tokens = tokenize(line, text)?;
+        let mut tokens = Tokens {
+            tokens,
+            curr: 0,
+            eol: SourcePosition::new(
+                Position::new(line, text.len() as isize + 1),
+                Position::new(line, text.len() as isize + 1),
+            ),
+        };
+
+        while tokens.is_label() {
+            self.define_label(&mut tokens)?;
+        }
+
+        if !tokens.at_end() {
+            self.assemble_instruction(&mut tokens)?;
+        }
+
+        if !tokens.at_end() {
+            return Err(error(
+                "unexpected text after the instruction",
+                tokens.peek_position(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn define_label(&mut self, tokens: &mut Tokens) -> GenResult<()> {
+        let token = tokens.next()?;
+        tokens.accept(':')?;
+
+        match token.kind {
+            TokenKind::Number(addr) => {
+                if addr != self.code.len() as i64 {
+                    return Err(error(
+                        &format!(
+                            "address {} does not match the instruction address {}",
+                            addr,
+                            self.code.len()
+                        ),
+                        token.position,
+                    ));
+                }
+            }
+            TokenKind::Name(label) => {
+                if self.labels.insert(label.clone(), self.code.len()).is_some() {
+                    return Err(error(
+                        &format!("label \"{}\" is already defined", label),
+                        token.position,
+                    ));
+                }
+            }
+            TokenKind::Punct(_) => unreachable!(),
+        }
+        Ok(())
+    }
+
+    fn assemble_instruction(&mut self, tokens: &mut Tokens) -> GenResult<()> {
+        use Opcode::*;
+
+        let token = tokens.next()?;
+        let op = match &token.kind {
+            TokenKind::Name(name) => opcode(name),
+            _ => None,
+        }
+        .ok_or_else(|| error("expected an instruction mnemonic", token.position))?;
+
+        if self.code.len() >= PB {
+            return Err(error(
+                "program is too large to fit in the code store",
+                token.position,
+            ));
+        }
+
+        let instr = match op {
+            Load | Store | JumpIf => {
+                let n = parse_size(tokens)?;
+                let (r, d) = self.parse_address(tokens)?;
+                Instruction::new(op, n, r, d)
+            }
+            LoadA | Jump => {
+                let (r, d) = self.parse_address(tokens)?;
+                Instruction::new(op, 0, r, d)
+            }
+            LoadI | StoreI => {
+                let n = parse_size(tokens)?;
+                Instruction::new(op, n, Register::CB, 0)
+            }
+            LoadL | Push => {
+                let d = self.parse_value(tokens)?;
+                Instruction::new(op, 0, Register::CB, d)
+            }
+            Return | Pop => {
+                let n = parse_size(tokens)?;
+                let d = parse_displacement(tokens)?;
+                Instruction::new(op, n, Register::CB, d)
+            }
+            Call => {
+                if tokens.peek_is('(') {
+                    tokens.accept('(')?;
+                    let n = parse_register(tokens)?;
+                    tokens.accept(')')?;
+                    let (r, d) = self.parse_address(tokens)?;
+                    Instruction::new(op, n as u8, r, d)
+                } else {
+                    let prim = parse_primitive(tokens)?;
+                    Instruction::new(
+                        op,
+                        Register::SB as u8,
+                        Register::PB,
+                        prim.displacement() as i16,
+                    )
+                }
+            }
+            CallI | JumpI | Halt => Instruction::new(op, 0, Register::CB, 0),
+        };
+
+        self.code.push(instr);
+        Ok(())
+    }
+
+    /// d[r], or a label, which denotes its address relative to CB
+    fn parse_address(&mut self, tokens: &mut Tokens) -> GenResult<(Register, i16)> {
+        let token = tokens.peek()?.clone();
+        match token.kind {
+            TokenKind::Name(label) => {
+                tokens.next()?;
+                self.refer_to(label, token.position);
+                Ok((Register::CB, 0))
+            }
+            _ => {
+                let d = parse_displacement(tokens)?;
+                tokens.accept('[')?;
+                let r = parse_register(tokens)?;
+                tokens.accept(']')?;
+                Ok((r, d))
+            }
+        }
+    }
+
+    /// a number, a character literal, or a label
+    fn parse_value(&mut self, tokens: &mut Tokens) -> GenResult<i16> {
+        let token = tokens.peek()?.clone();
+        match token.kind {
+            TokenKind::Name(label) => {
+                tokens.next()?;
+                self.refer_to(label, token.position);
+                Ok(0)
+            }
+            _ => parse_displacement(tokens),
+        }
+    }
+
+    fn refer_to(&mut self, label: String, position: SourcePosition) {
+        self.fixups.push(Fixup {
+            addr: self.code.len(),
+            label,
+            position,
+        });
+    }
+
+    /// patch every use of a label with the label's address
+    fn finish(mut self) -> GenResult<Vec<Instruction>> {
+        for fixup in &self.fixups {
+            match self.labels.get(&fixup.label) {
+                Some(&addr) => self.code[fixup.addr].d = addr as i16,
+                None => {
+                    return Err(error(
+                        &format!("label \"{}\" is not defined", fixup.label),
+                        fixup.position,
+                    ))
+                }
+            }
+        }
+        Ok(self.code)
+    }
+}
+
+struct Tokens {
+    tokens: Vec<Token>,
+    curr: usize,
+    eol: SourcePosition,
+}
+
+impl Tokens {
+    fn at_end(&self) -> bool {
+        self.curr >= self.tokens.len()
+    }
+
+    fn peek(&self) -> GenResult<&Token> {
+        self.tokens
+            .get(self.curr)
+            .ok_or_else(|| error("unexpected end of line", self.eol))
+    }
+
+    fn peek_position(&self) -> SourcePosition {
+        self.tokens
+            .get(self.curr)
+            .map_or(self.eol, |token| token.position)
+    }
+
+    fn peek_is(&self, c: char) -> bool {
+        matches!(self.tokens.get(self.curr), Some(Token { kind: TokenKind::Punct(p), .. }) if *p == c)
+    }
+
+    /// a name or number immediately followed by a colon
+    fn is_label(&self) -> bool {
+        match (self.tokens.get(self.curr), self.tokens.get(self.curr + 1)) {
+            (Some(token), Some(colon)) => {
+                matches!(token.kind, TokenKind::Name(_) | TokenKind::Number(_))
+                    && colon.kind == TokenKind::Punct(':')
+            }
+            _ => false,
+        }
+    }
+
+    fn next(&mut self) -> GenResult<Token> {
+        let token = self.peek()?.clone();
+        self.curr += 1;
+        Ok(token)
+    }
+
+    fn accept(&mut self, c: char) -> GenResult<()> {
+        if self.peek_is(c) {
+            self.curr += 1;
+            Ok(())
+        } else {
+            Err(error(
+                &format!("expected \"{}\" here", c),
+                self.peek_position(),
+            ))
+        }
+    }
+}
+
+fn tokenize(line: isize, text: &str) -> GenResult<Vec<Token>> {
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+
+        let kind = if c == ';' {
+            break;
+        } else if c.is_whitespace() {
+            i += 1;
+            continue;
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            TokenKind::Name(chars[start..i].iter().collect())
+        } else if c.is_ascii_digit()
+            || (c == '-' && i + 1 < chars.len() && chars[i + 1].is_ascii_digit())
+        {
+            i += 1;
+            while i < chars.len() && chars[i].is_ascii_digit() {
+                i += 1;
+            }
+            let digits = chars[start..i].iter().collect::<String>();
+            match digits.parse() {
+                Ok(value) => TokenKind::Number(value),
+                Err(_) => return Err(error("number is too large", span(line, start, i))),
+            }
+        } else if c == '\'' {
+            if i + 2 < chars.len() && chars[i + 2] == '\'' {
+                i += 3;
+                TokenKind::Number(chars[start + 1] as i64)
+            } else {
+                return Err(error(
+                    "malformed character literal",
+                    span(line, start, start + 1),
+                ));
+            }
+        } else if "()[]:".contains(c) {
+            i += 1;
+            TokenKind::Punct(c)
+        } else {
+            return Err(error(
+                &format!("unexpected character \"{}\"", c),
+                span(line, start, start + 1),
+            ));
+        };
+
+        tokens.push(Token {
+            kind,
+            position: span(line, start, i),
+        });
+    }
+
+    Ok(tokens)
+}
+
+fn opcode(name: &str) -> Option<Opcode> {
+    (0..16)
+        .filter_map(Opcode::from_u8)
+        .find(|op| op.as_str().eq_ignore_ascii_case(name))
+}
+
+/// (n)
+fn parse_size(tokens: &mut Tokens) -> GenResult<u8> {
+    tokens.accept('(')?;
+    let token = tokens.next()?;
+    let n = match token.kind {
+        TokenKind::Number(n) if (0..=u8::MAX as i64).contains(&n) => n as u8,
+        _ => return Err(error("expected a size between 0 and 255", token.position)),
+    };
+    tokens.accept(')')?;
+    Ok(n)
+}
+
+fn parse_register(tokens: &mut Tokens) -> GenResult<Register> {
+    let token = tokens.next()?;
+    let r = match &token.kind {
+        TokenKind::Name(name) => (0..16)
+            .filter_map(Register::from_u8)
+            .find(|r| r.as_str().eq_ignore_ascii_case(name)),
+        TokenKind::Number(r) if (0..16).contains(r) => Register::from_u8(*r as u8),
+        _ => None,
+    };
+    r.ok_or_else(|| error("expected a register name", token.position))
+}
+
+fn parse_primitive(tokens: &mut Tokens) -> GenResult<Primitive> {
+    let token = tokens.next()?;
+    let prim = match &token.kind {
+        TokenKind::Name(name) => (1..=28)
+            .filter_map(Primitive::from_displacement)
+            .find(|prim| prim.as_str().eq_ignore_ascii_case(name)),
+        _ => None,
+    };
+    prim.ok_or_else(|| error("expected the name of a primitive routine", token.position))
+}
+
+fn parse_displacement(tokens: &mut Tokens) -> GenResult<i16> {
+    let token = tokens.next()?;
+    match token.kind {
+        TokenKind::Number(d) if (i16::MIN as i64..=i16::MAX as i64).contains(&d) => Ok(d as i16),
+        TokenKind::Number(_) => Err(error(
+            "displacement does not fit into 16 bits",
+            token.position,
+        )),
+        _ => Err(error("expected a number", token.position)),
+    }
+}
+
+fn span(line: isize, start: usize, finish: usize) -> SourcePosition {
+    SourcePosition::new(
+        Position::new(line, start as isize + 1),
+        Position::new(line, finish as isize),
+    )
+}
+
+fn error(message: &str, position: SourcePosition) -> GenError {
+    GenError::from(AssemblerError::new(message, position))
+}
@@ -0,0 +1,154 @@
+//! The TAM disassembler
+//!
+//! Turns a TAM code segment back into a readable listing, one instruction per line, using the
+//! mnemonics of the TAM instruction table:
+//!
+//! ```text
+//!      0:  LOADL 42
+//!      1:  CALL putint
+//!      2:  HALT
+//! ```
+//!
+//! Calls into the primitive segment are shown by the name of the primitive routine. A call to a
+//! host primitive keeps its address, so that the listing can still be assembled, and is marked
+//! by a comment naming the host primitive - by its number, or by the name it was registered
+//! under if the names are given:
+//!
+//! ```text
+//!      3:  CALL(SB) 29[PB]  ; host primitive max
+//! ```
+//!
+//! When the
+//! source positions that the Encoder recorded for each instruction are available, the listing
+//! can be interleaved with the lines of the Triangle source that the code was generated from,
+//! as `;` comment lines.
+
+use super::host::HOST_PRIMITIVE_BASE;
+use super::{Instruction, Opcode, Primitive, Register};
+use crate::scanner::SourcePosition;
+
+use std::fmt;
+use std::fmt::Write;
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write_to(f, &[])
+    }
+}
+
+impl Instruction {
+    /// write the instruction in its mnemonic form, naming calls to host primitives after
+    /// `host_names` where it has a name for them
+    fn write_to<W: Write + ?Sized>(&self, f: &mut W, host_names: &[&str]) -> fmt::Result {
+        use Opcode::*;
+
+        match self.op {
+            Load | Store | JumpIf => write!(f, "{}({}) {}", self.op, self.n, self.address()),
+            LoadA | Jump => write!(f, "{} {}", self.op, self.address()),
+            LoadI | StoreI => write!(f, "{}({})", self.op, self.n),
+            LoadL | Push => write!(f, "{} {}", self.op, self.d),
+            Return | Pop => write!(f, "{}({}) {}", self.op, self.n, self.d),
+            Call => match self.primitive() {
+                Some(prim) => write!(f, "{} {}", self.op, prim),
+                None => {
+                    write!(
+                        f,
+                        "{}({}) {}",
+                        self.op,
+                        register_name(self.n),
+                        self.address()
+                    )?;
+                    match self.host_primitive() {
+                        Some(index) => match host_names.get(index) {
+                            Some(name) => write!(f, "  ; host primitive {}", name),
+                            None => write!(f, "  ; host primitive {}", index),
+                        },
+                        None => Ok(()),
+                    }
+                }
+            },
+            CallI | JumpI | Halt => write!(f, "{}", self.op),
+        }
+    }
+
+    /// the primitive routine that this instruction calls, if it is a call into the primitive
+    /// segment
+    pub fn primitive(&self) -> Option<Primitive> {
+        match (self.op, Register::from_u8(self.r)) {
+            (Opcode::Call, Some(Register::PB)) => Primitive::from_displacement(self.d as isize),
+            _ => None,
+        }
+    }
+
+    /// the number of the host primitive that this instruction calls, if it is a call past the
+    /// built-in primitives of the primitive segment
+    pub fn host_primitive(&self) -> Option<usize> {
+        match (self.op, Register::from_u8(self.r)) {
+            (Opcode::Call, Some(Register::PB)) if self.d as isize >= HOST_PRIMITIVE_BASE => {
+                Some((self.d as isize - HOST_PRIMITIVE_BASE) as usize)
+            }
+            _ => None,
+        }
+    }
+
+    fn address(&self) -> String {
+        format!("{}[{}]", self.d, register_name(self.r))
+    }
+}
+
+fn register_name(r: u8) -> String {
+    match Register::from_u8(r) {
+        Some(reg) => reg.to_string(),
+        None => r.to_string(),
+    }
+}
+
+/// produce a listing of the code segment, one numbered instruction per line
+pub fn disassemble(code: &[Instruction]) -> String {
+    disassemble_with_host_names(code, &[])
+}
+
+/// produce a listing of the code segment in which the calls to host primitives are named after
+/// `host_names`, the names of the host primitives in the order of their slots (as given by
+/// `StdEnvironment::host_names`)
+pub fn disassemble_with_host_names(code: &[Instruction], host_names: &[&str]) -> String {
+    let mut listing = String::new();
+    for (addr, instr) in code.iter().enumerate() {
+        write_line(&mut listing, addr, instr, host_names);
+    }
+    listing
+}
+
+/// produce a listing of the code segment in which each run of instructions is preceded by the
+/// source line that it was generated from. `positions` holds the source position of each
+/// instruction, as recorded by the Encoder.
+pub fn disassemble_with_source(
+    code: &[Instruction],
+    positions: &[SourcePosition],
+    source: &str,
+) -> String {
+    let lines = source.lines().collect::<Vec<_>>();
+    let mut listing = String::new();
+    let mut curr_line = -1;
+
+    for (addr, instr) in code.iter().enumerate() {
+        let line = positions
+            .get(addr)
+            .map_or(-1, |position| position.start.line);
+
+        if line > 0 && line != curr_line {
+            if let Some(text) = lines.get(line as usize - 1) {
+                writeln!(listing, "; {:>4}: {}", line, text.trim_end()).unwrap();
+            }
+            curr_line = line;
+        }
+        write_line(&mut listing, addr, instr, &[]);
+    }
+    listing
+}
+
+fn write_line(listing: &mut String, addr: usize, instr: &Instruction, host_names: &[&str]) {
+    write!(listing, "{:>6}:  ", addr).unwrap();
+    instr.write_to(listing, host_names).unwrap();
+    listing.push('\n');
+}
@@ -0,0 +1,56 @@
+//! Host primitives
+//!
+//! Primitive routines that are implemented in Rust by the program embedding the compiler, rather
+//! than built into the TAM. They are called like any other primitive routine, through the
+//! primitive segment - host primitive n lives at displacement `HOST_PRIMITIVE_BASE + n` from
+//! PB, after the built-in primitives.
+//!
+//! A host primitive is given the machine with its arguments on top of the stack, the last
+//! argument topmost. Like the built-in primitives, it pops its arguments and pushes its result
+//! (if it is a function):
+//!
+//! ```
+//! use std::sync::Arc;
+//! use triangle_rs::tam::host::HostRoutine;
+//!
+//! // func max(x: Integer, y: Integer): Integer
+//! let max: HostRoutine = Arc::new(|machine| {
+//!     let y = machine.pop()?;
+//!     let x = machine.pop()?;
+//!     machine.push(x.max(y))
+//! });
+//! ```
+
+use super::interpreter::Status;
+use super::{PB, PT};
+
+use std::sync::Arc;
+
+/// the displacement from PB of the first host primitive
+pub const HOST_PRIMITIVE_BASE: isize = (PT - PB) as isize + 1;
+
+/// the implementation of a host primitive. It can be shared by any number of interpreters, on
+/// any number of threads.
+pub type HostRoutine = Arc<dyn Fn(&mut dyn Machine) -> Result<(), Status> + Send + Sync>;
+
+/// the parts of the TAM that a host primitive can work with. Every operation fails with the
+/// status that the program should fail with, as the built-in primitives do.
+pub trait Machine {
+    /// pop a word off the stack
+    fn pop(&mut self) -> Result<i16, Status>;
+
+    /// push a word onto the stack
+    fn push(&mut self, value: i16) -> Result<(), Status>;
+
+    /// the word at a data store address, such as the address passed for a var parameter
+    fn read(&self, addr: i16) -> Result<i16, Status>;
+
+    /// replace the word at a data store address
+    fn write(&mut self, addr: i16, value: i16) -> Result<(), Status>;
+
+    /// the next character of the input, or None at the end of the input
+    fn read_char(&mut self) -> Result<Option<u8>, Status>;
+
+    /// write the text to the output
+    fn write_output(&mut self, s: &str) -> Result<(), Status>;
+}
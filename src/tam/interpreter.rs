@@ -0,0 +1,630 @@
+//! The TAM interpreter
+//!
+//! Loads a code segment into the code store and executes it until it halts, or until a runtime
+//! fault occurs, as described in `docs/tam_specification.md`. Input and output go through the
+//! reader and writer that the interpreter is created with, so that programs can be run against
+//! the console as well as against in-memory buffers. Host primitives, implemented in Rust, can
+//! be added to the primitive segment with `set_host_routines`.
+
+use super::host::{HostRoutine, Machine, HOST_PRIMITIVE_BASE};
+use super::*;
+
+use std::fmt;
+use std::io::{BufRead, Write};
+use std::sync::Arc;
+
+/// the number of words in the data store - the heap grows down from the top of it
+pub const DATA_STORE_SIZE: usize = 1024;
+
+/// the state of execution of the program
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Status {
+    Running,
+    Halted,
+    FailedDataStoreFull,
+    FailedInvalidCodeAddress,
+    FailedInvalidDataAddress,
+    FailedInvalidInstruction,
+    FailedOverflow,
+    FailedZeroDivide,
+    FailedIOError,
+}
+
+impl Status {
+    pub fn as_str(&self) -> &'static str {
+        use Status::*;
+
+        match *self {
+            Running => "Program is running.",
+            Halted => "Program has halted normally.",
+            FailedDataStoreFull => "Program has failed due to exhaustion of Data Store.",
+            FailedInvalidCodeAddress => "Program has failed due to an invalid code address.",
+            FailedInvalidDataAddress => "Program has failed due to an invalid data address.",
+            FailedInvalidInstruction => "Program has failed due to an invalid instruction.",
+            FailedOverflow => "Program has failed due to overflow.",
+            FailedZeroDivide => "Program has failed due to division by zero.",
+            FailedIOError => "Program has failed due to an IO error.",
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// every operation that can fault reports the status the program fails with
+type Execution<T> = Result<T, Status>;
+
+pub struct Interpreter<R: BufRead, W: Write> {
+    code: Vec<Instruction>,
+    data: Vec<i16>,
+
+    // the registers that are not constant
+    ct: isize,
+    st: isize,
+    ht: isize,
+    lb: isize,
+    cp: isize,
+
+    status: Status,
+    input: R,
+    output: W,
+    wrote_output: bool,
+
+    host_routines: Vec<HostRoutine>,
+}
+
+impl<R: BufRead, W: Write> Interpreter<R, W> {
+    pub fn new(input: R, output: W) -> Self {
+        Interpreter {
+            code: Vec::new(),
+            data: vec![0; DATA_STORE_SIZE],
+            ct: 0,
+            st: 0,
+            ht: DATA_STORE_SIZE as isize,
+            lb: 0,
+            cp: 0,
+            status: Status::Running,
+            input,
+            output,
+            wrote_output: false,
+            host_routines: Vec::new(),
+        }
+    }
+
+    /// install the host primitives that the program may call, in the order of their slots in
+    /// the primitive segment
+    pub fn set_host_routines(&mut self, routines: &[HostRoutine]) {
+        self.host_routines = routines.to_vec();
+    }
+
+    /// load the code segment at CB, and reset the machine
+    pub fn load(&mut self, code: &[Instruction]) {
+        self.code = code.to_vec();
+        self.data = vec![0; DATA_STORE_SIZE];
+        self.ct = code.len() as isize;
+        self.st = 0;
+        self.ht = DATA_STORE_SIZE as isize;
+        self.lb = 0;
+        self.cp = 0;
+        self.status = Status::Running;
+        self.wrote_output = false;
+    }
+
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    /// Run the loaded program until it halts or fails, and then report how it terminated.
+    pub fn run(&mut self) -> Status {
+        while self.status == Status::Running {
+            if let Err(status) = self.step() {
+                self.status = status;
+            }
+        }
+
+        let trailer = if self.wrote_output {
+            format!("\n{}\n", self.status)
+        } else {
+            format!("{}\n", self.status)
+        };
+
+        if self
+            .output
+            .write_all(trailer.as_bytes())
+            .and_then(|_| self.output.flush())
+            .is_err()
+        {
+            self.status = Status::FailedIOError;
+        }
+
+        self.status
+    }
+
+    /// the current value of register r
+    fn content(&self, r: u8) -> Execution<isize> {
+        let register = match Register::from_u8(r) {
+            Some(register) => register,
+            None => return Err(Status::FailedInvalidInstruction),
+        };
+
+        Ok(match register {
+            Register::CB => 0,
+            Register::CT => self.ct,
+            Register::PB => PB as isize,
+            Register::PT => PT as isize,
+            Register::SB => 0,
+            Register::ST => self.st,
+            Register::HB => DATA_STORE_SIZE as isize,
+            Register::HT => self.ht,
+            Register::LB => self.lb,
+            Register::CP => self.cp,
+            // L1 to L6 follow the static links up from LB
+            display => {
+                let mut addr = self.lb;
+                for _ in 0..(display as u8 - Register::LB as u8) {
+                    addr = self.read(addr)? as isize;
+                }
+                addr
+            }
+        })
+    }
+
+    fn read(&self, addr: isize) -> Execution<i16> {
+        if addr < 0 || addr >= DATA_STORE_SIZE as isize {
+            return Err(Status::FailedInvalidDataAddress);
+        }
+        Ok(self.data[addr as usize])
+    }
+
+    fn write(&mut self, addr: isize, value: i16) -> Execution<()> {
+        if addr < 0 || addr >= DATA_STORE_SIZE as isize {
+            return Err(Status::FailedInvalidDataAddress);
+        }
+        self.data[addr as usize] = value;
+        Ok(())
+    }
+
+    /// copy n words from one data address to another (the areas may overlap)
+    fn copy(&mut self, from: isize, to: isize, n: isize) -> Execution<()> {
+        if from <= to {
+            for index in (0..n).rev() {
+                let value = self.read(from + index)?;
+                self.write(to + index, value)?;
+            }
+        } else {
+            for index in 0..n {
+                let value = self.read(from + index)?;
+                self.write(to + index, value)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// check that n more words fit between the stack and the heap
+    fn check_space(&self, n: isize) -> Execution<()> {
+        if self.ht - self.st < n {
+            return Err(Status::FailedDataStoreFull);
+        }
+        Ok(())
+    }
+
+    fn push(&mut self, value: i16) -> Execution<()> {
+        self.check_space(1)?;
+        self.write(self.st, value)?;
+        self.st += 1;
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Execution<i16> {
+        self.st -= 1;
+        self.read(self.st)
+    }
+
+    /// the value on top of the stack, which is replaced by the result of a primitive routine
+    fn top(&self) -> Execution<i16> {
+        self.read(self.st - 1)
+    }
+
+    fn set_top(&mut self, value: i16) -> Execution<()> {
+        self.write(self.st - 1, value)
+    }
+
+    fn address(value: isize) -> Execution<i16> {
+        if value < i16::MIN as isize || value > i16::MAX as isize {
+            return Err(Status::FailedInvalidDataAddress);
+        }
+        Ok(value as i16)
+    }
+
+    /// execute the instruction at CP
+    fn step(&mut self) -> Execution<()> {
+        if self.cp < 0 || self.cp >= self.ct {
+            return Err(Status::FailedInvalidCodeAddress);
+        }
+
+        let instr = self.code[self.cp as usize];
+        let n = instr.n as isize;
+        let d = instr.d as isize;
+
+        match instr.op {
+            Opcode::Load => {
+                let addr = d + self.content(instr.r)?;
+                self.check_space(n)?;
+                self.copy(addr, self.st, n)?;
+                self.st += n;
+                self.cp += 1;
+            }
+
+            Opcode::LoadA => {
+                let addr = d + self.content(instr.r)?;
+                self.push(Self::address(addr)?)?;
+                self.cp += 1;
+            }
+
+            Opcode::LoadI => {
+                let addr = self.pop()? as isize;
+                self.check_space(n)?;
+                self.copy(addr, self.st, n)?;
+                self.st += n;
+                self.cp += 1;
+            }
+
+            Opcode::LoadL => {
+                self.push(instr.d)?;
+                self.cp += 1;
+            }
+
+            Opcode::Store => {
+                let addr = d + self.content(instr.r)?;
+                self.st -= n;
+                self.copy(self.st, addr, n)?;
+                self.cp += 1;
+            }
+
+            Opcode::StoreI => {
+                let addr = self.pop()? as isize;
+                self.st -= n;
+                self.copy(self.st, addr, n)?;
+                self.cp += 1;
+            }
+
+            Opcode::Call => {
+                let addr = d + self.content(instr.r)?;
+                if addr >= PB as isize {
+                    self.call_primitive(addr - PB as isize)?;
+                    self.cp += 1;
+                } else {
+                    let static_link = self.content(instr.n)?;
+                    self.call(static_link, addr)?;
+                }
+            }
+
+            Opcode::CallI => {
+                let addr = self.pop()? as isize;
+                let static_link = self.pop()? as isize;
+                if addr >= PB as isize {
+                    self.call_primitive(addr - PB as isize)?;
+                    self.cp += 1;
+                } else {
+                    self.call(static_link, addr)?;
+                }
+            }
+
+            Opcode::Return => {
+                let addr = self.lb - d;
+                self.cp = self.read(self.lb + 2)? as isize;
+                self.lb = self.read(self.lb + 1)? as isize;
+                self.st -= n;
+                self.copy(self.st, addr, n)?;
+                self.st = addr + n;
+            }
+
+            Opcode::Push => {
+                self.check_space(d)?;
+                self.st += d;
+                self.cp += 1;
+            }
+
+            Opcode::Pop => {
+                let addr = self.st - n - d;
+                self.st -= n;
+                self.copy(self.st, addr, n)?;
+                self.st = addr + n;
+                self.cp += 1;
+            }
+
+            Opcode::Jump => {
+                self.cp = d + self.content(instr.r)?;
+            }
+
+            Opcode::JumpI => {
+                self.cp = self.pop()? as isize;
+            }
+
+            Opcode::JumpIf => {
+                if self.pop()? as isize == n {
+                    self.cp = d + self.content(instr.r)?;
+                } else {
+                    self.cp += 1;
+                }
+            }
+
+            Opcode::Halt => {
+                self.status = Status::Halted;
+            }
+        }
+
+        if self.st < 0 {
+            return Err(Status::FailedInvalidDataAddress);
+        }
+        Ok(())
+    }
+
+    /// push a new frame (static link, dynamic link, return address) and enter the routine
+    fn call(&mut self, static_link: isize, addr: isize) -> Execution<()> {
+        self.check_space(LINK_DATA_SIZE as isize)?;
+        let frame = self.st;
+        self.write(frame, Self::address(static_link)?)?;
+        self.write(frame + 1, Self::address(self.lb)?)?;
+        self.write(frame + 2, Self::address(self.cp + 1)?)?;
+        self.lb = frame;
+        self.st = frame + LINK_DATA_SIZE as isize;
+        self.cp = addr;
+        Ok(())
+    }
+
+    fn call_primitive(&mut self, displacement: isize) -> Execution<()> {
+        let primitive = match Primitive::from_displacement(displacement) {
+            Some(primitive) => primitive,
+            None => return self.call_host_routine(displacement - HOST_PRIMITIVE_BASE),
+        };
+
+        match primitive {
+            Primitive::Id => {}
+
+            Primitive::Not => {
+                let t = self.top()?;
+                self.set_top(to_word(!is_true(t)))?;
+            }
+
+            Primitive::And => {
+                let t2 = self.pop()?;
+                let t1 = self.top()?;
+                self.set_top(to_word(is_true(t1) && is_true(t2)))?;
+            }
+
+            Primitive::Or => {
+                let t2 = self.pop()?;
+                let t1 = self.top()?;
+                self.set_top(to_word(is_true(t1) || is_true(t2)))?;
+            }
+
+            Primitive::Succ => {
+                let i = self.top()? as i32;
+                self.set_top(overflow_checked(i + 1)?)?;
+            }
+
+            Primitive::Pred => {
+                let i = self.top()? as i32;
+                self.set_top(overflow_checked(i - 1)?)?;
+            }
+
+            Primitive::Neg => {
+                let i = self.top()? as i32;
+                self.set_top(overflow_checked(-i)?)?;
+            }
+
+            Primitive::Add | Primitive::Sub | Primitive::Mult | Primitive::Div | Primitive::Mod => {
+                let i2 = self.pop()? as i32;
+                let i1 = self.top()? as i32;
+                let result = match primitive {
+                    Primitive::Add => i1 + i2,
+                    Primitive::Sub => i1 - i2,
+                    Primitive::Mult => i1 * i2,
+                    _ if i2 == 0 => return Err(Status::FailedZeroDivide),
+                    Primitive::Div => i1 / i2,
+                    _ => i1 % i2,
+                };
+                self.set_top(overflow_checked(result)?)?;
+            }
+
+            Primitive::Lt | Primitive::Le | Primitive::Ge | Primitive::Gt => {
+                let i2 = self.pop()?;
+                let i1 = self.top()?;
+                let result = match primitive {
+                    Primitive::Lt => i1 < i2,
+                    Primitive::Le => i1 <= i2,
+                    Primitive::Ge => i1 >= i2,
+                    _ => i1 > i2,
+                };
+                self.set_top(to_word(result))?;
+            }
+
+            Primitive::Eq | Primitive::Ne => {
+                let size = self.pop()? as isize;
+                self.st -= 2 * size;
+                let mut equal = true;
+                for index in 0..size {
+                    if self.read(self.st + index)? != self.read(self.st + size + index)? {
+                        equal = false;
+                    }
+                }
+                self.push(to_word(equal == (primitive == Primitive::Eq)))?;
+            }
+
+            Primitive::Eol => {
+                let c = self.peek_char()?;
+                self.push(to_word(c == Some(b'\n')))?;
+            }
+
+            Primitive::Eof => {
+                let c = self.peek_char()?;
+                self.push(to_word(c.is_none()))?;
+            }
+
+            Primitive::Get => {
+                let addr = self.pop()? as isize;
+                let c = match self.read_char()? {
+                    Some(c) => c as i16,
+                    None => -1,
+                };
+                self.write(addr, c)?;
+            }
+
+            Primitive::Put => {
+                let c = self.pop()?;
+                let c = std::char::from_u32(c as u16 as u32).unwrap_or('?');
+                self.write_output(&c.to_string())?;
+            }
+
+            Primitive::GetEol => loop {
+                match self.read_char()? {
+                    Some(b'\n') | None => break,
+                    _ => {}
+                }
+            },
+
+            Primitive::PutEol => {
+                self.write_output("\n")?;
+            }
+
+            Primitive::GetInt => {
+                let addr = self.pop()? as isize;
+                let i = self.read_int()?;
+                self.write(addr, i)?;
+            }
+
+            Primitive::PutInt => {
+                let i = self.pop()?;
+                self.write_output(&i.to_string())?;
+            }
+
+            Primitive::New => {
+                let size = self.top()? as isize;
+                self.check_space(size)?;
+                self.ht -= size;
+                self.set_top(Self::address(self.ht)?)?;
+            }
+
+            Primitive::Dispose => {
+                // the heap is never compacted, so there is nothing to do apart from popping
+                // the arguments
+                self.st -= 2;
+            }
+        }
+        Ok(())
+    }
+
+    fn call_host_routine(&mut self, index: isize) -> Execution<()> {
+        let routine = match self.host_routines.get(index as usize) {
+            Some(routine) if index >= 0 => Arc::clone(routine),
+            _ => return Err(Status::FailedInvalidCodeAddress),
+        };
+        routine(self)
+    }
+
+    fn peek_char(&mut self) -> Execution<Option<u8>> {
+        match self.input.fill_buf() {
+            Ok(buf) => Ok(buf.first().cloned()),
+            Err(_) => Err(Status::FailedIOError),
+        }
+    }
+
+    fn read_char(&mut self) -> Execution<Option<u8>> {
+        let c = self.peek_char()?;
+        if c.is_some() {
+            self.input.consume(1);
+        }
+        Ok(c)
+    }
+
+    /// read an optionally signed integer-literal, skipping any leading whitespace
+    fn read_int(&mut self) -> Execution<i16> {
+        while let Some(c) = self.peek_char()? {
+            if !c.is_ascii_whitespace() {
+                break;
+            }
+            self.read_char()?;
+        }
+
+        let mut sign = 1;
+        while let Some(c) = self.peek_char()? {
+            match c {
+                b'-' => sign = -1,
+                b'+' => sign = 1,
+                _ => break,
+            }
+            self.read_char()?;
+        }
+
+        let mut value: i32 = 0;
+        while let Some(c) = self.peek_char()? {
+            if !c.is_ascii_digit() {
+                break;
+            }
+            self.read_char()?;
+            value = value * 10 + (c - b'0') as i32;
+            if value > MAX_INT as i32 {
+                return Err(Status::FailedOverflow);
+            }
+        }
+
+        overflow_checked(sign * value)
+    }
+
+    fn write_output(&mut self, s: &str) -> Execution<()> {
+        self.wrote_output = true;
+        self.output
+            .write_all(s.as_bytes())
+            .map_err(|_| Status::FailedIOError)
+    }
+}
+
+impl<R: BufRead, W: Write> Machine for Interpreter<R, W> {
+    fn pop(&mut self) -> Execution<i16> {
+        Interpreter::pop(self)
+    }
+
+    fn push(&mut self, value: i16) -> Execution<()> {
+        Interpreter::push(self, value)
+    }
+
+    fn read(&self, addr: i16) -> Execution<i16> {
+        Interpreter::read(self, addr as isize)
+    }
+
+    fn write(&mut self, addr: i16, value: i16) -> Execution<()> {
+        Interpreter::write(self, addr as isize, value)
+    }
+
+    fn read_char(&mut self) -> Execution<Option<u8>> {
+        Interpreter::read_char(self)
+    }
+
+    fn write_output(&mut self, s: &str) -> Execution<()> {
+        Interpreter::write_output(self, s)
+    }
+}
+
+fn is_true(word: i16) -> bool {
+    word == TRUE_REP
+}
+
+fn to_word(b: bool) -> i16 {
+    if b {
+        TRUE_REP
+    } else {
+        FALSE_REP
+    }
+}
+
+/// integers are represented in the range -MAX_INT to MAX_INT
+fn overflow_checked(value: i32) -> Execution<i16> {
+    if value < -(MAX_INT as i32) || value > MAX_INT as i32 {
+        return Err(Status::FailedOverflow);
+    }
+    Ok(value as i16)
+}
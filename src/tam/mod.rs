@@ -0,0 +1,343 @@
+//! The TAM module
+//!
+//! Definitions for the Triangle Abstract Machine (TAM) - the instruction format, the registers,
+//! the primitive routines, and the sizes of the basic data representations. These are shared
+//! by the Encoder, which generates TAM code, and every other component that needs to make sense
+//! of that code.
+
+use std::fmt;
+
+pub mod assembler;
+pub mod disassembler;
+pub mod host;
+pub mod interpreter;
+pub mod object;
+
+/// the code store address of the base of the primitive segment
+pub const PB: usize = 1024;
+
+/// the code store address of the top of the primitive segment
+pub const PT: usize = PB + 28;
+
+/// sizes (in words) of the primitive data representations
+pub const BOOLEAN_SIZE: usize = 1;
+pub const CHARACTER_SIZE: usize = 1;
+pub const INTEGER_SIZE: usize = 1;
+pub const ADDRESS_SIZE: usize = 1;
+pub const CLOSURE_SIZE: usize = 2 * ADDRESS_SIZE;
+pub const LINK_DATA_SIZE: usize = 3 * ADDRESS_SIZE;
+
+/// the largest integer value that fits into a data word
+pub const MAX_INT: i16 = i16::MAX;
+
+/// the representations of the Boolean values
+pub const FALSE_REP: i16 = 0;
+pub const TRUE_REP: i16 = 1;
+
+/// the deepest level of routine nesting that the display registers (L1 to L6) can reach
+pub const MAX_ROUTINE_LEVEL: usize = 7;
+
+/// The 16 TAM opcodes. Opcode 9 is unused.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Opcode {
+    Load = 0,
+    LoadA = 1,
+    LoadI = 2,
+    LoadL = 3,
+    Store = 4,
+    StoreI = 5,
+    Call = 6,
+    CallI = 7,
+    Return = 8,
+    Push = 10,
+    Pop = 11,
+    Jump = 12,
+    JumpI = 13,
+    JumpIf = 14,
+    Halt = 15,
+}
+
+impl Opcode {
+    pub fn from_u8(op: u8) -> Option<Opcode> {
+        use Opcode::*;
+
+        match op {
+            0 => Some(Load),
+            1 => Some(LoadA),
+            2 => Some(LoadI),
+            3 => Some(LoadL),
+            4 => Some(Store),
+            5 => Some(StoreI),
+            6 => Some(Call),
+            7 => Some(CallI),
+            8 => Some(Return),
+            10 => Some(Push),
+            11 => Some(Pop),
+            12 => Some(Jump),
+            13 => Some(JumpI),
+            14 => Some(JumpIf),
+            15 => Some(Halt),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        use Opcode::*;
+
+        match *self {
+            Load => "LOAD",
+            LoadA => "LOADA",
+            LoadI => "LOADI",
+            LoadL => "LOADL",
+            Store => "STORE",
+            StoreI => "STOREI",
+            Call => "CALL",
+            CallI => "CALLI",
+            Return => "RETURN",
+            Push => "PUSH",
+            Pop => "POP",
+            Jump => "JUMP",
+            JumpI => "JUMPI",
+            JumpIf => "JUMPIF",
+            Halt => "HALT",
+        }
+    }
+}
+
+impl fmt::Display for Opcode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// The 16 TAM registers
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Register {
+    CB = 0,
+    CT = 1,
+    PB = 2,
+    PT = 3,
+    SB = 4,
+    ST = 5,
+    HB = 6,
+    HT = 7,
+    LB = 8,
+    L1 = 9,
+    L2 = 10,
+    L3 = 11,
+    L4 = 12,
+    L5 = 13,
+    L6 = 14,
+    CP = 15,
+}
+
+impl Register {
+    pub fn from_u8(r: u8) -> Option<Register> {
+        use Register::*;
+
+        match r {
+            0 => Some(CB),
+            1 => Some(CT),
+            2 => Some(PB),
+            3 => Some(PT),
+            4 => Some(SB),
+            5 => Some(ST),
+            6 => Some(HB),
+            7 => Some(HT),
+            8 => Some(LB),
+            9 => Some(L1),
+            10 => Some(L2),
+            11 => Some(L3),
+            12 => Some(L4),
+            13 => Some(L5),
+            14 => Some(L6),
+            15 => Some(CP),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        use Register::*;
+
+        match *self {
+            CB => "CB",
+            CT => "CT",
+            PB => "PB",
+            PT => "PT",
+            SB => "SB",
+            ST => "ST",
+            HB => "HB",
+            HT => "HT",
+            LB => "LB",
+            L1 => "L1",
+            L2 => "L2",
+            L3 => "L3",
+            L4 => "L4",
+            L5 => "L5",
+            L6 => "L6",
+            CP => "CP",
+        }
+    }
+}
+
+impl fmt::Display for Register {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// The primitive routines, identified by their displacement from PB
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Primitive {
+    Id = 1,
+    Not = 2,
+    And = 3,
+    Or = 4,
+    Succ = 5,
+    Pred = 6,
+    Neg = 7,
+    Add = 8,
+    Sub = 9,
+    Mult = 10,
+    Div = 11,
+    Mod = 12,
+    Lt = 13,
+    Le = 14,
+    Ge = 15,
+    Gt = 16,
+    Eq = 17,
+    Ne = 18,
+    Eol = 19,
+    Eof = 20,
+    Get = 21,
+    Put = 22,
+    GetEol = 23,
+    PutEol = 24,
+    GetInt = 25,
+    PutInt = 26,
+    New = 27,
+    Dispose = 28,
+}
+
+impl Primitive {
+    pub fn from_displacement(d: isize) -> Option<Primitive> {
+        use Primitive::*;
+
+        match d {
+            1 => Some(Id),
+            2 => Some(Not),
+            3 => Some(And),
+            4 => Some(Or),
+            5 => Some(Succ),
+            6 => Some(Pred),
+            7 => Some(Neg),
+            8 => Some(Add),
+            9 => Some(Sub),
+            10 => Some(Mult),
+            11 => Some(Div),
+            12 => Some(Mod),
+            13 => Some(Lt),
+            14 => Some(Le),
+            15 => Some(Ge),
+            16 => Some(Gt),
+            17 => Some(Eq),
+            18 => Some(Ne),
+            19 => Some(Eol),
+            20 => Some(Eof),
+            21 => Some(Get),
+            22 => Some(Put),
+            23 => Some(GetEol),
+            24 => Some(PutEol),
+            25 => Some(GetInt),
+            26 => Some(PutInt),
+            27 => Some(New),
+            28 => Some(Dispose),
+            _ => None,
+        }
+    }
+
+    pub fn displacement(&self) -> isize {
+        *self as isize
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        use Primitive::*;
+
+        match *self {
+            Id => "id",
+            Not => "not",
+            And => "and",
+            Or => "or",
+            Succ => "succ",
+            Pred => "pred",
+            Neg => "neg",
+            Add => "add",
+            Sub => "sub",
+            Mult => "mult",
+            Div => "div",
+            Mod => "mod",
+            Lt => "lt",
+            Le => "le",
+            Ge => "ge",
+            Gt => "gt",
+            Eq => "eq",
+            Ne => "ne",
+            Eol => "eol",
+            Eof => "eof",
+            Get => "get",
+            Put => "put",
+            GetEol => "geteol",
+            PutEol => "puteol",
+            GetInt => "getint",
+            PutInt => "putint",
+            New => "new",
+            Dispose => "dispose",
+        }
+    }
+}
+
+impl fmt::Display for Primitive {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// A TAM instruction - a 4-bit opcode, a 4-bit register field, an 8-bit operand size (or
+/// other), and a 16-bit displacement.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    pub op: Opcode,
+    pub r: u8,
+    pub n: u8,
+    pub d: i16,
+}
+
+impl Instruction {
+    pub fn new(op: Opcode, n: u8, r: Register, d: i16) -> Self {
+        Instruction {
+            op,
+            r: r as u8,
+            n,
+            d,
+        }
+    }
+
+    /// pack the instruction into its 32-bit format - op in the highest 4 bits, followed by r,
+    /// n, and d
+    pub fn to_word(&self) -> u32 {
+        (self.op as u32) << 28
+            | ((self.r & 0x0f) as u32) << 24
+            | (self.n as u32) << 16
+            | (self.d as u16) as u32
+    }
+
+    /// unpack an instruction from its 32-bit format, if the opcode is a valid one
+    pub fn from_word(word: u32) -> Option<Instruction> {
+        Some(Instruction {
+            op: Opcode::from_u8((word >> 28) as u8)?,
+            r: ((word >> 24) & 0x0f) as u8,
+            n: (word >> 16) as u8,
+            d: word as u16 as i16,
+        })
+    }
+}
@@ -0,0 +1,102 @@
+//! TAM object files
+//!
+//! A `.tam` object file holds a compiled code segment so that it can be run any number of times
+//! without recompiling the source, or handed over to a separate VM process. The format is:
+//!
+//! ```text
+//!   magic      4 bytes   "TAM\0"
+//!   version    2 bytes   big-endian, currently 1
+//!   length     2 bytes   big-endian, the number of instructions in the code segment
+//!   code       4 bytes per instruction, big-endian, in the TAM instruction format
+//! ```
+
+use super::{Instruction, PB};
+use crate::error::{GenError, GenResult, TamError};
+
+use std::io::{ErrorKind, Read, Write};
+
+pub const MAGIC: &[u8; 4] = b"TAM\0";
+pub const VERSION: u16 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TamObject {
+    pub code: Vec<Instruction>,
+}
+
+impl TamObject {
+    pub fn new(code: Vec<Instruction>) -> Self {
+        TamObject { code }
+    }
+
+    /// write the header followed by the code segment
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> GenResult<()> {
+        if self.code.len() > PB {
+            return Err(GenError::from(TamError::new(
+                "code segment is too large to fit in the code store",
+            )));
+        }
+
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_be_bytes())?;
+        writer.write_all(&(self.code.len() as u16).to_be_bytes())?;
+
+        for instr in &self.code {
+            writer.write_all(&instr.to_word().to_be_bytes())?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// read and validate the header, and then the code segment
+    pub fn read_from<R: Read>(reader: &mut R) -> GenResult<Self> {
+        let mut magic = [0u8; 4];
+        read_exact(reader, &mut magic)?;
+        if &magic != MAGIC {
+            return Err(GenError::from(TamError::new("not a TAM object file")));
+        }
+
+        let mut half_word = [0u8; 2];
+        read_exact(reader, &mut half_word)?;
+        let version = u16::from_be_bytes(half_word);
+        if version != VERSION {
+            return Err(GenError::from(TamError::new(&format!(
+                "unsupported TAM object file version {}",
+                version
+            ))));
+        }
+
+        read_exact(reader, &mut half_word)?;
+        let length = u16::from_be_bytes(half_word) as usize;
+        if length > PB {
+            return Err(GenError::from(TamError::new(
+                "code segment is too large to fit in the code store",
+            )));
+        }
+
+        let mut code = Vec::with_capacity(length);
+        let mut word = [0u8; 4];
+        for addr in 0..length {
+            read_exact(reader, &mut word)?;
+            match Instruction::from_word(u32::from_be_bytes(word)) {
+                Some(instr) => code.push(instr),
+                None => {
+                    return Err(GenError::from(TamError::new(&format!(
+                        "invalid instruction at code address {}",
+                        addr
+                    ))))
+                }
+            }
+        }
+
+        Ok(TamObject { code })
+    }
+}
+
+fn read_exact<R: Read>(reader: &mut R, buf: &mut [u8]) -> GenResult<()> {
+    reader.read_exact(buf).map_err(|err| match err.kind() {
+        ErrorKind::UnexpectedEof => {
+            GenError::from(TamError::new("unexpected end of TAM object file"))
+        }
+        _ => GenError::from(err),
+    })
+}
@@ -0,0 +1,113 @@
+use triangle_rs::tam::assembler::*;
+use triangle_rs::tam::disassembler::*;
+use triangle_rs::tam::interpreter::*;
+use triangle_rs::tam::Opcode::*;
+use triangle_rs::tam::Register::*;
+use triangle_rs::tam::{Instruction, Primitive};
+
+fn run(source: &str, input: &str) -> (Status, String) {
+    let code = assemble(source).unwrap();
+    let mut output = Vec::new();
+    let mut interpreter = Interpreter::new(input.as_bytes(), &mut output);
+    interpreter.load(&code);
+    let status = interpreter.run();
+    (status, String::from_utf8(output).unwrap())
+}
+
+fn assert_error(source: &str, message: &str) {
+    match assemble(source) {
+        Ok(code) => panic!("expected an error, but assembled {:?}", code),
+        Err(err) => assert_eq!(message, err.to_string()),
+    }
+}
+
+#[test]
+fn test_instructions() {
+    let source = "
+        LOAD(2) -3[LB]
+        loada 4[sb]
+        CALL(LB) 12[CB]
+        CALL putint
+        JUMPIF(0) 9[CB]
+        LOADL 'A'
+        RETURN(1) 2
+        HALT
+    ";
+    let expected_code = vec![
+        Instruction::new(Load, 2, LB, -3),
+        Instruction::new(LoadA, 0, SB, 4),
+        Instruction::new(Call, LB as u8, CB, 12),
+        Instruction::new(Call, SB as u8, PB, Primitive::PutInt.displacement() as i16),
+        Instruction::new(JumpIf, 0, CB, 9),
+        Instruction::new(LoadL, 0, CB, 65),
+        Instruction::new(Return, 1, CB, 2),
+        Instruction::new(Halt, 0, CB, 0),
+    ];
+    assert_eq!(expected_code, assemble(source).unwrap());
+}
+
+#[test]
+fn test_labels() {
+    // print the numbers from 3 down to 1
+    let source = "
+                LOADL 3
+        loop:   LOAD(1) 0[SB]     ; the counter
+                CALL putint
+                CALL puteol
+                LOAD(1) 0[SB]
+                CALL pred
+                STORE(1) 0[SB]
+                LOAD(1) 0[SB]
+                JUMPIF(0) done
+                JUMP loop
+                HALT              ; never reached
+        done:   HALT
+    ";
+    let (status, output) = run(source, "");
+    assert_eq!(Status::Halted, status);
+    assert_eq!("3\n2\n1\n\nProgram has halted normally.\n", output);
+}
+
+#[test]
+fn test_round_trip() {
+    let code = vec![
+        Instruction::new(Push, 0, CB, 1),
+        Instruction::new(LoadA, 0, SB, 0),
+        Instruction::new(Call, SB as u8, PB, Primitive::GetInt.displacement() as i16),
+        Instruction::new(Call, SB as u8, CB, 6),
+        Instruction::new(Pop, 0, CB, 1),
+        Instruction::new(Halt, 0, CB, 0),
+        Instruction::new(Load, 1, L1, 0),
+        Instruction::new(StoreI, 1, CB, 0),
+        Instruction::new(Return, 0, CB, 0),
+    ];
+    assert_eq!(code, assemble(&disassemble(&code)).unwrap());
+}
+
+#[test]
+fn test_errors() {
+    assert_error(
+        "  JUMP nowhere",
+        "AssemblerError error at line 1 and column 8: label \"nowhere\" is not defined",
+    );
+    assert_error(
+        "a: HALT\na: HALT",
+        "AssemblerError error at line 2 and column 1: label \"a\" is already defined",
+    );
+    assert_error(
+        "HALT\n  FETCH 1",
+        "AssemblerError error at line 2 and column 3: expected an instruction mnemonic",
+    );
+    assert_error(
+        "  LOAD 0[SB]",
+        "AssemblerError error at line 1 and column 8: expected \"(\" here",
+    );
+    assert_error(
+        "  CALL launch",
+        "AssemblerError error at line 1 and column 8: expected the name of a primitive routine",
+    );
+    assert_error(
+        "     3:  HALT",
+        "AssemblerError error at line 1 and column 6: address 3 does not match the instruction address 0",
+    );
+}
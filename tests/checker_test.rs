@@ -19,10 +19,15 @@ use triangle_rs::ast::typedenoters::*;
 use triangle_rs::ast::vnames::Vname::*;
 use triangle_rs::ast::vnames::*;
 use triangle_rs::ast::*;
+use triangle_rs::checker::std_env::StdEnvironment;
 use triangle_rs::checker::*;
+use triangle_rs::diagnostics::Diagnostics;
 use triangle_rs::parser::*;
 use triangle_rs::scanner::*;
 
+use std::sync::Arc;
+use std::thread;
+
 pub mod checkers;
 
 use checkers::*;
@@ -30,171 +35,579 @@ use checkers::*;
 #[test]
 fn test_emptycommandeot() {
     let source_file = "samples/source/emptycommandeot.t";
-    let mut parser = Parser::new(Scanner::new(source_file));
-    let mut program = parser.parse_program();
+    let mut parser = Parser::new(Scanner::new(source_file).unwrap());
+    let mut program = parser.parse_program().unwrap();
     let mut checker = Checker::new();
-    checker.check(&mut program);
+    checker.check(&mut program).unwrap();
     assert!(emptycommandeot_checker::check(&program));
 }
 
 #[test]
 fn test_emptycommandsemicolon() {
     let source_file = "samples/source/emptycommandsemicolon.t";
-    let mut parser = Parser::new(Scanner::new(source_file));
-    let mut program = parser.parse_program();
+    let mut parser = Parser::new(Scanner::new(source_file).unwrap());
+    let mut program = parser.parse_program().unwrap();
     let mut checker = Checker::new();
-    checker.check(&mut program);
+    checker.check(&mut program).unwrap();
     assert!(emptycommandsemicolon_checker::check(&program));
 }
 
 #[test]
 fn test_hello() {
     let source_file = "samples/source/hello.t";
-    let mut parser = Parser::new(Scanner::new(source_file));
-    let mut program = parser.parse_program();
+    let mut parser = Parser::new(Scanner::new(source_file).unwrap());
+    let mut program = parser.parse_program().unwrap();
     let mut checker = Checker::new();
-    checker.check(&mut program);
+    checker.check(&mut program).unwrap();
     assert!(hello_checker::check(&program));
 }
 
 #[test]
 fn test_inc() {
     let source_file = "samples/source/inc.t";
-    let mut parser = Parser::new(Scanner::new(source_file));
-    let mut program = parser.parse_program();
+    let mut parser = Parser::new(Scanner::new(source_file).unwrap());
+    let mut program = parser.parse_program().unwrap();
     let mut checker = Checker::new();
-    checker.check(&mut program);
+    checker.check(&mut program).unwrap();
     assert!(inc_checker::check(&program));
 }
 
 #[test]
 fn test_echo() {
     let source_file = "samples/source/echo.t";
-    let mut parser = Parser::new(Scanner::new(source_file));
+    let mut parser = Parser::new(Scanner::new(source_file).unwrap());
 }
 
 #[test]
 fn test_odd() {
     let source_file = "samples/source/odd.t";
-    let mut parser = Parser::new(Scanner::new(source_file));
+    let mut parser = Parser::new(Scanner::new(source_file).unwrap());
 }
 
 #[test]
 fn test_sum_proc() {
     let source_file = "samples/source/sum_proc.t";
-    let mut parser = Parser::new(Scanner::new(source_file));
+    let mut parser = Parser::new(Scanner::new(source_file).unwrap());
 }
 
 #[test]
 fn test_power() {
     let source_file = "samples/source/power.t";
-    let mut parser = Parser::new(Scanner::new(source_file));
+    let mut parser = Parser::new(Scanner::new(source_file).unwrap());
 }
 
 #[test]
 fn test_factorial() {
     let source_file = "samples/source/factorial.t";
-    let mut parser = Parser::new(Scanner::new(source_file));
+    let mut parser = Parser::new(Scanner::new(source_file).unwrap());
 }
 
 #[test]
 fn test_record() {
     let source_file = "samples/source/record.t";
-    let mut parser = Parser::new(Scanner::new(source_file));
+    let mut parser = Parser::new(Scanner::new(source_file).unwrap());
 }
 
 #[test]
 fn test_leapyear() {
     let source_file = "samples/source/leapyear.t";
-    let mut parser = Parser::new(Scanner::new(source_file));
+    let mut parser = Parser::new(Scanner::new(source_file).unwrap());
 }
 
 #[test]
 fn test_date() {
     let source_file = "samples/source/date.t";
-    let mut parser = Parser::new(Scanner::new(source_file));
+    let mut parser = Parser::new(Scanner::new(source_file).unwrap());
 }
 
 #[test]
 fn test_print_array() {
     let source_file = "samples/source/print_array.t";
-    let mut parser = Parser::new(Scanner::new(source_file));
+    let mut parser = Parser::new(Scanner::new(source_file).unwrap());
 }
 
 #[test]
 fn test_string() {
     let source_file = "samples/source/string.t";
-    let mut parser = Parser::new(Scanner::new(source_file));
+    let mut parser = Parser::new(Scanner::new(source_file).unwrap());
 }
 
 #[test]
 fn test_reverse_line() {
     let source_file = "samples/source/reverse_line.t";
-    let mut parser = Parser::new(Scanner::new(source_file));
+    let mut parser = Parser::new(Scanner::new(source_file).unwrap());
 }
 
 #[test]
 fn test_iteratively() {
     let source_file = "samples/source/iteratively.t";
-    let mut parser = Parser::new(Scanner::new(source_file));
+    let mut parser = Parser::new(Scanner::new(source_file).unwrap());
 }
 
 #[test]
 fn test_line() {
     let source_file = "samples/source/line.t";
-    let mut parser = Parser::new(Scanner::new(source_file));
+    let mut parser = Parser::new(Scanner::new(source_file).unwrap());
 }
 
 #[test]
 fn test_dates() {
     let source_file = "samples/source/dates.t";
-    let mut parser = Parser::new(Scanner::new(source_file));
+    let mut parser = Parser::new(Scanner::new(source_file).unwrap());
 }
 
 #[test]
 fn test_monthsofyear() {
     let source_file = "samples/source/monthsofyear.t";
-    let mut parser = Parser::new(Scanner::new(source_file));
+    let mut parser = Parser::new(Scanner::new(source_file).unwrap());
 }
 
 #[test]
 fn test_capitalise() {
     let source_file = "samples/source/capitalise.t";
-    let mut parser = Parser::new(Scanner::new(source_file));
+    let mut parser = Parser::new(Scanner::new(source_file).unwrap());
 }
 
 #[test]
 fn test_freq() {
     let source_file = "samples/source/freq.t";
-    let mut parser = Parser::new(Scanner::new(source_file));
+    let mut parser = Parser::new(Scanner::new(source_file).unwrap());
 }
 
 #[test]
 fn test_insertion_sort() {
     let source_file = "samples/source/insertion_sort.t";
-    let mut parser = Parser::new(Scanner::new(source_file));
+    let mut parser = Parser::new(Scanner::new(source_file).unwrap());
 }
 
 #[test]
 fn test_rationals() {
     let source_file = "samples/source/rationals.t";
-    let mut parser = Parser::new(Scanner::new(source_file));
+    let mut parser = Parser::new(Scanner::new(source_file).unwrap());
 }
 
 #[test]
 fn test_eqnoteq() {
     let source_file = "samples/source/eqnoteq.t";
-    let mut parser = Parser::new(Scanner::new(source_file));
+    let mut parser = Parser::new(Scanner::new(source_file).unwrap());
 }
 
 #[test]
 fn test_nestedarrays() {
     let source_file = "samples/source/nestedarrays.t";
-    let mut parser = Parser::new(Scanner::new(source_file));
+    let mut parser = Parser::new(Scanner::new(source_file).unwrap());
 }
 
 #[test]
 fn test_nestedrecords() {
     let source_file = "samples/source/nestedrecords.t";
-    let mut parser = Parser::new(Scanner::new(source_file));
+    let mut parser = Parser::new(Scanner::new(source_file).unwrap());
+}
+
+#[test]
+fn test_undeclared_procedure() {
+    let mut parser = Parser::new(Scanner::from_text("launch(42)"));
+    let mut program = parser.parse_program().unwrap();
+    let mut checker = Checker::new();
+    let err = checker.check(&mut program).unwrap_err();
+    assert_eq!(
+        "CheckerError error at line 1 and column 1: \"launch\" is not declared",
+        err.to_string()
+    );
+}
+
+#[test]
+fn test_redeclaration() {
+    let mut parser = Parser::new(Scanner::from_text(
+        "let var x: Integer; var x: Char in putint(1)",
+    ));
+    let mut program = parser.parse_program().unwrap();
+    let mut checker = Checker::new();
+    assert!(checker.check(&mut program).is_err());
+}
+
+#[test]
+fn test_diagnostics() {
+    let mut parser = Parser::new(Scanner::from_text(
+        "let var x: Integer; var x: Char in begin launch(1); land(2) end",
+    ));
+    let mut diagnostics = Diagnostics::new();
+    let mut program = parser.parse_program_with(&mut diagnostics);
+    assert!(!diagnostics.has_errors());
+
+    let mut checker = Checker::new();
+    checker.check_with(&mut program, &mut diagnostics);
+    assert_eq!(3, diagnostics.len());
+    assert_eq!(
+        "CheckerError error at line 1 and column 21: identifier x is already defined at level 1\n\
+         CheckerError error at line 1 and column 42: \"launch\" is not declared\n\
+         CheckerError error at line 1 and column 53: \"land\" is not declared",
+        diagnostics.into_result().unwrap_err().to_string()
+    );
+}
+
+/// check the program, returning every error reported
+fn check_errors(source: &str) -> Vec<String> {
+    let mut parser = Parser::new(Scanner::from_text(source));
+    let mut program = parser.parse_program().unwrap();
+    let mut diagnostics = Diagnostics::new();
+    Checker::new().check_with(&mut program, &mut diagnostics);
+    diagnostics
+        .errors()
+        .iter()
+        .map(|err| err.to_string())
+        .collect()
+}
+
+#[test]
+fn test_integer_literal_range() {
+    assert!(check_errors("let var a: array 32767 of Char in putint(32767)").is_empty());
+    assert_eq!(
+        vec![
+            "CheckerError error at line 1 and column 18: integer literal 40000 is larger than 32767",
+            "CheckerError error at line 1 and column 42: integer literal 32768 is larger than 32767",
+        ],
+        check_errors("let var a: array 40000 of Char in putint(32768)")
+    );
+}
+
+#[test]
+fn test_expression_types() {
+    assert!(check_errors(
+        "let const c ~ 'a'; var a: array 3 of Integer; var p: record x: Integer end \
+         in begin putint((a[1] + p.x) * 2); putint(ord(c)); \
+         putint(let const k ~ 3 in if \\ (k > 2) then k else succ(k)) end"
+    )
+    .is_empty());
+
+    assert_eq!(
+        vec![
+            "CheckerError error at line 1 and column 12: expected type was Integer, actual type was Char",
+            "CheckerError error at line 1 and column 25: expected type was Integer, actual type was Boolean",
+            "CheckerError error at line 1 and column 42: expected type was Boolean, actual type was Integer",
+        ],
+        check_errors("putint(2 + 'a'); putint(true); putint(if 1 then 2 else 3)")
+    );
+}
+
+#[test]
+fn test_expression_decorations() {
+    let mut parser = Parser::new(Scanner::from_text("putint(if 1 < 2 then 3 else 4)"));
+    let mut program = parser.parse_program().unwrap();
+    Checker::new().check(&mut program).unwrap();
+
+    let nodes = &program.nodes;
+    let call = program.cmd.get(nodes).get_call_command().unwrap();
+    let aps = call
+        .aps
+        .get(nodes)
+        .get_single_actual_parameter_sequence()
+        .unwrap();
+    let ap = aps.ap.get(nodes).get_const_actual_parameter().unwrap();
+    let ifexpr = ap.expr.get(nodes).get_if_expression().unwrap();
+    assert_eq!(
+        Some(&IntTypeDenoter(IntTypeDenoterState::new())),
+        program.decorations.type_of(ifexpr.common_state.id)
+    );
+
+    let cond = ifexpr.expr1.get(nodes).get_binary_expression().unwrap();
+    assert_eq!(
+        Some(&BoolTypeDenoter(BoolTypeDenoterState::new())),
+        program.decorations.type_of(cond.common_state.id)
+    );
+    assert!(program
+        .decorations
+        .linked(cond.op.common_state.id)
+        .is_some());
+}
+
+/// the var declaration of `let var x: Integer in x := x + 1`, and the two applied occurrences of x
+fn assigned_variable(
+    program: &Program,
+) -> (&VarDeclarationState, &SimpleVnameState, &SimpleVnameState) {
+    let nodes = &program.nodes;
+    let let_cmd = program.cmd.get(nodes).get_let_command().unwrap();
+    let var_decl = let_cmd.decl.get(nodes).get_var_declaration().unwrap();
+    let assign = let_cmd.cmd.get(nodes).get_assign_command().unwrap();
+    let target = assign.vname.get(nodes).get_simple_vname().unwrap();
+    let operand = assign
+        .expr
+        .get(nodes)
+        .get_binary_expression()
+        .unwrap()
+        .expr1
+        .get(nodes)
+        .get_vname_expression()
+        .unwrap()
+        .vname
+        .get(nodes)
+        .get_simple_vname()
+        .unwrap();
+    (var_decl, target, operand)
+}
+
+#[test]
+fn test_declaration_links() {
+    let mut parser = Parser::new(Scanner::from_text("let var x: Integer in x := x + 1"));
+    let mut program = parser.parse_program().unwrap();
+    Checker::new().check(&mut program).unwrap();
+
+    let (var_decl, target, operand) = assigned_variable(&program);
+    let decorations = &program.decorations;
+    let decl_id = var_decl.common_state.id;
+    assert_eq!(Some(decl_id), decorations.linked(target.id.common_state.id));
+    assert_eq!(
+        Some(decl_id),
+        decorations.linked(operand.id.common_state.id)
+    );
+
+    let mut references = decorations.references(decl_id);
+    references.sort();
+    let mut expected = vec![target.id.common_state.id, operand.id.common_state.id];
+    expected.sort();
+    assert_eq!(expected, references);
+
+    let simple = var_decl.td.get_simple_type_denoter().unwrap();
+    assert_eq!(
+        Some(StdEnvironment::shared().int_decl.id()),
+        decorations.linked(simple.id.common_state.id)
+    );
+}
+
+#[test]
+fn test_references_share_declaration() {
+    let source = "let var x: Integer in x := x + 1";
+    let mut program = Parser::new(Scanner::from_text(source))
+        .parse_program()
+        .unwrap();
+    Checker::new().check(&mut program).unwrap();
+
+    let (var_decl, target, operand) = assigned_variable(&program);
+    let decorations = &program.decorations;
+    let target_decl = decorations.linked(target.id.common_state.id).unwrap();
+    let operand_decl = decorations.linked(operand.id.common_state.id).unwrap();
+    assert_eq!(target_decl, operand_decl);
+
+    // the link leads back to the declaration in the arena, not to a copy of it
+    match program.definition(target_decl) {
+        Some(Definition::Var(decl)) => assert!(std::ptr::eq(decl, var_decl)),
+        other => panic!("expected the var declaration, got {:?}", other),
+    }
+
+    // the same source is numbered the same way every time it is parsed
+    let again = Parser::new(Scanner::from_text(source))
+        .parse_program()
+        .unwrap();
+    let (again_decl, _, _) = assigned_variable(&again);
+    assert_eq!(var_decl.common_state.id, again_decl.common_state.id);
+}
+
+#[test]
+fn test_polymorphic_equality() {
+    assert!(check_errors(
+        "let var c: Char; var b: Boolean \
+         in putint(if (1 = 2) /\\ (c \\= 'x') /\\ (b = false) then 1 else 0)"
+    )
+    .is_empty());
+
+    assert_eq!(
+        vec!["CheckerError error at line 1 and column 15: expected type was Integer, actual type was Char"],
+        check_errors("putint(if 1 = 'a' then 1 else 0)")
+    );
+}
+
+#[test]
+fn test_vname_errors() {
+    assert_eq!(
+        vec![
+            "CheckerError error at line 1 and column 52: no field \"y\" in this record type",
+            "CheckerError error at line 1 and column 63: an array is expected here",
+            "CheckerError error at line 1 and column 77: \"Integer\" is not a constant or variable",
+        ],
+        check_errors(
+            "let var p: record x: Integer end in begin putint(p.y); putint(p[1]); putint(Integer) end"
+        )
+    );
+}
+
+#[test]
+fn test_command_types() {
+    assert!(check_errors(
+        "let var x: Integer; var r: record a: array 2 of Char end \
+         in begin x := 0; r.a[1] := 'c'; while x < 10 do x := x + 1; if x = 10 then x := 0 else end"
+    )
+    .is_empty());
+
+    assert_eq!(
+        vec![
+            "CheckerError error at line 1 and column 42: \"c\" is not a variable",
+            "CheckerError error at line 1 and column 55: expected type was Integer, actual type was Char",
+            "CheckerError error at line 1 and column 63: expected type was Boolean, actual type was Integer",
+            "CheckerError error at line 1 and column 96: expected type was Boolean, actual type was Char",
+        ],
+        check_errors(
+            "let const c ~ 1; var x: Integer in begin c := 2; x := 'a'; if x then x := 1 else x := 2; while 'q' do x := 0 end"
+        )
+    );
+}
+
+#[test]
+fn test_routine_declarations() {
+    assert!(check_errors(
+        "let func gcd(a: Integer, b: Integer): Integer ~ if b = 0 then a else gcd(b, a // b); \
+         proc twice(proc p(var n: Integer), var n: Integer) ~ begin p(var n); p(var n) end; \
+         proc inc(var n: Integer) ~ n := n + 1; var x: Integer \
+         in begin x := gcd(12, 18); twice(proc inc, var x) end"
+    )
+    .is_empty());
+
+    assert_eq!(
+        vec![
+            "CheckerError error at line 1 and column 35: expected type was Integer, actual type was Boolean",
+            "CheckerError error at line 1 and column 79: \"n\" is not a variable",
+            "CheckerError error at line 1 and column 97: too few actual parameters",
+            "CheckerError error at line 1 and column 106: a var actual parameter is expected here",
+        ],
+        check_errors(
+            "let func f(n: Integer): Integer ~ n > 0; proc p(n: Integer, var m: Integer) ~ n := 1 \
+             in begin p(1); p(1, 2) end"
+        )
+    );
+}
+
+#[test]
+fn test_structural_equivalence() {
+    assert!(check_errors(
+        "let type Day ~ Integer; type Date ~ record m: Integer, d: Day end; \
+         type Dates ~ array 2 of Date; var d: Date; var e: record m: Day, d: Integer end; \
+         var ds: Dates; var es: array 02 of record m: Integer, d: Integer end \
+         in begin d := e; d := {m ~ 12, d ~ 25}; ds := es; ds := [d, e]; e := ds[1] end"
+    )
+    .is_empty());
+
+    assert_eq!(
+        vec![
+            "CheckerError error at line 1 and column 105: expected type was record m: Integer, d: Integer end, actual type was record d: Integer, m: Integer end",
+            "CheckerError error at line 1 and column 126: expected type was array 2 of Integer, actual type was array 3 of Integer",
+            "CheckerError error at line 1 and column 142: expected type was record m: Integer, d: Integer end, actual type was record m: Integer, d: Char end",
+        ],
+        check_errors(
+            "let type Date ~ record m: Integer, d: Integer end; var d: Date; var a: array 2 of Integer \
+             in begin d := {d ~ 1, m ~ 2}; a := [1, 2, 3]; d := {m ~ 1, d ~ 'x'} end"
+        )
+    );
+}
+
+#[test]
+fn test_parallel_checks() {
+    let std_env = Arc::new(StdEnvironment::default());
+    let handles = (0..8)
+        .map(|n| {
+            let std_env = Arc::clone(&std_env);
+            thread::spawn(move || {
+                let source = if n % 2 == 0 {
+                    format!("let var x: Integer in x := {}", n)
+                } else {
+                    format!("let var x: Integer in x := '{}'", n)
+                };
+                let mut parser = Parser::new(Scanner::from_text(&source));
+                let mut program = parser.parse_program().unwrap();
+                Checker::with_environment(std_env)
+                    .check(&mut program)
+                    .is_ok()
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for (n, handle) in handles.into_iter().enumerate() {
+        assert_eq!(n % 2 == 0, handle.join().unwrap());
+    }
+}
+
+#[test]
+fn test_symbol_table() {
+    let source = "let\n  var x: Integer;\n  proc p(y: Integer) ~\n    putint(y)\nin\n  p(x)\n";
+    let mut program = Parser::new(Scanner::from_text(source))
+        .parse_program()
+        .unwrap();
+    let mut checker = Checker::new();
+    checker.check(&mut program).unwrap();
+    assert!(checker.take_warnings().is_empty());
+
+    let symbols = checker.symbols();
+    let names = |line, column| {
+        symbols
+            .visible_at(Position::new(line, column))
+            .into_iter()
+            .filter(|entry| entry.level > 0)
+            .map(|entry| (entry.id.as_str(), entry.level))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(vec![("y", 2), ("p", 1), ("x", 1)], names(4, 5));
+    assert_eq!(vec![("p", 1), ("x", 1)], names(6, 3));
+    assert_eq!(Vec::<(&str, usize)>::new(), names(2, 1));
+
+    let entry = symbols
+        .visible_at(Position::new(4, 5))
+        .into_iter()
+        .find(|entry| entry.id == "x")
+        .unwrap();
+    assert_eq!(Position::new(2, 3), entry.position.start);
+
+    // once the program has been checked, only the standard environment is still open
+    assert_eq!(0, symbols.level());
+    assert_eq!(0, symbols.lookup("putint").unwrap().level);
+    assert!(symbols.lookup("x").is_none());
+    assert!(symbols.snapshot().iter().all(|entry| entry.level == 0));
+}
+
+#[test]
+fn test_symbol_table_of_last_program() {
+    let mut checker = Checker::new();
+    for source in &[
+        "let var x: Integer in\n  x := 1\n",
+        "let var y: Char in\n  y := 'a'\n",
+    ] {
+        let mut program = Parser::new(Scanner::from_text(source))
+            .parse_program()
+            .unwrap();
+        checker.check(&mut program).unwrap();
+    }
+
+    // the scopes of the first program are forgotten when the second is checked
+    assert_eq!(
+        vec!["y"],
+        checker
+            .symbols()
+            .visible_at(Position::new(2, 3))
+            .into_iter()
+            .filter(|entry| entry.level > 0)
+            .map(|entry| entry.id.as_str())
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_shadowing() {
+    let mut parser = Parser::new(Scanner::from_text(
+        "let var n: Integer; const put ~ 1; func f(n: Integer): Integer ~ let const n ~ 2 in n \
+         in putint(f(n))",
+    ));
+    let mut program = parser.parse_program().unwrap();
+    let mut checker = Checker::new();
+    checker.check(&mut program).unwrap();
+
+    // hiding a standard declaration is not worth a warning
+    assert_eq!(
+        vec![
+            "CheckerWarning at line 1 and column 43: \"n\" hides a declaration in an enclosing scope",
+            "CheckerWarning at line 1 and column 70: \"n\" hides a declaration in an enclosing scope",
+        ],
+        checker
+            .take_warnings()
+            .errors()
+            .iter()
+            .map(|warning| warning.to_string())
+            .collect::<Vec<_>>()
+    );
 }
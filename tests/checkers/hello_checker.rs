@@ -1,37 +1,49 @@
 use triangle_rs::ast::commands::*;
+use triangle_rs::ast::declarations::Definition;
 use triangle_rs::ast::expressions::*;
 use triangle_rs::ast::parameters::ActualParameter::*;
 use triangle_rs::ast::parameters::ActualParameterSequence::*;
 use triangle_rs::ast::parameters::*;
 use triangle_rs::ast::Program;
-use triangle_rs::checker::std_env::STANDARD_ENVIRONMENT;
+use triangle_rs::checker::std_env::StdEnvironment;
 
 pub fn check(program: &Program) -> bool {
-    let putint_decl = match *program.cmd {
-        Command::CallCommand(ref cmd) => cmd.id.decl.clone(),
+    let nodes = &program.nodes;
+    let putint_decl = match *program.cmd.get(nodes) {
+        Command::CallCommand(ref cmd) => program
+            .decorations
+            .linked(cmd.id.common_state.id)
+            .and_then(|decl| program.definition(decl)),
         _ => unreachable!(),
     };
 
-    let putint_orig_decl = STANDARD_ENVIRONMENT.lock().unwrap().putint_decl.clone();
-    assert_eq!(*putint_decl.unwrap(), putint_orig_decl);
+    let std_env = StdEnvironment::shared();
+    let putint_orig_decl = Definition::of(std_env.putint_decl.get(&std_env.nodes));
+    assert_eq!(putint_decl, putint_orig_decl);
+    assert_eq!(
+        putint_decl.unwrap().common_state().id,
+        putint_orig_decl.unwrap().common_state().id
+    );
 
-    let iexpr1 = match *program.cmd {
-        Command::CallCommand(ref cmd) => match *cmd.aps {
-            ActualParameterSequence::SingleActualParameterSequence(ref aps) => match *aps.ap {
-                ActualParameter::ConstActualParameter(ref ap) => match *ap.expr {
-                    Expression::IntegerExpression(ref iexpr) => iexpr.clone(),
+    let iexpr1 = match *program.cmd.get(nodes) {
+        Command::CallCommand(ref cmd) => match *cmd.aps.get(nodes) {
+            ActualParameterSequence::SingleActualParameterSequence(ref aps) => {
+                match *aps.ap.get(nodes) {
+                    ActualParameter::ConstActualParameter(ref ap) => match *ap.expr.get(nodes) {
+                        Expression::IntegerExpression(ref iexpr) => iexpr.clone(),
+                        _ => unreachable!(),
+                    },
                     _ => unreachable!(),
-                },
-                _ => unreachable!(),
-            },
+                }
+            }
             _ => unreachable!(),
         },
         _ => unreachable!(),
     };
 
     assert_eq!(
-        *iexpr1.td.unwrap(),
-        STANDARD_ENVIRONMENT.lock().unwrap().int_type.clone()
+        program.decorations.type_of(iexpr1.common_state.id),
+        Some(&std_env.int_type)
     );
 
     true
@@ -1 +1,249 @@
+use triangle_rs::ast::parameters::*;
+use triangle_rs::ast::primitives::Identifier;
+use triangle_rs::ast::typedenoters::TypeDenoter;
+use triangle_rs::checker::std_env::StdEnvironment;
+use triangle_rs::checker::*;
+use triangle_rs::encoder::*;
+use triangle_rs::parser::*;
+use triangle_rs::scanner::*;
+use triangle_rs::tam::disassembler::disassemble_with_host_names;
+use triangle_rs::tam::interpreter::*;
+use triangle_rs::tam::object::TamObject;
 
+use std::fs;
+use std::sync::Arc;
+
+fn compile(source_file: &str) -> TamObject {
+    let mut parser = Parser::new(Scanner::new(source_file).unwrap());
+    let mut program = parser.parse_program().unwrap();
+    let mut checker = Checker::new();
+    checker.check(&mut program).unwrap();
+    let mut encoder = Encoder::new();
+    TamObject::new(encoder.encode(&mut program).unwrap())
+}
+
+fn run(object: &TamObject, input: &str) -> String {
+    let mut output = Vec::new();
+    let mut interpreter = Interpreter::new(input.as_bytes(), &mut output);
+    interpreter.load(&object.code);
+    assert_eq!(Status::Halted, interpreter.run());
+    String::from_utf8(output).unwrap()
+}
+
+/// compile the sample and run it, returning everything that the interpreter wrote
+fn compile_and_run(source_file: &str, input: &str) -> String {
+    run(&compile(source_file), input)
+}
+
+fn expected_output(out_file: &str) -> String {
+    fs::read_to_string(out_file).unwrap()
+}
+
+#[test]
+fn test_emptycommandeot() {
+    assert_eq!(
+        expected_output("samples/out/emptycommandeot.out"),
+        compile_and_run("samples/source/emptycommandeot.t", "")
+    );
+}
+
+#[test]
+fn test_emptycommandsemicolon() {
+    assert_eq!(
+        expected_output("samples/out/emptycommandsemicolon.out"),
+        compile_and_run("samples/source/emptycommandsemicolon.t", "")
+    );
+}
+
+#[test]
+fn test_hello() {
+    assert_eq!(
+        expected_output("samples/out/hello.out"),
+        compile_and_run("samples/source/hello.t", "")
+    );
+}
+
+#[test]
+fn test_factorial() {
+    assert_eq!(
+        expected_output("samples/out/factorial.out"),
+        compile_and_run("samples/source/factorial.t", "5\n")
+    );
+}
+
+#[test]
+fn test_inc() {
+    assert_eq!(
+        expected_output("samples/out/inc.out"),
+        compile_and_run("samples/source/inc.t", "99\n")
+    );
+}
+
+#[test]
+fn test_odd() {
+    assert_eq!(
+        expected_output("samples/out/odd.out"),
+        compile_and_run("samples/source/odd.t", "3\n")
+    );
+}
+
+#[test]
+fn test_power() {
+    assert_eq!(
+        expected_output("samples/out/power.out"),
+        compile_and_run("samples/source/power.t", "11 3\n")
+    );
+}
+
+#[test]
+fn test_sum_proc() {
+    assert_eq!(
+        expected_output("samples/out/sum_proc.out"),
+        compile_and_run("samples/source/sum_proc.t", "12 13\n")
+    );
+}
+
+#[test]
+fn test_dates() {
+    assert_eq!(
+        expected_output("samples/out/dates.out"),
+        compile_and_run("samples/source/dates.t", "4 4\n11 22\n1 28\n12 31\n")
+    );
+}
+
+#[test]
+fn test_nestedrecords() {
+    assert_eq!(
+        expected_output("samples/out/nestedrecords.out"),
+        compile_and_run("samples/source/nestedrecords.t", "")
+    );
+}
+
+#[test]
+fn test_rationals() {
+    assert_eq!(
+        expected_output("samples/out/rationals.out"),
+        compile_and_run("samples/source/rationals.t", "4 6\n9 12\n")
+    );
+}
+
+#[test]
+fn test_record() {
+    assert_eq!(
+        expected_output("samples/out/record.out"),
+        compile_and_run("samples/source/record.t", "")
+    );
+}
+
+#[test]
+fn test_hello_object_file() {
+    let object = compile("samples/source/hello.t");
+    let mut bytes = Vec::new();
+    object.write_to(&mut bytes).unwrap();
+    let object = TamObject::read_from(&mut bytes.as_slice()).unwrap();
+
+    assert_eq!(expected_output("samples/out/hello.out"), run(&object, ""));
+}
+
+/// a sequence of formal parameters, each either a var parameter or a const parameter of the
+/// given type
+fn formal_parameters(params: &[(bool, &TypeDenoter)]) -> FormalParameterSequence {
+    params.iter().rev().fold(
+        FormalParameterSequence::EmptyFormalParameterSequence(
+            EmptyFormalParameterSequenceState::new(),
+        ),
+        |fps, &(var, td)| {
+            let fp = if var {
+                FormalParameter::VarFormalParameter(VarFormalParameterState::new(
+                    Identifier::default(),
+                    td.clone(),
+                ))
+            } else {
+                FormalParameter::ConstFormalParameter(ConstFormalParameterState::new(
+                    Identifier::default(),
+                    td.clone(),
+                ))
+            };
+            match fps {
+                FormalParameterSequence::EmptyFormalParameterSequence(_) => {
+                    FormalParameterSequence::SingleFormalParameterSequence(
+                        SingleFormalParameterSequenceState::new(fp),
+                    )
+                }
+                fps => FormalParameterSequence::MultipleFormalParameterSequence(
+                    MultipleFormalParameterSequenceState::new(fp, fps),
+                ),
+            }
+        },
+    )
+}
+
+#[test]
+fn test_host_primitives() {
+    let mut std_env = StdEnvironment::default();
+    let int_type = std_env.int_type.clone();
+    std_env
+        .register_primitive(
+            "max",
+            formal_parameters(&[(false, &int_type), (false, &int_type)]),
+            Some(int_type.clone()),
+            Arc::new(|machine| {
+                let y = machine.pop()?;
+                let x = machine.pop()?;
+                machine.push(x.max(y))
+            }),
+        )
+        .unwrap();
+    std_env
+        .register_primitive(
+            "swap",
+            formal_parameters(&[(true, &int_type), (true, &int_type)]),
+            None,
+            Arc::new(|machine| {
+                let y = machine.pop()?;
+                let x = machine.pop()?;
+                let (old_x, old_y) = (machine.read(x)?, machine.read(y)?);
+                machine.write(x, old_y)?;
+                machine.write(y, old_x)
+            }),
+        )
+        .unwrap();
+    std_env
+        .register_primitive(
+            "shout",
+            formal_parameters(&[]),
+            None,
+            Arc::new(|machine| machine.write_output("hey!")),
+        )
+        .unwrap();
+    let std_env = Arc::new(std_env);
+
+    let source = "let var a: Integer; var b: Integer \
+                  in begin a := max(3, 7); b := 1; swap(var a, var b); \
+                  putint(a); putint(b); shout() end";
+    let mut program = Parser::new(Scanner::from_text(source))
+        .parse_program()
+        .unwrap();
+    Checker::with_environment(Arc::clone(&std_env))
+        .check(&mut program)
+        .unwrap();
+    let code = Encoder::new().encode(&mut program).unwrap();
+    assert!(disassemble_with_host_names(&code, &std_env.host_names())
+        .contains("CALL(SB) 29[PB]  ; host primitive max"));
+
+    let mut output = Vec::new();
+    let mut interpreter = Interpreter::new("".as_bytes(), &mut output);
+    interpreter.set_host_routines(&std_env.host_routines());
+    interpreter.load(&code);
+    assert_eq!(Status::Halted, interpreter.run());
+    assert_eq!(
+        "17hey!\nProgram has halted normally.\n",
+        String::from_utf8(output).unwrap()
+    );
+
+    // the primitives are only known to checkers that use the extended environment
+    let mut program = Parser::new(Scanner::from_text(source))
+        .parse_program()
+        .unwrap();
+    assert!(Checker::new().check(&mut program).is_err());
+}
@@ -0,0 +1,69 @@
+use triangle_rs::cst::{self, SyntaxElement, SyntaxKind, SyntaxNode};
+
+use std::fs;
+
+/// the kinds of the nodes directly inside the node
+fn kinds(node: &SyntaxNode) -> Vec<SyntaxKind> {
+    node.children
+        .iter()
+        .filter_map(|child| match *child {
+            SyntaxElement::Node(ref node) => Some(node.kind),
+            SyntaxElement::Token(_) => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_samples() {
+    for entry in fs::read_dir("samples/source").unwrap() {
+        let path = entry.unwrap().path();
+        let source = fs::read_to_string(&path).unwrap();
+        let (_, root) = cst::parse(&source).unwrap();
+        assert_eq!(source, root.to_string(), "{:?}", path);
+    }
+}
+
+#[test]
+fn test_structure() {
+    let source = "! double it\nlet const n ~ (1 + 2) ! three\nin putint(n * 2)\n";
+    let (program, root) = cst::parse(source).unwrap();
+    assert_eq!(SyntaxKind::Program, root.kind);
+    assert_eq!(vec![SyntaxKind::LetCommand], kinds(&root));
+
+    // the end of the text, and the trivia before it, belong to the program
+    match root.children.last() {
+        Some(SyntaxElement::Token(ref token)) => assert_eq!("\n", token.leading[0].text),
+        _ => panic!("the program should end with the end of the text"),
+    }
+
+    let let_cmd = root.find(program.cmd.id()).unwrap();
+    assert_eq!(SyntaxKind::LetCommand, let_cmd.kind);
+    assert_eq!(source.trim_end(), let_cmd.text());
+    assert_eq!(
+        vec![SyntaxKind::ConstDeclaration, SyntaxKind::CallCommand],
+        kinds(let_cmd)
+    );
+
+    // the parentheses have no node of their own, so they belong to the declaration
+    let decl = match let_cmd.children[1] {
+        SyntaxElement::Node(ref decl) => decl,
+        _ => panic!("expected the declaration"),
+    };
+    assert_eq!(" const n ~ (1 + 2)", decl.text());
+    assert_eq!(
+        vec!["const", "n", "~", "(", "1", "+", "2", ")"],
+        decl.tokens()
+            .iter()
+            .map(|token| token.text())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec![SyntaxKind::Identifier, SyntaxKind::BinaryExpression],
+        kinds(decl)
+    );
+}
+
+#[test]
+fn test_syntax_error() {
+    assert!(cst::parse("let in").is_err());
+}
@@ -0,0 +1,116 @@
+use triangle_rs::checker::Checker;
+use triangle_rs::diagnostics::{self, Diagnostics};
+use triangle_rs::error::{GenError, ParserError};
+use triangle_rs::parser::Parser;
+use triangle_rs::scanner::{Position, Scanner, SourcePosition};
+
+fn check(source: &str) -> Diagnostics {
+    let mut diagnostics = Diagnostics::new();
+    let mut program = Parser::new(Scanner::from_text(source)).parse_program_with(&mut diagnostics);
+    Checker::new().check_with(&mut program, &mut diagnostics);
+    diagnostics
+}
+
+#[test]
+fn test_render() {
+    let source = "let\n  var x : Integer;\n  var x : Char\nin\n  foo(x)\n";
+    let diagnostics = check(source);
+    assert_eq!(
+        "error: identifier x is already defined at level 1\n \
+         --> test.t:3:3\n  \
+         |\n\
+         3 |   var x : Char\n  \
+         |   ^~~~~~~~~~~~\n\
+         note: x was first defined here\n \
+         --> test.t:2:3\n  \
+         |\n\
+         2 |   var x : Integer;\n  \
+         |   ^~~~~~~~~~~~~~~\n\
+         error: \"foo\" is not declared\n \
+         --> test.t:5:3\n  \
+         |\n\
+         5 |   foo(x)\n  \
+         |   ^~~\n",
+        diagnostics.render("test.t", source, false)
+    );
+}
+
+#[test]
+fn test_render_multiple_lines() {
+    let source = "let\n  const c ~\n    1 +\n    ;\nin\n  putint(c)\n";
+    let error = GenError::from(ParserError::new(
+        "a made-up error",
+        SourcePosition::new(Position::new(2, 3), Position::new(3, 7)),
+    ));
+    assert_eq!(
+        "error: a made-up error\n \
+         --> test.t:2:3\n  \
+         |\n\
+         2 |   const c ~\n  \
+         |   ^~~~~~~~~\n\
+         3 |     1 +\n  \
+         | ~~~~~~~\n",
+        diagnostics::render(&error, "test.t", source, false)
+    );
+}
+
+#[test]
+fn test_render_colour() {
+    let source = "foo(1)";
+    let rendered = check(source).render("test.t", source, true);
+    assert!(rendered.starts_with("\x1b[1;31merror\x1b[0m: "));
+    assert!(rendered.contains("\x1b[1;31m^~~\x1b[0m"));
+}
+
+#[test]
+fn test_render_without_position() {
+    let error = GenError::from(triangle_rs::error::CompilerError::new("no file given"));
+    assert_eq!(
+        "error: CompilerError error: no file given\n",
+        diagnostics::render(&error, "test.t", "", false)
+    );
+}
+
+#[test]
+fn test_render_warnings() {
+    let source = "let\n  var n : Integer;\n  proc p(n : Integer) ~ putint(n)\nin\n  p(n)\n";
+    let mut program = Parser::new(Scanner::from_text(source))
+        .parse_program()
+        .unwrap();
+    let mut checker = Checker::new();
+    checker.check(&mut program).unwrap();
+    let warnings = checker.take_warnings();
+    assert_eq!(
+        "warning: \"n\" hides a declaration in an enclosing scope\n \
+         --> test.t:3:10\n  \
+         |\n\
+         3 |   proc p(n : Integer) ~ putint(n)\n  \
+         |          ^~~~~~~~~~~\n\
+         note: \"n\" is declared here\n \
+         --> test.t:2:3\n  \
+         |\n\
+         2 |   var n : Integer;\n  \
+         |   ^~~~~~~~~~~~~~~\n",
+        warnings.render_warnings("test.t", source, false)
+    );
+    // a warning is still labelled as one when it is rendered among errors
+    assert_eq!(
+        warnings.render_warnings("test.t", source, false),
+        warnings.render("test.t", source, false)
+    );
+}
+
+#[test]
+fn test_render_unterminated_program() {
+    let source = "let var x : Integer in\n  x :=\n";
+    let mut diagnostics = Diagnostics::new();
+    Parser::new(Scanner::from_text(source)).parse_program_with(&mut diagnostics);
+    assert_eq!(
+        "error: Eot cannot start a primary expression\n \
+         --> test.t:2:8\n  \
+         |\n\
+         2 |   x :=\n  \
+         |       ^\n",
+        diagnostics.render("test.t", source, false)
+    );
+}
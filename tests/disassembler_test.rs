@@ -0,0 +1,84 @@
+use triangle_rs::checker::*;
+use triangle_rs::encoder::*;
+use triangle_rs::parser::*;
+use triangle_rs::scanner::*;
+use triangle_rs::tam::disassembler::*;
+use triangle_rs::tam::Opcode::*;
+use triangle_rs::tam::Register::*;
+use triangle_rs::tam::{Instruction, Primitive};
+
+use std::fs;
+
+fn call_primitive(prim: Primitive) -> Instruction {
+    Instruction::new(Call, SB as u8, PB, prim.displacement() as i16)
+}
+
+#[test]
+fn test_mnemonics() {
+    let cases = vec![
+        (Instruction::new(Load, 2, LB, -3), "LOAD(2) -3[LB]"),
+        (Instruction::new(LoadA, 0, SB, 4), "LOADA 4[SB]"),
+        (Instruction::new(LoadI, 1, CB, 0), "LOADI(1)"),
+        (Instruction::new(LoadL, 0, CB, -7), "LOADL -7"),
+        (Instruction::new(Store, 1, L1, 2), "STORE(1) 2[L1]"),
+        (Instruction::new(StoreI, 3, CB, 0), "STOREI(3)"),
+        (Instruction::new(Call, LB as u8, CB, 12), "CALL(LB) 12[CB]"),
+        (Instruction::new(CallI, 0, CB, 0), "CALLI"),
+        (Instruction::new(Return, 1, CB, 2), "RETURN(1) 2"),
+        (Instruction::new(Push, 0, CB, 5), "PUSH 5"),
+        (Instruction::new(Pop, 1, CB, 3), "POP(1) 3"),
+        (Instruction::new(Jump, 0, CB, 8), "JUMP 8[CB]"),
+        (Instruction::new(JumpI, 0, CB, 0), "JUMPI"),
+        (Instruction::new(JumpIf, 0, CB, 9), "JUMPIF(0) 9[CB]"),
+        (Instruction::new(Halt, 0, CB, 0), "HALT"),
+        (call_primitive(Primitive::Eq), "CALL eq"),
+        (call_primitive(Primitive::New), "CALL new"),
+        (
+            Instruction::new(Call, SB as u8, PB, 30),
+            "CALL(SB) 30[PB]  ; host primitive 1",
+        ),
+    ];
+
+    for (instr, text) in cases {
+        assert_eq!(text, instr.to_string());
+    }
+}
+
+#[test]
+fn test_host_names() {
+    let code = vec![
+        Instruction::new(Call, SB as u8, PB, 29),
+        Instruction::new(Call, SB as u8, PB, 30),
+        Instruction::new(Halt, 0, CB, 0),
+    ];
+
+    assert_eq!(
+        "     0:  CALL(SB) 29[PB]  ; host primitive max\n     \
+         1:  CALL(SB) 30[PB]  ; host primitive 1\n     2:  HALT\n",
+        disassemble_with_host_names(&code, &["max"])
+    );
+}
+
+#[test]
+fn test_hello() {
+    let source_file = "samples/source/hello.t";
+    let mut parser = Parser::new(Scanner::new(source_file).unwrap());
+    let mut program = parser.parse_program().unwrap();
+    let mut checker = Checker::new();
+    checker.check(&mut program).unwrap();
+    let mut encoder = Encoder::new();
+    let code = encoder.encode(&mut program).unwrap();
+
+    assert_eq!(
+        "     0:  LOADL 42\n     1:  CALL putint\n     2:  HALT\n",
+        disassemble(&code)
+    );
+    assert_eq!(
+        ";    3: putint(42)\n     0:  LOADL 42\n     1:  CALL putint\n     2:  HALT\n",
+        disassemble_with_source(
+            &code,
+            encoder.positions(),
+            &fs::read_to_string(source_file).unwrap()
+        )
+    );
+}
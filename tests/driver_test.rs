@@ -0,0 +1,90 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+/// a fresh directory holding a file of the given name with a valid program in it
+fn source_dir(test: &str, file: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("triangle-driver-{}-{}", test, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join(file), "let var n: Integer in n := 1").unwrap();
+    dir
+}
+
+fn triangle(dir: &PathBuf, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_triangle"))
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_multibyte_file_name() {
+    let dir = source_dir("multibyte", "é.t");
+    let output = triangle(&dir, &["check", "é.t"]);
+    assert_eq!(Some(0), output.status.code());
+
+    let output = triangle(&dir, &["check", "ü.t"]);
+    assert_eq!(Some(1), output.status.code());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_file_names_like_verbosity() {
+    for file in &["vv", "av"] {
+        let dir = source_dir("verbosity", file);
+        let output = triangle(&dir, &["check", file]);
+        assert_eq!(Some(0), output.status.code(), "{}", file);
+        assert!(output.stderr.is_empty(), "{}", file);
+
+        let output = triangle(&dir, &["check", "-vv", file]);
+        assert_eq!(Some(0), output.status.code(), "{}", file);
+        assert_eq!(
+            format!("{}: no errors found\n", file),
+            String::from_utf8(output.stderr).unwrap()
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
+
+#[test]
+fn test_warnings_on_every_checking_command() {
+    let dir = source_dir("warnings", "hide.t");
+    fs::write(
+        dir.join("hide.t"),
+        "let var n: Integer in let const n ~ 2 in putint(n)",
+    )
+    .unwrap();
+
+    for command in &["check", "compile", "run", "disassemble"] {
+        let output = triangle(&dir, &[command, "hide.t"]);
+        assert_eq!(Some(0), output.status.code(), "{}", command);
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(
+            stderr.contains("\"n\" hides a declaration in an enclosing scope"),
+            "{}: {}",
+            command,
+            stderr
+        );
+    }
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_failed_run_reports_status() {
+    let dir = source_dir("status", "divide.t");
+    fs::write(
+        dir.join("divide.t"),
+        "let var n: Integer in begin n := 0; putint(1 / n) end",
+    )
+    .unwrap();
+
+    let output = triangle(&dir, &["run", "divide.t"]);
+    assert_eq!(Some(1), output.status.code());
+    assert_eq!(
+        "Program has failed due to division by zero.\n",
+        String::from_utf8(output.stderr).unwrap()
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
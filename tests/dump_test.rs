@@ -0,0 +1,135 @@
+use triangle_rs::ast::Program;
+use triangle_rs::checker::Checker;
+use triangle_rs::json::Json;
+use triangle_rs::parser::Parser;
+use triangle_rs::scanner::Scanner;
+
+fn parse(source: &str) -> Program {
+    Parser::new(Scanner::from_text(source))
+        .parse_program()
+        .unwrap()
+}
+
+#[test]
+fn test_tree() {
+    let program = parse("let const c ~ 'x' in\n  put(c)");
+    let tree = "\
+Program 1:1-2:8
+  LetCommand 1:1-2:8
+    ConstDeclaration 1:5-1:17
+      Identifier \"c\" 1:11-1:11
+      CharacterExpression 1:15-1:17
+        CharacterLiteral \"x\" 1:15-1:17
+    CallCommand 2:3-2:8
+      Identifier \"put\" 2:3-2:5
+      SingleActualParameterSequence 2:7-2:7
+        ConstActualParameter 2:7-2:7
+          VnameExpression 2:7-2:7
+            SimpleVname 2:7-2:7
+              Identifier \"c\" 2:7-2:7
+";
+    assert_eq!(tree, program.tree());
+    assert_eq!(tree, program.to_string());
+}
+
+#[test]
+fn test_checked_tree() {
+    let mut program = parse("if \\ (1 < 2) then putint(1) else puteol()");
+    Checker::new().check(&mut program).unwrap();
+    assert_eq!(
+        "\
+Program 1:1-1:41
+  IfCommand 1:1-1:41
+    UnaryExpression 1:4-1:12 : Boolean
+      Operator \"\\\\\" 1:4-1:4
+      BinaryExpression 1:7-1:11 : Boolean
+        IntegerExpression 1:7-1:7 : Integer
+          IntegerLiteral \"1\" 1:7-1:7
+        Operator \"<\" 1:9-1:9
+        IntegerExpression 1:11-1:11 : Integer
+          IntegerLiteral \"2\" 1:11-1:11
+    CallCommand 1:19-1:27
+      Identifier \"putint\" 1:19-1:24
+      SingleActualParameterSequence 1:26-1:26
+        ConstActualParameter 1:26-1:26
+          IntegerExpression 1:26-1:26 : Integer
+            IntegerLiteral \"1\" 1:26-1:26
+    CallCommand 1:34-1:41
+      Identifier \"puteol\" 1:34-1:39
+      EmptyActualParameterSequence 1:41-1:40
+",
+        program.tree()
+    );
+}
+
+#[test]
+fn test_json() {
+    let mut program = parse("x := 1");
+    let position = |line: i64, start: i64, finish: i64| {
+        Json::object(vec![
+            (
+                "start",
+                Json::object(vec![
+                    ("line", Json::from(line)),
+                    ("column", Json::from(start)),
+                ]),
+            ),
+            (
+                "finish",
+                Json::object(vec![
+                    ("line", Json::from(line)),
+                    ("column", Json::from(finish)),
+                ]),
+            ),
+        ])
+    };
+    let leaf = |kind: &str, spelling: &str, column: i64| {
+        Json::object(vec![
+            ("kind", Json::from(kind)),
+            ("position", position(1, column, column)),
+            ("spelling", Json::from(spelling)),
+            ("children", Json::Array(Vec::new())),
+        ])
+    };
+    let expected = Json::object(vec![
+        ("kind", Json::from("Program")),
+        ("position", position(1, 1, 6)),
+        (
+            "children",
+            Json::from(vec![Json::object(vec![
+                ("kind", Json::from("AssignCommand")),
+                ("position", position(1, 1, 6)),
+                (
+                    "children",
+                    Json::from(vec![
+                        Json::object(vec![
+                            ("kind", Json::from("SimpleVname")),
+                            ("position", position(1, 1, 1)),
+                            ("children", Json::from(vec![leaf("Identifier", "x", 1)])),
+                        ]),
+                        Json::object(vec![
+                            ("kind", Json::from("IntegerExpression")),
+                            ("position", position(1, 6, 6)),
+                            ("children", Json::from(vec![leaf("IntegerLiteral", "1", 6)])),
+                        ]),
+                    ]),
+                ),
+            ])]),
+        ),
+    ]);
+    assert_eq!(expected, program.to_json());
+    assert_eq!(
+        expected,
+        Json::parse(&program.to_json().to_string()).unwrap()
+    );
+
+    // an undeclared variable has no type, but the literal does once checked
+    assert!(Checker::new().check(&mut program).is_err());
+    let json = program.to_json();
+    let cmd = &json.get("children").unwrap().as_array().unwrap()[0];
+    let children = cmd.get("children").unwrap().as_array().unwrap();
+    assert_eq!(
+        Some("Integer"),
+        children[1].get("type").and_then(|td| td.as_str())
+    );
+}
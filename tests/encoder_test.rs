@@ -1 +1,63 @@
+use triangle_rs::checker::*;
+use triangle_rs::diagnostics::Diagnostics;
+use triangle_rs::encoder::*;
+use triangle_rs::error::locate;
+use triangle_rs::parser::*;
+use triangle_rs::scanner::*;
+use triangle_rs::tam::Opcode::*;
+use triangle_rs::tam::Register::*;
+use triangle_rs::tam::{Instruction, Primitive};
 
+fn encode(source_file: &str) -> Vec<Instruction> {
+    let mut parser = Parser::new(Scanner::new(source_file).unwrap());
+    let mut program = parser.parse_program().unwrap();
+    let mut checker = Checker::new();
+    checker.check(&mut program).unwrap();
+    let mut encoder = Encoder::new();
+    encoder.encode(&mut program).unwrap()
+}
+
+#[test]
+fn test_emptycommandeot() {
+    let source_file = "samples/source/emptycommandeot.t";
+    let expected_code = vec![Instruction::new(Halt, 0, CB, 0)];
+    assert_eq!(expected_code, encode(source_file));
+}
+
+#[test]
+fn test_emptycommandsemicolon() {
+    let source_file = "samples/source/emptycommandsemicolon.t";
+    let expected_code = vec![Instruction::new(Halt, 0, CB, 0)];
+    assert_eq!(expected_code, encode(source_file));
+}
+
+#[test]
+fn test_hello() {
+    let source_file = "samples/source/hello.t";
+    let expected_code = vec![
+        Instruction::new(LoadL, 0, CB, 42),
+        Instruction::new(Call, SB as u8, PB, Primitive::PutInt.displacement() as i16),
+        Instruction::new(Halt, 0, CB, 0),
+    ];
+    assert_eq!(expected_code, encode(source_file));
+}
+
+#[test]
+fn test_restrictions() {
+    let source = "let var a: array 30000 of array 2 of Integer in a[0][0] := 1";
+    let mut program = Parser::new(Scanner::from_text(source))
+        .parse_program()
+        .unwrap();
+    Checker::new().check(&mut program).unwrap();
+
+    let err = Encoder::new().encode(&mut program).unwrap_err();
+    let errors = err.downcast_ref::<Diagnostics>().unwrap().errors();
+    assert_eq!(2, errors.len());
+    assert_eq!(
+        "EncoderError error at line 1 and column 5: restriction: operand can't exceed 32767 words",
+        errors[0].to_string()
+    );
+    for err in errors {
+        assert!(locate(err).is_some());
+    }
+}
@@ -0,0 +1,126 @@
+use triangle_rs::ast::Program;
+use triangle_rs::formatter::format;
+use triangle_rs::parser::Parser;
+use triangle_rs::scanner::Scanner;
+
+use std::fs;
+
+fn parse(source: &str) -> Program {
+    Parser::new(Scanner::from_text(source))
+        .parse_program()
+        .unwrap()
+}
+
+/// the text of every comment, in order
+fn comments(source: &str) -> Vec<String> {
+    source
+        .lines()
+        .filter_map(|line| {
+            let line = line.replace("'!'", "");
+            line.find('!')
+                .map(|start| line[start..].trim_end().to_owned())
+        })
+        .collect()
+}
+
+#[test]
+fn test_samples() {
+    for entry in fs::read_dir("samples/source").unwrap() {
+        let path = entry.unwrap().path();
+        let source = fs::read_to_string(&path).unwrap();
+
+        let formatted = format(&source).unwrap();
+        assert_eq!(formatted, format(&formatted).unwrap(), "{:?}", path);
+        assert_eq!(parse(&source), parse(&formatted), "{:?}", path);
+        assert_eq!(comments(&source), comments(&formatted), "{:?}", path);
+    }
+}
+
+#[test]
+fn test_layout() {
+    let source = "\
+! count down
+let var n:Integer;   const c~'!'
+! the loop
+; proc p ( var x:Integer , y:array 2 of Char) ~ x:=x-1
+in begin getint(var n);
+while n>0 do begin if n=c then puteol() else begin end; p(var n,['a','b']) ! down
+
+
+end; ! done
+end";
+    let expected = "\
+! count down
+let
+  var n: Integer;
+  const c ~ '!';
+  ! the loop
+  proc p(var x: Integer, y: array 2 of Char) ~
+    x := x - 1
+in
+  begin
+    getint(var n);
+    while n > 0 do
+      begin
+        if n = c
+          then puteol()
+          else begin end;
+        p(var n, ['a', 'b']) ! down
+      end; ! done
+  end
+";
+    let formatted = format(source).unwrap();
+    assert_eq!(expected, formatted);
+    assert_eq!(formatted, format(&formatted).unwrap());
+    assert_eq!(parse(source), parse(&formatted));
+}
+
+#[test]
+fn test_expressions() {
+    let source = "\
+let
+  const r ~ {a ~ 1, b ~ if x then 1 else 2};
+  func f(func g(x: Integer): Integer): Integer ~ let const y ~ 1 in g(y)
+in
+  begin a := (b - c) - d; a := b - (c - d); a := (b < c) /\\ \\ (d - e);
+  a := -(b + c); a := (-b) + c; a := - - b; a := b + (if c then d else e) end";
+    let expected = "\
+let
+  const r ~ { a ~ 1, b ~ if x
+    then 1
+    else 2 };
+  func f(func g(x: Integer): Integer): Integer ~
+    let
+      const y ~ 1
+    in
+      g(y)
+in
+  begin
+    a := b - c - d;
+    a := b - (c - d);
+    a := (b < c) /\\ (\\(d - e));
+    a := -(b + c);
+    a := (-b) + c;
+    a := - -b;
+    a := b + (if c
+      then d
+      else e)
+  end
+";
+    let formatted = format(source).unwrap();
+    assert_eq!(expected, formatted);
+    assert_eq!(formatted, format(&formatted).unwrap());
+    assert_eq!(parse(source), parse(&formatted));
+}
+
+#[test]
+fn test_empty_programs() {
+    assert_eq!("", format("").unwrap());
+    assert_eq!("", format("  ;  ").unwrap());
+    assert_eq!("! nothing\n", format("; ! nothing").unwrap());
+}
+
+#[test]
+fn test_syntax_error() {
+    assert!(format("let var x : Integer in x :=").is_err());
+}
@@ -0,0 +1,136 @@
+use triangle_rs::tam::interpreter::*;
+use triangle_rs::tam::Opcode::*;
+use triangle_rs::tam::Register::*;
+use triangle_rs::tam::{Instruction, Primitive};
+
+fn call_primitive(prim: Primitive) -> Instruction {
+    Instruction::new(Call, SB as u8, PB, prim.displacement() as i16)
+}
+
+fn run(code: &[Instruction], input: &str) -> (Status, String) {
+    let mut output = Vec::new();
+    let mut interpreter = Interpreter::new(input.as_bytes(), &mut output);
+    interpreter.load(code);
+    let status = interpreter.run();
+    (status, String::from_utf8(output).unwrap())
+}
+
+#[test]
+fn test_halt() {
+    let code = vec![Instruction::new(Halt, 0, CB, 0)];
+    let (status, output) = run(&code, "");
+    assert_eq!(Status::Halted, status);
+    assert_eq!("Program has halted normally.\n", output);
+}
+
+#[test]
+fn test_arithmetic() {
+    let code = vec![
+        Instruction::new(LoadL, 0, CB, 6),
+        Instruction::new(LoadL, 0, CB, 7),
+        call_primitive(Primitive::Mult),
+        call_primitive(Primitive::PutInt),
+        Instruction::new(Halt, 0, CB, 0),
+    ];
+    let (status, output) = run(&code, "");
+    assert_eq!(Status::Halted, status);
+    assert_eq!("42\nProgram has halted normally.\n", output);
+}
+
+#[test]
+fn test_call_and_return() {
+    // putint(double(21)), where double is a code routine at address 4
+    let code = vec![
+        Instruction::new(LoadL, 0, CB, 21),
+        Instruction::new(Call, SB as u8, CB, 4),
+        call_primitive(Primitive::PutInt),
+        Instruction::new(Halt, 0, CB, 0),
+        Instruction::new(Load, 1, LB, -1),
+        Instruction::new(Load, 1, LB, -1),
+        call_primitive(Primitive::Add),
+        Instruction::new(Return, 1, CB, 1),
+    ];
+    let (status, output) = run(&code, "");
+    assert_eq!(Status::Halted, status);
+    assert_eq!("42\nProgram has halted normally.\n", output);
+}
+
+#[test]
+fn test_multiword_equality() {
+    let code = vec![
+        Instruction::new(LoadL, 0, CB, 1),
+        Instruction::new(LoadL, 0, CB, 2),
+        Instruction::new(LoadL, 0, CB, 1),
+        Instruction::new(LoadL, 0, CB, 2),
+        Instruction::new(LoadL, 0, CB, 2),
+        call_primitive(Primitive::Eq),
+        call_primitive(Primitive::PutInt),
+        Instruction::new(Halt, 0, CB, 0),
+    ];
+    let (status, output) = run(&code, "");
+    assert_eq!(Status::Halted, status);
+    assert_eq!("1\nProgram has halted normally.\n", output);
+}
+
+#[test]
+fn test_input() {
+    // read two integers into 0[SB] and 1[SB], print their sum, and whether the line has ended
+    let code = vec![
+        Instruction::new(Push, 0, CB, 2),
+        Instruction::new(LoadA, 0, SB, 0),
+        call_primitive(Primitive::GetInt),
+        Instruction::new(LoadA, 0, SB, 1),
+        call_primitive(Primitive::GetInt),
+        Instruction::new(Load, 2, SB, 0),
+        call_primitive(Primitive::Add),
+        call_primitive(Primitive::PutInt),
+        call_primitive(Primitive::Eol),
+        call_primitive(Primitive::PutInt),
+        Instruction::new(Halt, 0, CB, 0),
+    ];
+    let (status, output) = run(&code, "12 -5\n");
+    assert_eq!(Status::Halted, status);
+    assert_eq!("71\nProgram has halted normally.\n", output);
+}
+
+#[test]
+fn test_overflow() {
+    let code = vec![
+        Instruction::new(LoadL, 0, CB, 32767),
+        Instruction::new(LoadL, 0, CB, 1),
+        call_primitive(Primitive::Add),
+        Instruction::new(Halt, 0, CB, 0),
+    ];
+    let (status, output) = run(&code, "");
+    assert_eq!(Status::FailedOverflow, status);
+    assert_eq!("Program has failed due to overflow.\n", output);
+}
+
+#[test]
+fn test_zero_divide() {
+    let code = vec![
+        Instruction::new(LoadL, 0, CB, 1),
+        Instruction::new(LoadL, 0, CB, 0),
+        call_primitive(Primitive::Div),
+        Instruction::new(Halt, 0, CB, 0),
+    ];
+    let (status, _) = run(&code, "");
+    assert_eq!(Status::FailedZeroDivide, status);
+}
+
+#[test]
+fn test_invalid_code_address() {
+    let code = vec![Instruction::new(Jump, 0, CB, 100)];
+    let (status, _) = run(&code, "");
+    assert_eq!(Status::FailedInvalidCodeAddress, status);
+}
+
+#[test]
+fn test_data_store_full() {
+    let code = vec![
+        Instruction::new(Push, 0, CB, 2000),
+        Instruction::new(Halt, 0, CB, 0),
+    ];
+    let (status, _) = run(&code, "");
+    assert_eq!(Status::FailedDataStoreFull, status);
+}
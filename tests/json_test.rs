@@ -0,0 +1,62 @@
+use triangle_rs::json::Json;
+
+#[test]
+fn test_parse() {
+    let value =
+        Json::parse(r#" {"a": [1, -2.5, true, null], "b": "x\"\n\u00e9\ud834\udd1e", "c": {}} "#)
+            .unwrap();
+    assert_eq!(
+        Json::object(vec![
+            (
+                "a",
+                Json::from(vec![
+                    Json::from(1i64),
+                    Json::Number(-2.5),
+                    Json::from(true),
+                    Json::Null,
+                ])
+            ),
+            ("b", Json::from("x\"\né𝄞")),
+            ("c", Json::Object(Vec::new())),
+        ]),
+        value
+    );
+    assert_eq!(
+        Some(1),
+        value
+            .get("a")
+            .and_then(|a| a.as_array())
+            .and_then(|a| a[0].as_i64())
+    );
+    assert!(value.get("d").is_none());
+}
+
+#[test]
+fn test_parse_errors() {
+    assert!(Json::parse("").is_err());
+    assert!(Json::parse("[1, 2").is_err());
+    assert!(Json::parse(r#"{"a" 1}"#).is_err());
+    assert!(Json::parse("\"abc").is_err());
+    assert!(Json::parse("1 2").is_err());
+    assert!(Json::parse("nul").is_err());
+}
+
+#[test]
+fn test_write() {
+    let value = Json::object(vec![
+        ("name", Json::from("a \"b\"\t")),
+        ("n", Json::from(3i64)),
+        ("x", Json::Number(0.5)),
+        ("list", Json::from(vec![Json::Null, Json::from(false)])),
+        ("empty", Json::Array(Vec::new())),
+    ]);
+    let text = r#"{"name":"a \"b\"\t","n":3,"x":0.5,"list":[null,false],"empty":[]}"#;
+    assert_eq!(text, value.to_string());
+    assert_eq!(value, Json::parse(text).unwrap());
+
+    assert_eq!(
+        "{\n  \"name\": \"a \\\"b\\\"\\t\",\n  \"n\": 3,\n  \"x\": 0.5,\n  \"list\": [\n    null,\n    false\n  ],\n  \"empty\": []\n}",
+        value.pretty()
+    );
+    assert_eq!(value, Json::parse(&value.pretty()).unwrap());
+}
@@ -0,0 +1,293 @@
+use triangle_rs::json::Json;
+use triangle_rs::lsp::{self, Server};
+use triangle_rs::scanner::{Position, SourcePosition};
+
+use std::io::Cursor;
+
+/// frame each of the messages as the editor would send them
+fn script(messages: &[&str]) -> Vec<u8> {
+    let mut input = Vec::new();
+    for message in messages {
+        let body = Json::parse(message).unwrap().to_string();
+        input.extend(format!("Content-Length: {}\r\n\r\n{}", body.len(), body).bytes());
+    }
+    input
+}
+
+/// every message that the server sent back
+fn replies(output: Vec<u8>) -> Vec<Json> {
+    let mut reader = Cursor::new(output);
+    let mut messages = Vec::new();
+    while let Some(body) = lsp::read_message(&mut reader).unwrap() {
+        messages.push(Json::parse(&body).unwrap());
+    }
+    messages
+}
+
+fn open(uri: &str, text: &str) -> Json {
+    Json::object(vec![
+        ("jsonrpc", Json::from("2.0")),
+        ("method", Json::from("textDocument/didOpen")),
+        (
+            "params",
+            Json::object(vec![(
+                "textDocument",
+                Json::object(vec![
+                    ("uri", Json::from(uri)),
+                    ("languageId", Json::from("triangle")),
+                    ("version", Json::from(1i64)),
+                    ("text", Json::from(text)),
+                ]),
+            )]),
+        ),
+    ])
+}
+
+fn range(line1: i64, char1: i64, line2: i64, char2: i64) -> Json {
+    Json::parse(&format!(
+        r#"{{"start":{{"line":{},"character":{}}},"end":{{"line":{},"character":{}}}}}"#,
+        line1, char1, line2, char2
+    ))
+    .unwrap()
+}
+
+#[test]
+fn test_session() {
+    let text = "let\n  var x : Integer\nin\n  x := y\n";
+    let did_open = open("file:///test.t", text).to_string();
+    let did_change = r#"{"jsonrpc":"2.0","method":"textDocument/didChange","params":{
+        "textDocument":{"uri":"file:///test.t","version":2},
+        "contentChanges":[{"text":"let\n  var x : Integer\nin\n  x := 1\n"}]}}"#;
+    let input = script(&[
+        r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"capabilities":{}}}"#,
+        r#"{"jsonrpc":"2.0","method":"initialized","params":{}}"#,
+        &did_open,
+        did_change,
+        r#"{"jsonrpc":"2.0","id":2,"method":"textDocument/formatting","params":{}}"#,
+        r#"{"jsonrpc":"2.0","id":3,"method":"shutdown"}"#,
+        r#"{"jsonrpc":"2.0","method":"exit"}"#,
+    ]);
+
+    let mut output = Vec::new();
+    assert_eq!(0, lsp::serve(Cursor::new(input), &mut output).unwrap());
+    let replies = replies(output);
+    assert_eq!(5, replies.len());
+
+    let init = &replies[0];
+    assert_eq!(Some(1), init.get("id").and_then(Json::as_i64));
+    let capabilities = init.get("result").and_then(|r| r.get("capabilities"));
+    assert_eq!(
+        Some(1),
+        capabilities
+            .and_then(|c| c.get("textDocumentSync"))
+            .and_then(Json::as_i64)
+    );
+
+    let opened = &replies[1];
+    assert_eq!(
+        Some("textDocument/publishDiagnostics"),
+        opened.get("method").and_then(Json::as_str)
+    );
+    let diagnostics = opened
+        .get("params")
+        .and_then(|p| p.get("diagnostics"))
+        .and_then(Json::as_array)
+        .unwrap();
+    assert_eq!(1, diagnostics.len());
+    assert_eq!(
+        Some("\"y\" is not declared"),
+        diagnostics[0].get("message").and_then(Json::as_str)
+    );
+    assert_eq!(Some(&range(3, 7, 3, 8)), diagnostics[0].get("range"));
+    assert_eq!(
+        Some(1),
+        diagnostics[0].get("severity").and_then(Json::as_i64)
+    );
+
+    // the change fixed the error
+    let changed = &replies[2];
+    assert_eq!(
+        Some(0),
+        changed
+            .get("params")
+            .and_then(|p| p.get("diagnostics"))
+            .and_then(Json::as_array)
+            .map(|d| d.len())
+    );
+
+    let unknown = &replies[3];
+    assert_eq!(Some(2), unknown.get("id").and_then(Json::as_i64));
+    assert_eq!(
+        Some(-32601),
+        unknown
+            .get("error")
+            .and_then(|e| e.get("code"))
+            .and_then(Json::as_i64)
+    );
+
+    let shutdown = &replies[4];
+    assert_eq!(Some(3), shutdown.get("id").and_then(Json::as_i64));
+    assert_eq!(Some(&Json::Null), shutdown.get("result"));
+}
+
+#[test]
+fn test_exit_without_shutdown() {
+    let input = script(&[r#"{"jsonrpc":"2.0","method":"exit"}"#]);
+    let mut output = Vec::new();
+    assert_eq!(1, lsp::serve(Cursor::new(input), &mut output).unwrap());
+    assert!(output.is_empty());
+}
+
+#[test]
+fn test_requests_after_shutdown() {
+    let input = script(&[
+        r#"{"jsonrpc":"2.0","id":1,"method":"shutdown"}"#,
+        r#"{"jsonrpc":"2.0","id":2,"method":"textDocument/hover","params":{}}"#,
+        r#"{"jsonrpc":"2.0","method":"textDocument/didOpen","params":{}}"#,
+        r#"{"jsonrpc":"2.0","method":"exit"}"#,
+    ]);
+    let mut output = Vec::new();
+    assert_eq!(0, lsp::serve(Cursor::new(input), &mut output).unwrap());
+    let replies = replies(output);
+    assert_eq!(2, replies.len());
+    assert_eq!(Some(2), replies[1].get("id").and_then(Json::as_i64));
+    assert_eq!(
+        Some(-32600),
+        replies[1]
+            .get("error")
+            .and_then(|e| e.get("code"))
+            .and_then(Json::as_i64)
+    );
+}
+
+#[test]
+fn test_bad_headers() {
+    let read = |input: &str| lsp::read_message(&mut Cursor::new(input.as_bytes().to_vec()));
+
+    assert_eq!(
+        Some(String::from("{}")),
+        read("\r\nContent-Length: 2\r\n\r\n{}").unwrap()
+    );
+    assert_eq!(None, read("").unwrap());
+    assert!(read("Content-Type: application/json\r\n\r\n{}").is_err());
+    assert!(read("Content-Type: application/json\r\n").is_err());
+    assert!(read("Content-Length: 18446744073709551615\r\n\r\n{}").is_err());
+    assert!(read("Content-Length: 2\r\n\r\n{").is_err());
+}
+
+#[test]
+fn test_related_information() {
+    let mut server = Server::new();
+    let text = "let\n  var x : Integer;\n  var x : Char\nin\n  x := 1\n";
+    let replies = server.handle(&open("file:///dup.t", text));
+    assert_eq!(Some(text), server.document("file:///dup.t"));
+
+    let diagnostic = &replies[0]
+        .get("params")
+        .and_then(|p| p.get("diagnostics"))
+        .and_then(Json::as_array)
+        .unwrap()[0];
+    assert_eq!(Some(&range(2, 2, 2, 14)), diagnostic.get("range"));
+
+    let related = diagnostic
+        .get("relatedInformation")
+        .and_then(Json::as_array)
+        .unwrap();
+    assert_eq!(1, related.len());
+    assert_eq!(
+        Some("x was first defined here"),
+        related[0].get("message").and_then(Json::as_str)
+    );
+    let location = related[0].get("location").unwrap();
+    assert_eq!(
+        Some("file:///dup.t"),
+        location.get("uri").and_then(Json::as_str)
+    );
+    assert_eq!(Some(&range(1, 2, 1, 17)), location.get("range"));
+}
+
+#[test]
+fn test_warnings() {
+    let text = "let\n  var x : Integer;\n  proc p() ~ let var x : Char in x := 'a'\nin\n  p()\n";
+    let diagnostics = lsp::diagnose("file:///warn.t", text);
+    assert_eq!(1, diagnostics.len());
+    assert_eq!(
+        Some(2),
+        diagnostics[0].get("severity").and_then(Json::as_i64)
+    );
+}
+
+#[test]
+fn test_positions() {
+    // "é" is one UTF-16 code unit, and "𝄞" two
+    let text = "! é𝄞\nputint(1)\n";
+    assert_eq!((0, 3), lsp::to_lsp(text, Position::new(1, 4)));
+    assert_eq!((0, 5), lsp::to_lsp(text, Position::new(1, 5)));
+    assert_eq!((1, 0), lsp::to_lsp(text, Position::new(2, 1)));
+    assert_eq!(Position::new(1, 4), lsp::from_lsp(text, 0, 3));
+    assert_eq!(Position::new(1, 5), lsp::from_lsp(text, 0, 5));
+
+    assert_eq!(
+        range(0, 3, 0, 5),
+        lsp::range(
+            text,
+            SourcePosition::new(Position::new(1, 4), Position::new(1, 4))
+        )
+    );
+    assert_eq!(
+        range(0, 0, 0, 0),
+        lsp::range(text, SourcePosition::default())
+    );
+}
+
+fn request(id: i64, method: &str, uri: &str, line: i64, character: i64) -> Json {
+    Json::parse(&format!(
+        r#"{{"jsonrpc":"2.0","id":{},"method":"{}","params":{{
+            "textDocument":{{"uri":"{}"}},
+            "position":{{"line":{},"character":{}}},
+            "context":{{"includeDeclaration":true}}}}}}"#,
+        id, method, uri, line, character
+    ))
+    .unwrap()
+}
+
+#[test]
+fn test_navigation() {
+    let mut server = Server::new();
+    let uri = "file:///nav.t";
+    let text = "let\n  var x : Integer\nin\n  begin x := 1; putint(x + 1) end\n";
+    server.handle(&open(uri, text));
+
+    let definition = server.handle(&request(1, "textDocument/definition", uri, 3, 23));
+    let result = definition[0].get("result").unwrap();
+    assert_eq!(Some(uri), result.get("uri").and_then(Json::as_str));
+    assert_eq!(Some(&range(1, 6, 1, 7)), result.get("range"));
+
+    // putint is declared in the standard environment, which has no location
+    let definition = server.handle(&request(2, "textDocument/definition", uri, 3, 17));
+    assert_eq!(Some(&Json::Null), definition[0].get("result"));
+
+    let hover = server.handle(&request(3, "textDocument/hover", uri, 3, 8));
+    let result = hover[0].get("result").unwrap();
+    assert_eq!(
+        Some("var x : Integer"),
+        result
+            .get("contents")
+            .and_then(|c| c.get("value"))
+            .and_then(Json::as_str)
+    );
+    assert_eq!(Some(&range(3, 8, 3, 9)), result.get("range"));
+
+    let references = server.handle(&request(4, "textDocument/references", uri, 1, 6));
+    let ranges = references[0]
+        .get("result")
+        .and_then(Json::as_array)
+        .unwrap()
+        .iter()
+        .map(|location| location.get("range").cloned().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![range(1, 6, 1, 7), range(3, 8, 3, 9), range(3, 23, 3, 24)],
+        ranges
+    );
+}
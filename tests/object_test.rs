@@ -0,0 +1,64 @@
+use triangle_rs::tam::object::*;
+use triangle_rs::tam::Opcode::*;
+use triangle_rs::tam::Register::*;
+use triangle_rs::tam::{Instruction, Primitive};
+
+fn sample_code() -> Vec<Instruction> {
+    vec![
+        Instruction::new(Push, 0, CB, 1),
+        Instruction::new(LoadL, 0, CB, -42),
+        Instruction::new(Store, 1, SB, 0),
+        Instruction::new(Load, 1, L2, -3),
+        Instruction::new(JumpIf, 1, CB, 0),
+        Instruction::new(Call, SB as u8, PB, Primitive::PutInt.displacement() as i16),
+        Instruction::new(Halt, 0, CB, 0),
+    ]
+}
+
+#[test]
+fn test_instruction_word() {
+    let instr = Instruction::new(Call, SB as u8, PB, 26);
+    assert_eq!(0x6204_001a, instr.to_word());
+    assert_eq!(Some(instr), Instruction::from_word(instr.to_word()));
+
+    let instr = Instruction::new(LoadL, 0, CB, -1);
+    assert_eq!(0x3000_ffff, instr.to_word());
+    assert_eq!(Some(instr), Instruction::from_word(instr.to_word()));
+
+    // opcode 9 is unused
+    assert_eq!(None, Instruction::from_word(0x9000_0000));
+}
+
+#[test]
+fn test_round_trip() {
+    let object = TamObject::new(sample_code());
+    let mut bytes = Vec::new();
+    object.write_to(&mut bytes).unwrap();
+
+    assert_eq!(&bytes[..4], MAGIC);
+    assert_eq!(8 + 4 * object.code.len(), bytes.len());
+
+    let read_object = TamObject::read_from(&mut bytes.as_slice()).unwrap();
+    assert_eq!(object, read_object);
+}
+
+#[test]
+fn test_bad_magic() {
+    let bytes = b"ELF\0\x00\x01\x00\x00".to_vec();
+    assert!(TamObject::read_from(&mut bytes.as_slice()).is_err());
+}
+
+#[test]
+fn test_bad_version() {
+    let bytes = b"TAM\0\x00\x02\x00\x00".to_vec();
+    assert!(TamObject::read_from(&mut bytes.as_slice()).is_err());
+}
+
+#[test]
+fn test_truncated() {
+    let object = TamObject::new(sample_code());
+    let mut bytes = Vec::new();
+    object.write_to(&mut bytes).unwrap();
+    bytes.truncate(bytes.len() - 2);
+    assert!(TamObject::read_from(&mut bytes.as_slice()).is_err());
+}
@@ -0,0 +1,125 @@
+use triangle_rs::ast::Program;
+use triangle_rs::checker::Checker;
+use triangle_rs::parser::Parser;
+use triangle_rs::scanner::{Position, Scanner};
+
+const SOURCE: &str = "\
+let
+  type Point ~ record x: Integer, y: Integer end;
+  var p : Point;
+  var n : Integer;
+  proc shift(d : Integer) ~
+    p.x := p.x + d
+in
+  begin
+    shift(n);
+    putint(p.x)
+  end
+";
+
+fn check(source: &str) -> Program {
+    let mut program = Parser::new(Scanner::from_text(source))
+        .parse_program()
+        .unwrap();
+    Checker::new().check(&mut program).unwrap();
+    program
+}
+
+fn at(line: isize, column: isize) -> Position {
+    Position::new(line, column)
+}
+
+#[test]
+fn test_definition_at() {
+    let program = check(SOURCE);
+
+    // a use of a variable, and its declaration
+    let decl = program.definition_at(at(9, 11)).unwrap();
+    assert_eq!("var n : Integer", program.describe(decl));
+    assert_eq!(Some(decl), program.definition_at(at(4, 7)));
+
+    // a formal parameter
+    let decl = program.definition_at(at(6, 18)).unwrap();
+    assert_eq!("d : Integer", program.describe(decl));
+
+    // a type identifier
+    let decl = program.definition_at(at(3, 11)).unwrap();
+    assert_eq!(
+        "type Point ~ record x: Integer, y: Integer end",
+        program.describe(decl)
+    );
+
+    // the standard environment
+    let decl = program.definition_at(at(10, 5)).unwrap();
+    assert_eq!("proc putint", program.describe(decl));
+    let decl = program.definition_at(at(6, 16)).unwrap();
+    assert_eq!("+ : Integer, Integer -> Integer", program.describe(decl));
+
+    // keywords and blanks stand for nothing
+    assert!(program.definition_at(at(1, 1)).is_none());
+    assert!(program.definition_at(at(9, 3)).is_none());
+}
+
+#[test]
+fn test_type_at() {
+    let program = check(SOURCE);
+    let type_at = |line, column| program.type_at(at(line, column)).map(|td| td.name());
+
+    assert_eq!(Some("Integer".to_owned()), type_at(10, 14));
+    assert_eq!(
+        Some("record x: Integer, y: Integer end".to_owned()),
+        type_at(10, 12)
+    );
+    // between the operands of the binary expression
+    assert_eq!(Some("Integer".to_owned()), type_at(6, 15));
+    assert_eq!(None, type_at(5, 3));
+}
+
+#[test]
+fn test_references_of() {
+    let program = check(SOURCE);
+    let decl = program.definition_at(at(3, 7)).unwrap();
+    let references = program
+        .references_of(decl)
+        .into_iter()
+        .map(|occurrence| {
+            (
+                occurrence.position.start.line,
+                occurrence.position.start.column,
+                occurrence.defining,
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![(3, 7, true), (6, 5, false), (6, 12, false), (10, 12, false)],
+        references
+    );
+
+    // the occurrences of the standard environment are all uses
+    let putint = program.definition_at(at(10, 5)).unwrap();
+    let references = program.references_of(putint);
+    assert_eq!(1, references.len());
+    assert!(!references[0].defining);
+    assert_eq!("putint", references[0].spelling);
+}
+
+#[test]
+fn test_shadowing() {
+    let program = check("let var x : Integer in let var x : Char in x := 'a'");
+    let inner = program.definition_at(at(1, 44)).unwrap();
+    assert_eq!("var x : Char", program.describe(inner));
+    assert_eq!(
+        vec![(1, 32), (1, 44)],
+        program
+            .references_of(inner)
+            .iter()
+            .map(|occurrence| (
+                occurrence.position.start.line,
+                occurrence.position.start.column
+            ))
+            .collect::<Vec<_>>()
+    );
+
+    let outer = program.definition_at(at(1, 9)).unwrap();
+    assert_eq!(1, program.references_of(outer).len());
+}
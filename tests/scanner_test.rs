@@ -2,7 +2,7 @@ use triangle_rs::scanner::*;
 
 #[test]
 fn test_sourcefile() {
-    let mut source_file = SourceFile::new("samples/source/odd_degenerate.t");
+    let mut source_file = SourceFile::new("samples/source/odd_degenerate.t").unwrap();
     while let Some(c) = source_file.next() {
         println!("{:?}", c);
     }
@@ -11,7 +11,7 @@ fn test_sourcefile() {
 #[test]
 fn test_emptycommandeot() {
     let source_file = "samples/source/emptycommandeot.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![Token::new(
         TokenType::Eot,
         NULL_STR,
@@ -19,7 +19,7 @@ fn test_emptycommandeot() {
     )];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -27,7 +27,7 @@ fn test_emptycommandeot() {
 #[test]
 fn test_emptycommandeot_degenerate() {
     let source_file = "samples/source/emptycommandeot_degenerate.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![Token::new(
         TokenType::Eot,
         NULL_STR,
@@ -35,7 +35,7 @@ fn test_emptycommandeot_degenerate() {
     )];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -43,14 +43,14 @@ fn test_emptycommandeot_degenerate() {
 #[test]
 fn test_emptycommandsemicolon() {
     let source_file = "samples/source/emptycommandsemicolon.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Semicolon, ";", SourcePosition::default()),
         Token::new(TokenType::Eot, NULL_STR, SourcePosition::default()),
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -58,14 +58,14 @@ fn test_emptycommandsemicolon() {
 #[test]
 fn test_emptycommandsemicolon_degenerate() {
     let source_file = "samples/source/emptycommandsemicolon_degenerate.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Semicolon, ";", SourcePosition::default()),
         Token::new(TokenType::Eot, NULL_STR, SourcePosition::default()),
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -73,7 +73,7 @@ fn test_emptycommandsemicolon_degenerate() {
 #[test]
 fn test_hello() {
     let source_file = "samples/source/hello.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Identifier, "putint", SourcePosition::default()),
         Token::new(TokenType::LeftParen, "(", SourcePosition::default()),
@@ -83,7 +83,7 @@ fn test_hello() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -91,7 +91,7 @@ fn test_hello() {
 #[test]
 fn test_hello_degenerate() {
     let source_file = "samples/source/hello_degenerate.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Identifier, "putint", SourcePosition::default()),
         Token::new(TokenType::LeftParen, "(", SourcePosition::default()),
@@ -101,7 +101,7 @@ fn test_hello_degenerate() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -109,7 +109,7 @@ fn test_hello_degenerate() {
 #[test]
 fn test_inc() {
     let source_file = "samples/source/inc.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Var, "var", SourcePosition::default()),
@@ -154,7 +154,7 @@ fn test_inc() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -162,7 +162,7 @@ fn test_inc() {
 #[test]
 fn test_inc_degenerate() {
     let source_file = "samples/source/inc_degenerate.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Var, "var", SourcePosition::default()),
@@ -207,7 +207,7 @@ fn test_inc_degenerate() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -215,7 +215,7 @@ fn test_inc_degenerate() {
 #[test]
 fn test_echo() {
     let source_file = "samples/source/echo.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Var, "var", SourcePosition::default()),
@@ -256,7 +256,7 @@ fn test_echo() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -264,7 +264,7 @@ fn test_echo() {
 #[test]
 fn test_echo_degenerate() {
     let source_file = "samples/source/echo_degenerate.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Var, "var", SourcePosition::default()),
@@ -305,7 +305,7 @@ fn test_echo_degenerate() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -313,7 +313,7 @@ fn test_echo_degenerate() {
 #[test]
 fn test_odd() {
     let source_file = "samples/source/odd.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Var, "var", SourcePosition::default()),
@@ -366,7 +366,7 @@ fn test_odd() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -374,7 +374,7 @@ fn test_odd() {
 #[test]
 fn test_odd_degenerate() {
     let source_file = "samples/source/odd_degenerate.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Var, "var", SourcePosition::default()),
@@ -427,7 +427,7 @@ fn test_odd_degenerate() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -435,7 +435,7 @@ fn test_odd_degenerate() {
 #[test]
 fn test_sum_proc() {
     let source_file = "samples/source/sum_proc.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Var, "var", SourcePosition::default()),
@@ -508,7 +508,7 @@ fn test_sum_proc() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -516,7 +516,7 @@ fn test_sum_proc() {
 #[test]
 fn test_sum_proc_degenerate() {
     let source_file = "samples/source/sum_proc_degenerate.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Var, "var", SourcePosition::default()),
@@ -589,7 +589,7 @@ fn test_sum_proc_degenerate() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -597,7 +597,7 @@ fn test_sum_proc_degenerate() {
 #[test]
 fn test_power() {
     let source_file = "samples/source/power.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Var, "var", SourcePosition::default()),
@@ -669,7 +669,7 @@ fn test_power() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -677,7 +677,7 @@ fn test_power() {
 #[test]
 fn test_power_degenerate() {
     let source_file = "samples/source/power_degenerate.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases: Vec<Token> = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Var, "var", SourcePosition::default()),
@@ -749,7 +749,7 @@ fn test_power_degenerate() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -757,7 +757,7 @@ fn test_power_degenerate() {
 #[test]
 fn test_factorial() {
     let source_file = "samples/source/factorial.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Var, "var", SourcePosition::default()),
@@ -853,7 +853,7 @@ fn test_factorial() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -861,7 +861,7 @@ fn test_factorial() {
 #[test]
 fn test_factorial_degenerate() {
     let source_file = "samples/source/factorial_degenerate.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Var, "var", SourcePosition::default()),
@@ -957,7 +957,7 @@ fn test_factorial_degenerate() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -965,7 +965,7 @@ fn test_factorial_degenerate() {
 #[test]
 fn test_record() {
     let source_file = "samples/source/record.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Type, "type", SourcePosition::default()),
@@ -1082,7 +1082,7 @@ fn test_record() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -1090,7 +1090,7 @@ fn test_record() {
 #[test]
 fn test_record_degenerate() {
     let source_file = "samples/source/record_degenerate.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Type, "type", SourcePosition::default()),
@@ -1207,7 +1207,7 @@ fn test_record_degenerate() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -1215,11 +1215,11 @@ fn test_record_degenerate() {
 #[test]
 fn test_leapyear() {
     let source_file = "samples/source/leapyear.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases: Vec<Token> = vec![];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -1227,11 +1227,11 @@ fn test_leapyear() {
 #[test]
 fn test_leapyear_degenerate() {
     let source_file = "samples/source/leapyear_degenerate.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases: Vec<Token> = vec![];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -1239,7 +1239,7 @@ fn test_leapyear_degenerate() {
 #[test]
 fn test_date() {
     let source_file = "samples/source/date.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Type, "type", SourcePosition::default()),
@@ -1408,7 +1408,7 @@ fn test_date() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -1416,7 +1416,7 @@ fn test_date() {
 #[test]
 fn test_date_degenerate() {
     let source_file = "samples/source/date_degenerate.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Type, "type", SourcePosition::default()),
@@ -1585,7 +1585,7 @@ fn test_date_degenerate() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -1593,7 +1593,7 @@ fn test_date_degenerate() {
 #[test]
 fn test_print_array() {
     let source_file = "samples/source/print_array.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Var, "var", SourcePosition::default()),
@@ -1732,7 +1732,7 @@ fn test_print_array() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -1740,7 +1740,7 @@ fn test_print_array() {
 #[test]
 fn test_print_array_degenerate() {
     let source_file = "samples/source/print_array_degenerate.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Var, "var", SourcePosition::default()),
@@ -1879,7 +1879,7 @@ fn test_print_array_degenerate() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -1887,7 +1887,7 @@ fn test_print_array_degenerate() {
 #[test]
 fn test_string() {
     let source_file = "samples/source/string.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Type, "type", SourcePosition::default()),
@@ -2043,7 +2043,7 @@ fn test_string() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -2051,7 +2051,7 @@ fn test_string() {
 #[test]
 fn test_string_degenerate() {
     let source_file = "samples/source/string_degenerate.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Type, "type", SourcePosition::default()),
@@ -2207,7 +2207,7 @@ fn test_string_degenerate() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -2215,7 +2215,7 @@ fn test_string_degenerate() {
 #[test]
 fn test_reverse_line() {
     let source_file = "samples/source/reverse_line.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Type, "type", SourcePosition::default()),
@@ -2391,7 +2391,7 @@ fn test_reverse_line() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -2399,7 +2399,7 @@ fn test_reverse_line() {
 #[test]
 fn test_reverse_line_degenerate() {
     let source_file = "samples/source/reverse_line_degenerate.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Type, "type", SourcePosition::default()),
@@ -2575,7 +2575,7 @@ fn test_reverse_line_degenerate() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -2583,7 +2583,7 @@ fn test_reverse_line_degenerate() {
 #[test]
 fn test_iteratively() {
     let source_file = "samples/source/iteratively.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Procedure, "proc", SourcePosition::default()),
@@ -2765,7 +2765,7 @@ fn test_iteratively() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -2773,7 +2773,7 @@ fn test_iteratively() {
 #[test]
 fn test_iteratively_degenerate() {
     let source_file = "samples/source/iteratively_degenerate.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Procedure, "proc", SourcePosition::default()),
@@ -2955,7 +2955,7 @@ fn test_iteratively_degenerate() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -2963,7 +2963,7 @@ fn test_iteratively_degenerate() {
 #[test]
 fn test_line() {
     let source_file = "samples/source/line.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Type, "type", SourcePosition::default()),
@@ -3188,7 +3188,7 @@ fn test_line() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -3196,7 +3196,7 @@ fn test_line() {
 #[test]
 fn test_line_degenerate() {
     let source_file = "samples/source/line_degenerate.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Type, "type", SourcePosition::default()),
@@ -3421,7 +3421,7 @@ fn test_line_degenerate() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -3429,7 +3429,7 @@ fn test_line_degenerate() {
 #[test]
 fn test_dates() {
     let source_file = "samples/source/dates.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Type, "type", SourcePosition::default()),
@@ -3686,7 +3686,7 @@ fn test_dates() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -3694,7 +3694,7 @@ fn test_dates() {
 #[test]
 fn test_dates_degenerate() {
     let source_file = "samples/source/dates_degenerate.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Type, "type", SourcePosition::default()),
@@ -3951,7 +3951,7 @@ fn test_dates_degenerate() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -3959,7 +3959,7 @@ fn test_dates_degenerate() {
 #[test]
 fn test_monthsofyear() {
     let source_file = "samples/source/monthsofyear.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Type, "type", SourcePosition::default()),
@@ -4229,7 +4229,7 @@ fn test_monthsofyear() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -4237,7 +4237,7 @@ fn test_monthsofyear() {
 #[test]
 fn test_monthsofyear_degenerate() {
     let source_file = "samples/source/monthsofyear_degenerate.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases: Vec<Token> = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Type, "type", SourcePosition::default()),
@@ -4501,7 +4501,7 @@ fn test_monthsofyear_degenerate() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -4509,7 +4509,7 @@ fn test_monthsofyear_degenerate() {
 #[test]
 fn test_capitalise() {
     let source_file = "samples/source/capitalise.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Var, "var", SourcePosition::default()),
@@ -4803,7 +4803,7 @@ fn test_capitalise() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -4811,7 +4811,7 @@ fn test_capitalise() {
 #[test]
 fn test_capitalise_degenerate() {
     let source_file = "samples/source/capitalise_degenerate.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Var, "var", SourcePosition::default()),
@@ -5105,7 +5105,7 @@ fn test_capitalise_degenerate() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -5113,7 +5113,7 @@ fn test_capitalise_degenerate() {
 #[test]
 fn test_freq() {
     let source_file = "samples/source/freq.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases: Vec<Token> = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Procedure, "proc", SourcePosition::default()),
@@ -5445,7 +5445,7 @@ fn test_freq() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -5453,7 +5453,7 @@ fn test_freq() {
 #[test]
 fn test_freq_degenerate() {
     let source_file = "samples/source/freq_degenerate.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases: Vec<Token> = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Procedure, "proc", SourcePosition::default()),
@@ -5785,7 +5785,7 @@ fn test_freq_degenerate() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -5793,7 +5793,7 @@ fn test_freq_degenerate() {
 #[test]
 fn test_insertion_sort() {
     let source_file = "samples/source/insertion_sort.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Var, "var", SourcePosition::default()),
@@ -6185,7 +6185,7 @@ fn test_insertion_sort() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -6193,7 +6193,7 @@ fn test_insertion_sort() {
 #[test]
 fn test_insertion_sort_degenerate() {
     let source_file = "samples/source/insertion_sort_degenerate.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Var, "var", SourcePosition::default()),
@@ -6585,7 +6585,7 @@ fn test_insertion_sort_degenerate() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -6593,7 +6593,7 @@ fn test_insertion_sort_degenerate() {
 #[test]
 fn test_rationals() {
     let source_file = "samples/source/rationals.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Type, "type", SourcePosition::default()),
@@ -7105,7 +7105,7 @@ fn test_rationals() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -7113,7 +7113,7 @@ fn test_rationals() {
 #[test]
 fn test_rationals_degenerate() {
     let source_file = "samples/source/rationals_degenerate.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Type, "type", SourcePosition::default()),
@@ -7625,7 +7625,7 @@ fn test_rationals_degenerate() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -7633,7 +7633,7 @@ fn test_rationals_degenerate() {
 #[test]
 fn test_eqnoteq() {
     let source_file = "samples/source/eqnoteq.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Var, "var", SourcePosition::default()),
@@ -7678,7 +7678,7 @@ fn test_eqnoteq() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -7686,7 +7686,7 @@ fn test_eqnoteq() {
 #[test]
 fn test_eqnoteq_degenerate() {
     let source_file = "samples/source/eqnoteq_degenerate.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Var, "var", SourcePosition::default()),
@@ -7731,7 +7731,7 @@ fn test_eqnoteq_degenerate() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -7739,7 +7739,7 @@ fn test_eqnoteq_degenerate() {
 #[test]
 fn test_nestedarrays() {
     let source_file = "samples/source/nestedarrays.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Type, "type", SourcePosition::default()),
@@ -8038,7 +8038,7 @@ fn test_nestedarrays() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -8046,7 +8046,7 @@ fn test_nestedarrays() {
 #[test]
 fn test_nestedarrays_degenerate() {
     let source_file = "samples/source/nestedarrays_degenerate.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Type, "type", SourcePosition::default()),
@@ -8345,7 +8345,7 @@ fn test_nestedarrays_degenerate() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -8353,7 +8353,7 @@ fn test_nestedarrays_degenerate() {
 #[test]
 fn test_nestedrecords() {
     let source_file = "samples/source/nestedrecords.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Type, "type", SourcePosition::default()),
@@ -8525,7 +8525,7 @@ fn test_nestedrecords() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
@@ -8533,7 +8533,7 @@ fn test_nestedrecords() {
 #[test]
 fn test_nestedrecords_degenerate() {
     let source_file = "samples/source/nestedrecords_degenerate.t";
-    let mut scanner = Scanner::new(source_file);
+    let mut scanner = Scanner::new(source_file).unwrap();
     let test_cases = vec![
         Token::new(TokenType::Let, "let", SourcePosition::default()),
         Token::new(TokenType::Type, "type", SourcePosition::default()),
@@ -8705,7 +8705,115 @@ fn test_nestedrecords_degenerate() {
     ];
 
     for tt in test_cases {
-        let token = scanner.scan_token();
+        let token = scanner.scan_token().unwrap();
         assert_eq!(tt, token);
     }
 }
+
+#[test]
+fn test_from_text() {
+    let source_file = "samples/source/hello.t";
+    let mut file_scanner = Scanner::new(source_file).unwrap();
+    let mut text_scanner = Scanner::from_text(&std::fs::read_to_string(source_file).unwrap());
+
+    loop {
+        let expected = file_scanner.scan_token().unwrap();
+        let token = text_scanner.scan_token().unwrap();
+        assert_eq!(expected, token);
+        assert_eq!(expected.position, token.position);
+
+        if token.kind == TokenType::Eot {
+            break;
+        }
+    }
+}
+
+#[test]
+fn test_from_reader() {
+    let source = "let\n  var n: Integer\nin\n  n := 1";
+    let mut scanner = Scanner::from_reader(source.as_bytes()).unwrap();
+    let test_cases = vec![
+        (TokenType::Let, "let", 1, 1, 1, 3),
+        (TokenType::Var, "var", 2, 3, 2, 5),
+        (TokenType::Identifier, "n", 2, 7, 2, 7),
+        (TokenType::Colon, ":", 2, 8, 2, 8),
+        (TokenType::Identifier, "Integer", 2, 10, 2, 16),
+        (TokenType::In, "in", 3, 1, 3, 2),
+        (TokenType::Identifier, "n", 4, 3, 4, 3),
+        (TokenType::Becomes, ":=", 4, 5, 4, 6),
+        (TokenType::IntegerLiteral, "1", 4, 8, 4, 8),
+    ];
+
+    for (kind, spelling, start_line, start_column, finish_line, finish_column) in test_cases {
+        let token = scanner.scan_token().unwrap();
+        assert_eq!(Token::new(kind, spelling, SourcePosition::default()), token);
+        assert_eq!(
+            SourcePosition::new(
+                Position::new(start_line, start_column),
+                Position::new(finish_line, finish_column)
+            ),
+            token.position
+        );
+    }
+}
+
+#[test]
+fn test_missing_file() {
+    let err = Scanner::new("samples/source/no_such_file.t").err().unwrap();
+    assert!(err.downcast_ref::<std::io::Error>().is_some());
+}
+
+#[test]
+fn test_unexpected_character() {
+    let mut scanner = Scanner::from_text("x := 1 # 2");
+    for _ in 0..3 {
+        scanner.scan_token().unwrap();
+    }
+
+    let err = scanner.scan_token().unwrap_err();
+    assert_eq!(
+        "ScannerError error at line 1 and column 8: unexpected character #",
+        err.to_string()
+    );
+}
+
+#[test]
+fn test_trivia() {
+    let source = "x := '!' ! set x\n\n  ! done\n";
+    let tokens = Scanner::from_text(source).with_trivia().scan_all().unwrap();
+
+    let trivia = |token: &Token| {
+        token
+            .leading
+            .iter()
+            .map(|trivia| (trivia.kind, trivia.text.clone()))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(4, tokens.len());
+    assert!(tokens[0].leading.is_empty());
+    assert_eq!(
+        vec![(TriviaKind::Whitespace, " ".to_owned())],
+        trivia(&tokens[1])
+    );
+    assert_eq!("'!'", tokens[2].text());
+    assert_eq!(
+        vec![
+            (TriviaKind::Whitespace, " ".to_owned()),
+            (TriviaKind::Comment, "! set x".to_owned()),
+            (TriviaKind::Whitespace, "\n\n  ".to_owned()),
+            (TriviaKind::Comment, "! done".to_owned()),
+            (TriviaKind::Whitespace, "\n".to_owned()),
+        ],
+        trivia(&tokens[3])
+    );
+    assert_eq!(TokenType::Eot, tokens[3].kind);
+    assert_eq!(
+        SourcePosition::new(Position::new(3, 3), Position::new(3, 8)),
+        tokens[3].leading[3].position
+    );
+
+    // without trivia, the tokens are the same but hold nothing else
+    let plain = Scanner::from_text(source).scan_all().unwrap();
+    assert_eq!(tokens, plain);
+    assert!(plain.iter().all(|token| token.leading.is_empty()));
+}
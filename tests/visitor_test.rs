@@ -0,0 +1,120 @@
+use triangle_rs::ast::arena::{Child, Nodes, Tree};
+use triangle_rs::ast::commands::Command;
+use triangle_rs::ast::expressions::*;
+use triangle_rs::ast::fold::{self, AstFold};
+use triangle_rs::ast::primitives::*;
+use triangle_rs::ast::visit_ref::AstRefVisitor;
+use triangle_rs::ast::*;
+use triangle_rs::parser::*;
+use triangle_rs::scanner::*;
+
+fn parse(source: &str) -> Program {
+    let mut parser = Parser::new(Scanner::from_text(source));
+    parser.parse_program().unwrap()
+}
+
+/// the command of the program, holding its whole tree
+fn tree(program: &Program) -> Child<Command> {
+    let mut cmd = program.cmd.clone();
+    cmd.detach(&mut program.nodes.clone());
+    cmd
+}
+
+/// collects the spelling of every identifier, in the order that they are visited
+struct Identifiers<'a> {
+    nodes: &'a Nodes,
+    spellings: Vec<String>,
+}
+
+impl<'a> AstRefVisitor<'a> for Identifiers<'a> {
+    type Arg = ();
+    type Output = ();
+
+    fn nodes(&self) -> &'a Nodes {
+        self.nodes
+    }
+
+    fn visit_identifier(&mut self, id: &Identifier, _arg: ()) {
+        self.spellings.push(id.spelling.clone());
+    }
+}
+
+/// renames every identifier with the one spelling to the other
+struct Rename(&'static str, &'static str, Nodes);
+
+impl AstVisitor for Rename {
+    type Arg = ();
+    type Output = ();
+
+    fn nodes(&mut self) -> &mut Nodes {
+        &mut self.2
+    }
+
+    fn visit_identifier(&mut self, id: &mut Identifier, _arg: ()) {
+        if id.spelling == self.0 {
+            id.spelling = self.1.to_owned();
+        }
+    }
+}
+
+/// adds up integer literals
+struct ConstantFolder;
+
+impl AstFold for ConstantFolder {
+    fn fold_binary_expression(&mut self, expr: BinaryExpressionState) -> Expression {
+        let position = expr.common_state.position;
+        match fold::walk_binary_expression(self, expr) {
+            Expression::BinaryExpression(ref binary) if binary.op.spelling == "+" => {
+                // the children of a folded node are detached, so there is no arena to look them
+                // up in
+                let nodes = Nodes::new();
+                match (binary.expr1.get(&nodes), binary.expr2.get(&nodes)) {
+                    (Expression::IntegerExpression(il1), Expression::IntegerExpression(il2)) => {
+                        let sum = il1.il.spelling.parse::<i32>().unwrap()
+                            + il2.il.spelling.parse::<i32>().unwrap();
+                        Expression::IntegerExpression(IntegerExpressionState::new_with_position(
+                            IntegerLiteral::new(&sum.to_string()),
+                            position,
+                        ))
+                    }
+                    _ => Expression::BinaryExpression(binary.clone()),
+                }
+            }
+            expr => expr,
+        }
+    }
+}
+
+#[test]
+fn test_ref_visitor() {
+    let program = parse("let var x: Integer; proc p(n: Integer) ~ x := n in p(x.y[1])");
+    let mut identifiers = Identifiers {
+        nodes: &program.nodes,
+        spellings: Vec::new(),
+    };
+    identifiers.visit_program(&program, ());
+    assert_eq!(
+        vec!["x", "Integer", "p", "n", "Integer", "x", "n", "p", "x", "y"],
+        identifiers.spellings
+    );
+}
+
+#[test]
+fn test_default_walk() {
+    let mut program = parse("let const x ~ 1 in while x < 10 do putint(x + f(x))");
+    program.accept(&mut Rename("x", "y", Nodes::new()), ());
+    assert_eq!(
+        parse("let const y ~ 1 in while y < 10 do putint(y + f(y))"),
+        program
+    );
+}
+
+#[test]
+fn test_fold() {
+    let program = parse("putint(1 + 2 + 3 * (4 + 5))");
+    let cmd_id = program.cmd.id();
+
+    let folded = ConstantFolder.fold_program(program);
+    assert_eq!(tree(&parse("putint(6 * 9)")), tree(&folded));
+    assert_eq!(cmd_id, folded.cmd.id());
+}